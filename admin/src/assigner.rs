@@ -1,8 +1,8 @@
 //! RustSec Advisory DB tool to assign ids
 
-use crate::{error::ErrorKind, prelude::*, Map};
+use crate::{error::ErrorKind, prelude::*};
 use rustsec::{
-    advisory::{id::Kind, parser},
+    advisory::{self, parser},
     collection::Collection,
     Advisory,
 };
@@ -50,26 +50,9 @@ pub fn assign_ids(repo_path: &Path, output_mode: OutputMode) {
         );
     }
 
-    let mut highest_id = Map::new();
-
-    for advisory in advisories {
-        let advisory_clone = advisory.clone();
-        let metadata = advisory_clone.metadata;
-        let id = metadata.id;
-        let year = metadata.date.year();
-
-        if let Kind::RUSTSEC = id.kind() {
-            let id_num = id.numerical_part().unwrap();
-
-            if let Some(&number) = highest_id.get(&year) {
-                if number < id_num {
-                    highest_id.insert(year, id_num);
-                }
-            } else {
-                highest_id.insert(year, id_num);
-            }
-        }
-    }
+    let mut assigned_ids = advisories
+        .map(|advisory| advisory.id().clone())
+        .collect::<Vec<_>>();
 
     let mut collection_strs = vec![];
     let crates_str = Collection::Crates.to_string();
@@ -82,7 +65,7 @@ pub fn assign_ids(repo_path: &Path, output_mode: OutputMode) {
         assign_ids_across_directory(
             collection_str,
             repo_path,
-            &mut highest_id,
+            &mut assigned_ids,
             output_mode,
             &mut assignments,
         );
@@ -97,7 +80,7 @@ pub fn assign_ids(repo_path: &Path, output_mode: OutputMode) {
 fn assign_ids_across_directory(
     collection_str: String,
     repo_path: &Path,
-    highest_ids: &mut Map<u32, u32>,
+    assigned_ids: &mut Vec<advisory::Id>,
     output_mode: OutputMode,
     assignments: &mut Vec<String>,
 ) {
@@ -134,9 +117,8 @@ fn assign_ids_across_directory(
                     let advisory: Advisory = toml::from_str(&advisory_parts.front_matter).unwrap();
                     let date = advisory.metadata.date;
                     let year = date.year();
-                    let new_id = highest_ids.get(&year).cloned().unwrap_or_default() + 1;
-                    let year_str = year.to_string();
-                    let string_id = format!("RUSTSEC-{}-{:04}", year_str, new_id);
+                    let new_id = advisory::Id::next_id(year, assigned_ids.iter()).unwrap();
+                    let string_id = new_id.to_string();
                     let new_filename = format!("{}.md", string_id);
                     let new_path = dir_path_clone.join(new_filename);
                     let original_file = File::open(advisory_path_for_reading).unwrap();
@@ -156,7 +138,7 @@ fn assign_ids_across_directory(
                                 .unwrap();
                         }
                     }
-                    highest_ids.insert(year, new_id);
+                    assigned_ids.push(new_id);
                     fs::remove_file(advisory_path_for_deleting).unwrap();
                     if output_mode == OutputMode::HumanReadable {
                         status_ok!("Assignment", "Assigned {} to {}", string_id, dir_name);
@@ -0,0 +1,213 @@
+//! RustSec Advisory DB consistency checker
+//!
+//! Verifies the invariants of the advisory DB's directory structure which
+//! aren't covered by linting individual advisories.
+
+use crate::{error::Error, linter::COLLECTIONS};
+use chrono::{NaiveDate, NaiveDateTime};
+use rustsec::{
+    advisory,
+    repository::git::{GitModificationTimes, GitPath, Repository},
+    Advisory, Collection,
+};
+use std::{
+    collections::BTreeMap as Map,
+    convert::TryInto,
+    ffi::OsStr,
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A problem found in the advisory DB
+#[derive(Clone, Debug)]
+pub struct Problem {
+    /// Path to the file or directory with the problem
+    pub path: PathBuf,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+/// Advisory DB consistency checker
+pub struct Checker {
+    /// Path to the advisory database
+    repo_path: PathBuf,
+
+    /// Git repository containing the advisory database (if any)
+    repository: Option<Repository>,
+
+    /// Files from which each advisory ID was loaded
+    ids: Map<advisory::Id, PathBuf>,
+
+    /// Problems found so far
+    problems: Vec<Problem>,
+}
+
+impl Checker {
+    /// Create a new checker for the database at the given path.
+    ///
+    /// Advisory dates are only checked against git history if the path is
+    /// a git repository.
+    pub fn new(repo_path: impl Into<PathBuf>) -> Self {
+        let repo_path = repo_path.into();
+        let repository = Repository::open(&repo_path).ok();
+
+        Self {
+            repo_path,
+            repository,
+            ids: Map::new(),
+            problems: vec![],
+        }
+    }
+
+    /// Is the advisory DB a git repository (i.e. will dates be checked)?
+    pub fn has_git_history(&self) -> bool {
+        self.repository.is_some()
+    }
+
+    /// Check the advisory DB, returning all of the problems found
+    pub fn check(mut self) -> Result<Vec<Problem>, Error> {
+        let mod_times = match &self.repository {
+            Some(repo) => Some(GitModificationTimes::new(repo)?),
+            None => None,
+        };
+
+        for &collection in COLLECTIONS {
+            let collection_path = self.repo_path.join(collection.as_str());
+
+            if !collection_path.is_dir() {
+                continue;
+            }
+
+            for crate_entry in fs::read_dir(&collection_path)? {
+                let crate_path = crate_entry?.path();
+
+                if !crate_path.is_dir() {
+                    self.problem(&crate_path, "orphan file in collection directory");
+                    continue;
+                }
+
+                for advisory_entry in fs::read_dir(&crate_path)? {
+                    let advisory_path = advisory_entry?.path();
+
+                    if !advisory_path.is_file()
+                        || advisory_path.extension() != Some(OsStr::new("md"))
+                    {
+                        self.problem(&advisory_path, "orphan entry in crate directory");
+                        continue;
+                    }
+
+                    self.check_advisory(collection, &advisory_path, mod_times.as_ref());
+                }
+            }
+        }
+
+        Ok(self.problems)
+    }
+
+    /// Check an individual advisory file
+    fn check_advisory(
+        &mut self,
+        collection: Collection,
+        advisory_path: &Path,
+        mod_times: Option<&GitModificationTimes>,
+    ) {
+        let advisory = match Advisory::load_file(advisory_path) {
+            Ok(advisory) => advisory,
+            Err(e) => {
+                self.problem(advisory_path, format!("couldn't parse advisory: {}", e));
+                return;
+            }
+        };
+
+        let metadata = &advisory.metadata;
+        let expected_filename = format!("{}.md", metadata.id);
+
+        if advisory_path.file_name() != Some(OsStr::new(&expected_filename)) {
+            self.problem(
+                advisory_path,
+                format!("file name doesn't match advisory ID {}", metadata.id),
+            );
+        }
+
+        let crate_dir = advisory_path.parent().and_then(Path::file_name);
+
+        if crate_dir != Some(OsStr::new(metadata.package.as_str())) {
+            self.problem(
+                advisory_path,
+                format!("directory doesn't match package `{}`", metadata.package),
+            );
+        }
+
+        if let Some(declared) = metadata.collection {
+            if declared != collection {
+                self.problem(
+                    advisory_path,
+                    format!(
+                        "advisory is in `{}` but declares collection `{}`",
+                        collection, declared
+                    ),
+                );
+            }
+        }
+
+        if !metadata.id.is_placeholder() {
+            if let Some(other_path) = self.ids.get(&metadata.id) {
+                let message = format!(
+                    "duplicate advisory ID {} (also in {})",
+                    metadata.id,
+                    other_path.display()
+                );
+                self.problem(advisory_path, message);
+            } else {
+                self.ids
+                    .insert(metadata.id.clone(), advisory_path.to_owned());
+            }
+        }
+
+        if let (Some(repo), Some(mod_times)) = (&self.repository, mod_times) {
+            let committed = advisory_path
+                .strip_prefix(&self.repo_path)
+                .ok()
+                .and_then(|path| GitPath::new(repo, path).ok())
+                .and_then(|path| mod_times.created(path))
+                .and_then(|time| NaiveDateTime::from_timestamp_opt(time.seconds(), 0))
+                .map(|datetime| datetime.date());
+
+            let date = &metadata.date;
+            let advisory_date = date
+                .year()
+                .try_into()
+                .ok()
+                .and_then(|year| NaiveDate::from_ymd_opt(year, date.month(), date.day()));
+
+            if let (Some(committed), Some(advisory_date)) = (committed, advisory_date) {
+                if advisory_date > committed {
+                    self.problem(
+                        advisory_path,
+                        format!(
+                            "advisory date {} is after the advisory was first committed ({})",
+                            date.as_str(),
+                            committed
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Record a problem
+    fn problem(&mut self, path: &Path, message: impl Into<String>) {
+        self.problems.push(Problem {
+            path: path.to_owned(),
+            message: message.into(),
+        });
+    }
+}
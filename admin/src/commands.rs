@@ -1,19 +1,31 @@
 //! `rustsec-admin` CLI subcommands
 
 mod assign_id;
+mod check_db;
+mod db_info;
+mod enrich;
 mod lint;
 mod list_affected_versions;
+mod mirror;
+mod new_advisories;
 mod osv;
+mod stats;
 mod version;
 mod web;
 
 use self::{
-    assign_id::AssignIdCmd, lint::LintCmd, list_affected_versions::ListAffectedVersionsCmd,
-    osv::OsvCmd, version::VersionCmd, web::WebCmd,
+    assign_id::AssignIdCmd, check_db::CheckDbCmd, db_info::DbInfoCmd, enrich::EnrichCmd,
+    lint::LintCmd, list_affected_versions::ListAffectedVersionsCmd, mirror::MirrorCmd,
+    new_advisories::NewAdvisoriesCmd, osv::OsvCmd, stats::StatsCmd, version::VersionCmd,
+    web::WebCmd,
 };
 use crate::config::AppConfig;
+use crate::prelude::*;
 use abscissa_core::{Command, Configurable, Help, Options, Runnable};
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    process::exit,
+};
 
 /// `rustsec-admin` CLI subcommands
 #[derive(Command, Debug, Options, Runnable)]
@@ -22,6 +34,18 @@ pub enum AdminCmd {
     #[options(help = "lint Advisory DB and ensure is well-formed")]
     Lint(LintCmd),
 
+    /// The `check-db` subcommand
+    #[options(help = "check the Advisory DB's directory structure is consistent")]
+    CheckDb(CheckDbCmd),
+
+    /// The `db-info` subcommand
+    #[options(help = "summarize the Advisory DB (advisory counts, last commit, format version)")]
+    DbInfo(DbInfoCmd),
+
+    /// The `stats` subcommand
+    #[options(help = "compute aggregated advisory statistics (per year, category, etc)")]
+    Stats(StatsCmd),
+
     /// The `web` subcommand
     #[options(help = "render advisory Markdown files for the rustsec.org web site")]
     Web(WebCmd),
@@ -42,9 +66,21 @@ pub enum AdminCmd {
     #[options(help = "export advisories to OSV format")]
     Osv(OsvCmd),
 
+    /// The `mirror` subcommand
+    #[options(help = "generate a statically hostable mirror of the advisory DB")]
+    Mirror(MirrorCmd),
+
+    /// The `new-advisories` subcommand
+    #[options(help = "list advisories added since a given commit or date")]
+    NewAdvisories(NewAdvisoriesCmd),
+
     /// The `version` subcommand
     #[options(help = "list affected crate versions")]
     ListAffectedVersions(ListAffectedVersionsCmd),
+
+    /// The `enrich` subcommand
+    #[options(help = "backfill missing advisory metadata (e.g. CVSS vectors from NVD)")]
+    Enrich(EnrichCmd),
 }
 
 impl Configurable<AppConfig> for AdminCmd {
@@ -53,3 +89,39 @@ impl Configurable<AppConfig> for AdminCmd {
         None
     }
 }
+
+/// Get the single path among a subcommand's free arguments (`None` if there
+/// are none), erroring clearly if more than one was given.
+///
+/// `description` says what the path is for (e.g. "advisory DB path").
+pub(crate) fn single_path<'a>(paths: &'a [PathBuf], description: &str) -> Option<&'a Path> {
+    match paths {
+        [] => None,
+        [path] => Some(path.as_path()),
+        _ => {
+            let paths = paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>();
+
+            status_err!(
+                "expected at most one {}, got {}: {}",
+                description,
+                paths.len(),
+                paths.join(", ")
+            );
+            exit(1);
+        }
+    }
+}
+
+/// Get the advisory DB paths among a subcommand's free arguments, for
+/// subcommands which can operate on several databases at once (e.g. the main
+/// DB plus a staging overlay), defaulting to the current directory
+pub(crate) fn db_paths(paths: &[PathBuf]) -> Vec<&Path> {
+    if paths.is_empty() {
+        vec![Path::new(".")]
+    } else {
+        paths.iter().map(PathBuf::as_path).collect()
+    }
+}
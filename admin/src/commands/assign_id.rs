@@ -2,6 +2,7 @@
 //!
 //! Assigns RUSTSEC ids to new vulnerabilities
 
+use super::single_path;
 use abscissa_core::{Command, Runnable};
 use gumdrop::Options;
 use std::path::{Path, PathBuf};
@@ -18,11 +19,8 @@ pub struct AssignIdCmd {
 
 impl Runnable for AssignIdCmd {
     fn run(&self) {
-        let repo_path = match self.path.len() {
-            0 => Path::new("."),
-            1 => self.path[0].as_path(),
-            _ => Self::print_usage_and_exit(&[]),
-        };
+        let repo_path =
+            single_path(&self.path, "advisory DB path").unwrap_or_else(|| Path::new("."));
         let output_mode = if self.github_action_output {
             crate::assigner::OutputMode::GithubAction
         } else {
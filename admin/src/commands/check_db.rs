@@ -0,0 +1,53 @@
+//! `rustsec-admin check-db` subcommand
+
+use super::db_paths;
+use crate::{checker::Checker, prelude::*};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use std::{path::PathBuf, process::exit};
+
+/// `rustsec-admin check-db` subcommand
+#[derive(Command, Debug, Default, Options)]
+pub struct CheckDbCmd {
+    /// Paths to the advisory databases
+    #[options(
+        free,
+        help = "filesystem paths to RustSec advisory DB git repos (e.g. the DB and a staging overlay)"
+    )]
+    path: Vec<PathBuf>,
+}
+
+impl Runnable for CheckDbCmd {
+    fn run(&self) {
+        let mut problem_count = 0;
+
+        for repo_path in db_paths(&self.path) {
+            let checker = Checker::new(repo_path);
+
+            if !checker.has_git_history() {
+                status_warn!(
+                    "{} is not a git repository; skipping checks against git history",
+                    repo_path.display()
+                );
+            }
+
+            let problems = checker.check().unwrap_or_else(|e| {
+                status_err!("error checking advisory DB {}: {}", repo_path.display(), e);
+                exit(1);
+            });
+
+            for problem in &problems {
+                status_err!("{}", problem);
+            }
+
+            problem_count += problems.len();
+        }
+
+        if problem_count == 0 {
+            status_ok!("Success", "advisory DB structure is consistent");
+        } else {
+            status_err!("{} problems found in advisory DB!", problem_count);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! `rustsec-admin db-info` subcommand
+
+use super::single_path;
+use crate::{db_info::DbInfo, prelude::*};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// `rustsec-admin db-info` subcommand
+#[derive(Command, Debug, Default, Options)]
+pub struct DbInfoCmd {
+    /// Output format
+    #[options(
+        no_short,
+        long = "format",
+        meta = "FORMAT",
+        help = "output format: text (default) or json"
+    )]
+    format: Option<String>,
+
+    /// Path to the advisory database
+    #[options(free, help = "filesystem path to the RustSec advisory DB git repo")]
+    path: Vec<PathBuf>,
+}
+
+impl Runnable for DbInfoCmd {
+    fn run(&self) {
+        let repo_path =
+            single_path(&self.path, "advisory DB path").unwrap_or_else(|| Path::new("."));
+
+        let json = match self.format.as_deref() {
+            None | Some("text") => false,
+            Some("json") => true,
+            Some(other) => {
+                status_err!("invalid format: {} (expected text or json)", other);
+                exit(1);
+            }
+        };
+
+        let info = DbInfo::load(repo_path).unwrap_or_else(|e| {
+            status_err!(
+                "error loading advisory DB from {}: {}",
+                repo_path.display(),
+                e
+            );
+            exit(1);
+        });
+
+        if json {
+            serde_json::to_writer_pretty(io::stdout(), &info).unwrap();
+            println!();
+            io::stdout().flush().unwrap();
+            return;
+        }
+
+        println!("format version:  {}", info.format_version);
+        println!("rustsec version: {}", info.rustsec_version);
+        println!("advisories:      {}", info.advisories);
+
+        for (collection, count) in &info.collections {
+            println!("  {:<14} {}", format!("{}:", collection), count);
+        }
+
+        println!("informational:   {}", info.informational);
+        println!("withdrawn:       {}", info.withdrawn);
+
+        if let Some(commit) = &info.last_commit {
+            println!(
+                "last commit:     {} ({}) {}",
+                commit.id, commit.timestamp, commit.summary
+            );
+        }
+    }
+}
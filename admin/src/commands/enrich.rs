@@ -0,0 +1,120 @@
+//! `rustsec-admin enrich` subcommand
+
+use super::single_path;
+use crate::{
+    enrich::{apply_cvss, propose_cvss, NvdClient},
+    prelude::*,
+};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use std::{
+    env,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// `rustsec-admin enrich` subcommand
+#[derive(Command, Debug, Default, Options)]
+pub struct EnrichCmd {
+    /// Propose CVSS vectors for advisories which lack them
+    #[options(
+        no_short,
+        long = "cvss",
+        help = "propose CVSS vectors for unscored advisories from NVD"
+    )]
+    cvss: bool,
+
+    /// Write the proposed changes to the advisory files
+    #[options(
+        no_short,
+        long = "write",
+        help = "update the advisory files (for review) rather than only listing proposals"
+    )]
+    write: bool,
+
+    /// Directory to cache NVD API responses in
+    #[options(
+        no_short,
+        long = "cache",
+        meta = "DIR",
+        help = "cache NVD API responses in this directory (for up to a week)"
+    )]
+    cache: Option<PathBuf>,
+
+    /// Output JSON
+    #[options(no_short, long = "json", help = "output proposals as JSON")]
+    json: bool,
+
+    /// Path to the advisory database
+    #[options(free, help = "filesystem path to the RustSec advisory DB git repo")]
+    path: Vec<PathBuf>,
+}
+
+impl Runnable for EnrichCmd {
+    fn run(&self) {
+        let repo_path =
+            single_path(&self.path, "advisory DB path").unwrap_or_else(|| Path::new("."));
+
+        if !self.cvss {
+            status_err!("nothing to enrich: pass --cvss");
+            exit(1);
+        }
+
+        let cache_dir = self
+            .cache
+            .clone()
+            .unwrap_or_else(|| env::temp_dir().join("rustsec-admin-nvd"));
+
+        let mut client = NvdClient::new(&cache_dir).unwrap_or_else(|e| {
+            status_err!("error creating cache in {}: {}", cache_dir.display(), e);
+            exit(1);
+        });
+
+        let proposals = propose_cvss(repo_path, &mut client).unwrap_or_else(|e| {
+            status_err!(
+                "error looking up CVSS vectors for {}: {}",
+                repo_path.display(),
+                e
+            );
+            exit(1);
+        });
+
+        if self.write {
+            for proposal in &proposals {
+                apply_cvss(proposal).unwrap_or_else(|e| {
+                    status_err!("error updating {}: {}", proposal.path.display(), e);
+                    exit(1);
+                });
+            }
+        }
+
+        if self.json {
+            serde_json::to_writer_pretty(io::stdout(), &proposals).unwrap();
+            println!();
+            io::stdout().flush().unwrap();
+            return;
+        }
+
+        for proposal in &proposals {
+            println!(
+                "{}\t{}\t{:.1}\t{}",
+                proposal.id, proposal.cve, proposal.score, proposal.cvss
+            );
+        }
+
+        if self.write {
+            status_ok!(
+                "Updated",
+                "{} advisories with CVSS vectors (review the changes before committing)",
+                proposals.len()
+            );
+        } else {
+            status_ok!(
+                "Found",
+                "{} CVSS vectors to add (rerun with --write to apply them)",
+                proposals.len()
+            );
+        }
+    }
+}
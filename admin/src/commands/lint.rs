@@ -1,9 +1,14 @@
 //! `rustsec-admin lint` subcommand
 
-use crate::{linter::Linter, prelude::*};
+use super::db_paths;
+use crate::{
+    linter::{Finding, Linter},
+    prelude::*,
+};
 use abscissa_core::{Command, Runnable};
 use gumdrop::Options;
 use std::{
+    collections::BTreeSet as Set,
     path::{Path, PathBuf},
     process::exit,
 };
@@ -11,20 +16,72 @@ use std::{
 /// `rustsec-admin lint` subcommand
 #[derive(Command, Debug, Default, Options)]
 pub struct LintCmd {
-    /// Path to the advisory database
-    #[options(free, help = "filesystem path to the RustSec advisory DB git repo")]
+    /// Output format
+    #[options(
+        no_short,
+        long = "format",
+        meta = "FORMAT",
+        help = "output format: text (default) or json"
+    )]
+    format: Option<String>,
+
+    /// Check `affected.functions` paths against the crates' public APIs
+    #[options(
+        no_short,
+        long = "check-function-paths",
+        help = "check affected function paths exist (documents crates with cargo +nightly rustdoc, running their build scripts: only use in a sandbox)"
+    )]
+    check_function_paths: bool,
+
+    /// Paths to the advisory databases
+    #[options(
+        free,
+        help = "filesystem paths to RustSec advisory DB git repos (e.g. the DB and a staging overlay)"
+    )]
     path: Vec<PathBuf>,
 }
 
 impl Runnable for LintCmd {
     fn run(&self) {
-        let repo_path = match self.path.len() {
-            0 => Path::new("."),
-            1 => self.path[0].as_path(),
-            _ => Self::print_usage_and_exit(&[]),
+        let json = match self.format.as_deref() {
+            None | Some("text") => false,
+            Some("json") => true,
+            Some(other) => {
+                status_err!("invalid format: {} (expected text or json)", other);
+                exit(1);
+            }
         };
 
-        let linter = Linter::new(&repo_path).unwrap_or_else(|e| {
+        let findings = db_paths(&self.path)
+            .into_iter()
+            .flat_map(|repo_path| self.lint(repo_path))
+            .collect::<Vec<_>>();
+
+        if json {
+            serde_json::to_writer_pretty(std::io::stdout(), &findings).unwrap();
+            println!();
+        } else {
+            print_findings(&findings);
+        }
+
+        let invalid_advisories = findings
+            .iter()
+            .map(|finding| &finding.file)
+            .collect::<Set<_>>();
+
+        if invalid_advisories.is_empty() {
+            status_ok!("Success", "all advisories are well-formed");
+        } else {
+            status_err!("{} advisories contain errors!", invalid_advisories.len());
+            exit(1);
+        }
+    }
+}
+
+impl LintCmd {
+    /// Lint the advisory DB at the given path
+    fn lint(&self, repo_path: &Path) -> Vec<Finding> {
+        let mut linter = Linter::new(repo_path).unwrap_or_else(|e| {
             status_err!(
                 "error loading advisory DB repo from {}: {}",
                 repo_path.display(),
@@ -34,11 +91,18 @@ impl Runnable for LintCmd {
             exit(1);
         });
 
+        if self.check_function_paths {
+            linter.check_function_paths().unwrap_or_else(|e| {
+                status_err!("error preparing to check function paths: {}", e);
+                exit(1);
+            });
+        }
+
         let advisories = linter.advisory_db().iter();
 
         // Ensure we're parsing some advisories
         if advisories.len() == 0 {
-            status_err!("no advisories found!");
+            status_err!("no advisories found in {}!", repo_path.display());
             exit(1);
         }
 
@@ -49,17 +113,34 @@ impl Runnable for LintCmd {
             repo_path.display()
         );
 
-        let invalid_advisory_count = linter.lint().unwrap_or_else(|e| {
+        linter.lint().unwrap_or_else(|e| {
             status_err!("error linting advisory DB {}: {}", repo_path.display(), e);
 
             exit(1);
-        });
+        })
+    }
+}
 
-        if invalid_advisory_count == 0 {
-            status_ok!("Success", "all advisories are well-formed");
-        } else {
-            status_err!("{} advisories contain errors!", invalid_advisory_count);
-            exit(1);
+/// Print lint findings as text, grouped by file
+fn print_findings(findings: &[Finding]) {
+    let mut current_file = None;
+
+    for finding in findings {
+        if current_file != Some(&finding.file) {
+            status_err!(
+                "{} contained the following lint errors:",
+                finding.file.display()
+            );
+            current_file = Some(&finding.file);
+        }
+
+        match finding.line {
+            Some(line) => println!("  - line {}: {}", line, finding.message),
+            None => println!("  - {}", finding.message),
+        }
+
+        if let Some(suggestion) = &finding.suggestion {
+            println!("    suggestion: {}", suggestion);
         }
     }
 }
@@ -13,6 +13,7 @@ use std::{
 
 use abscissa_core::{Command, Options, Runnable};
 
+use super::single_path;
 use crate::list_versions::AffectedVersionLister;
 use crate::prelude::*;
 
@@ -26,11 +27,8 @@ pub struct ListAffectedVersionsCmd {
 
 impl Runnable for ListAffectedVersionsCmd {
     fn run(&self) {
-        let repo_path = match self.path.len() {
-            0 => Path::new("."),
-            1 => self.path[0].as_path(),
-            _ => Self::print_usage_and_exit(&[]),
-        };
+        let repo_path =
+            single_path(&self.path, "advisory DB path").unwrap_or_else(|| Path::new("."));
 
         let lister = AffectedVersionLister::new(&repo_path).unwrap_or_else(|e| {
             status_err!(
@@ -0,0 +1,71 @@
+//! `rustsec-admin mirror` subcommand
+//!
+//! Generates a self-contained snapshot of the advisory database (advisories,
+//! OSV exports, an advisory index, and an integrity manifest) which can be
+//! hosted on a static file server and fetched by `cargo audit --mirror`.
+
+use std::{path::PathBuf, process::exit};
+
+use abscissa_core::{status_err, status_ok, Command, Options, Runnable};
+
+use crate::{mirror::MirrorGenerator, osv_export::StaleFiles};
+
+#[derive(Command, Debug, Default, Options)]
+pub struct MirrorCmd {
+    /// Path to the advisory database
+    #[options(
+        long = "db",
+        help = "filesystem path to the RustSec advisory DB git repo"
+    )]
+    repo_path: Option<PathBuf>,
+
+    /// Path to the output directory
+    #[options(
+        long = "out",
+        help = "filesystem directory where the mirror will be written"
+    )]
+    out_path: Option<PathBuf>,
+
+    /// Delete stale OSV files from the output directory
+    #[options(
+        no_short,
+        long = "sync",
+        help = "delete OSV files in the output directory which don't match any current advisory"
+    )]
+    sync: bool,
+}
+
+impl Runnable for MirrorCmd {
+    fn run(&self) {
+        let out_path = match &self.out_path {
+            Some(path) => path,
+            None => Self::print_usage_and_exit(&[]),
+        };
+
+        let generator = MirrorGenerator::new(self.repo_path.as_deref()).unwrap_or_else(|e| {
+            status_err!("Failed to fetch the advisory database: {}", e);
+            exit(1);
+        });
+
+        let stale_files = if self.sync {
+            StaleFiles::Delete
+        } else {
+            StaleFiles::Keep
+        };
+
+        let manifest = generator
+            .generate(out_path, stale_files)
+            .unwrap_or_else(|e| {
+                status_err!("failed to write mirror to '{}': {}", out_path.display(), e);
+                exit(1);
+            });
+
+        status_ok!(
+            "Success",
+            "wrote mirror of {} files to '{}' (manifest digest: {})",
+            manifest.files.len(),
+            out_path.display(),
+            manifest.digest()
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! `rustsec-admin new-advisories` subcommand
+
+use super::single_path;
+use crate::{
+    new_advisories::{new_advisories, Since},
+    prelude::*,
+};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// `rustsec-admin new-advisories` subcommand
+#[derive(Command, Debug, Default, Options)]
+pub struct NewAdvisoriesCmd {
+    /// Commit or date to list new advisories since
+    #[options(
+        long = "since",
+        meta = "COMMIT|YYYY-MM-DD",
+        help = "list advisories added after this commit, or on or after this date"
+    )]
+    since: Option<String>,
+
+    /// Output JSON
+    #[options(no_short, long = "json", help = "output new advisories as JSON")]
+    json: bool,
+
+    /// Path to the advisory database
+    #[options(free, help = "filesystem path to the RustSec advisory DB git repo")]
+    path: Vec<PathBuf>,
+}
+
+impl Runnable for NewAdvisoriesCmd {
+    fn run(&self) {
+        let repo_path =
+            single_path(&self.path, "advisory DB path").unwrap_or_else(|| Path::new("."));
+
+        let since = match &self.since {
+            Some(since) if !since.is_empty() => Since::from(since.as_str()),
+            _ => {
+                status_err!("--since <COMMIT|YYYY-MM-DD> is required");
+                exit(1);
+            }
+        };
+
+        let advisories = new_advisories(repo_path, &since).unwrap_or_else(|e| {
+            status_err!(
+                "error listing new advisories in {}: {}",
+                repo_path.display(),
+                e
+            );
+            exit(1);
+        });
+
+        if self.json {
+            serde_json::to_writer_pretty(io::stdout(), &advisories).unwrap();
+            println!();
+            io::stdout().flush().unwrap();
+            return;
+        }
+
+        for advisory in &advisories {
+            println!(
+                "{}\t{}\t{}\t{}",
+                advisory.id,
+                advisory.date.as_str(),
+                advisory.package,
+                advisory.title
+            );
+        }
+    }
+}
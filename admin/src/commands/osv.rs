@@ -4,13 +4,16 @@
 //! https://github.com/google/osv
 
 use std::{
+    io,
     path::{Path, PathBuf},
     process::exit,
 };
 
-use abscissa_core::{status_err, Command, Options, Runnable};
+use abscissa_core::{status_err, status_ok, Command, Options, Runnable};
+use rustsec::{fs, registry::Index};
 
-use crate::osv_export::OsvExporter;
+use super::single_path;
+use crate::osv_export::{OsvExporter, StaleFiles};
 
 #[derive(Command, Debug, Default, Options)]
 pub struct OsvCmd {
@@ -20,30 +23,179 @@ pub struct OsvCmd {
         help = "filesystem path to the RustSec advisory DB git repo"
     )]
     repo_path: Option<PathBuf>,
-    /// Path to the output directory
+
+    /// Output format
+    #[options(
+        no_short,
+        long = "format",
+        meta = "FORMAT",
+        help = "json (one file per advisory, default), ndjson (single stream), or zip (archive)"
+    )]
+    format: Option<String>,
+
+    /// Delete stale files from the output directory
+    #[options(
+        no_short,
+        long = "sync",
+        help = "delete JSON files in the output directory which don't match any current advisory"
+    )]
+    sync: bool,
+
+    /// List stale files instead of deleting them
+    #[options(
+        no_short,
+        long = "dry-run",
+        help = "list the files --sync would delete without deleting them"
+    )]
+    dry_run: bool,
+
+    /// List affected versions from the crates.io index
+    #[options(
+        no_short,
+        long = "versions",
+        help = "list affected versions explicitly (affected[].versions) using the crates.io index"
+    )]
+    versions: bool,
+
+    /// License of the advisory database
+    #[options(
+        no_short,
+        long = "license",
+        meta = "SPDX",
+        help = "license of the advisory DB to include in every advisory (default: CC0-1.0)"
+    )]
+    license: Option<String>,
+
+    /// URL of the advisory database's source repository
+    #[options(
+        no_short,
+        long = "source-url",
+        meta = "URL",
+        help = "source repository of the advisory DB to include in every advisory"
+    )]
+    source_url: Option<String>,
+
+    /// Attribution notice
+    #[options(
+        no_short,
+        long = "attribution",
+        meta = "NOTICE",
+        help = "attribution notice to include in every advisory (e.g. for CC-BY licensed forks)"
+    )]
+    attribution: Option<String>,
+
+    /// Path to the output directory (or file, for `ndjson` and `zip`)
     #[options(
         free,
-        help = "filesystem directory where OSV JSON files will be written"
+        help = "filesystem directory where OSV JSON files will be written (or file for ndjson/zip, \
+                where ndjson defaults to STDOUT)"
     )]
     path: Vec<PathBuf>,
 }
 
 impl Runnable for OsvCmd {
     fn run(&self) {
-        let out_path = match self.path.len() {
-            0 => Path::new("."),
-            1 => self.path[0].as_path(),
-            _ => Self::print_usage_and_exit(&[]),
-        };
+        let out_path = single_path(&self.path, "output path");
+
+        let format = self.format.as_deref().unwrap_or("json");
+
+        if format != "json" && (self.sync || self.dry_run) {
+            status_err!("--sync and --dry-run can only be used with the json format");
+            exit(1);
+        }
 
         let repo_path: Option<&Path> = self.repo_path.as_deref();
-        let exporter = OsvExporter::new(repo_path).unwrap_or_else(|e| {
+        let mut exporter = OsvExporter::new(repo_path).unwrap_or_else(|e| {
             status_err!("Failed to fetch the advisory database: {}", e);
             exit(1);
         });
-        exporter.export_all(out_path).unwrap_or_else(|e| {
-            status_err!("failed not export to '{}': {}", out_path.display(), e);
-            exit(1);
-        });
+
+        if self.versions {
+            let index = Index::fetch().unwrap_or_else(|e| {
+                status_err!("failed to fetch the crates.io index: {}", e);
+                exit(1);
+            });
+
+            exporter = exporter.with_index(index);
+        }
+
+        let mut attribution = exporter.attribution().clone();
+
+        if let Some(license) = &self.license {
+            attribution.license = license.clone();
+        }
+
+        if let Some(source_url) = &self.source_url {
+            attribution.source = source_url.clone();
+        }
+
+        if let Some(notice) = &self.attribution {
+            attribution.notice = Some(notice.clone());
+        }
+
+        exporter = exporter.with_attribution(attribution);
+
+        match format {
+            "json" => self.export_all(&exporter, out_path.unwrap_or(Path::new("."))),
+            "ndjson" => {
+                let result = match out_path {
+                    Some(path) => fs::File::create(path)
+                        .map_err(Into::into)
+                        .and_then(|file| exporter.export_ndjson(io::BufWriter::new(file))),
+                    None => exporter.export_ndjson(io::stdout()),
+                };
+
+                result.unwrap_or_else(|e| {
+                    status_err!("failed to export NDJSON: {}", e);
+                    exit(1);
+                });
+            }
+            "zip" => {
+                let path = out_path.unwrap_or_else(|| {
+                    status_err!("an output file is required for the zip format");
+                    exit(1);
+                });
+
+                fs::File::create(path)
+                    .map_err(Into::into)
+                    .and_then(|file| exporter.export_zip(file))
+                    .unwrap_or_else(|e| {
+                        status_err!("failed to export to '{}': {}", path.display(), e);
+                        exit(1);
+                    });
+            }
+            other => {
+                status_err!("invalid format: {} (expected json, ndjson, or zip)", other);
+                exit(1);
+            }
+        }
+    }
+}
+
+impl OsvCmd {
+    /// Export advisories as individual JSON files in the given directory
+    fn export_all(&self, exporter: &OsvExporter, out_path: &Path) {
+        let stale_files = if self.dry_run {
+            StaleFiles::List
+        } else if self.sync {
+            StaleFiles::Delete
+        } else {
+            StaleFiles::Keep
+        };
+
+        let stale = exporter
+            .export_all(out_path, stale_files)
+            .unwrap_or_else(|e| {
+                status_err!("failed not export to '{}': {}", out_path.display(), e);
+                exit(1);
+            });
+
+        for path in &stale {
+            if stale_files == StaleFiles::Delete {
+                status_ok!("Deleted", "stale file {}", path.display());
+            } else {
+                status_ok!("Stale", "would delete {}", path.display());
+            }
+        }
     }
 }
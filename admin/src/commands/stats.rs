@@ -0,0 +1,62 @@
+//! `rustsec-admin stats` subcommand
+
+use super::single_path;
+use crate::{prelude::*, stats::Stats, web};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// `rustsec-admin stats` subcommand
+#[derive(Command, Debug, Default, Options)]
+pub struct StatsCmd {
+    /// Output format
+    #[options(
+        no_short,
+        long = "format",
+        meta = "FORMAT",
+        help = "output format: json (default) or html"
+    )]
+    format: Option<String>,
+
+    /// Path to the advisory database
+    #[options(free, help = "filesystem path to the RustSec advisory DB git repo")]
+    path: Vec<PathBuf>,
+}
+
+impl Runnable for StatsCmd {
+    fn run(&self) {
+        let repo_path =
+            single_path(&self.path, "advisory DB path").unwrap_or_else(|| Path::new("."));
+
+        let html = match self.format.as_deref() {
+            None | Some("json") => false,
+            Some("html") => true,
+            Some(other) => {
+                status_err!("invalid format: {} (expected json or html)", other);
+                exit(1);
+            }
+        };
+
+        let stats = Stats::load(repo_path).unwrap_or_else(|e| {
+            status_err!(
+                "error loading advisory DB from {}: {}",
+                repo_path.display(),
+                e
+            );
+            exit(1);
+        });
+
+        if html {
+            println!("{}", web::render_stats(&stats));
+        } else {
+            serde_json::to_writer_pretty(io::stdout(), &stats).unwrap();
+            println!();
+        }
+
+        io::stdout().flush().unwrap();
+    }
+}
@@ -7,6 +7,8 @@ use std::path::PathBuf;
 use abscissa_core::{Command, Runnable};
 use gumdrop::Options;
 
+use super::single_path;
+
 /// `rustsec-admin web` subcommand
 #[derive(Command, Debug, Default, Options)]
 pub struct WebCmd {
@@ -19,11 +21,8 @@ pub struct WebCmd {
 
 impl Runnable for WebCmd {
     fn run(&self) {
-        let output_folder = match self.path.len() {
-            0 => PathBuf::from("_site/"),
-            1 => self.path[0].clone(),
-            _ => Self::print_usage_and_exit(&[]),
-        };
+        let output_folder = single_path(&self.path, "output directory")
+            .map_or_else(|| PathBuf::from("_site/"), PathBuf::from);
         crate::web::render_advisories(output_folder);
     }
 }
@@ -0,0 +1,106 @@
+//! Backend for the `db-info` subcommand.
+//!
+//! Summarizes an advisory DB (advisory counts, latest commit and format
+//! version) in a form deploy and ingestion jobs can check without parsing
+//! human-readable output.
+
+use crate::error::Error;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rustsec::{advisory, repository::git::Repository, Database};
+use serde::Serialize;
+use std::{collections::BTreeMap as Map, path::Path};
+
+/// Summary of an advisory DB
+#[derive(Clone, Debug, Serialize)]
+pub struct DbInfo {
+    /// Version of the advisory file format
+    pub format_version: u32,
+
+    /// Version of the `rustsec` crate the DB was loaded with
+    pub rustsec_version: &'static str,
+
+    /// Total number of advisories
+    pub advisories: usize,
+
+    /// Number of advisories in each collection
+    pub collections: Map<String, usize>,
+
+    /// Number of informational advisories
+    pub informational: usize,
+
+    /// Number of withdrawn advisories
+    pub withdrawn: usize,
+
+    /// Latest commit of the DB, if it's a git repository
+    pub last_commit: Option<CommitInfo>,
+}
+
+/// Commit of the advisory DB repository
+#[derive(Clone, Debug, Serialize)]
+pub struct CommitInfo {
+    /// Commit hash
+    pub id: String,
+
+    /// Commit time, in RFC 3339 format
+    pub timestamp: String,
+
+    /// First line of the commit message
+    pub summary: String,
+}
+
+impl DbInfo {
+    /// Load and summarize the DB at the given path
+    pub fn load(repo_path: &Path) -> Result<Self, Error> {
+        Ok(Self::new(&load_database(repo_path)?))
+    }
+
+    /// Summarize a loaded DB
+    pub fn new(db: &Database) -> Self {
+        let mut collections = Map::new();
+        let mut informational = 0;
+        let mut withdrawn = 0;
+
+        for advisory in db.iter() {
+            let metadata = &advisory.metadata;
+
+            if let Some(collection) = &metadata.collection {
+                *collections.entry(collection.to_string()).or_insert(0) += 1;
+            }
+
+            if metadata.informational.is_some() {
+                informational += 1;
+            }
+
+            if metadata.withdrawn.is_some() {
+                withdrawn += 1;
+            }
+        }
+
+        let last_commit = db.latest_commit().map(|commit| CommitInfo {
+            id: commit.commit_id.clone(),
+            timestamp: DateTime::<Utc>::from(commit.timestamp)
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            summary: commit.summary.clone(),
+        });
+
+        Self {
+            format_version: advisory::FORMAT_VERSION,
+            rustsec_version: rustsec::VERSION,
+            advisories: db.iter().len(),
+            collections,
+            informational,
+            withdrawn,
+            last_commit,
+        }
+    }
+}
+
+/// Load the advisory DB at the given path: from its latest commit if it's a
+/// git repository, or from the files on disk otherwise
+pub fn load_database(repo_path: &Path) -> Result<Database, Error> {
+    if repo_path.join(".git").exists() {
+        Ok(Database::load_from_repo(&Repository::open(repo_path)?)?)
+    } else {
+        Ok(Database::open(repo_path)?)
+    }
+}
@@ -0,0 +1,259 @@
+//! Backend for the `enrich` subcommand.
+//!
+//! Backfills metadata missing from advisories using external sources.
+//! Currently this proposes CVSS vectors for unscored advisories, looked up
+//! from the NVD entries of their CVE aliases.
+
+use crate::{
+    error::{Error, ErrorKind},
+    prelude::*,
+};
+use abscissa_core::terminal;
+use rustsec::{advisory, Advisory};
+use serde::Serialize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Endpoint of the NVD CVE API
+pub const NVD_API_URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+
+/// Environment variable containing an (optional) NVD API key
+pub const NVD_API_KEY_VAR: &str = "NVD_API_KEY";
+
+/// How long cached NVD responses are used for before they're fetched again,
+/// since NVD scores CVEs (and revises its scores) after publishing them
+pub const CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Delay between requests without an API key: NVD allows 5 requests in a
+/// rolling 30 second window
+const PUBLIC_REQUEST_INTERVAL: Duration = Duration::from_secs(6);
+
+/// Delay between requests with an API key: NVD allows 50 requests in a
+/// rolling 30 second window
+const KEYED_REQUEST_INTERVAL: Duration = Duration::from_millis(600);
+
+/// CVSS vector proposed for an advisory which lacks one
+#[derive(Clone, Debug, Serialize)]
+pub struct CvssProposal {
+    /// Advisory ID
+    pub id: advisory::Id,
+
+    /// CVE the vector was taken from
+    pub cve: advisory::Id,
+
+    /// Proposed CVSS vector
+    pub cvss: String,
+
+    /// Base score of the proposed vector
+    pub score: f64,
+
+    /// Path to the advisory file
+    pub path: PathBuf,
+}
+
+/// Client for the NVD CVE API, which caches responses on disk and limits
+/// its request rate to stay within NVD's limits
+pub struct NvdClient {
+    /// Directory responses are cached in
+    cache_dir: PathBuf,
+
+    /// API key to send with requests
+    api_key: Option<String>,
+
+    /// When the last request was sent
+    last_request: Option<Instant>,
+}
+
+impl NvdClient {
+    /// Create a client caching responses in the given directory, using the
+    /// API key in `NVD_API_KEY` if it's set
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Result<Self, Error> {
+        let cache_dir = cache_dir.into();
+        fs::create_dir_all(&cache_dir)?;
+
+        Ok(Self {
+            cache_dir,
+            api_key: env::var(NVD_API_KEY_VAR).ok().filter(|key| !key.is_empty()),
+            last_request: None,
+        })
+    }
+
+    /// Look up the CVSS v3 vector NVD lists for a CVE, preferring NVD's own
+    /// ("Primary") scoring and CVSS 3.1 over 3.0
+    pub fn cvss(&mut self, cve: &advisory::Id) -> Result<Option<cvss::v3::Base>, Error> {
+        let response = self.fetch(cve)?;
+        let metrics = &response["vulnerabilities"][0]["cve"]["metrics"];
+
+        let mut candidates = vec![];
+
+        for key in &["cvssMetricV31", "cvssMetricV30"] {
+            for metric in metrics[*key].as_array().into_iter().flatten() {
+                if let Some(vector) = metric["cvssData"]["vectorString"].as_str() {
+                    candidates.push((metric["type"].as_str() != Some("Primary"), vector));
+                }
+            }
+        }
+
+        // Stable sort, so the version preference is kept within each type
+        candidates.sort_by_key(|(secondary, _)| *secondary);
+
+        match candidates.first() {
+            Some((_, vector)) => vector.parse().map(Some).map_err(|e| {
+                format_err!(ErrorKind::Nvd, "invalid CVSS vector for {}: {}", cve, e).into()
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the NVD API response for a CVE, from the cache if it's been
+    /// cached within the last [`CACHE_TTL`]
+    fn fetch(&mut self, cve: &advisory::Id) -> Result<serde_json::Value, Error> {
+        let cache_path = self.cache_dir.join(format!("{}.json", cve));
+
+        if is_fresh(&cache_path) {
+            return parse_response(cve, &fs::read_to_string(&cache_path)?);
+        }
+
+        self.wait();
+
+        let mut request = ureq::get(NVD_API_URL).query("cveId", cve.as_str());
+
+        if let Some(api_key) = &self.api_key {
+            request = request.set("apiKey", api_key);
+        }
+
+        let body = request
+            .call()
+            .map_err(|e| format_err!(ErrorKind::Nvd, "error fetching {}: {}", cve, e))?
+            .into_string()?;
+
+        // Only cache valid responses, so a bad one is retried next time
+        let response = parse_response(cve, &body)?;
+        fs::write(&cache_path, &body)?;
+        Ok(response)
+    }
+
+    /// Sleep until another request can be sent without exceeding the rate limit
+    fn wait(&mut self) {
+        let interval = if self.api_key.is_some() {
+            KEYED_REQUEST_INTERVAL
+        } else {
+            PUBLIC_REQUEST_INTERVAL
+        };
+
+        if let Some(last_request) = self.last_request {
+            let elapsed = last_request.elapsed();
+
+            if elapsed < interval {
+                thread::sleep(interval - elapsed);
+            }
+        }
+
+        self.last_request = Some(Instant::now());
+    }
+}
+
+/// Propose CVSS vectors for the advisories in the DB at `repo_path` which
+/// don't have one, using the NVD entries of their CVE aliases.
+///
+/// Informational and withdrawn advisories are skipped, as are ones without a
+/// CVE alias or whose CVEs NVD hasn't scored. CVEs which can't be looked up
+/// (e.g. because NVD is unavailable) are logged and skipped, so one failure
+/// doesn't lose the proposals for the rest of the DB.
+pub fn propose_cvss(repo_path: &Path, client: &mut NvdClient) -> Result<Vec<CvssProposal>, Error> {
+    let db = rustsec::Database::open(repo_path)?;
+    let mut proposals = vec![];
+
+    for advisory in db.iter() {
+        let metadata = &advisory.metadata;
+
+        if metadata.cvss.is_some()
+            || metadata.informational.is_some()
+            || metadata.withdrawn.is_some()
+        {
+            continue;
+        }
+
+        for cve in metadata.aliases.iter().filter(|alias| alias.is_cve()) {
+            match client.cvss(cve) {
+                Ok(Some(cvss)) => {
+                    proposals.push(CvssProposal {
+                        id: metadata.id.clone(),
+                        cve: cve.clone(),
+                        score: cvss.score().value(),
+                        cvss: cvss.to_string(),
+                        path: advisory_path(repo_path, advisory),
+                    });
+                    break;
+                }
+                Ok(None) => (),
+                // Warn on STDERR, so the proposals on STDOUT stay valid JSON
+                Err(e) => terminal::status::Status::new()
+                    .bold()
+                    .color(terminal::Color::Yellow)
+                    .status("warning:")
+                    .print_stderr(format!(
+                        "couldn't look up {} of {}: {}",
+                        cve, metadata.id, e
+                    ))
+                    .unwrap(),
+            }
+        }
+    }
+
+    Ok(proposals)
+}
+
+/// Set the proposed CVSS vector in its advisory, rewriting the advisory in
+/// the canonical format
+pub fn apply_cvss(proposal: &CvssProposal) -> Result<(), Error> {
+    let mut advisory = Advisory::load_file(&proposal.path)?;
+
+    let cvss = proposal.cvss.parse().map_err(|e| {
+        format_err!(
+            ErrorKind::Nvd,
+            "invalid CVSS vector for {}: {}",
+            proposal.id,
+            e
+        )
+    })?;
+
+    advisory.metadata.cvss = Some(cvss);
+    fs::write(&proposal.path, advisory.to_toml_string()?)?;
+    Ok(())
+}
+
+/// Was the cached response at the given path written within the last
+/// [`CACHE_TTL`]?
+fn is_fresh(cache_path: &Path) -> bool {
+    fs::metadata(cache_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .map_or(false, |age| age < CACHE_TTL)
+}
+
+/// Parse an NVD API response
+fn parse_response(cve: &advisory::Id, body: &str) -> Result<serde_json::Value, Error> {
+    serde_json::from_str(body)
+        .map_err(|e| format_err!(ErrorKind::Nvd, "invalid NVD response for {}: {}", cve, e).into())
+}
+
+/// Get the path to the file of an advisory in the database
+fn advisory_path(repo_path: &Path, advisory: &Advisory) -> PathBuf {
+    let metadata = &advisory.metadata;
+    let collection = metadata
+        .collection
+        .as_ref()
+        .map(|collection| collection.as_str())
+        .unwrap_or_default();
+
+    repo_path
+        .join(collection)
+        .join(metadata.package.as_str())
+        .join(format!("{}.md", metadata.id))
+}
@@ -24,9 +24,17 @@ pub enum ErrorKind {
     #[error("I/O error")]
     Io,
 
+    /// Errors fetching data from the NVD API
+    #[error("NVD API error")]
+    Nvd,
+
     /// `rustsec` crate errors
     #[error("RustSec error")]
     RustSec,
+
+    /// Errors generating or parsing rustdoc output
+    #[error("rustdoc error")]
+    Rustdoc,
 }
 
 impl ErrorKind {
@@ -7,13 +7,18 @@
 
 pub mod application;
 pub mod assigner;
+pub mod checker;
 pub mod commands;
 pub mod config;
+pub mod db_info;
+pub mod enrich;
 pub mod error;
 pub mod linter;
 pub mod list_versions;
+pub mod mirror;
+pub mod new_advisories;
 pub mod osv_export;
 pub mod prelude;
+pub mod rustdoc;
+pub mod stats;
 pub mod web;
-
-use std::collections::BTreeMap as Map;
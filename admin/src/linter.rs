@@ -3,12 +3,16 @@
 use crate::{
     error::{Error, ErrorKind},
     prelude::*,
+    rustdoc::PublicApi,
 };
-use crates_index::Index;
+use rustsec::{advisory, package, registry::Index, Version};
+use serde::Serialize;
 use std::{
+    collections::BTreeMap as Map,
     fs,
     path::{Path, PathBuf},
 };
+use tempfile::TempDir;
 
 /// List of "collections" within the Advisory DB
 // TODO(tarcieri): provide some other means of iterating over the collections?
@@ -26,33 +30,88 @@ pub struct Linter {
     /// Loaded Advisory DB
     advisory_db: rustsec::Database,
 
-    /// Total number of invalid advisories encountered
-    invalid_advisories: usize,
+    /// Problems found in advisories
+    findings: Vec<Finding>,
+
+    /// Scratch directory for documenting crates, when checking
+    /// `affected.functions` paths (see [`Linter::check_function_paths`])
+    rustdoc_dir: Option<TempDir>,
+
+    /// Public APIs of the crates documented so far (`None` if documenting
+    /// the crate failed)
+    public_apis: Map<(package::Name, Version), Option<PublicApi>>,
+}
+
+/// Problem found in an advisory
+#[derive(Clone, Debug, Serialize)]
+pub struct Finding {
+    /// Path to the advisory file
+    pub file: PathBuf,
+
+    /// Line of the advisory file the problem is on (1-based), if known
+    pub line: Option<usize>,
+
+    /// Identifier of the lint rule which found the problem
+    pub rule: &'static str,
+
+    /// Severity of the problem
+    pub severity: Severity,
+
+    /// Description of the problem
+    pub message: String,
+
+    /// Suggested fix for the problem, if there's an obvious one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+/// Severity of a lint finding
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Advisory is invalid and must be fixed
+    Error,
 }
 
 impl Linter {
     /// Create a new linter for the database at the given path
     pub fn new(repo_path: impl Into<PathBuf>) -> Result<Self, Error> {
         let repo_path = repo_path.into();
-        let crates_index = crates_index::Index::new_cargo_default();
-        crates_index.retrieve_or_update()?;
+        let crates_index = Index::fetch()?;
         let advisory_db = rustsec::Database::open(&repo_path)?;
 
         Ok(Self {
             repo_path,
             crates_index,
             advisory_db,
-            invalid_advisories: 0,
+            findings: vec![],
+            rustdoc_dir: None,
+            public_apis: Map::new(),
         })
     }
 
+    /// Check that the `affected.functions` paths of advisories exist in the
+    /// public API of the latest affected version of each crate.
+    ///
+    /// This documents each of those crates using `cargo +nightly rustdoc`,
+    /// so it's slow and requires a nightly toolchain. Crates which can't be
+    /// documented are skipped with a warning.
+    ///
+    /// Documenting crates runs the build scripts and procedural macros of
+    /// their dependencies, so this should only be enabled in a sandbox (see
+    /// [`crate::rustdoc`]).
+    pub fn check_function_paths(&mut self) -> Result<(), Error> {
+        self.rustdoc_dir = Some(TempDir::new()?);
+        Ok(())
+    }
+
     /// Borrow the loaded advisory database
     pub fn advisory_db(&self) -> &rustsec::Database {
         &self.advisory_db
     }
 
-    /// Lint the loaded database
-    pub fn lint(mut self) -> Result<usize, Error> {
+    /// Lint the loaded database, returning the problems found
+    pub fn lint(mut self) -> Result<Vec<Finding>, Error> {
         for collection in COLLECTIONS {
             for crate_entry in fs::read_dir(self.repo_path.join(collection.as_str())).unwrap() {
                 let crate_dir = crate_entry.unwrap().path();
@@ -73,11 +132,10 @@ impl Linter {
             }
         }
 
-        Ok(self.invalid_advisories)
+        Ok(self.findings)
     }
 
     /// Lint an advisory at the specified path
-    // TODO(tarcieri): separate out presentation (`status_*`) from linting code?
     fn lint_advisory(
         &mut self,
         collection: rustsec::Collection,
@@ -93,55 +151,331 @@ impl Linter {
         }
 
         let advisory = rustsec::Advisory::load_file(advisory_path)?;
+        let contents = fs::read_to_string(advisory_path)?;
 
         if collection == rustsec::Collection::Crates {
-            self.crates_io_lints(&advisory)?;
+            self.crates_io_lints(advisory_path, &contents, &advisory);
+
+            if self.rustdoc_dir.is_some() {
+                self.function_path_lints(advisory_path, &contents, &advisory);
+            }
         }
 
-        let lint_result = rustsec::advisory::Linter::lint_file(&advisory_path)?;
+        self.cross_reference_lints(advisory_path, &contents, &advisory);
 
-        if lint_result.errors().is_empty() {
-            status_ok!("Linted", "ok: {}", advisory_path.display());
-        } else {
-            self.invalid_advisories += 1;
+        let lint_result = advisory::Linter::lint_file(&advisory_path)?;
 
-            status_err!(
-                "{} contained the following lint errors:",
-                advisory_path.display()
-            );
+        if let Err(e) = rustsec::testing::check_ranges(&advisory.versions) {
+            self.findings.push(Finding {
+                file: advisory_path.to_owned(),
+                line: find_line(&contents, Some("versions"), None),
+                rule: "version-ranges",
+                severity: Severity::Error,
+                message: format!("inconsistent version ranges: {}", e),
+                suggestion: None,
+            });
+        }
 
-            for error in lint_result.errors() {
-                println!("  - {}", error);
-            }
+        for error in lint_result.errors() {
+            self.findings.push(Finding {
+                file: advisory_path.to_owned(),
+                line: find_line(&contents, error.section(), error.kind().key_name()),
+                rule: error.kind().rule_id(),
+                severity: Severity::Error,
+                message: error.to_string(),
+                suggestion: None,
+            });
         }
 
         Ok(())
     }
 
     /// Perform lints that connect to https://crates.io
-    fn crates_io_lints(&mut self, advisory: &rustsec::Advisory) -> Result<(), Error> {
-        if !self.name_exists_on_crates_io(advisory.metadata.package.as_str()) {
-            self.invalid_advisories += 1;
+    fn crates_io_lints(
+        &mut self,
+        advisory_path: &Path,
+        contents: &str,
+        advisory: &rustsec::Advisory,
+    ) {
+        if !self.name_exists_on_crates_io(&advisory.metadata.package) {
+            self.findings.push(Finding {
+                file: advisory_path.to_owned(),
+                line: find_line(contents, Some("advisory"), Some("package")),
+                rule: "crates-io-name",
+                severity: Severity::Error,
+                message: format!(
+                    "crates.io package name does not match package name in advisory for {}",
+                    advisory.metadata.package.as_str()
+                ),
+                suggestion: None,
+            });
+        }
+    }
 
-            fail!(
-                ErrorKind::CratesIo,
-                "crates.io package name does not match package name in advisory for {}",
-                advisory.metadata.package.as_str()
-            );
+    /// Check that the affected function paths of an advisory exist in the
+    /// latest affected release of the crate
+    fn function_path_lints(
+        &mut self,
+        advisory_path: &Path,
+        contents: &str,
+        advisory: &rustsec::Advisory,
+    ) {
+        let functions = match &advisory.affected {
+            Some(affected) if !affected.functions.is_empty() => &affected.functions,
+            _ => return,
+        };
+
+        // Missing crates are reported by `crates_io_lints`
+        let package = &advisory.metadata.package;
+        let releases = match self.crates_index.releases(package) {
+            Ok(releases) => releases,
+            Err(_) => return,
+        };
+
+        for (path, reqs) in functions {
+            let version = releases
+                .iter()
+                .filter(|release| !release.is_yanked)
+                .map(|release| &release.version)
+                .filter(|version| reqs.iter().any(|req| req.matches(version)))
+                .max();
+
+            let version = match version {
+                Some(version) => version.clone(),
+                None => continue,
+            };
+
+            let exists = match self.public_api(package, &version) {
+                Some(api) => api.contains(path),
+                None => continue,
+            };
+
+            if !exists {
+                let path = path.to_string();
+                let line = contents
+                    .lines()
+                    .position(|line| line.contains(&path))
+                    .map(|index| index + 1)
+                    .or_else(|| find_line(contents, Some("affected"), Some("functions")));
+
+                self.findings.push(Finding {
+                    file: advisory_path.to_owned(),
+                    line,
+                    rule: "function-path",
+                    severity: Severity::Error,
+                    message: format!(
+                        "affected function `{}` not found in the public API of {} {}",
+                        path, package, version
+                    ),
+                    suggestion: None,
+                });
+            }
         }
+    }
 
-        Ok(())
+    /// Check the `aliases` and `related` IDs of an advisory: they must be
+    /// well-formed, mustn't refer to the advisory itself, and RustSec IDs
+    /// must exist and refer back to the advisory in the same field
+    fn cross_reference_lints(
+        &mut self,
+        advisory_path: &Path,
+        contents: &str,
+        advisory: &rustsec::Advisory,
+    ) {
+        let metadata = &advisory.metadata;
+        let fields = [
+            ("aliases", &metadata.aliases),
+            ("related", &metadata.related),
+        ];
+
+        let refers_back = |other: &rustsec::Advisory, field: &str| {
+            let ids = match field {
+                "aliases" => &other.metadata.aliases,
+                _ => &other.metadata.related,
+            };
+
+            ids.contains(&metadata.id)
+        };
+
+        for &(field, ids) in fields.iter() {
+            let line = find_line(contents, Some("advisory"), Some(field));
+
+            for id in ids.iter() {
+                let (rule, message, suggestion) = if id == &metadata.id {
+                    (
+                        "cross-reference-self",
+                        format!("`{}` lists the advisory's own ID", field),
+                        format!("remove `{}` from `{}`", id, field),
+                    )
+                } else if !is_well_formed(id) {
+                    (
+                        "cross-reference-format",
+                        format!("`{}` contains malformed {:?} ID `{}`", field, id.kind(), id),
+                        format!("correct or remove `{}` in `{}`", id, field),
+                    )
+                } else if !id.is_rustsec() {
+                    continue;
+                } else {
+                    match self.advisory_db.get(id) {
+                        None => (
+                            "cross-reference-dangling",
+                            format!("`{}` refers to nonexistent advisory `{}`", field, id),
+                            format!("correct or remove `{}` in `{}`", id, field),
+                        ),
+                        Some(other) if !refers_back(other, field) => (
+                            "cross-reference-reciprocal",
+                            format!("`{}` doesn't list this advisory in its `{}`", id, field),
+                            format!(
+                                "add `{}` to `{}` in {}",
+                                metadata.id,
+                                field,
+                                self.advisory_file(other).display()
+                            ),
+                        ),
+                        Some(_) => continue,
+                    }
+                };
+
+                self.findings.push(Finding {
+                    file: advisory_path.to_owned(),
+                    line,
+                    rule,
+                    severity: Severity::Error,
+                    message,
+                    suggestion: Some(suggestion),
+                });
+            }
+        }
+    }
+
+    /// Get the path to the file of an advisory in the database
+    fn advisory_file(&self, advisory: &rustsec::Advisory) -> PathBuf {
+        let metadata = &advisory.metadata;
+        let collection = metadata
+            .collection
+            .as_ref()
+            .map(|collection| collection.as_str())
+            .unwrap_or_default();
+
+        self.repo_path
+            .join(collection)
+            .join(metadata.package.as_str())
+            .join(format!("{}.md", metadata.id))
+    }
+
+    /// Get the public API of the given version of a crate, documenting it
+    /// if it hasn't been already
+    fn public_api(&mut self, name: &package::Name, version: &Version) -> Option<&PublicApi> {
+        let work_dir = self.rustdoc_dir.as_ref()?.path().to_owned();
+
+        self.public_apis
+            .entry((name.clone(), version.clone()))
+            .or_insert_with(|| match PublicApi::generate(name, version, &work_dir) {
+                Ok(api) => Some(api),
+                Err(e) => {
+                    status_warn!(
+                        "skipping function path checks for {} {}: {}",
+                        name,
+                        version,
+                        e
+                    );
+                    None
+                }
+            })
+            .as_ref()
     }
 
     /// Checks if a crate with this name is present on crates.io
-    fn name_exists_on_crates_io(&self, name: &str) -> bool {
-        if let Some(crate_) = self.crates_index.crate_(name) {
+    fn name_exists_on_crates_io(&self, name: &package::Name) -> bool {
+        match self.crates_index.releases(name) {
             // This check verifies name normalization.
             // A request for "serde-json" might return "serde_json",
             // and we want to catch use a non-canonical name and report it as an error.
-            crate_.name() == name
-        } else {
-            false
+            Ok(releases) => releases.first().map(|release| release.package == *name) == Some(true),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Is the given advisory ID well-formed for its kind? Only RustSec, CVE and
+/// GHSA IDs have a known format.
+fn is_well_formed(id: &advisory::Id) -> bool {
+    let parts = id.as_str().split('-').collect::<Vec<_>>();
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    match id.kind() {
+        // e.g. `RUSTSEC-2021-0001` or `CVE-2021-12345`
+        advisory::id::Kind::RUSTSEC | advisory::id::Kind::CVE => match parts.as_slice() {
+            [_, year, number] => {
+                year.len() == 4
+                    && is_digits(*year)
+                    && is_digits(*number)
+                    && (number.len() == 4 || (id.is_cve() && number.len() > 4))
+            }
+            _ => false,
+        },
+        // e.g. `GHSA-jfh8-c2jp-5v3q`
+        advisory::id::Kind::GHSA => {
+            parts.len() == 4
+                && parts[1..].iter().all(|part| {
+                    part.len() == 4 && part.chars().all(|c| "23456789cfghjmpqrvwx".contains(c))
+                })
+        }
+        _ => true,
+    }
+}
+
+/// Find the (1-based) line of an advisory's TOML front matter where the given
+/// key of the given section is set, falling back to the section's header, or
+/// to the key in any section if the section can't be found
+fn find_line(contents: &str, section: Option<&str>, key: Option<&str>) -> Option<usize> {
+    let mut current_section = None;
+    let mut section_line = None;
+    let mut key_line = None;
+    let mut in_front_matter = false;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+
+        // Only consider the front matter, not the Markdown which follows it
+        if line.starts_with("```") {
+            if in_front_matter {
+                break;
+            }
+
+            in_front_matter = true;
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            let name = line.trim_matches(|c| c == '[' || c == ']').trim();
+            current_section = Some(name);
+
+            // Unknown toplevel tables are reported as invalid keys
+            if section.is_none() && key == Some(name) {
+                return Some(index + 1);
+            }
+
+            if section == current_section && section_line.is_none() {
+                section_line = Some(index + 1);
+            }
+
+            continue;
+        }
+
+        let is_key = key.map_or(false, |key| {
+            line.strip_prefix(key)
+                .map_or(false, |rest| rest.trim_start().starts_with('='))
+        });
+
+        if is_key {
+            if current_section == section {
+                return Some(index + 1);
+            }
+
+            key_line = key_line.or(Some(index + 1));
         }
     }
+
+    section_line.or(key_line)
 }
@@ -2,8 +2,7 @@
 
 use std::path::PathBuf;
 
-use crates_index::Index;
-use rustsec::{Advisory, Database};
+use rustsec::{registry::Index, Advisory, Database};
 
 use crate::{error::Error, prelude::*};
 
@@ -20,8 +19,7 @@ impl AffectedVersionLister {
     /// Load the the database at the given path
     pub fn new(repo_path: impl Into<PathBuf>) -> Result<Self, Error> {
         let repo_path = repo_path.into();
-        let crates_index = crates_index::Index::new_cargo_default();
-        crates_index.retrieve_or_update()?;
+        let crates_index = Index::fetch()?;
         let advisory_db = Database::open(&repo_path)?;
         Ok(Self {
             crates_index,
@@ -42,23 +40,21 @@ impl AffectedVersionLister {
             advisory.id(),
             advisory.metadata.package
         );
-        let crate_name = advisory.metadata.package.as_str();
-        let crate_info = self.crates_index.crate_(crate_name).unwrap();
-        for version in crate_info.versions() {
-            let parsed_version = rustsec::Version::parse(version.version()).unwrap();
-            if advisory.versions.is_vulnerable(&parsed_version) {
-                println!("{} vulnerable", version.version())
+        let releases = self
+            .crates_index
+            .releases(&advisory.metadata.package)
+            .unwrap();
+        for release in releases {
+            if advisory.versions.is_vulnerable(&release.version) {
+                println!("{} vulnerable", release.version)
             } else {
-                println!("{} OK", version.version())
+                println!("{} OK", release.version)
             }
         }
     }
 
     /// List affected and unaffected crate versions for all advisories
     pub fn process_all_advisories(&self) -> Result<(), Error> {
-        let crates_index = crates_index::Index::new_cargo_default();
-        crates_index.retrieve_or_update()?;
-
         for advisory in self.advisory_db.iter() {
             // We currently only support crate versions, not advisories against Rust versions
             if advisory.metadata.collection.unwrap() != rustsec::Collection::Crates {
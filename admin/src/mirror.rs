@@ -0,0 +1,141 @@
+//! Backend for the `mirror` subcommand.
+
+use std::path::{Path, PathBuf};
+
+use rustsec::{
+    fs,
+    repository::{git::Repository, mirror::Manifest},
+    Advisory, Collection,
+};
+use serde::Serialize;
+
+use crate::{
+    error::{Error, ErrorKind},
+    osv_export::{OsvExporter, StaleFiles},
+    prelude::*,
+};
+
+/// Name of the advisory index file at the root of a mirror
+pub const INDEX_FILE: &str = "index.json";
+
+/// Name of the directory containing OSV advisories within a mirror
+pub const OSV_DIRECTORY: &str = "osv";
+
+/// Entry in a mirror's advisory index
+#[derive(Debug, Serialize)]
+pub struct IndexEntry {
+    /// Advisory ID
+    pub id: String,
+
+    /// Collection the advisory belongs to
+    pub collection: Collection,
+
+    /// Name of the affected package
+    pub package: String,
+
+    /// Path to the advisory relative to the root of the mirror
+    pub path: String,
+}
+
+/// Generates self-contained, statically hostable snapshots of the advisory DB
+pub struct MirrorGenerator {
+    /// Loaded git repository
+    repository: Repository,
+}
+
+impl MirrorGenerator {
+    /// Load the the database at the given path
+    pub fn new(repo_path: Option<&Path>) -> Result<Self, Error> {
+        let repository = match repo_path {
+            Some(path) => Repository::open(path)?,
+            None => Repository::fetch_default_repo()?,
+        };
+
+        Ok(Self { repository })
+    }
+
+    /// Write a mirror of the advisory database to the given directory,
+    /// returning its manifest.
+    ///
+    /// `stale_files` determines what happens to OSV files left over from a
+    /// previous mirror which don't correspond to any current advisory.
+    pub fn generate(&self, out_path: &Path, stale_files: StaleFiles) -> Result<Manifest, Error> {
+        let repo_path = self.repository.path();
+        let mut index = vec![];
+
+        fs::create_dir_all(out_path)?;
+
+        for collection in Collection::all() {
+            let collection_path = repo_path.join(collection.as_str());
+
+            if let Ok(collection_entry) = fs::read_dir(&collection_path) {
+                for dir_entry in collection_entry {
+                    let package_path = dir_entry?.path();
+
+                    // Files alongside package directories (e.g. a README)
+                    // aren't part of the collection
+                    if !package_path.is_dir() {
+                        continue;
+                    }
+
+                    for advisory_entry in fs::read_dir(&package_path)? {
+                        let advisory_path = advisory_entry?.path();
+
+                        if !advisory_path.is_file()
+                            || advisory_path.extension().and_then(|ext| ext.to_str()) != Some("md")
+                        {
+                            continue;
+                        }
+
+                        let advisory = Advisory::load_file(&advisory_path)?;
+
+                        // Advisory paths are always below the repo path
+                        let relative_path = advisory_path.strip_prefix(repo_path).unwrap();
+                        let output_path: PathBuf = out_path.join(relative_path);
+
+                        if let Some(parent) = output_path.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+
+                        fs::copy(&advisory_path, &output_path)?;
+
+                        index.push(IndexEntry {
+                            id: advisory.id().to_string(),
+                            collection: *collection,
+                            package: advisory.metadata.package.to_string(),
+                            path: relative_path
+                                .components()
+                                .map(|c| c.as_os_str().to_string_lossy())
+                                .collect::<Vec<_>>()
+                                .join("/"),
+                        });
+                    }
+                }
+            }
+        }
+
+        if index.is_empty() {
+            return Err(format_err!(
+                ErrorKind::Io,
+                format!("Could not find any advisories in {:?}", repo_path)
+            )
+            .into());
+        }
+
+        index.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let index_file = fs::File::create(out_path.join(INDEX_FILE))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(index_file), &index)
+            .map_err(|err| format_err!(ErrorKind::Io, "{}", err))?;
+
+        let osv_path = out_path.join(OSV_DIRECTORY);
+        fs::create_dir_all(&osv_path)?;
+        OsvExporter::new(Some(repo_path))?.export_all(&osv_path, stale_files)?;
+
+        let mut manifest = Manifest::generate(out_path)?;
+        manifest.commit = Some(self.repository.latest_commit()?.commit_id);
+        manifest.save(out_path)?;
+
+        Ok(manifest)
+    }
+}
@@ -0,0 +1,125 @@
+//! Backend for the `new-advisories` subcommand.
+//!
+//! Lists the advisories which were added to the advisory DB since a given
+//! commit or date, so newsletters and notification bots don't need to parse
+//! the git log themselves.
+
+use crate::{
+    error::{Error, ErrorKind},
+    prelude::*,
+};
+use chrono::NaiveDate;
+use rustsec::{advisory, database, repository::git::Repository, Advisory};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+/// Point in the advisory DB's history to list new advisories from
+#[derive(Clone, Debug)]
+pub enum Since {
+    /// Git revision (e.g. a commit hash or tag)
+    Revision(String),
+
+    /// Date (in UTC): advisories added on or after this date are listed
+    Date(NaiveDate),
+}
+
+impl From<&str> for Since {
+    /// Parse a date in `YYYY-MM-DD` form, treating anything else as a
+    /// git revision
+    fn from(s: &str) -> Self {
+        match NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            Ok(date) => Since::Date(date),
+            Err(_) => Since::Revision(s.to_owned()),
+        }
+    }
+}
+
+/// Advisory which was added to the advisory DB
+#[derive(Clone, Debug, Serialize)]
+pub struct NewAdvisory {
+    /// Advisory ID
+    pub id: advisory::Id,
+
+    /// Affected package
+    pub package: rustsec::package::Name,
+
+    /// Advisory title
+    pub title: String,
+
+    /// Date the advisory was reported
+    pub date: advisory::Date,
+
+    /// URL with more information about the advisory
+    pub url: Option<String>,
+
+    /// Other IDs for the same advisory (e.g. CVEs)
+    pub aliases: Vec<advisory::Id>,
+
+    /// Path to the advisory file, relative to the root of the DB
+    pub path: PathBuf,
+}
+
+impl NewAdvisory {
+    /// Summarize an advisory loaded from the given path
+    fn new(advisory: Advisory, path: PathBuf) -> Self {
+        let metadata = advisory.metadata;
+
+        let url = metadata
+            .id
+            .url()
+            .or_else(|| metadata.url.as_ref().map(ToString::to_string));
+
+        Self {
+            id: metadata.id,
+            package: metadata.package,
+            title: metadata.title,
+            date: metadata.date,
+            url,
+            aliases: metadata.aliases,
+            path,
+        }
+    }
+}
+
+/// List the advisories added to the DB at `repo_path` since the given
+/// point in its history, sorted by ID
+pub fn new_advisories(repo_path: &Path, since: &Since) -> Result<Vec<NewAdvisory>, Error> {
+    let repo = Repository::open(repo_path)?;
+
+    let from = match since {
+        Since::Revision(rev) => Some(rev.clone()),
+        Since::Date(date) => {
+            let timestamp = date.and_hms(0, 0, 0).timestamp();
+
+            if timestamp < 0 {
+                None
+            } else {
+                repo.commit_before(UNIX_EPOCH + Duration::from_secs(timestamp as u64))?
+            }
+        }
+    };
+
+    let mut advisories = vec![];
+
+    for path in repo.added_files(from.as_deref(), "HEAD")? {
+        if database::advisory_id(&path).is_none() {
+            continue;
+        }
+
+        // Read advisories as of `HEAD` rather than from the working tree,
+        // which may have uncommitted changes
+        let data = repo.read_file_at("HEAD", &path)?;
+        let advisory = String::from_utf8(data)
+            .map_err(|e| format_err!(ErrorKind::RustSec, "{}: {}", path.display(), e))?
+            .parse::<Advisory>()
+            .map_err(|e| format_err!(ErrorKind::RustSec, "{}: {}", path.display(), e))?;
+
+        advisories.push(NewAdvisory::new(advisory, path));
+    }
+
+    advisories.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(advisories)
+}
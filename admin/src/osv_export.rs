@@ -1,13 +1,18 @@
 //! Backend for the `osv` subcommand.
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeSet as Set,
+    io::{Seek, Write},
+    path::{Path, PathBuf},
+};
 
 use rustsec::{
-    advisory::Informational,
+    advisory::{Id, Informational},
     fs,
-    osv::OsvAdvisory,
+    osv::{OsvAdvisory, OsvAttribution},
+    registry::Index,
     repository::git::{GitModificationTimes, GitPath, Repository},
-    Advisory, Collection,
+    Advisory, Collection, Version,
 };
 
 use crate::{
@@ -15,6 +20,21 @@ use crate::{
     prelude::*,
 };
 
+/// How to handle stale files in the output directory, i.e. JSON files which
+/// don't correspond to any exported advisory (e.g. because the advisory was
+/// renamed, or is of a kind which is no longer exported)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StaleFiles {
+    /// Leave stale files in place
+    Keep,
+
+    /// Find stale files, but don't delete them (i.e. a dry run)
+    List,
+
+    /// Delete stale files
+    Delete,
+}
+
 /// Lists all versions for a crate and prints info on which ones are affected
 pub struct OsvExporter {
     /// Loaded git repository
@@ -22,6 +42,12 @@ pub struct OsvExporter {
 
     /// Loaded modification times for files in Git
     mod_times: GitModificationTimes,
+
+    /// crates.io index to list the affected versions of crates from, if any
+    index: Option<Index>,
+
+    /// Licensing and attribution information included in every advisory
+    attribution: OsvAttribution,
 }
 
 impl OsvExporter {
@@ -32,25 +58,151 @@ impl OsvExporter {
             None => Repository::fetch_default_repo()?,
         };
         let mod_times = GitModificationTimes::new(&repository)?;
+        let commit = repository.latest_commit()?.commit_id;
+
         Ok(Self {
             repository,
             mod_times,
+            index: None,
+            attribution: OsvAttribution::rustsec(Some(commit)),
         })
     }
 
+    /// List the affected published versions of each crate (the OSV
+    /// `affected[].versions` field) in addition to the ranges, using the
+    /// given crates.io index
+    pub fn with_index(mut self, index: Index) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Get the licensing and attribution information included in every
+    /// advisory (by default, that of the RustSec advisory database at the
+    /// exported commit)
+    pub fn attribution(&self) -> &OsvAttribution {
+        &self.attribution
+    }
+
+    /// Include the given licensing and attribution information in every
+    /// advisory instead, e.g. when exporting a fork of the database
+    pub fn with_attribution(mut self, attribution: OsvAttribution) -> Self {
+        self.attribution = attribution;
+        self
+    }
+
     /// Exports all advisories to OSV JSON format to the specified directory.
-    pub fn export_all(&self, destination_folder: &Path) -> Result<(), Error> {
+    ///
+    /// Unless `stale_files` is [`StaleFiles::Keep`], returns the paths of
+    /// stale files found in the directory, which have been deleted if it's
+    /// [`StaleFiles::Delete`].
+    pub fn export_all(
+        &self,
+        destination_folder: &Path,
+        stale_files: StaleFiles,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let mut exported = Set::new();
+
+        for (id, osv) in self.osv_advisories()? {
+            // Serialize the OSV advisory to JSON and write it to file
+            let mut output_path: PathBuf = destination_folder.join(id.as_str());
+            output_path.set_extension("json");
+            let output_file = fs::File::create(&output_path)?;
+            exported.insert(output_path);
+            let writer = std::io::BufWriter::new(output_file);
+            serde_json::to_writer_pretty(writer, &osv)
+                .map_err(|err| format_err!(ErrorKind::Io, "{}", err))?
+        }
+
+        if stale_files == StaleFiles::Keep {
+            return Ok(vec![]);
+        }
+
+        let mut stale = vec![];
+
+        for entry in fs::read_dir(destination_folder)? {
+            let path = entry?.path();
+
+            if path.is_file()
+                && path.extension().map_or(false, |ext| ext == "json")
+                && !exported.contains(&path)
+            {
+                stale.push(path);
+            }
+        }
+
+        stale.sort();
+
+        if stale_files == StaleFiles::Delete {
+            for path in &stale {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(stale)
+    }
+
+    /// Exports all advisories in OSV JSON format as newline-delimited JSON
+    /// (i.e. one advisory per line), ordered by ID
+    pub fn export_ndjson(&self, mut writer: impl Write) -> Result<(), Error> {
+        for (_, osv) in self.osv_advisories()? {
+            serde_json::to_writer(&mut writer, &osv)
+                .map_err(|err| format_err!(ErrorKind::Io, "{}", err))?;
+            writer.write_all(b"\n")?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Exports all advisories in OSV JSON format as a zip archive containing
+    /// one `<ID>.json` file per advisory
+    pub fn export_zip(&self, writer: impl Write + Seek) -> Result<(), Error> {
+        let mut zip = zip::ZipWriter::new(writer);
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        for (id, osv) in self.osv_advisories()? {
+            zip.start_file(format!("{}.json", id), options)
+                .map_err(|err| format_err!(ErrorKind::Io, "{}", err))?;
+            serde_json::to_writer_pretty(&mut zip, &osv)
+                .map_err(|err| format_err!(ErrorKind::Io, "{}", err))?;
+        }
+
+        zip.finish()
+            .map_err(|err| format_err!(ErrorKind::Io, "{}", err))?;
+
+        Ok(())
+    }
+
+    /// Convert all exportable advisories to OSV format, ordered by ID
+    fn osv_advisories(&self) -> Result<Vec<(Id, OsvAdvisory)>, Error> {
         let repo_path = self.repository.path();
         let collection_path = repo_path.join(Collection::Crates.as_str());
         let mut found_at_least_one_advisory = false;
+        let mut advisories = vec![];
 
         if let Ok(collection_entry) = fs::read_dir(&collection_path) {
             for dir_entry in collection_entry {
-                for advisory_entry in fs::read_dir(dir_entry?.path())? {
+                let package_path = dir_entry?.path();
+
+                // Files alongside package directories (e.g. a README) aren't
+                // part of the collection
+                if !package_path.is_dir() {
+                    continue;
+                }
+
+                for advisory_entry in fs::read_dir(&package_path)? {
+                    let advisory_path = advisory_entry?.path();
+
+                    if !advisory_path.is_file()
+                        || advisory_path.extension().and_then(|ext| ext.to_str()) != Some("md")
+                    {
+                        continue;
+                    }
+
                     found_at_least_one_advisory = true;
 
                     // Load the RustSec advisory
-                    let advisory_path = advisory_entry?.path();
                     let advisory = Advisory::load_file(&advisory_path)?;
                     let id = advisory.id().clone();
 
@@ -71,26 +223,51 @@ impl OsvExporter {
                     // it *should* reverse cleanly, hence the `.unwrap()`
                     let relative_path = advisory_path.strip_prefix(repo_path).unwrap();
                     let gitpath = GitPath::new(&self.repository, relative_path)?;
-                    let osv = OsvAdvisory::from_rustsec(advisory, &self.mod_times, gitpath);
-
-                    // Serialize the OSV advisory to JSON and write it to file
-                    let mut output_path: PathBuf = destination_folder.join(id.as_str());
-                    output_path.set_extension("json");
-                    let output_file = fs::File::create(output_path)?;
-                    let writer = std::io::BufWriter::new(output_file);
-                    serde_json::to_writer_pretty(writer, &osv)
-                        .map_err(|err| format_err!(ErrorKind::Io, "{}", err))?
+                    let affected_versions = self.affected_versions(&advisory)?;
+                    let mut osv = OsvAdvisory::from_rustsec(advisory, &self.mod_times, gitpath)
+                        .with_attribution(self.attribution.clone());
+
+                    if let Some(versions) = affected_versions {
+                        osv = osv.with_affected_versions(versions);
+                    }
+
+                    advisories.push((id, osv));
                 }
             }
         }
-        if found_at_least_one_advisory {
-            Ok(())
-        } else {
-            Err(format_err!(
+
+        if !found_at_least_one_advisory {
+            return Err(format_err!(
                 ErrorKind::Io,
                 format!("Could not find any advisories in {:?}", repo_path)
             )
-            .into())
+            .into());
         }
+
+        advisories.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(advisories)
+    }
+
+    /// Get the published versions of the advisory's crate which it affects,
+    /// if an index was given and the crate is in it
+    fn affected_versions(&self, advisory: &Advisory) -> Result<Option<Vec<Version>>, Error> {
+        let index = match &self.index {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+
+        let releases = match index.releases(&advisory.metadata.package) {
+            Ok(releases) => releases,
+            // e.g. crates which were removed from crates.io
+            Err(e) if e.kind() == rustsec::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let published = releases
+            .into_iter()
+            .map(|release| release.version)
+            .collect::<Vec<_>>();
+
+        Ok(Some(advisory.versions.affected_subset(&published)))
     }
 }
@@ -0,0 +1,240 @@
+//! Public APIs of crates, extracted from rustdoc's JSON output.
+//!
+//! Used to check that the `affected.functions` paths of advisories actually
+//! exist in the affected versions of crates, since typos in these paths
+//! silently break any tooling which checks whether vulnerable code is
+//! reachable.
+//!
+//! Generating rustdoc JSON requires a nightly toolchain (`cargo +nightly`).
+//!
+//! **Documenting a crate builds its dependencies, which runs their build
+//! scripts and procedural macros: arbitrary code from crates.io.** `cargo`
+//! is run with a minimal environment so secrets in environment variables
+//! (e.g. CI tokens) aren't exposed to it, but it's otherwise unsandboxed and
+//! can access the filesystem and network, so only check function paths in
+//! a disposable sandbox (e.g. a container without credentials).
+
+use crate::{
+    error::{Error, ErrorKind},
+    prelude::*,
+};
+use rustsec::{advisory::affected::FunctionPath, package, Version};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap as Map, BTreeSet as Set},
+    env, fs,
+    path::Path,
+    process::Command,
+};
+
+/// Environment variables passed through to `cargo`, which it (and `rustup`)
+/// need to run: everything else is cleared
+const CARGO_ENV_VARS: &[&str] = &[
+    "PATH",
+    "HOME",
+    "CARGO_HOME",
+    "RUSTUP_HOME",
+    "RUSTUP_TOOLCHAIN",
+];
+
+/// Kinds of items which may have associated functions
+const TYPE_KINDS: &[&str] = &["struct", "enum", "union", "trait"];
+
+/// Public API of a particular version of a crate
+#[derive(Clone, Debug, Default)]
+pub struct PublicApi {
+    /// Paths of all public items (e.g. `base::belongs::All`)
+    paths: Set<String>,
+
+    /// Names of the associated items (e.g. methods) of each public type and
+    /// trait, by the path of the type or trait
+    members: Map<String, Set<String>>,
+}
+
+impl PublicApi {
+    /// Generate the public API of the given version of a crate by
+    /// documenting it with rustdoc in a scratch project in `work_dir`.
+    ///
+    /// This runs the build scripts and procedural macros of the crate's
+    /// dependencies (see the module documentation).
+    pub fn generate(
+        name: &package::Name,
+        version: &Version,
+        work_dir: &Path,
+    ) -> Result<Self, Error> {
+        let project_dir = work_dir.join(format!("{}-{}", name, version));
+        fs::create_dir_all(project_dir.join("src"))?;
+        fs::write(project_dir.join("src").join("lib.rs"), "")?;
+        fs::write(
+            project_dir.join("Cargo.toml"),
+            format!(
+                "[package]\n\
+                 name = \"rustdoc-scratch\"\n\
+                 version = \"0.0.0\"\n\
+                 edition = \"2018\"\n\
+                 \n\
+                 [dependencies]\n\
+                 {} = \"={}\"\n\
+                 \n\
+                 [workspace]\n",
+                name, version
+            ),
+        )?;
+
+        let mut command = Command::new("cargo");
+        command.env_clear();
+
+        for var in CARGO_ENV_VARS {
+            if let Some(value) = env::var_os(var) {
+                command.env(var, value);
+            }
+        }
+
+        let output = command
+            .args(&["+nightly", "rustdoc", "--quiet", "--lib", "-p"])
+            .arg(format!("{}@{}", name, version))
+            .args(&["--", "-Z", "unstable-options", "--output-format", "json"])
+            .current_dir(&project_dir)
+            .output()?;
+
+        if !output.status.success() {
+            fail!(
+                ErrorKind::Rustdoc,
+                "couldn't document {} {}: {}",
+                name,
+                version,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let crate_name = name.as_str().replace('-', "_");
+        let json_path = project_dir
+            .join("target")
+            .join("doc")
+            .join(format!("{}.json", crate_name));
+
+        Self::from_json(&fs::read_to_string(&json_path)?)
+    }
+
+    /// Parse the public API from rustdoc's JSON output
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let doc: Value = serde_json::from_str(json)
+            .map_err(|e| format_err!(ErrorKind::Rustdoc, "invalid rustdoc JSON: {}", e))?;
+
+        let mut api = Self::default();
+
+        // Items of the documented crate itself have a crate ID of 0
+        let is_local = |item: &Value| item.get("crate_id").and_then(Value::as_u64) == Some(0);
+
+        let paths = doc
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or_else(|| format_err!(ErrorKind::Rustdoc, "rustdoc JSON is missing `paths`"))?;
+
+        let index = doc.get("index").and_then(Value::as_object);
+
+        for (id, summary) in paths.iter().filter(|(_, summary)| is_local(summary)) {
+            let path = match summary.get("path").and_then(Value::as_array) {
+                Some(segments) => segments
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join("::"),
+                None => continue,
+            };
+
+            match (summary.get("kind").and_then(Value::as_str), index) {
+                (Some(kind), Some(index)) if TYPE_KINDS.contains(&kind) => {
+                    api.members
+                        .insert(path.clone(), member_names(index, id, kind));
+                }
+                _ => (),
+            }
+
+            api.paths.insert(path);
+        }
+
+        Ok(api)
+    }
+
+    /// Does the given function path exist in this API?
+    ///
+    /// Paths to associated functions (e.g. methods) are accepted if their
+    /// parent is a public type or trait with an associated item of the same
+    /// name (in any of the type's impls), since rustdoc doesn't record paths
+    /// for associated items.
+    pub fn contains(&self, function: &FunctionPath) -> bool {
+        let segments = function
+            .segments()
+            .iter()
+            .map(|segment| segment.as_str().replace('-', "_"))
+            .collect::<Vec<_>>();
+
+        if self.paths.contains(&segments.join("::")) {
+            return true;
+        }
+
+        match segments.split_last() {
+            Some((name, parent)) => self
+                .members
+                .get(&parent.join("::"))
+                .map(|members| members.contains(name))
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+}
+
+/// Get the names of the associated items of the type or trait of the given
+/// kind with the given ID: the items in the type's impls (including trait
+/// impls), or the trait's own items
+fn member_names(index: &serde_json::Map<String, Value>, id: &str, kind: &str) -> Set<String> {
+    let ids = |item: Option<&Value>, kind: &str, key: &str| -> Vec<String> {
+        item.and_then(|item| inner(item, kind))
+            .and_then(|inner| inner.get(key))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(id_string)
+            .collect()
+    };
+
+    let item_ids = if kind == "trait" {
+        ids(index.get(id), kind, "items")
+    } else {
+        ids(index.get(id), kind, "impls")
+            .iter()
+            .flat_map(|impl_id| ids(index.get(impl_id), "impl", "items"))
+            .collect()
+    };
+
+    item_ids
+        .iter()
+        .filter_map(|item_id| index.get(item_id))
+        .filter_map(|item| item.get("name").and_then(Value::as_str))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Get the kind-specific details of an item of the given kind, e.g. the
+/// `impls` of a struct. These are nested under the kind in newer versions of
+/// the rustdoc JSON format, and directly under `inner` in older ones.
+fn inner<'a>(item: &'a Value, kind: &str) -> Option<&'a Value> {
+    let inner = item.get("inner")?;
+
+    match inner.get(kind) {
+        Some(details) => Some(details),
+        None if item.get("kind").and_then(Value::as_str) == Some(kind) => Some(inner),
+        None => None,
+    }
+}
+
+/// Convert an item ID (a string or, in newer versions of the rustdoc JSON
+/// format, an integer) to the string keys of the `index` and `paths` maps
+fn id_string(id: &Value) -> Option<String> {
+    match id {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
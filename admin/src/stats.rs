@@ -0,0 +1,161 @@
+//! Backend for the `stats` subcommand.
+//!
+//! Computes aggregate statistics about the advisories in the DB (per year,
+//! category and collection, and how long vulnerabilities took to patch),
+//! for the rustsec.org statistics page and for researchers, who would
+//! otherwise have to scrape the site for them.
+
+use crate::{db_info::load_database, error::Error};
+use chrono::NaiveDate;
+use rustsec::{advisory, Database};
+use serde::Serialize;
+use std::{collections::BTreeMap as Map, convert::TryInto, path::Path};
+
+/// Aggregate statistics about the advisories in a DB.
+///
+/// Withdrawn advisories aren't counted.
+#[derive(Clone, Debug, Serialize)]
+pub struct Stats {
+    /// Total number of advisories
+    pub advisories: usize,
+
+    /// Number of advisories which are informational (e.g. unmaintained
+    /// crates) rather than vulnerabilities
+    pub informational: usize,
+
+    /// Number of advisories issued in each year
+    pub per_year: Map<u32, usize>,
+
+    /// Number of advisories in each category (advisories can have several)
+    pub per_category: Map<String, usize>,
+
+    /// Number of advisories in each collection
+    pub per_collection: Map<String, usize>,
+
+    /// Time taken to patch vulnerabilities, for those where it's known
+    pub time_to_patch: Option<TimeToPatch>,
+}
+
+/// Time taken to patch vulnerabilities, i.e. from when they were reported to
+/// the crate's maintainers (or discovered, if that's all that's known) until
+/// the first patched version was released
+#[derive(Clone, Debug, Serialize)]
+pub struct TimeToPatch {
+    /// Number of advisories the time to patch is known for
+    pub advisories: usize,
+
+    /// Average number of days taken to patch
+    pub average_days: f64,
+
+    /// Median number of days taken to patch
+    pub median_days: f64,
+}
+
+impl Stats {
+    /// Load the DB at the given path and compute its statistics
+    pub fn load(repo_path: &Path) -> Result<Self, Error> {
+        Ok(Self::new(&load_database(repo_path)?))
+    }
+
+    /// Compute the statistics of a loaded DB
+    pub fn new(db: &Database) -> Self {
+        let mut stats = Self {
+            advisories: 0,
+            informational: 0,
+            per_year: Map::new(),
+            per_category: Map::new(),
+            per_collection: Map::new(),
+            time_to_patch: None,
+        };
+
+        let mut days_to_patch = vec![];
+
+        for advisory in db.iter() {
+            let metadata = &advisory.metadata;
+
+            if metadata.withdrawn.is_some() {
+                continue;
+            }
+
+            stats.advisories += 1;
+            *stats.per_year.entry(metadata.date.year()).or_insert(0) += 1;
+
+            if metadata.informational.is_some() {
+                stats.informational += 1;
+            }
+
+            for category in &metadata.categories {
+                *stats.per_category.entry(category.to_string()).or_insert(0) += 1;
+            }
+
+            if let Some(collection) = &metadata.collection {
+                *stats
+                    .per_collection
+                    .entry(collection.to_string())
+                    .or_insert(0) += 1;
+            }
+
+            if let Some(days) = days_to_patch_for(metadata) {
+                days_to_patch.push(days);
+            }
+        }
+
+        stats.time_to_patch = TimeToPatch::new(days_to_patch);
+        stats
+    }
+}
+
+impl TimeToPatch {
+    /// Summarize the given numbers of days taken to patch (if there are any)
+    fn new(mut days: Vec<i64>) -> Option<Self> {
+        if days.is_empty() {
+            return None;
+        }
+
+        days.sort_unstable();
+
+        let count = days.len();
+        let average_days = days.iter().sum::<i64>() as f64 / count as f64;
+        let median_days = if count % 2 == 0 {
+            (days[count / 2 - 1] + days[count / 2]) as f64 / 2.0
+        } else {
+            days[count / 2] as f64
+        };
+
+        Some(Self {
+            advisories: count,
+            average_days,
+            median_days,
+        })
+    }
+}
+
+/// Get the number of days taken to patch the vulnerability described by the
+/// given advisory, if it's known. Dates which are inconsistent (i.e. a patch
+/// released before the report) are ignored.
+fn days_to_patch_for(metadata: &advisory::Metadata) -> Option<i64> {
+    if metadata.informational.is_some() {
+        return None;
+    }
+
+    let reported = metadata
+        .reported
+        .as_ref()
+        .or(metadata.discovered.as_ref())?;
+    let patched = metadata.patched.as_ref()?;
+
+    let days = to_naive_date(patched)?
+        .signed_duration_since(to_naive_date(reported)?)
+        .num_days();
+
+    if days < 0 {
+        None
+    } else {
+        Some(days)
+    }
+}
+
+/// Convert an advisory date into a `NaiveDate`
+fn to_naive_date(date: &advisory::Date) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(date.year().try_into().ok()?, date.month(), date.day())
+}
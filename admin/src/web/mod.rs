@@ -1,6 +1,6 @@
 //! Code relating to the generation of the https://rustsec.org web site.
 //!
-use crate::prelude::*;
+use crate::{prelude::*, stats::Stats};
 use std::str::FromStr;
 use std::{
     fs::{self, File},
@@ -49,6 +49,12 @@ struct AdvisoryTemplate<'a> {
     rendered_title: String,
 }
 
+#[derive(Template)]
+#[template(path = "stats.html")]
+struct StatsTemplate<'a> {
+    stats: &'a Stats,
+}
+
 // Used for feed and included by `AdvisoryTemplate`
 #[derive(Template)]
 #[template(path = "advisory-content.html")]
@@ -60,8 +66,9 @@ struct AdvisoryContentTemplate<'a> {
 
 /// Render all advisories using the Markdown template
 pub fn render_advisories(output_folder: PathBuf) {
-    let mut advisories: Vec<rustsec::Advisory> =
-        rustsec::Database::fetch().unwrap().into_iter().collect();
+    let db = rustsec::Database::fetch().unwrap();
+    let stats = Stats::new(&db);
+    let mut advisories: Vec<rustsec::Advisory> = db.into_iter().collect();
 
     // Render individual advisory pages (/advisories/${id}.html)
     let advisories_folder = output_folder.join("advisories");
@@ -132,6 +139,17 @@ pub fn render_advisories(output_folder: PathBuf) {
         advisories.len()
     );
 
+    // Render the statistics page (/stats) and its data (/stats.json).
+    let stats_folder = output_folder.join("stats");
+    fs::create_dir_all(&stats_folder).unwrap();
+    fs::write(stats_folder.join("index.html"), render_stats(&stats)).unwrap();
+    fs::write(
+        output_folder.join("stats.json"),
+        serde_json::to_string_pretty(&stats).unwrap(),
+    )
+    .unwrap();
+    status_ok!("Rendered", "{}", stats_folder.display());
+
     // Render the per-package pages (/packages/${package}.html).
     let mut advisories_per_package = Vec::<AdvisoriesPerPackage>::new();
 
@@ -198,6 +216,11 @@ pub fn render_advisories(output_folder: PathBuf) {
     status_ok!("Completed", "{} advisories rendered in atom feed", len);
 }
 
+/// Render the statistics page for the given advisory DB statistics
+pub fn render_stats(stats: &Stats) -> String {
+    StatsTemplate { stats }.render().unwrap()
+}
+
 /// Title with the id, the package name and the advisory type
 fn title_type(advisory: &rustsec::Advisory) -> String {
     use rustsec::advisory::informational::Informational;
@@ -4,6 +4,7 @@
 use abscissa_core::testing::prelude::*;
 use once_cell::sync::Lazy;
 use rustsec::repository::git;
+use std::process::Command;
 
 pub static RUNNER: Lazy<CmdRunner> = Lazy::new(CmdRunner::default);
 
@@ -22,3 +23,817 @@ fn lint_advisory_db() {
         .status()
         .expect_success();
 }
+
+/// Passing several paths to a subcommand which takes one is an error
+#[test]
+fn too_many_paths() {
+    let mut runner = RUNNER.clone();
+
+    runner
+        .args(&["new-advisories", "--since", "2021-01-01", "db", "staging"])
+        .capture_stdout()
+        .status()
+        .expect_code(1);
+}
+
+/// Run `rustsec-admin db-info --format json` against the advisory DB repo
+#[test]
+fn db_info_json() {
+    git::Repository::fetch_default_repo().unwrap();
+
+    let mut runner = RUNNER.clone();
+
+    runner
+        .args(&["db-info", "--format", "json"])
+        .arg(&git::Repository::default_path())
+        .capture_stdout()
+        .status()
+        .expect_success();
+}
+
+/// Run `rustsec-admin stats --format json` against the advisory DB repo
+#[test]
+fn stats_json() {
+    git::Repository::fetch_default_repo().unwrap();
+
+    let mut runner = RUNNER.clone();
+
+    runner
+        .args(&["stats", "--format", "json"])
+        .arg(&git::Repository::default_path())
+        .capture_stdout()
+        .status()
+        .expect_success();
+}
+
+/// Minimal advisory for the `base` crate
+const ADVISORY: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2001-2101"
+package = "base"
+date = "2001-02-03"
+
+[versions]
+patched = [">= 1.2.3"]
+```
+
+# All your base are belong to us
+
+You have no chance to survive. Make your time.
+"#;
+
+/// Create an advisory DB containing the given files
+fn advisory_db(files: &[(&str, &str)]) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+
+    for (path, contents) in files {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    dir
+}
+
+/// Commit all files in the given advisory DB at the given date, initializing
+/// a git repository if needed
+fn git_commit(db: &tempfile::TempDir, date: &str) {
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(db.path())
+            .env("GIT_AUTHOR_NAME", "RustSec")
+            .env("GIT_AUTHOR_EMAIL", "rustsec@example.com")
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_NAME", "RustSec")
+            .env("GIT_COMMITTER_EMAIL", "rustsec@example.com")
+            .env("GIT_COMMITTER_DATE", date)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    };
+
+    if !db.path().join(".git").exists() {
+        git(&["init", "--quiet"]);
+    }
+
+    git(&["add", "--all"]);
+    git(&["commit", "--quiet", "--message", "Update advisories"]);
+}
+
+/// Run `rustsec-admin check-db` against the given advisory DB, expecting it
+/// to exit with the given code
+fn check_db(db: &tempfile::TempDir, expected_code: i32) {
+    let mut runner = RUNNER.clone();
+    runner
+        .arg("check-db")
+        .arg(db.path())
+        .capture_stdout()
+        .capture_stderr()
+        .status()
+        .expect_code(expected_code);
+}
+
+/// `rustsec-admin check-db` accepts a consistent advisory DB
+#[test]
+fn check_db_consistent() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    check_db(&db, 0);
+}
+
+/// `rustsec-admin check-db` rejects advisories which aren't in the directory
+/// of their package
+#[test]
+fn check_db_wrong_package_dir() {
+    let db = advisory_db(&[("crates/other/RUSTSEC-2001-2101.md", ADVISORY)]);
+    check_db(&db, 1);
+}
+
+/// `rustsec-admin check-db` rejects files which aren't advisories
+#[test]
+fn check_db_orphan_file() {
+    let db = advisory_db(&[
+        ("crates/base/RUSTSEC-2001-2101.md", ADVISORY),
+        ("crates/base/notes.txt", "TODO"),
+    ]);
+    check_db(&db, 1);
+}
+
+/// `rustsec-admin check-db` checks advisory dates against when the advisory
+/// was first committed, even if it was modified after its date
+#[test]
+fn check_db_date_after_first_commit() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    git_commit(&db, "2001-01-01T00:00:00Z");
+    check_db(&db, 1);
+
+    std::fs::write(
+        db.path().join("crates/base/RUSTSEC-2001-2101.md"),
+        ADVISORY.replace("Make your time.", "Make your time!"),
+    )
+    .unwrap();
+    git_commit(&db, "2002-01-01T00:00:00Z");
+    check_db(&db, 1);
+}
+
+/// `rustsec-admin check-db` accepts advisories committed after their date
+#[test]
+fn check_db_date_before_first_commit() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+    check_db(&db, 0);
+}
+
+/// `rustsec-admin new-advisories` lists the advisories committed since a
+/// date, ignoring uncommitted changes
+#[test]
+fn new_advisories_since_date() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let newer_path = db.path().join("crates/base/RUSTSEC-2001-2102.md");
+    std::fs::write(
+        &newer_path,
+        ADVISORY.replace("RUSTSEC-2001-2101", "RUSTSEC-2001-2102"),
+    )
+    .unwrap();
+    git_commit(&db, "2001-04-01T00:00:00Z");
+    std::fs::remove_file(&newer_path).unwrap();
+
+    let mut runner = RUNNER.clone();
+    let mut process = runner
+        .args(&["new-advisories", "--json", "--since", "2001-03-15"])
+        .arg(db.path())
+        .capture_stdout()
+        .capture_stderr()
+        .run();
+
+    let json: serde_json::Value = serde_json::from_reader(&mut **process.stdout()).unwrap();
+    process.wait().unwrap().expect_success();
+
+    let ids = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|advisory| advisory["id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["RUSTSEC-2001-2102"]);
+}
+
+/// `rustsec-admin osv` only deletes stale JSON files from the output
+/// directory with `--sync`, and only lists them with `--dry-run`
+#[test]
+fn osv_sync() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let out = tempfile::tempdir().unwrap();
+    let stale_path = out.path().join("RUSTSEC-2000-0000.json");
+    std::fs::write(&stale_path, "{}").unwrap();
+
+    let osv = |args: &[&str]| {
+        let mut runner = RUNNER.clone();
+        runner
+            .args(&["osv", "--db"])
+            .arg(db.path())
+            .args(args)
+            .arg(out.path())
+            .capture_stdout()
+            .status()
+            .expect_success();
+    };
+
+    osv(&[]);
+    assert!(out.path().join("RUSTSEC-2001-2101.json").exists());
+    assert!(stale_path.exists());
+
+    osv(&["--sync", "--dry-run"]);
+    assert!(stale_path.exists());
+
+    osv(&["--sync"]);
+    assert!(!stale_path.exists());
+    assert!(out.path().join("RUSTSEC-2001-2101.json").exists());
+}
+
+/// `rustsec-admin mirror` only deletes stale OSV files with `--sync`
+#[test]
+fn mirror_sync() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let out = tempfile::tempdir().unwrap();
+    let stale_path = out.path().join("osv/RUSTSEC-2000-0000.json");
+    std::fs::create_dir_all(stale_path.parent().unwrap()).unwrap();
+    std::fs::write(&stale_path, "{}").unwrap();
+
+    let mirror = |args: &[&str]| {
+        let mut runner = RUNNER.clone();
+        runner
+            .args(&["mirror", "--db"])
+            .arg(db.path())
+            .arg("--out")
+            .arg(out.path())
+            .args(args)
+            .capture_stdout()
+            .status()
+            .expect_success();
+    };
+
+    mirror(&[]);
+    assert!(out.path().join("osv/RUSTSEC-2001-2101.json").exists());
+    assert!(stale_path.exists());
+
+    mirror(&["--sync"]);
+    assert!(!stale_path.exists());
+}
+
+/// `rustsec-admin mirror` skips files which aren't advisories
+#[test]
+fn mirror_skips_other_files() {
+    let db = advisory_db(&[
+        ("crates/base/RUSTSEC-2001-2101.md", ADVISORY),
+        ("crates/base/.gitkeep", ""),
+        ("crates/README.md", "# Crates"),
+    ]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let out = tempfile::tempdir().unwrap();
+
+    let mut runner = RUNNER.clone();
+    runner
+        .args(&["mirror", "--db"])
+        .arg(db.path())
+        .arg("--out")
+        .arg(out.path())
+        .capture_stdout()
+        .status()
+        .expect_success();
+
+    assert!(out.path().join("crates/base/RUSTSEC-2001-2101.md").exists());
+    assert!(!out.path().join("crates/base/.gitkeep").exists());
+    assert!(!out.path().join("crates/README.md").exists());
+}
+
+/// `rustsec-admin lint --format json` reports the line of the offending key
+/// in the right section of the front matter, ignoring the Markdown after it
+#[test]
+fn lint_json_lines() {
+    let advisory = r#"```toml
+[versions]
+patched = [">= 1.2.3"]
+foo = "versions"
+
+[advisory]
+id = "RUSTSEC-2001-2101"
+package = "std"
+date = "2001-02-03"
+foo = "advisory"
+url = "http://example.com"
+
+[bogus]
+x = 1
+```
+
+# Example
+
+url = "http://example.com"
+"#;
+
+    let db = advisory_db(&[("rust/std/RUSTSEC-2001-2101.md", advisory)]);
+    std::fs::create_dir(db.path().join("crates")).unwrap();
+
+    let mut runner = RUNNER.clone();
+    let mut process = runner
+        .args(&["lint", "--format", "json"])
+        .arg(db.path())
+        .capture_stdout()
+        .capture_stderr()
+        .run();
+
+    let json: serde_json::Value = serde_json::from_reader(&mut **process.stdout()).unwrap();
+    process.wait().unwrap().expect_code(1);
+
+    let mut findings = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|finding| {
+            (
+                finding["line"].as_u64().unwrap(),
+                finding["message"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    findings.sort();
+
+    assert_eq!(
+        findings,
+        [
+            (4, "invalid key `foo` in [versions]"),
+            (10, "invalid key `foo` in [advisory]"),
+            (
+                11,
+                "invalid value `\"http://example.com\"` for key `url` in [advisory]: \
+                 URL must start with https://"
+            ),
+            (13, "invalid key `bogus` in toplevel"),
+        ]
+    );
+}
+
+/// `rustsec-admin lint` checks that the `aliases` and `related` IDs of
+/// advisories are well-formed, exist, and are reciprocated, suggesting fixes
+#[test]
+fn lint_cross_references() {
+    let advisory = |id: &str, aliases: &str, related: &str| {
+        format!(
+            "```toml\n[advisory]\nid = \"{}\"\npackage = \"std\"\ndate = \"2001-02-03\"\n\
+             aliases = {}\nrelated = {}\n```\n\n# Example\n\nExample.\n",
+            id, aliases, related
+        )
+    };
+
+    let db = advisory_db(&[
+        (
+            "rust/std/RUSTSEC-2001-2101.md",
+            &advisory(
+                "RUSTSEC-2001-2101",
+                r#"["RUSTSEC-2001-2101", "CVE-2001-21", "RUSTSEC-2001-9999"]"#,
+                r#"["RUSTSEC-2001-2102", "RUSTSEC-2001-2103"]"#,
+            ),
+        ),
+        (
+            "rust/std/RUSTSEC-2001-2102.md",
+            &advisory("RUSTSEC-2001-2102", "[]", "[]"),
+        ),
+        (
+            "rust/std/RUSTSEC-2001-2103.md",
+            &advisory("RUSTSEC-2001-2103", "[]", r#"["RUSTSEC-2001-2101"]"#),
+        ),
+    ]);
+    std::fs::create_dir(db.path().join("crates")).unwrap();
+
+    let mut runner = RUNNER.clone();
+    let mut process = runner
+        .args(&["lint", "--format", "json"])
+        .arg(db.path())
+        .capture_stdout()
+        .capture_stderr()
+        .run();
+
+    let json: serde_json::Value = serde_json::from_reader(&mut **process.stdout()).unwrap();
+    process.wait().unwrap().expect_code(1);
+
+    let mut findings = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|finding| {
+            finding["rule"]
+                .as_str()
+                .unwrap()
+                .starts_with("cross-reference")
+        })
+        .map(|finding| {
+            (
+                finding["rule"].as_str().unwrap(),
+                finding["suggestion"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    findings.sort();
+
+    let reciprocal = db.path().join("rust/std/RUSTSEC-2001-2102.md");
+
+    assert_eq!(
+        findings,
+        [
+            (
+                "cross-reference-dangling",
+                "correct or remove `RUSTSEC-2001-9999` in `aliases`"
+            ),
+            (
+                "cross-reference-format",
+                "correct or remove `CVE-2001-21` in `aliases`"
+            ),
+            (
+                "cross-reference-reciprocal",
+                &*format!(
+                    "add `RUSTSEC-2001-2101` to `related` in {}",
+                    reciprocal.display()
+                )
+            ),
+            (
+                "cross-reference-self",
+                "remove `RUSTSEC-2001-2101` from `aliases`"
+            ),
+        ]
+    );
+}
+
+/// `rustsec-admin enrich --cvss --write` sets the CVSS vectors NVD lists for
+/// the CVE aliases of unscored advisories, skipping CVEs which can't be
+/// looked up rather than giving up
+#[test]
+fn enrich_cvss() {
+    let with_aliases = |id: &str, aliases: &str| {
+        ADVISORY.replace("RUSTSEC-2001-2101", id).replace(
+            "date = \"2001-02-03\"",
+            &format!("date = \"2001-02-03\"\naliases = {}", aliases),
+        )
+    };
+
+    let db = advisory_db(&[
+        (
+            "crates/base/RUSTSEC-2001-2101.md",
+            &with_aliases("RUSTSEC-2001-2101", r#"["CVE-2001-0001"]"#),
+        ),
+        (
+            "crates/base/RUSTSEC-2001-2102.md",
+            &with_aliases("RUSTSEC-2001-2102", r#"["CVE-2001-0002", "CVE-2001-0003"]"#),
+        ),
+    ]);
+
+    // Responses are served from the cache, so NVD isn't queried
+    let response = |vector: &str| {
+        format!(
+            r#"{{"vulnerabilities": [{{"cve": {{"metrics": {{"cvssMetricV31": [{{"type": "Primary", "cvssData": {{"vectorString": "{}"}}}}]}}}}}}]}}"#,
+            vector
+        )
+    };
+
+    const CRITICAL: &str = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H";
+    const LOW: &str = "CVSS:3.1/AV:L/AC:H/PR:H/UI:R/S:U/C:L/I:N/A:N";
+
+    let cache = tempfile::tempdir().unwrap();
+    std::fs::write(cache.path().join("CVE-2001-0001.json"), response(CRITICAL)).unwrap();
+    std::fs::write(cache.path().join("CVE-2001-0002.json"), "not JSON").unwrap();
+    std::fs::write(cache.path().join("CVE-2001-0003.json"), response(LOW)).unwrap();
+
+    let mut runner = RUNNER.clone();
+    let mut process = runner
+        .args(&["enrich", "--cvss", "--write", "--json", "--cache"])
+        .arg(cache.path())
+        .arg(db.path())
+        .capture_stdout()
+        .capture_stderr()
+        .run();
+
+    let json: serde_json::Value = serde_json::from_reader(&mut **process.stdout()).unwrap();
+    process.wait().unwrap().expect_success();
+
+    let mut proposals = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|proposal| {
+            (
+                proposal["id"].as_str().unwrap(),
+                proposal["cve"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    proposals.sort();
+
+    assert_eq!(
+        proposals,
+        [
+            ("RUSTSEC-2001-2101", "CVE-2001-0001"),
+            ("RUSTSEC-2001-2102", "CVE-2001-0003"),
+        ]
+    );
+
+    for &(id, vector) in &[("RUSTSEC-2001-2101", CRITICAL), ("RUSTSEC-2001-2102", LOW)] {
+        let path = db.path().join(format!("crates/base/{}.md", id));
+        let advisory = rustsec::Advisory::load_file(&path).unwrap();
+
+        assert_eq!(advisory.metadata.cvss.as_ref().unwrap().to_string(), vector);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            advisory.to_toml_string().unwrap()
+        );
+    }
+}
+
+/// `rustsec-admin osv --format ndjson` writes one advisory per line
+#[test]
+fn osv_ndjson() {
+    let db = advisory_db(&[
+        ("crates/base/RUSTSEC-2001-2101.md", ADVISORY),
+        (
+            "crates/base/RUSTSEC-2001-2102.md",
+            &ADVISORY.replace("RUSTSEC-2001-2101", "RUSTSEC-2001-2102"),
+        ),
+    ]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let mut runner = RUNNER.clone();
+    let mut process = runner
+        .args(&["osv", "--format", "ndjson", "--db"])
+        .arg(db.path())
+        .capture_stdout()
+        .run();
+
+    let mut output = String::new();
+    std::io::Read::read_to_string(&mut **process.stdout(), &mut output).unwrap();
+
+    let ids = output
+        .lines()
+        .map(|line| {
+            let json: serde_json::Value = serde_json::from_str(line).unwrap();
+            json["id"].as_str().unwrap().to_owned()
+        })
+        .collect::<Vec<_>>();
+
+    process.wait().unwrap().expect_success();
+    assert_eq!(ids, ["RUSTSEC-2001-2101", "RUSTSEC-2001-2102"]);
+}
+
+/// `rustsec-admin osv --format zip` writes an archive of `<ID>.json` files
+#[test]
+fn osv_zip() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let out = tempfile::tempdir().unwrap();
+    let zip_path = out.path().join("osv.zip");
+
+    let mut runner = RUNNER.clone();
+    runner
+        .args(&["osv", "--format", "zip", "--db"])
+        .arg(db.path())
+        .arg(&zip_path)
+        .capture_stdout()
+        .status()
+        .expect_success();
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+    assert_eq!(archive.len(), 1);
+
+    let json: serde_json::Value =
+        serde_json::from_reader(archive.by_name("RUSTSEC-2001-2101.json").unwrap()).unwrap();
+    assert_eq!(json["id"], "RUSTSEC-2001-2101");
+}
+
+/// OSV exports list the published versions each advisory affects when given
+/// a registry index, omitting them for crates which aren't in the index
+#[test]
+fn osv_affected_versions() {
+    use rustsec::{
+        package,
+        registry::{Backend, Index, IndexPackage},
+    };
+    use rustsec_admin::osv_export::OsvExporter;
+
+    /// Index containing a few releases of `base` (and nothing else)
+    struct FixtureBackend;
+
+    impl Backend for FixtureBackend {
+        fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, rustsec::Error> {
+            if package.as_str() != "base" {
+                return Err(rustsec::Error::new(
+                    rustsec::ErrorKind::NotFound,
+                    &format!("no such package: {}", package),
+                ));
+            }
+
+            Ok(["1.3.0", "0.1.0", "1.2.3", "1.2.2"]
+                .iter()
+                .map(|version| IndexPackage {
+                    package: package.clone(),
+                    version: version.parse().unwrap(),
+                    is_yanked: false,
+                    checksum: None,
+                    published: None,
+                })
+                .collect())
+        }
+    }
+
+    let db = advisory_db(&[
+        ("crates/base/RUSTSEC-2001-2101.md", ADVISORY),
+        (
+            "crates/gone/RUSTSEC-2001-2102.md",
+            &ADVISORY
+                .replace("RUSTSEC-2001-2101", "RUSTSEC-2001-2102")
+                .replace("package = \"base\"", "package = \"gone\""),
+        ),
+    ]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let exporter = OsvExporter::new(Some(db.path()))
+        .unwrap()
+        .with_index(Index::with_backend(FixtureBackend));
+
+    let mut output = vec![];
+    exporter.export_ndjson(&mut output).unwrap();
+
+    let advisories = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(advisories.len(), 2);
+
+    // Versions are listed in ascending order, alongside the ranges
+    let affected = &advisories[0]["affected"][0];
+    assert_eq!(affected["versions"], serde_json::json!(["0.1.0", "1.2.2"]));
+    assert!(!affected["ranges"].as_array().unwrap().is_empty());
+
+    let affected = &advisories[1]["affected"][0];
+    assert_eq!(affected["package"]["name"], "gone");
+    assert!(affected.get("versions").is_none());
+}
+
+/// `--sync` and `--dry-run` only apply to the json format
+#[test]
+fn osv_sync_rejected_for_streams() {
+    let db = advisory_db(&[("crates/base/RUSTSEC-2001-2101.md", ADVISORY)]);
+    git_commit(&db, "2001-03-01T00:00:00Z");
+
+    let out = tempfile::tempdir().unwrap();
+
+    for &(format, flag) in &[("ndjson", "--sync"), ("zip", "--dry-run")] {
+        let mut runner = RUNNER.clone();
+        runner
+            .args(&["osv", "--format", format, flag, "--db"])
+            .arg(db.path())
+            .arg(out.path().join("osv"))
+            .capture_stdout()
+            .status()
+            .expect_code(1);
+
+        assert!(!out.path().join("osv").exists());
+    }
+}
+
+/// Public APIs parsed from rustdoc JSON contain the crate's own items, and
+/// associated functions only of the types and traits they belong to
+#[test]
+fn rustdoc_public_api() {
+    use rustsec::advisory::affected::FunctionPath;
+    use rustsec_admin::rustdoc::PublicApi;
+
+    // `Other` uses the older format, where item details are directly under
+    // `inner` rather than nested under the item's kind
+    let json = r#"{
+        "paths": {
+            "0:1": { "crate_id": 0, "path": ["base", "belongs"], "kind": "module" },
+            "0:2": { "crate_id": 0, "path": ["base", "belongs", "All"], "kind": "struct" },
+            "0:3": { "crate_id": 0, "path": ["base", "Survive"], "kind": "trait" },
+            "0:4": { "crate_id": 0, "path": ["base", "belongs", "make_time"], "kind": "function" },
+            "0:9": { "crate_id": 0, "path": ["base", "Other"], "kind": "struct" },
+            "1:1": { "crate_id": 1, "path": ["std", "vec", "Vec"], "kind": "struct" }
+        },
+        "index": {
+            "0:2": { "crate_id": 0, "name": "All", "inner": { "struct": { "impls": ["0:5"] } } },
+            "0:5": { "crate_id": 0, "name": null, "inner": { "impl": { "items": ["0:6"] } } },
+            "0:6": { "crate_id": 0, "name": "belong", "inner": { "function": {} } },
+            "0:3": { "crate_id": 0, "name": "Survive", "inner": { "trait": { "items": ["0:7"] } } },
+            "0:7": { "crate_id": 0, "name": "chance", "inner": { "function": {} } },
+            "0:9": { "crate_id": 0, "name": "Other", "kind": "struct", "inner": { "impls": ["0:10"] } },
+            "0:10": { "crate_id": 0, "name": null, "kind": "impl", "inner": { "items": ["0:11"] } },
+            "0:11": { "crate_id": 0, "name": "zig", "kind": "function", "inner": {} }
+        }
+    }"#;
+
+    let api = PublicApi::from_json(json).unwrap();
+    let contains = |path: &str| api.contains(&path.parse::<FunctionPath>().unwrap());
+
+    assert!(contains("base::belongs::make_time"));
+    assert!(contains("base::belongs::All::belong"));
+    assert!(contains("base::Survive::chance"));
+    assert!(contains("base::Other::zig"));
+
+    assert!(!contains("base::belongs::missing"));
+    assert!(!contains("base::belongs::All::chance"));
+    assert!(!contains("base::Other::belong"));
+    assert!(!contains("base::belongs::make_time::belong"));
+    assert!(!contains("std::vec::Vec"));
+
+    assert!(PublicApi::from_json("{}").is_err());
+    assert!(PublicApi::from_json("not json").is_err());
+}
+
+/// The time to patch is measured from when vulnerabilities were reported (or
+/// discovered, if that's all that's known), skipping informational and
+/// withdrawn advisories and ones with inconsistent or missing dates
+#[test]
+fn stats_time_to_patch() {
+    use rustsec_admin::stats::Stats;
+
+    let advisory = |id: &str, fields: &str| {
+        let path = format!("crates/base/{}.md", id);
+        let contents = ADVISORY.replace("RUSTSEC-2001-2101", id).replace(
+            "date = \"2001-02-03\"\n",
+            &format!("date = \"2001-02-03\"\n{}", fields),
+        );
+        (path, contents)
+    };
+
+    let advisories = [
+        // 10 days
+        advisory(
+            "RUSTSEC-2001-0001",
+            "reported = \"2001-01-01\"\npatched = \"2001-01-11\"\n",
+        ),
+        // 30 days, since only the discovery date is known
+        advisory(
+            "RUSTSEC-2001-0002",
+            "discovered = \"2001-01-01\"\npatched = \"2001-01-31\"\n",
+        ),
+        // 2 days, since the report date takes precedence
+        advisory(
+            "RUSTSEC-2001-0003",
+            "discovered = \"2001-01-01\"\nreported = \"2001-01-20\"\npatched = \"2001-01-22\"\n",
+        ),
+        // 4 days
+        advisory(
+            "RUSTSEC-2001-0004",
+            "reported = \"2001-01-01\"\npatched = \"2001-01-05\"\n",
+        ),
+        // Patched before being reported
+        advisory(
+            "RUSTSEC-2001-0005",
+            "reported = \"2001-01-10\"\npatched = \"2001-01-01\"\n",
+        ),
+        // Not patched yet
+        advisory("RUSTSEC-2001-0006", "reported = \"2001-01-01\"\n"),
+        advisory(
+            "RUSTSEC-2001-0007",
+            "informational = \"unmaintained\"\nreported = \"2001-01-01\"\npatched = \"2001-12-31\"\n",
+        ),
+        advisory(
+            "RUSTSEC-2001-0008",
+            "withdrawn = \"2001-02-04\"\nreported = \"2001-01-01\"\npatched = \"2001-12-31\"\n",
+        ),
+    ];
+
+    let files = advisories
+        .iter()
+        .map(|(path, contents)| (path.as_str(), contents.as_str()))
+        .collect::<Vec<_>>();
+    let db = advisory_db(&files);
+
+    let stats = Stats::load(db.path()).unwrap();
+    assert_eq!(stats.advisories, 7);
+    assert_eq!(stats.informational, 1);
+
+    // 2, 4, 10 and 30 days
+    let time_to_patch = stats.time_to_patch.unwrap();
+    assert_eq!(time_to_patch.advisories, 4);
+    assert_eq!(time_to_patch.average_days, 11.5);
+    assert_eq!(time_to_patch.median_days, 7.0);
+
+    // Odd numbers of advisories have an actual median
+    let db = advisory_db(&files[..3]);
+    let time_to_patch = Stats::load(db.path()).unwrap().time_to_patch.unwrap();
+    assert_eq!(time_to_patch.advisories, 3);
+    assert_eq!(time_to_patch.average_days, 14.0);
+    assert_eq!(time_to_patch.median_days, 10.0);
+
+    // Without any known times to patch, there's no summary
+    let db = advisory_db(&files[5..6]);
+    assert!(Stats::load(db.path()).unwrap().time_to_patch.is_none());
+}
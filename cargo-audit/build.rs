@@ -0,0 +1,152 @@
+//! Build script which embeds a snapshot of the advisory database into the
+//! `cargo-audit` binary when the `embedded-db` feature is enabled.
+//!
+//! The snapshot is taken from the advisory database checkout at the (absolute)
+//! path in `CARGO_AUDIT_EMBEDDED_DB`, which must be set explicitly so builds
+//! don't silently depend on whatever happens to be in `~/.cargo/advisory-db`.
+
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Environment variable containing the path of the database to embed
+const EMBEDDED_DB_VAR: &str = "CARGO_AUDIT_EMBEDDED_DB";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={}", EMBEDDED_DB_VAR);
+
+    if env::var_os("CARGO_FEATURE_EMBEDDED_DB").is_none() {
+        return;
+    }
+
+    let db_path = database_path();
+
+    let mut files = vec![];
+
+    for collection in read_dirs(&db_path) {
+        for package in read_dirs(&collection) {
+            for entry in fs::read_dir(&package).unwrap() {
+                let path = entry.unwrap().path();
+
+                if path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("md") {
+                    files.push(path);
+                }
+            }
+        }
+    }
+
+    files.sort();
+
+    let mut generated = String::from("/// Advisory files in the embedded snapshot\n");
+    generated.push_str("pub const FILES: &[(&str, &str)] = &[\n");
+
+    for path in &files {
+        let relative = path
+            .strip_prefix(&db_path)
+            .unwrap()
+            .iter()
+            .map(|component| component.to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        writeln!(
+            generated,
+            "    ({:?}, include_str!({:?})),",
+            relative,
+            path.to_str().unwrap()
+        )
+        .unwrap();
+    }
+
+    generated.push_str("];\n\n");
+
+    let (commit_id, timestamp) = latest_commit(&db_path)
+        .map(|(id, timestamp)| (format!("Some({:?})", id), format!("Some({})", timestamp)))
+        .unwrap_or_else(|| ("None".to_owned(), "None".to_owned()));
+
+    writeln!(
+        generated,
+        "/// Git commit the snapshot was taken at\npub const COMMIT_ID: Option<&str> = {};\n",
+        commit_id
+    )
+    .unwrap();
+
+    writeln!(
+        generated,
+        "/// Time of the commit the snapshot was taken at (seconds since the Unix epoch)\npub const TIMESTAMP: Option<u64> = {};",
+        timestamp
+    )
+    .unwrap();
+
+    let out_path = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("embedded_db.rs");
+    fs::write(out_path, generated).unwrap();
+
+    println!("cargo:rerun-if-changed={}", db_path.display());
+
+    for path in &files {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+}
+
+/// Get the path of the advisory database to embed
+fn database_path() -> PathBuf {
+    let path = match env::var_os(EMBEDDED_DB_VAR) {
+        Some(path) => PathBuf::from(path),
+        None => panic!(
+            "the `embedded-db` feature requires an advisory database checkout: set {} to its path",
+            EMBEDDED_DB_VAR
+        ),
+    };
+
+    // Relative paths would be resolved against the package's directory
+    // (e.g. in `~/.cargo/registry` when installing), not the working directory
+    if !path.is_absolute() {
+        panic!(
+            "{} must be an absolute path (got {})",
+            EMBEDDED_DB_VAR,
+            path.display()
+        );
+    }
+
+    path
+}
+
+/// Get the non-hidden subdirectories of a directory
+fn read_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut dirs = fs::read_dir(path)
+        .unwrap_or_else(|e| panic!("couldn't read {}: {}", path.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.is_dir()
+                && !path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(true, |name| name.starts_with('.'))
+        })
+        .collect::<Vec<_>>();
+
+    dirs.sort();
+    dirs
+}
+
+/// Get the ID and timestamp of the latest commit to the database, if it's a
+/// git checkout and `git` is available
+fn latest_commit(db_path: &Path) -> Option<(String, u64)> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(db_path)
+        .args(&["log", "-1", "--format=%H %ct"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    let output = String::from_utf8(output.stdout).ok()?;
+    let mut parts = output.trim().splitn(2, ' ');
+    let commit_id = parts.next()?.to_owned();
+    let timestamp = parts.next()?.parse().ok()?;
+    Some((commit_id, timestamp))
+}
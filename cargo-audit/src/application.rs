@@ -7,8 +7,9 @@ use abscissa_core::{
     application::{self, AppCell},
     config,
     terminal::ColorChoice,
-    trace, Application, EntryPoint, FrameworkError, StandardPaths,
+    trace, Application, EntryPoint, FrameworkError, FrameworkErrorKind, StandardPaths,
 };
+use std::{env, path::Path};
 
 /// Application state
 pub static APPLICATION: AppCell<CargoAuditApplication> = AppCell::new();
@@ -86,6 +87,11 @@ impl Application for CargoAuditApplication {
         self.state.components.register(components)
     }
 
+    /// Load `audit.toml`, pointing at the offending line if it's invalid
+    fn load_config(&mut self, path: &Path) -> Result<Self::Cfg, FrameworkError> {
+        AuditConfig::load_file(path).map_err(|e| FrameworkErrorKind::ConfigError.context(e).into())
+    }
+
     /// Post-configuration lifecycle callback.
     fn after_config(&mut self, config: Self::Cfg) -> Result<(), FrameworkError> {
         // Configure components
@@ -95,12 +101,24 @@ impl Application for CargoAuditApplication {
     }
 
     /// Color configuration for this application.
+    ///
+    /// In `auto` mode colors are disabled if `NO_COLOR` is set or STDOUT
+    /// isn't a terminal (e.g. when output is piped into a file or CI log).
     fn term_colors(&self, entrypoint: &EntryPoint<CargoAuditCommand>) -> ColorChoice {
-        entrypoint
+        let choice = entrypoint
             .command
             .as_ref()
             .and_then(|cmd| cmd.color_config())
-            .unwrap_or(ColorChoice::Auto)
+            .unwrap_or(ColorChoice::Auto);
+
+        match choice {
+            ColorChoice::Auto
+                if env::var_os("NO_COLOR").is_some() || !atty::is(atty::Stream::Stdout) =>
+            {
+                ColorChoice::Never
+            }
+            other => other,
+        }
     }
 
     /// Get tracing configuration from command-line options
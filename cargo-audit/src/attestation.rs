@@ -0,0 +1,145 @@
+//! in-toto attestations of audit results.
+//!
+//! Wraps a [`Report`] in an [in-toto Statement] whose subject is the audited
+//! lockfile, so the results of an audit can be attached to build artifacts
+//! (e.g. with `cosign attest`) alongside SBOM attestations.
+//!
+//! [in-toto Statement]: https://github.com/in-toto/attestation/blob/main/spec/README.md#statement
+
+use rustsec::{lockfile::Lockfile, Report};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap as Map, fs, path::Path};
+
+/// Type of in-toto statements
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v0.1";
+
+/// Type of the audit result predicate
+pub const PREDICATE_TYPE: &str = "https://rustsec.org/attestation/audit/v1";
+
+/// URI identifying `cargo-audit` as the tool which performed the audit
+pub const SCANNER_URI: &str = "https://github.com/RustSec/rustsec/tree/main/cargo-audit";
+
+/// in-toto statement attesting to the results of an audit
+#[derive(Clone, Debug, Serialize)]
+pub struct Statement<'a> {
+    /// Type of this statement (i.e. [`STATEMENT_TYPE`])
+    #[serde(rename = "_type")]
+    pub statement_type: &'static str,
+
+    /// Lockfile which was audited
+    pub subject: Vec<Subject>,
+
+    /// Type of the predicate (i.e. [`PREDICATE_TYPE`])
+    #[serde(rename = "predicateType")]
+    pub predicate_type: &'static str,
+
+    /// Results of the audit
+    pub predicate: Predicate<'a>,
+}
+
+impl<'a> Statement<'a> {
+    /// Create a statement attesting to the given report about the lockfile
+    /// at the given path
+    pub fn new(lockfile_path: &Path, lockfile: &Lockfile, report: &'a Report) -> Self {
+        Self {
+            statement_type: STATEMENT_TYPE,
+            subject: vec![Subject::new(lockfile_path, lockfile)],
+            predicate_type: PREDICATE_TYPE,
+            predicate: Predicate {
+                scanner: Scanner {
+                    uri: SCANNER_URI,
+                    version: crate::VERSION,
+                },
+                report,
+            },
+        }
+    }
+}
+
+/// Artifact an attestation is about
+#[derive(Clone, Debug, Serialize)]
+pub struct Subject {
+    /// Path to the artifact
+    pub name: String,
+
+    /// Digests of the artifact's contents, keyed by algorithm
+    pub digest: Map<String, String>,
+}
+
+impl Subject {
+    /// Create a subject for the lockfile at the given path.
+    ///
+    /// The digest is computed over the lockfile as it is on disk. If it
+    /// wasn't read from a file (e.g. it came from STDIN or a vendor
+    /// directory) its serialized form is digested instead.
+    pub fn new(lockfile_path: &Path, lockfile: &Lockfile) -> Self {
+        let contents = if lockfile_path.is_file() {
+            fs::read(lockfile_path).ok()
+        } else {
+            None
+        }
+        .unwrap_or_else(|| lockfile.to_string().into_bytes());
+
+        let mut digest = Map::new();
+        digest.insert(
+            "sha256".to_owned(),
+            format!("{:x}", Sha256::digest(&contents)),
+        );
+
+        Self {
+            name: lockfile_path.display().to_string(),
+            digest,
+        }
+    }
+}
+
+/// Predicate describing the results of an audit
+#[derive(Clone, Debug, Serialize)]
+pub struct Predicate<'a> {
+    /// Tool which performed the audit
+    pub scanner: Scanner,
+
+    /// Findings of the audit, including the advisory database the lockfile
+    /// was audited against
+    pub report: &'a Report,
+}
+
+/// Tool which performed the audit
+#[derive(Clone, Debug, Serialize)]
+pub struct Scanner {
+    /// URI identifying the tool
+    pub uri: &'static str,
+
+    /// Version of the tool
+    pub version: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Subject;
+    use rustsec::cargo_lock::{Lockfile, Metadata, Patch, ResolveVersion};
+    use std::fs;
+
+    #[test]
+    fn subject_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Cargo.lock");
+        fs::write(&path, "abc").unwrap();
+
+        let lockfile = Lockfile {
+            version: ResolveVersion::default(),
+            packages: vec![],
+            root: None,
+            metadata: Metadata::default(),
+            patch: Patch::default(),
+        };
+
+        let subject = Subject::new(&path, &lockfile);
+        assert_eq!(subject.name, path.display().to_string());
+        assert_eq!(
+            subject.digest["sha256"],
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
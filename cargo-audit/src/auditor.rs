@@ -1,17 +1,67 @@
 //! Core auditing functionality
 
-use crate::{config::AuditConfig, lockfile, prelude::*, presenter::Presenter};
-use rustsec::{error, lockfile::Lockfile, registry, report, warning, Warning};
+#[cfg(feature = "embedded-db")]
+use crate::embedded;
+use crate::{
+    config::{AuditConfig, IndexProtocol},
+    history, lockfile,
+    prelude::*,
+    presenter::Presenter,
+    progress::ProgressBar,
+    requirements, sbom,
+    vendor::VendorDir,
+};
+use rustsec::{
+    database::{LoadMethod, LoadProfile, Source},
+    error,
+    exposure::{self, ExposureWindow},
+    lockfile::{Lockfile, SkippedEntry},
+    package, registry, report,
+    repository::mirror::Manifest,
+    warning, Warning,
+};
 use std::{
     collections::btree_map as map,
+    fmt, fs,
     io::{self, Read},
-    path::Path,
+    path::{Path, PathBuf},
     process::exit,
+    time::Instant,
 };
 
 /// Name of `Cargo.lock`
 const CARGO_LOCK_FILE: &str = "Cargo.lock";
 
+/// Name of `Cargo.toml`
+const CARGO_TOML_FILE: &str = "Cargo.toml";
+
+/// Number of dependency paths keeping older versions of duplicated packages
+/// alive which are included in warnings about them
+const DUPLICATE_PATH_LIMIT: usize = 3;
+
+/// Error fetching or loading the advisory database
+#[derive(Debug)]
+pub struct LoadError {
+    /// What was being done when the error occurred
+    context: &'static str,
+
+    /// Underlying error
+    error: error::Error,
+}
+
+impl LoadError {
+    /// Create an error which occurred in the given context
+    fn new(context: &'static str, error: error::Error) -> Self {
+        Self { context, error }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.error)
+    }
+}
+
 /// Security vulnerability auditor
 pub struct Auditor {
     /// RustSec Advisory Database
@@ -25,68 +75,146 @@ pub struct Auditor {
 
     /// Audit report settings
     report_settings: report::Settings,
+
+    /// Refuse to audit an out-of-date `Cargo.lock`
+    locked: bool,
+
+    /// Never invoke `cargo` (or any other subprocess)
+    no_cargo: bool,
+
+    /// Fail on malformed `Cargo.lock` entries rather than skipping them
+    strict_lockfile: bool,
+
+    /// Stop at the first denied finding instead of generating a report
+    exit_on_first: bool,
+
+    /// Warn about packages present in several versions
+    check_duplicates: bool,
+
+    /// Kinds of warnings which cause the audit to fail
+    deny_warning_kinds: Vec<warning::Kind>,
+
+    /// Notices about checks skipped during the current audit
+    notices: Vec<String>,
+
+    /// Entries of the lockfile being audited which couldn't be parsed
+    malformed_entries: Vec<SkippedEntry>,
+
+    /// Baseline lockfile: findings about packages it contains are omitted
+    baseline: Option<Lockfile>,
+
+    /// `cargo vet` audit records of the project being audited (if it has any)
+    review_records: Option<report::ReviewRecords>,
+
+    /// File to append a summary of each audit to
+    history_file: Option<PathBuf>,
+
+    /// Timing and cache diagnostics to include in reports (if requested)
+    stats: Option<report::Stats>,
+
+    /// Include the hostname in reports
+    hostname: bool,
+
+    /// Quiet mode
+    quiet: bool,
 }
 
 impl Auditor {
     /// Initialize the auditor
     pub fn new(config: &AuditConfig) -> Self {
-        let advisory_db_url = config
-            .database
-            .url
-            .as_ref()
-            .map(AsRef::as_ref)
-            .unwrap_or(rustsec::repository::git::DEFAULT_URL);
+        let advisory_db_path = database_path(config);
 
-        let advisory_db_path = config
-            .database
-            .path
-            .as_ref()
-            .cloned()
-            .unwrap_or_else(rustsec::repository::git::Repository::default_path);
+        let mut stats = report::Stats::default();
+        let mut profile = LoadProfile::new();
 
-        let database = if config.database.fetch {
-            if !config.output.is_quiet() {
-                status_ok!("Fetching", "advisory database from `{}`", advisory_db_url);
+        let database = if config.database.embedded {
+            Self::load_embedded()
+        } else {
+            match Self::load_database(config, &mut stats, &mut profile) {
+                Ok(database) => database,
+                // A mirror which fails verification may have been tampered
+                // with, which shouldn't be papered over by a fallback
+                Err(e) if config.database.mirror.is_some() => {
+                    status_err!("{}", e);
+                    exit(1);
+                }
+                Err(e) => Self::fallback(config, &e),
             }
+        };
 
-            let advisory_db_repo = rustsec::repository::git::Repository::fetch(
-                advisory_db_url,
-                &advisory_db_path,
-                !config.database.stale,
-            )
-            .unwrap_or_else(|e| {
-                status_err!("couldn't fetch advisory database: {}", e);
-                exit(1);
-            });
+        // Prefer the embedded snapshot over an older database on disk
+        #[cfg(feature = "embedded-db")]
+        let database = if embedded::is_newer_than(&database) {
+            if !config.output.is_quiet() {
+                status_warn!(
+                    "advisory database at {} is older than the embedded snapshot; using the snapshot",
+                    advisory_db_path.display()
+                );
+            }
 
-            rustsec::Database::load_from_repo(&advisory_db_repo).unwrap_or_else(|e| {
-                status_err!("error loading advisory database: {}", e);
-                exit(1);
-            })
+            Self::load_embedded()
         } else {
-            rustsec::Database::open(&advisory_db_path).unwrap_or_else(|e| {
-                status_err!("error loading advisory database: {}", e);
-                exit(1);
-            })
+            database
         };
 
+        if config.output.stats {
+            stats.database_size = directory_size(&advisory_db_path).ok();
+        }
+
         if !config.output.is_quiet() {
+            let loaded_from = if database.source().method == LoadMethod::Embedded {
+                "embedded snapshot".to_owned()
+            } else {
+                advisory_db_path.display().to_string()
+            };
+
             status_ok!(
                 "Loaded",
                 "{} security advisories (from {})",
                 database.iter().count(),
-                advisory_db_path.display()
+                loaded_from
             );
         }
 
+        let presenter = Presenter::new(&config.output);
+
+        if let Some(count) = config.output.slow_parse_report {
+            presenter.print_slow_parse_report(&profile, count);
+        }
+
+        Self::with_presenter(config, database, presenter, stats)
+    }
+
+    /// Initialize an auditor which uses an already loaded advisory database
+    pub fn with_database(config: &AuditConfig, database: rustsec::Database) -> Self {
+        let presenter = Presenter::new(&config.output);
+        Self::with_presenter(config, database, presenter, report::Stats::default())
+    }
+
+    /// Initialize the auditor with the given database and presenter,
+    /// opening the crates.io index if yanked crates are checked for
+    fn with_presenter(
+        config: &AuditConfig,
+        database: rustsec::Database,
+        presenter: Presenter,
+        mut stats: report::Stats,
+    ) -> Self {
         let registry_index = if config.yanked.enabled {
-            if config.yanked.update_index && config.database.fetch {
+            if config.yanked.protocol == IndexProtocol::Sparse && config.database.fetch {
+                // The sparse index is queried on demand, so it's always up-to-date
+                Some(registry::Index::sparse())
+            } else if config.yanked.update_index && config.database.fetch {
                 if !config.output.is_quiet() {
                     status_ok!("Updating", "crates.io index");
                 }
 
+                let started = Instant::now();
+
                 match registry::Index::fetch() {
-                    Ok(index) => Some(index),
+                    Ok(index) => {
+                        stats.index_update_ms = Some(report::Stats::millis(started.elapsed()));
+                        Some(index)
+                    }
                     Err(err) => {
                         if !config.output.is_quiet() {
                             status_warn!("couldn't update crates.io index: {}", err);
@@ -114,9 +242,237 @@ impl Auditor {
         Self {
             database,
             registry_index,
-            presenter: Presenter::new(&config.output),
+            presenter,
             report_settings: config.report_settings(),
+            locked: config.lockfile.locked,
+            no_cargo: config.lockfile.no_cargo,
+            strict_lockfile: config.lockfile.strict,
+            exit_on_first: config.output.exit_on_first && !config.output.format.is_json(),
+            check_duplicates: config.packages.duplicates,
+            deny_warning_kinds: config
+                .output
+                .deny
+                .iter()
+                .filter_map(|kind| kind.get_warning_kind())
+                .collect(),
+            notices: vec![],
+            malformed_entries: vec![],
+            baseline: None,
+            review_records: None,
+            history_file: config.output.history_file.clone(),
+            stats: if config.output.stats {
+                Some(stats)
+            } else {
+                None
+            },
+            hostname: config.output.hostname,
+            quiet: config.output.is_quiet(),
+        }
+    }
+
+    /// Only report findings about packages which were added or changed
+    /// relative to the given baseline lockfile
+    pub fn set_baseline(&mut self, baseline_path: &Path) -> Result<(), error::Error> {
+        let baseline = self.load_lockfile(baseline_path).map_err(|e| {
+            error::Error::new(
+                error::ErrorKind::NotFound,
+                &format!("Couldn't load baseline {}: {}", baseline_path.display(), e),
+            )
+        })?;
+
+        // Only the audited lockfile's malformed entries are warned about
+        self.malformed_entries.clear();
+        self.baseline = Some(baseline);
+        Ok(())
+    }
+
+    /// Load the advisory database snapshot embedded at build time
+    #[cfg(feature = "embedded-db")]
+    fn load_embedded() -> rustsec::Database {
+        embedded::load().unwrap_or_else(|e| {
+            status_err!("error loading embedded advisory database: {}", e);
+            exit(1);
+        })
+    }
+
+    /// Load the advisory database snapshot embedded at build time (of which
+    /// there is none in this build)
+    #[cfg(not(feature = "embedded-db"))]
+    fn load_embedded() -> rustsec::Database {
+        status_err!("this build has no embedded advisory database (see the `embedded-db` feature)");
+        exit(1);
+    }
+
+    /// Handle a failure to fetch or load the advisory database by falling
+    /// back to the embedded snapshot (if there is one), or exiting otherwise
+    fn fallback(config: &AuditConfig, error: &LoadError) -> rustsec::Database {
+        if cfg!(feature = "embedded-db") {
+            if !config.output.is_quiet() {
+                status_warn!("{}; using the embedded snapshot", error);
+            }
+
+            Self::load_embedded()
+        } else {
+            status_err!("{}", error);
+            exit(1);
+        }
+    }
+
+    /// Load the advisory database according to the given configuration:
+    /// from a static file mirror, a git checkout (fetched first unless
+    /// fetching is disabled), or a vendored copy of the database. Timings
+    /// are recorded in the given stats and profile.
+    ///
+    /// This is also how `cargo audit daemon` refreshes its database.
+    pub fn load_database(
+        config: &AuditConfig,
+        stats: &mut report::Stats,
+        profile: &mut LoadProfile,
+    ) -> Result<rustsec::Database, LoadError> {
+        let advisory_db_url = config
+            .database
+            .url
+            .as_ref()
+            .map(AsRef::as_ref)
+            .unwrap_or(rustsec::repository::git::DEFAULT_URL);
+
+        let advisory_db_path = database_path(config);
+
+        if let Some(mirror_url) = &config.database.mirror {
+            return Self::load_mirror(config, mirror_url, &advisory_db_path, stats, profile);
         }
+
+        // A directory which isn't a git checkout (e.g. a vendored copy of the
+        // advisory DB) can't be fetched, but can still be audited against.
+        // Checkouts which can't be opened are still fetched, so they can be
+        // repaired.
+        let is_vendored_db = advisory_db_path.is_dir()
+            && !advisory_db_path.join(".git").exists()
+            && fs::read_dir(&advisory_db_path)
+                .map(|mut entries| entries.next().is_some())
+                .unwrap_or(false);
+
+        if config.database.fetch && !is_vendored_db {
+            if !config.output.is_quiet() {
+                status_ok!("Fetching", "advisory database from `{}`", advisory_db_url);
+            }
+
+            let started = Instant::now();
+
+            let result = if config.database.no_repair {
+                rustsec::repository::git::Repository::fetch(
+                    advisory_db_url,
+                    &advisory_db_path,
+                    !config.database.stale,
+                )
+            } else {
+                rustsec::repository::git::Repository::fetch_or_repair(
+                    advisory_db_url,
+                    &advisory_db_path,
+                    !config.database.stale,
+                    |e| {
+                        if config.output.verbose && !config.output.is_quiet() {
+                            status_warn!(
+                                "re-cloning advisory database at {} (the old checkout is kept as a backup): {}",
+                                advisory_db_path.display(),
+                                e
+                            );
+                        }
+                    },
+                )
+            };
+
+            let advisory_db_repo =
+                result.map_err(|e| LoadError::new("couldn't fetch advisory database", e))?;
+            stats.fetch_ms = Some(report::Stats::millis(started.elapsed()));
+
+            let mut progress = ProgressBar::new(!config.output.is_quiet());
+            let started = Instant::now();
+
+            let database =
+                rustsec::Database::load_from_repo_with_progress(&advisory_db_repo, |event| {
+                    profile.record(&event);
+                    progress.update(event)
+                })
+                .map_err(|e| LoadError::new("error loading advisory database", e))?;
+
+            stats.load_ms = report::Stats::millis(started.elapsed());
+
+            // Record the remote rather than the local checkout it was fetched to
+            let source = database.source().clone().url(advisory_db_url);
+            return Ok(database.with_source(source));
+        }
+
+        if config.database.fetch && !config.output.is_quiet() {
+            status_warn!(
+                "advisory database at {} is not a git repository; skipping fetch",
+                advisory_db_path.display()
+            );
+        }
+
+        let mut progress = ProgressBar::new(!config.output.is_quiet());
+        let started = Instant::now();
+
+        // Include commit information if the database is a git checkout
+        let result = match rustsec::repository::git::Repository::open(&advisory_db_path) {
+            Ok(repo) => rustsec::Database::load_from_repo_with_progress(&repo, |event| {
+                profile.record(&event);
+                progress.update(event)
+            }),
+            Err(_) => rustsec::Database::open_with_progress(&advisory_db_path, |event| {
+                profile.record(&event);
+                progress.update(event)
+            }),
+        };
+
+        let database = result.map_err(|e| LoadError::new("error loading advisory database", e))?;
+        stats.load_ms = report::Stats::millis(started.elapsed());
+        Ok(database)
+    }
+
+    /// Load the advisory database from a static file mirror, verifying its
+    /// contents against the mirror's manifest
+    fn load_mirror(
+        config: &AuditConfig,
+        mirror_url: &str,
+        path: &Path,
+        stats: &mut report::Stats,
+        profile: &mut LoadProfile,
+    ) -> Result<rustsec::Database, LoadError> {
+        let result = if config.database.fetch {
+            if !config.output.is_quiet() {
+                status_ok!("Fetching", "advisory database mirror from `{}`", mirror_url);
+            }
+
+            let started = Instant::now();
+            let result =
+                Manifest::fetch(mirror_url, path, config.database.mirror_digest.as_deref());
+            stats.fetch_ms = Some(report::Stats::millis(started.elapsed()));
+            result
+        } else {
+            Manifest::load(path).and_then(|manifest| {
+                if let Some(expected) = &config.database.mirror_digest {
+                    manifest.verify_digest(expected)?;
+                }
+
+                manifest.verify(path).map(|()| manifest)
+            })
+        };
+
+        let manifest =
+            result.map_err(|e| LoadError::new("couldn't verify advisory database mirror", e))?;
+
+        let mut progress = ProgressBar::new(!config.output.is_quiet());
+        let started = Instant::now();
+
+        let database = rustsec::Database::open_with_progress(path, |event| {
+            profile.record(&event);
+            progress.update(event)
+        })
+        .map_err(|e| LoadError::new("error loading advisory database", e))?;
+
+        stats.load_ms = report::Stats::millis(started.elapsed());
+        Ok(database.with_source(Source::mirror(manifest.digest()).url(mirror_url)))
     }
 
     /// Perform audit
@@ -124,31 +480,382 @@ impl Auditor {
         &mut self,
         maybe_lockfile_path: Option<&Path>,
     ) -> Result<rustsec::Report, error::Error> {
-        let lockfile_path = match maybe_lockfile_path {
+        let lockfile_path = self.lockfile_path(maybe_lockfile_path)?;
+        let lockfile = self.load_project_lockfile(lockfile_path)?;
+
+        let in_sync = if self.locked {
+            self.check_lockfile_sync(lockfile_path)?
+        } else {
+            None
+        };
+
+        let members = self.workspace_members(lockfile_path, &lockfile);
+        self.audit_lockfile(lockfile_path, &lockfile, in_sync, &members)
+    }
+
+    /// Generate a report for the project's lockfile without displaying it
+    /// (e.g. to summarize it in a badge)
+    pub fn report(
+        &mut self,
+        maybe_lockfile_path: Option<&Path>,
+    ) -> Result<rustsec::Report, error::Error> {
+        let lockfile_path = self.lockfile_path(maybe_lockfile_path)?;
+        let lockfile = self.load_project_lockfile(lockfile_path)?;
+
+        let in_sync = if self.locked {
+            self.check_lockfile_sync(lockfile_path)?
+        } else {
+            None
+        };
+
+        let members = self.workspace_members(lockfile_path, &lockfile);
+        self.generate_report(&lockfile, in_sync, &members)
+    }
+
+    /// Generate a report for the contents of a lockfile (e.g. one sent to
+    /// `cargo audit daemon`) without displaying it.
+    ///
+    /// The lockfile is treated like one read from STDIN, so checks which
+    /// need the rest of the project are skipped.
+    pub fn report_lockfile(
+        &mut self,
+        lockfile_toml: &str,
+    ) -> Result<rustsec::Report, error::Error> {
+        let lockfile = self.parse_lockfile(Path::new("-"), lockfile_toml)?;
+        self.generate_report(&lockfile, None, &[])
+    }
+
+    /// Replace the advisory database audits are performed against
+    pub fn set_database(&mut self, database: rustsec::Database) {
+        self.database = database;
+    }
+
+    /// Get the advisory database audits are performed against
+    pub fn database(&self) -> &rustsec::Database {
+        &self.database
+    }
+
+    /// Get the path to the lockfile to audit, generating `Cargo.lock` if the
+    /// project doesn't have one (and generating it is allowed)
+    fn lockfile_path<'a>(
+        &self,
+        maybe_lockfile_path: Option<&'a Path>,
+    ) -> Result<&'a Path, error::Error> {
+        Ok(match maybe_lockfile_path {
             Some(p) => p,
             None => {
                 let path = Path::new(CARGO_LOCK_FILE);
-                if !path.exists() && Path::new("Cargo.toml").exists() {
+                if !path.exists() && Path::new(CARGO_TOML_FILE).exists() {
+                    if self.locked {
+                        return Err(error::Error::new(
+                            error::ErrorKind::NotFound,
+                            &"Cargo.lock is missing and --locked was passed",
+                        ));
+                    }
+
+                    if self.no_cargo {
+                        return Err(error::Error::new(
+                            error::ErrorKind::NotFound,
+                            &"Cargo.lock is missing and --no-cargo was passed",
+                        ));
+                    }
+
                     lockfile::generate()?;
                 }
                 path
             }
+        })
+    }
+
+    /// Load the lockfile of the project being audited
+    fn load_project_lockfile(&mut self, lockfile_path: &Path) -> Result<Lockfile, error::Error> {
+        let lockfile = self.load_lockfile(lockfile_path).map_err(|e| {
+            error::Error::new(
+                error::ErrorKind::NotFound,
+                &format!("Couldn't load {}: {}", lockfile_path.display(), e),
+            )
+        })?;
+
+        self.load_review_records(lockfile_path);
+        Ok(lockfile)
+    }
+
+    /// Load the `cargo vet` audit records in the `supply-chain` directory
+    /// alongside the lockfile, if there is one
+    fn load_review_records(&mut self, lockfile_path: &Path) {
+        self.review_records = None;
+
+        if lockfile_path == Path::new("-") {
+            return;
+        }
+
+        let dir = match lockfile_path.parent() {
+            Some(parent) if parent != Path::new("") => parent.join(report::SUPPLY_CHAIN_DIR),
+            _ => PathBuf::from(report::SUPPLY_CHAIN_DIR),
+        };
+
+        if !dir.is_dir() {
+            return;
+        }
+
+        match report::ReviewRecords::load(&dir) {
+            Ok(records) => self.review_records = Some(records),
+            Err(e) => self.notices.push(format!(
+                "couldn't load cargo vet records from {}: {}",
+                dir.display(),
+                e
+            )),
+        }
+    }
+
+    /// Audit the packages listed in a CycloneDX or SPDX JSON SBOM
+    pub fn audit_sbom(&mut self, sbom_path: &Path) -> Result<rustsec::Report, error::Error> {
+        let lockfile = sbom::load(sbom_path).map_err(|e| {
+            // Keep the kind of the underlying error, so e.g. a malformed
+            // SBOM is reported as a parse error rather than a missing file
+            error::Error::new(
+                e.kind(),
+                &format!("Couldn't load SBOM {}: {}", sbom_path.display(), e),
+            )
+            .with_source(e)
+        })?;
+
+        self.audit_lockfile(sbom_path, &lockfile, None, &[])
+    }
+
+    /// Audit the crates in a `cargo vendor` directory
+    pub fn audit_vendor(&mut self, vendor_path: &Path) -> Result<rustsec::Report, error::Error> {
+        let vendor_dir = VendorDir::load(vendor_path)?;
+        self.presenter.set_package_paths(vendor_dir.paths);
+        self.audit_lockfile(vendor_path, &vendor_dir.lockfile, None, &[])
+    }
+
+    /// Audit the minimum versions of crates.io dependencies permitted by the
+    /// requirements in the project's `Cargo.toml`, rather than the versions
+    /// in `Cargo.lock`, reporting the requirements which admit vulnerable
+    /// versions.
+    ///
+    /// Only the direct dependencies of workspace members are considered,
+    /// since resolving minimal versions of transitive dependencies needs
+    /// Cargo's unstable `-Z minimal-versions`.
+    pub fn audit_minimal_versions(
+        &mut self,
+        maybe_lockfile_path: Option<&Path>,
+    ) -> Result<rustsec::Report, error::Error> {
+        let manifest_path = match maybe_lockfile_path {
+            Some(lockfile_path) => manifest_path(lockfile_path),
+            None => PathBuf::from(CARGO_TOML_FILE),
+        };
+
+        let index = requirements_index(
+            self.registry_index.as_ref(),
+            self.no_cargo,
+            "--minimal-versions",
+        )?;
+
+        let mut minimal = vec![];
+
+        for requirement in requirements::load(&manifest_path)? {
+            match requirement.minimal_version(index) {
+                Ok(Some(version)) => minimal.push((requirement, version)),
+                Ok(None) => self.notices.push(format!(
+                    "{}: no release of {} matches `{}`",
+                    requirement.manifest_path.display(),
+                    requirement.name,
+                    requirement.req
+                )),
+                Err(e) => self.notices.push(format!(
+                    "{}: couldn't find releases of {}: {}",
+                    requirement.manifest_path.display(),
+                    requirement.name,
+                    e
+                )),
+            }
+        }
+
+        let source =
+            package::SourceId::from_url(&format!("registry+{}", package::source::CRATES_IO_INDEX))?;
+        let packages = minimal
+            .iter()
+            .map(|(requirement, version)| package::Package {
+                name: requirement.name.clone(),
+                version: version.clone(),
+                source: Some(source.clone()),
+                checksum: None,
+                dependencies: vec![],
+                replace: None,
+            })
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let lockfile = Lockfile {
+            version: Default::default(),
+            packages,
+            root: None,
+            metadata: Default::default(),
+            patch: Default::default(),
         };
 
-        let lockfile = match self.load_lockfile(lockfile_path) {
-            Ok(l) => l,
-            Err(e) => {
-                return Err(error::Error::new(
-                    error::ErrorKind::NotFound,
-                    &format!("Couldn't load {}: {}", lockfile_path.display(), e),
-                ))
+        self.notices.push(
+            "--minimal-versions only audits direct dependencies of workspace members".to_owned(),
+        );
+        self.presenter.before_report(&manifest_path, &lockfile);
+
+        let mut report = self.generate_report(&lockfile, None, &[])?;
+
+        for vuln in &report.vulnerabilities.list {
+            for (requirement, _) in minimal.iter().filter(|(requirement, version)| {
+                requirement.name == vuln.package.name && *version == vuln.package.version
+            }) {
+                report.notices.push(format!(
+                    "{}: `{} = \"{}\"` (in {}) admits {} {}, which is vulnerable to {}",
+                    requirement.manifest_path.display(),
+                    requirement.name,
+                    requirement.req,
+                    requirement.member,
+                    vuln.package.name,
+                    vuln.package.version,
+                    vuln.advisory.id
+                ));
             }
+        }
+
+        self.finish_audit(&lockfile, report)
+    }
+
+    /// Generate and display a report for the given lockfile
+    fn audit_lockfile(
+        &mut self,
+        lockfile_path: &Path,
+        lockfile: &Lockfile,
+        in_sync: Option<bool>,
+        members: &[package::Name],
+    ) -> Result<rustsec::Report, error::Error> {
+        self.presenter.before_report(lockfile_path, lockfile);
+
+        if self.exit_on_first {
+            self.exit_on_first_finding(lockfile);
+        }
+
+        let report = self.generate_report(lockfile, in_sync, members)?;
+        self.finish_audit(lockfile, report)
+    }
+
+    /// Record and display a report generated for the given lockfile
+    fn finish_audit(
+        &mut self,
+        lockfile: &Lockfile,
+        report: rustsec::Report,
+    ) -> Result<rustsec::Report, error::Error> {
+        let self_advisories = self.self_advisories();
+
+        // Record the results before printing them, as the presenter exits
+        // the process when the audit fails
+        if let Some(history_file) = &self.history_file {
+            if let Err(e) = history::append(history_file, &report) {
+                if !self.quiet {
+                    status_warn!("{}", e);
+                }
+            }
+        }
+
+        self.presenter
+            .print_report(&report, self_advisories.as_slice(), lockfile);
+
+        Ok(report)
+    }
+
+    /// Audit all of the `Cargo.lock` files in the given directory tree using
+    /// the loaded advisory database, displaying an aggregated report.
+    ///
+    /// Lockfiles which can't be audited are skipped with a notice.
+    pub fn audit_recursive(&mut self, dir: &Path) -> Result<report::AggregateReport, error::Error> {
+        let lockfile_paths = lockfile::find_all(dir)?;
+
+        if lockfile_paths.is_empty() {
+            return Err(error::Error::new(
+                error::ErrorKind::NotFound,
+                &format!("no Cargo.lock files found in {}", dir.display()),
+            ));
+        }
+
+        let mut projects = vec![];
+        let mut lockfiles = vec![];
+        let mut notices = vec![];
+
+        for lockfile_path in lockfile_paths {
+            match self.audit_project(&lockfile_path) {
+                Ok((lockfile, report)) => {
+                    projects.push(report::ProjectReport {
+                        lockfile: lockfile_path,
+                        report,
+                    });
+                    lockfiles.push(lockfile);
+                }
+                Err(e) => {
+                    self.notices.clear();
+                    self.malformed_entries.clear();
+                    notices.push(format!("skipped {}: {}", lockfile_path.display(), e));
+                }
+            }
+        }
+
+        let mut aggregate = report::AggregateReport::new(projects);
+        aggregate.notices = notices;
+
+        let self_advisories = self.self_advisories();
+
+        self.presenter
+            .print_aggregate_report(&aggregate, &lockfiles, self_advisories.as_slice());
+
+        Ok(aggregate)
+    }
+
+    /// Generate a report for an individual project in a recursive audit
+    fn audit_project(
+        &mut self,
+        lockfile_path: &Path,
+    ) -> Result<(Lockfile, rustsec::Report), error::Error> {
+        let lockfile = self.load_lockfile(lockfile_path)?;
+        self.load_review_records(lockfile_path);
+        self.presenter.before_report(lockfile_path, &lockfile);
+
+        let in_sync = if self.locked {
+            self.check_lockfile_sync(lockfile_path)?
+        } else {
+            None
         };
 
-        self.presenter.before_report(&lockfile_path, &lockfile);
+        let members = self.workspace_members(lockfile_path, &lockfile);
+        let report = self.generate_report(&lockfile, in_sync, &members)?;
+        Ok((lockfile, report))
+    }
 
-        let mut report =
-            rustsec::Report::generate(&self.database, &lockfile, &self.report_settings);
+    /// Generate a report for the given lockfile without displaying it
+    fn generate_report(
+        &mut self,
+        lockfile: &Lockfile,
+        in_sync: Option<bool>,
+        members: &[package::Name],
+    ) -> Result<rustsec::Report, error::Error> {
+        let started = Instant::now();
+        let mut report = rustsec::Report::generate(&self.database, lockfile, &self.report_settings);
+
+        report.lockfile.in_sync = in_sync;
+        report.notices = self.notices.drain(..).collect();
+        report.tool = Some(self.tool_info());
+        report.check_malformed_entries(lockfile, &std::mem::take(&mut self.malformed_entries));
+
+        // Path dependencies aren't workspace members, so redo this with the
+        // members known from `Cargo.toml`
+        if !members.is_empty() {
+            report.annotate_dependencies(lockfile, members);
+            report.promote_warnings();
+        }
+
+        // Surface advisories about the project's own (published) crates
+        report.check_own_crates(&self.database, lockfile);
 
         // Warn for yanked crates
         // TODO(tarcieri): move this logic into the `rustsec` crate?
@@ -166,25 +873,298 @@ impl Auditor {
                     }
                 }
             }
+
+            report.check_checksums(lockfile, index);
+            report.annotate_owners();
+            report.annotate_dependencies(lockfile, members);
+            report.promote_warnings();
+            report.classify_remedies(index);
+            report.annotate_fix_status(&self.database);
+            report.annotate_fix_age(index, std::time::SystemTime::now());
+        }
+
+        if self.check_duplicates {
+            report.check_duplicates(lockfile, DUPLICATE_PATH_LIMIT)?;
+            report.annotate_owners();
+            report.annotate_dependencies(lockfile, members);
+            report.promote_warnings();
+        }
+
+        if let Some(records) = &self.review_records {
+            report.annotate_review_status(records);
+        }
+
+        if let Some(stats) = &self.stats {
+            let mut stats = stats.clone();
+            stats.match_ms = report::Stats::millis(started.elapsed());
+
+            if let Some(index) = &self.registry_index {
+                stats.index_cache_hits = index.cache_hits();
+                stats.index_cache_misses = index.cache_misses();
+            }
+
+            report.stats = Some(stats);
         }
 
-        let self_advisories = self.self_advisories();
+        if let Some(baseline) = &self.baseline {
+            let omitted = report.exclude_baseline(baseline);
 
-        self.presenter
-            .print_report(&report, self_advisories.as_slice(), &lockfile);
+            if omitted > 0 {
+                report.notices.push(format!(
+                    "omitted {} findings about packages unchanged from the baseline lockfile",
+                    omitted
+                ));
+            }
+        }
+
+        if !members.is_empty() {
+            report.summarize_workspace_members(lockfile, members)?;
+        }
 
         Ok(report)
     }
 
-    /// Load the lockfile to be audited
-    fn load_lockfile(&self, lockfile_path: &Path) -> Result<Lockfile, error::Error> {
+    /// Exit as soon as the first denied finding in the lockfile is
+    /// discovered, skipping report generation entirely
+    fn exit_on_first_finding(&self, lockfile: &Lockfile) -> ! {
+        let new_packages;
+        let lockfile = match &self.baseline {
+            Some(baseline) => {
+                new_packages = without_baseline(lockfile, baseline);
+                &new_packages
+            }
+            None => lockfile,
+        };
+
+        let mut finding = report::find_first(
+            &self.database,
+            lockfile,
+            &self.report_settings,
+            &self.deny_warning_kinds,
+        );
+
+        if finding.is_none() && self.deny_warning_kinds.contains(&warning::Kind::Yanked) {
+            if let Some(index) = &self.registry_index {
+                finding = lockfile
+                    .packages
+                    .iter()
+                    .find(|package| {
+                        index
+                            .find(&package.name, &package.version)
+                            .map(|pkg| pkg.is_yanked)
+                            .unwrap_or(false)
+                    })
+                    .map(|package| {
+                        report::Finding::Warning(Warning::new(
+                            warning::Kind::Yanked,
+                            package,
+                            None,
+                            None,
+                        ))
+                    });
+            }
+        }
+
+        match finding {
+            Some(finding) => {
+                self.presenter.print_first_finding(&finding);
+                exit(1);
+            }
+            None => exit(0),
+        }
+    }
+
+    /// Find the version requirements in `Cargo.toml` which admit releases
+    /// affected by advisories, even though the versions in `Cargo.lock` (if
+    /// it exists) aren't, so their lower bounds can be raised proactively
+    pub fn loose_requirements(
+        &mut self,
+        maybe_lockfile_path: Option<&Path>,
+    ) -> Result<Vec<requirements::LooseRequirement>, error::Error> {
+        let lockfile_path = maybe_lockfile_path.unwrap_or_else(|| Path::new(CARGO_LOCK_FILE));
+
         if lockfile_path == Path::new("-") {
+            return Err(error::Error::new(
+                error::ErrorKind::BadParam,
+                &"--requirements can't be used with a lockfile read from STDIN",
+            ));
+        }
+
+        let lockfile = if lockfile_path.exists() {
+            Some(self.load_lockfile(lockfile_path)?)
+        } else {
+            None
+        };
+
+        let index = requirements_index(
+            self.registry_index.as_ref(),
+            self.no_cargo,
+            "--requirements",
+        )?;
+
+        let requirements = requirements::load(&manifest_path(lockfile_path))?;
+
+        let loose = requirements::find_loose(
+            &requirements,
+            &self.database,
+            index,
+            lockfile.as_ref(),
+            &self.report_settings,
+        )?;
+
+        let notices = std::mem::take(&mut self.notices);
+        self.presenter.print_loose_requirements(&loose, &notices);
+        Ok(loose)
+    }
+
+    /// Compute the windows during which vulnerable dependencies were present
+    /// in the git history of the repository containing the lockfile
+    pub fn exposure(
+        &self,
+        maybe_lockfile_path: Option<&Path>,
+    ) -> Result<Vec<ExposureWindow>, error::Error> {
+        let lockfile_path = maybe_lockfile_path.unwrap_or_else(|| Path::new(CARGO_LOCK_FILE));
+
+        if lockfile_path == Path::new("-") {
+            return Err(error::Error::new(
+                error::ErrorKind::BadParam,
+                &"--exposure can't be used with a lockfile read from STDIN",
+            ));
+        }
+
+        let windows =
+            exposure::exposure_windows(&self.database, lockfile_path, &self.report_settings)?;
+
+        self.presenter.print_exposure_windows(&windows);
+        Ok(windows)
+    }
+
+    /// Load the lockfile to be audited.
+    ///
+    /// Unless strict, malformed entries are skipped and noted in the report,
+    /// where they're also warned about once it's generated.
+    fn load_lockfile(&mut self, lockfile_path: &Path) -> Result<Lockfile, error::Error> {
+        let lockfile_toml = if lockfile_path == Path::new("-") {
             // Read Cargo.lock from STDIN
             let mut lockfile_toml = String::new();
             io::stdin().read_to_string(&mut lockfile_toml)?;
-            Ok(lockfile_toml.parse()?)
+            lockfile_toml
         } else {
-            Ok(Lockfile::load(lockfile_path)?)
+            fs::read_to_string(lockfile_path)?
+        };
+
+        self.parse_lockfile(lockfile_path, &lockfile_toml)
+    }
+
+    /// Parse the lockfile to be audited, which was read from the given path
+    fn parse_lockfile(
+        &mut self,
+        lockfile_path: &Path,
+        lockfile_toml: &str,
+    ) -> Result<Lockfile, error::Error> {
+        if self.strict_lockfile {
+            return Ok(lockfile_toml.parse()?);
+        }
+
+        let (lockfile, skipped) = Lockfile::parse_lenient(lockfile_toml)?;
+
+        for entry in &skipped {
+            self.notices
+                .push(format!("{}: {}", lockfile_path.display(), entry));
+        }
+
+        self.malformed_entries = skipped;
+
+        Ok(lockfile)
+    }
+
+    /// Describe this run of `cargo audit`, for embedding in reports
+    fn tool_info(&self) -> report::ToolInfo {
+        // Skip the program name, along with the `audit` of `cargo audit`
+        let arguments = std::env::args().skip(1).skip_while(|arg| arg == "audit");
+
+        let tool =
+            report::ToolInfo::new("cargo-audit", env!("CARGO_PKG_VERSION")).arguments(arguments);
+
+        if !self.hostname {
+            return tool;
+        }
+
+        match hostname() {
+            Some(hostname) => tool.hostname(hostname),
+            None => tool,
+        }
+    }
+
+    /// Determine the workspace members to summarize vulnerabilities for.
+    ///
+    /// Returns nothing for single-crate projects. Falls back to all local
+    /// packages in the lockfile if `cargo metadata` can't (or mustn't) be run.
+    fn workspace_members(
+        &mut self,
+        lockfile_path: &Path,
+        lockfile: &Lockfile,
+    ) -> Vec<package::Name> {
+        let local_packages = lockfile
+            .packages
+            .iter()
+            .filter(|package| package.source.is_none())
+            .map(|package| package.name.clone())
+            .collect::<Vec<_>>();
+
+        if local_packages.len() < 2 || lockfile_path == Path::new("-") {
+            return vec![];
+        }
+
+        if self.no_cargo {
+            self.notices.push(
+                "workspace members were inferred from Cargo.lock as --no-cargo was passed".into(),
+            );
+            return local_packages;
+        }
+
+        lockfile::workspace_members(&manifest_path(lockfile_path)).unwrap_or(local_packages)
+    }
+
+    /// Determine whether the lockfile is in sync with its `Cargo.toml`.
+    ///
+    /// This runs `cargo metadata --locked`, which resolves the whole
+    /// dependency graph, so it's only done when `--locked` was passed.
+    ///
+    /// Returns `None` if the lockfile has no associated `Cargo.toml` or its
+    /// status wasn't checked (including when `--no-cargo` was passed), and
+    /// errors if the lockfile is out of date or its status couldn't be
+    /// determined.
+    fn check_lockfile_sync(&mut self, lockfile_path: &Path) -> Result<Option<bool>, error::Error> {
+        if lockfile_path == Path::new("-") {
+            return Ok(None);
+        }
+
+        let manifest_path = manifest_path(lockfile_path);
+
+        if !manifest_path.exists() {
+            return Ok(None);
+        }
+
+        if self.no_cargo {
+            self.notices.push(format!(
+                "skipped checking whether {} is in sync with {} as --no-cargo was passed",
+                lockfile_path.display(),
+                manifest_path.display()
+            ));
+            return Ok(None);
+        }
+
+        if lockfile::is_in_sync(&manifest_path)? {
+            Ok(Some(true))
+        } else {
+            Err(error::Error::new(
+                error::ErrorKind::BadParam,
+                &format!(
+                    "{} needs to be updated but --locked was passed",
+                    lockfile_path.display()
+                ),
+            ))
         }
     }
 
@@ -196,7 +1176,7 @@ impl Auditor {
             ("cargo-audit", crate::VERSION),
             ("rustsec", rustsec::VERSION),
         ] {
-            let package: rustsec::package::Name = package_str.parse().unwrap();
+            let package: package::Name = package_str.parse().unwrap();
             let version: rustsec::Version = version_str.parse().unwrap();
             let query = rustsec::database::Query::crate_scope();
 
@@ -211,3 +1191,95 @@ impl Auditor {
         results
     }
 }
+
+/// Get the path to the `Cargo.toml` alongside the given lockfile
+fn manifest_path(lockfile_path: &Path) -> PathBuf {
+    lockfile_path
+        .parent()
+        .map(|dir| dir.join(CARGO_TOML_FILE))
+        .unwrap_or_else(|| CARGO_TOML_FILE.into())
+}
+
+/// Get the crates.io index needed to check the requirements in `Cargo.toml`
+/// for the given option, which also needs to run `cargo metadata`
+fn requirements_index<'a>(
+    registry_index: Option<&'a registry::Index>,
+    no_cargo: bool,
+    option: &str,
+) -> Result<&'a registry::Index, error::Error> {
+    if no_cargo {
+        return Err(error::Error::new(
+            error::ErrorKind::BadParam,
+            &format!(
+                "{} needs to run `cargo metadata`, but --no-cargo was passed",
+                option
+            ),
+        ));
+    }
+
+    registry_index.ok_or_else(|| {
+        error::Error::new(
+            error::ErrorKind::NotFound,
+            &format!("{} needs the crates.io index, which is unavailable", option),
+        )
+    })
+}
+
+/// Get a copy of the given lockfile without the packages in the baseline
+fn without_baseline(lockfile: &Lockfile, baseline: &Lockfile) -> Lockfile {
+    let mut result = lockfile.clone();
+    result.packages.retain(|package| {
+        !baseline.packages.iter().any(|old| {
+            old.name == package.name
+                && old.version == package.version
+                && old.source == package.source
+        })
+    });
+    result
+}
+
+/// Get the name of the host we're running on, if it can be determined
+fn hostname() -> Option<String> {
+    let hostname = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .or_else(|_| fs::read_to_string("/etc/hostname"))
+        .ok()?;
+
+    let hostname = hostname.trim();
+
+    if hostname.is_empty() {
+        None
+    } else {
+        Some(hostname.to_owned())
+    }
+}
+
+/// Get the path the advisory database is stored at according to the given
+/// configuration
+fn database_path(config: &AuditConfig) -> PathBuf {
+    config.database.path.as_ref().cloned().unwrap_or_else(|| {
+        if config.database.mirror.is_some() {
+            Manifest::default_path()
+        } else {
+            rustsec::repository::git::Repository::default_path()
+        }
+    })
+}
+
+/// Get the total size in bytes of the files in the given directory tree
+fn directory_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            size += directory_size(&entry.path())?;
+        } else if file_type.is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+
+    Ok(size)
+}
@@ -0,0 +1,171 @@
+//! Status badges summarizing audit results.
+//!
+//! Renders a [shields.io]-style SVG badge (e.g. `audit | passing`) from a
+//! [`Report`], which can be committed or published by a scheduled job and
+//! embedded in a README without relying on any third-party service.
+//!
+//! [shields.io]: https://shields.io
+
+use rustsec::{advisory::Severity, Report};
+
+/// Label shown on the left-hand side of badges
+pub const LABEL: &str = "audit";
+
+/// Color of the label
+const LABEL_COLOR: &str = "#555";
+
+/// Color of badges for audits without any vulnerabilities
+const PASSING_COLOR: &str = "#4c1";
+
+/// Horizontal padding around the text on each side of a badge
+const PADDING: usize = 10;
+
+/// Status badge for an audit
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Badge {
+    /// Message shown on the right-hand side (e.g. `2 vulnerabilities`)
+    pub message: String,
+
+    /// Background color of the message
+    pub color: &'static str,
+}
+
+impl Badge {
+    /// Create a badge for the given number of vulnerabilities, colored by
+    /// the highest severity among them (if known)
+    pub fn new(vulnerabilities: usize, highest_severity: Option<Severity>) -> Self {
+        if vulnerabilities == 0 {
+            return Self {
+                message: "passing".to_owned(),
+                color: PASSING_COLOR,
+            };
+        }
+
+        let message = match vulnerabilities {
+            1 => "1 vulnerability".to_owned(),
+            n => format!("{} vulnerabilities", n),
+        };
+
+        // Vulnerabilities of unknown severity are treated as critical
+        let color = match highest_severity {
+            Some(Severity::None) => "#9f9f9f",
+            Some(Severity::Low) => "#a4a61d",
+            Some(Severity::Medium) => "#dfb317",
+            Some(Severity::High) => "#fe7d37",
+            Some(Severity::Critical) | None => "#e05d44",
+        };
+
+        Self { message, color }
+    }
+
+    /// Create a badge summarizing the vulnerabilities in the given report
+    pub fn from_report(report: &Report) -> Self {
+        let vulnerabilities = &report.vulnerabilities.list;
+        let severities = vulnerabilities
+            .iter()
+            .map(|vuln| vuln.severity.map(|info| info.severity))
+            .collect::<Vec<_>>();
+
+        // Any vulnerability of unknown severity makes the highest one unknown
+        let highest_severity = if severities.iter().any(Option::is_none) {
+            None
+        } else {
+            severities.into_iter().flatten().max()
+        };
+
+        Self::new(vulnerabilities.len(), highest_severity)
+    }
+
+    /// Render the badge as an SVG image
+    pub fn to_svg(&self) -> String {
+        let label_width = text_width(LABEL) + 2 * PADDING;
+        let message_width = text_width(&self.message) + 2 * PADDING;
+        let width = label_width + message_width;
+        let title = format!("{}: {}", LABEL, self.message);
+
+        // Text is positioned at its center, with a slightly offset shadow
+        let label_x = label_width as f64 / 2.0;
+        let message_x = label_width as f64 + message_width as f64 / 2.0;
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{title}">
+  <title>{title}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r">
+    <rect width="{width}" height="20" rx="3" fill="#fff"/>
+  </clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="{label_color}"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+    <text x="{message_x}" y="14">{message}</text>
+  </g>
+</svg>
+"##,
+            width = width,
+            title = title,
+            label_width = label_width,
+            label_color = LABEL_COLOR,
+            message_width = message_width,
+            color = self.color,
+            label_x = label_x,
+            label = LABEL,
+            message_x = message_x,
+            message = self.message,
+        )
+    }
+}
+
+/// Approximate the width in pixels of the given text in 11px Verdana
+fn text_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | '!' | '|' => 3,
+            'f' | 'r' | 't' | ' ' => 4,
+            'm' | 'w' | 'M' | 'W' => 10,
+            c if c.is_ascii_uppercase() => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Badge, PASSING_COLOR};
+    use rustsec::advisory::Severity;
+
+    #[test]
+    fn passing() {
+        let badge = Badge::new(0, None);
+        assert_eq!(badge.message, "passing");
+        assert_eq!(badge.color, PASSING_COLOR);
+
+        let svg = badge.to_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("aria-label=\"audit: passing\""));
+    }
+
+    #[test]
+    fn vulnerable() {
+        assert_eq!(
+            Badge::new(1, Some(Severity::Medium)),
+            Badge {
+                message: "1 vulnerability".to_owned(),
+                color: "#dfb317",
+            }
+        );
+
+        let badge = Badge::new(3, Some(Severity::Critical));
+        assert_eq!(badge.message, "3 vulnerabilities");
+        assert_eq!(badge.color, Badge::new(3, None).color);
+        assert!(badge.to_svg().contains(">3 vulnerabilities</text>"));
+    }
+}
@@ -23,26 +23,7 @@ pub enum CargoAuditCommand {
 impl Configurable<AuditConfig> for CargoAuditCommand {
     /// Location of `audit.toml` (if it exists)
     fn config_path(&self) -> Option<PathBuf> {
-        // Check if the config file exists, and if it does not, ignore it.
-        //
-        // The order of precedence for which config file to use is:
-        // 1. The current project's `.cargo` configuration directory.
-        // 2. The current user's home directory configuration.
-
-        let project_config_filename = PathBuf::from("./.cargo").join(CONFIG_FILE);
-        if project_config_filename.exists() {
-            return Some(project_config_filename);
-        }
-
-        let home_config_filename = home::cargo_home()
-            .ok()
-            .map(|cargo_home| cargo_home.join(CONFIG_FILE))?;
-
-        if home_config_filename.exists() {
-            Some(home_config_filename)
-        } else {
-            None
-        }
+        config_path()
     }
 
     /// Override loaded config with explicit command-line arguments
@@ -53,6 +34,30 @@ impl Configurable<AuditConfig> for CargoAuditCommand {
     }
 }
 
+/// Location of `audit.toml` (if it exists)
+pub fn config_path() -> Option<PathBuf> {
+    // Check if the config file exists, and if it does not, ignore it.
+    //
+    // The order of precedence for which config file to use is:
+    // 1. The current project's `.cargo` configuration directory.
+    // 2. The current user's home directory configuration.
+
+    let project_config_filename = PathBuf::from("./.cargo").join(CONFIG_FILE);
+    if project_config_filename.exists() {
+        return Some(project_config_filename);
+    }
+
+    let home_config_filename = home::cargo_home()
+        .ok()
+        .map(|cargo_home| cargo_home.join(CONFIG_FILE))?;
+
+    if home_config_filename.exists() {
+        Some(home_config_filename)
+    } else {
+        None
+    }
+}
+
 impl Deref for CargoAuditCommand {
     type Target = AuditCommand;
 
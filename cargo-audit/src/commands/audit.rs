@@ -1,28 +1,46 @@
 //! The `cargo audit` subcommand
 
+mod badge;
+mod completions;
+mod daemon;
+mod db;
 #[cfg(feature = "fix")]
 mod fix;
+mod ignore_check;
+mod manpage;
+mod usage;
 
 use super::CargoAuditCommand;
 use crate::{
     auditor::Auditor,
-    config::{AuditConfig, DenyOption, OutputFormat},
+    config::{env_flag, env_vars, AuditConfig, DenyOption, DescriptionMode, OutputFormat},
+    lockfile,
     prelude::*,
 };
 use abscissa_core::{config::Override, terminal::ColorChoice, FrameworkError};
 use gumdrop::Options;
-use rustsec::database::scope;
-use rustsec::platforms::target::{Arch, OS};
-use std::{path::PathBuf, process::exit};
+use rustsec::platforms::{
+    target::{Arch, OS},
+    Platform,
+};
+use rustsec::{advisory::Date, database::scope, report};
+use std::{env, path::PathBuf, process::exit};
+
+use self::{
+    badge::BadgeCommand, completions::CompletionsCommand, daemon::DaemonCommand, db::DbCommand,
+    ignore_check::IgnoreCheckCommand, manpage::ManpageCommand,
+};
 
 #[cfg(feature = "fix")]
 use self::fix::FixCommand;
 
 /// The `cargo audit` subcommand
+///
+/// Flags and subcommands also need to be listed in `usage.rs`, which shell
+/// completions and the manpage are generated from.
 #[derive(Command, Default, Debug, Options)]
 pub struct AuditCommand {
-    /// Optional subcommand (used for `cargo audit fix`)
-    #[cfg(feature = "fix")]
+    /// Optional subcommand (e.g. `cargo audit fix`)
     #[options(command)]
     subcommand: Option<AuditSubcommand>,
 
@@ -38,22 +56,32 @@ pub struct AuditCommand {
     #[options(
         short = "c",
         long = "color",
-        help = "color configuration: always, never (default: auto)"
+        meta = "WHEN",
+        help = "color configuration: auto, always, never (default: auto)"
     )]
     color: Option<String>,
 
     /// Filesystem path to the advisory database git repository
     #[options(
         long = "db",
-        help = "advisory database git repo path (default: ~/.cargo/advisory-db)"
+        help = "advisory database path, either a git repo or a plain (e.g. vendored) directory (default: ~/.cargo/advisory-db)"
     )]
     db: Option<PathBuf>,
 
+    /// Audit against the advisory database as of a past date
+    #[options(
+        no_short,
+        long = "db-as-of",
+        meta = "YYYY-MM-DD",
+        help = "only consider advisories published on or before this date"
+    )]
+    db_as_of: Option<Date>,
+
     /// Deny flag
     #[options(
         short = "D",
         long = "deny",
-        help = "exit with an error on: warnings (any), unmaintained, unsound, yanked"
+        help = "exit with an error on: warnings (any), unmaintained, unsound, yanked, duplicate, own-crate, malformed-lockfile"
     )]
     deny: Vec<DenyOption>,
 
@@ -73,15 +101,67 @@ pub struct AuditCommand {
     )]
     file: Option<PathBuf>,
 
-    /// Advisory IDs to ignore
+    /// Path to a CycloneDX or SPDX SBOM
+    #[options(
+        no_short,
+        long = "sbom",
+        meta = "PATH",
+        help = "audit packages listed in a CycloneDX or SPDX JSON SBOM"
+    )]
+    sbom: Option<PathBuf>,
+
+    /// Path to a `cargo vendor` directory
+    #[options(
+        no_short,
+        long = "vendor",
+        meta = "PATH",
+        help = "audit crates in a `cargo vendor` directory"
+    )]
+    vendor: Option<PathBuf>,
+
+    /// Audit the minimum versions allowed by `Cargo.toml`
+    #[options(
+        no_short,
+        long = "minimal-versions",
+        help = "audit the minimum versions of direct dependencies allowed by Cargo.toml rather than those in Cargo.lock"
+    )]
+    minimal_versions: bool,
+
+    /// Advisory IDs (or patterns) to ignore
     #[options(
         no_short,
         long = "ignore",
         meta = "ADVISORY_ID",
-        help = "Advisory id to ignore (can be specified multiple times)"
+        help = "Advisory id or pattern (e.g. RUSTSEC-2019-*, package:foo-*) to ignore (can be specified multiple times)"
     )]
     ignore: Vec<String>,
 
+    /// Advisory IDs to restrict the audit to
+    #[options(
+        no_short,
+        long = "only",
+        meta = "ADVISORY_ID",
+        help = "only check for the advisory with this id, ignoring all others (can be specified multiple times)"
+    )]
+    only: Vec<String>,
+
+    /// Git sources of patched forks whose vulnerabilities are accepted
+    #[options(
+        no_short,
+        long = "ignore-source",
+        meta = "GIT_URL",
+        help = "accept vulnerabilities in crates from a patched fork at the given git URL, optionally pinned to URL#REV (can be specified multiple times)"
+    )]
+    ignore_source: Vec<String>,
+
+    /// Report vulnerabilities in crates overridden via `[patch]` or `[replace]` as warnings
+    #[options(
+        no_short,
+        long = "overrides-as-warnings",
+        help = "report vulnerabilities in crates overridden via [patch] or [replace] as warnings"
+    )]
+    overrides_as_warnings: bool,
+
     /// Skip fetching the advisory database git repository
     #[options(
         short = "n",
@@ -94,6 +174,14 @@ pub struct AuditCommand {
     #[options(no_short, long = "stale", help = "allow stale database")]
     stale: bool,
 
+    /// Don't re-clone the advisory database if it's corrupted or diverged
+    #[options(
+        no_short,
+        long = "no-repair",
+        help = "fail instead of re-cloning a corrupted or diverged advisory DB"
+    )]
+    no_repair: bool,
+
     /// Target CPU architecture to find vulnerabilities for
     #[options(
         no_short,
@@ -110,10 +198,71 @@ pub struct AuditCommand {
     )]
     target_os: Option<OS>,
 
+    /// Target triples to find vulnerabilities for
+    #[options(
+        no_short,
+        long = "target",
+        meta = "TRIPLE",
+        help = "filter vulnerabilities by target triple (can be specified multiple times)"
+    )]
+    target: Vec<String>,
+
+    /// CPU architectures to ignore advisories for
+    #[options(
+        no_short,
+        long = "exclude-target-arch",
+        meta = "ARCH",
+        help = "ignore vulnerabilities which only affect this CPU, taking precedence over --target-arch (can be specified multiple times)"
+    )]
+    exclude_target_arch: Vec<Arch>,
+
+    /// Operating systems to ignore advisories for
+    #[options(
+        no_short,
+        long = "exclude-target-os",
+        meta = "OS",
+        help = "ignore vulnerabilities which only affect this OS, taking precedence over --target-os (can be specified multiple times)"
+    )]
+    exclude_target_os: Vec<OS>,
+
     /// URL to the advisory database git repository
     #[options(short = "u", long = "url", help = "URL for advisory database git repo")]
     url: Option<String>,
 
+    /// URL to a static file mirror of the advisory database
+    #[options(
+        no_short,
+        long = "mirror",
+        meta = "URL",
+        help = "URL for a static file mirror of the advisory DB (instead of git)"
+    )]
+    mirror: Option<String>,
+
+    /// Expected digest of the mirror's manifest
+    #[options(
+        no_short,
+        long = "mirror-digest",
+        meta = "SHA256",
+        help = "expected digest of the mirror's manifest (printed by `rustsec-admin mirror`)"
+    )]
+    mirror_digest: Option<String>,
+
+    /// Warn about packages present in several versions
+    #[options(
+        no_short,
+        long = "duplicates",
+        help = "warn about crates present in several versions in Cargo.lock"
+    )]
+    duplicates: bool,
+
+    /// Use the advisory database embedded at build time
+    #[options(
+        no_short,
+        long = "embedded-db",
+        help = "use the advisory DB snapshot embedded in this binary (requires the embedded-db feature)"
+    )]
+    embedded_db: bool,
+
     /// Quiet mode - avoids printing extraneous information
     #[options(
         short = "q",
@@ -122,10 +271,170 @@ pub struct AuditCommand {
     )]
     quiet: bool,
 
+    /// Verbose mode - show full details for each finding
+    #[options(
+        short = "v",
+        long = "verbose",
+        help = "show full details and dependency trees for each finding, even with --condensed"
+    )]
+    verbose: bool,
+
+    /// Condensed mode - show a single line for each finding
+    #[options(
+        no_short,
+        long = "condensed",
+        help = "show a single line for each finding rather than its full details"
+    )]
+    condensed: bool,
+
+    /// Stop at the first denied finding
+    #[options(
+        no_short,
+        long = "exit-on-first",
+        help = "exit as soon as the first denied finding is discovered"
+    )]
+    exit_on_first: bool,
+
     /// Output reports as JSON
     #[options(no_short, long = "json", help = "Output report in JSON format")]
     output_json: bool,
 
+    /// Output format
+    #[options(
+        no_short,
+        long = "format",
+        meta = "FORMAT",
+        help = "output format: terminal, json, attestation (default: terminal)"
+    )]
+    format: Option<OutputFormat>,
+
+    /// Require `Cargo.lock` to be up-to-date
+    #[options(
+        no_short,
+        long = "locked",
+        help = "Require Cargo.lock to be up-to-date with Cargo.toml"
+    )]
+    locked: bool,
+
+    /// Never invoke `cargo`
+    #[options(
+        no_short,
+        long = "no-cargo",
+        help = "never invoke cargo, skipping checks which require it"
+    )]
+    no_cargo: bool,
+
+    /// Never execute external processes
+    #[options(
+        no_short,
+        long = "no-exec",
+        help = "never execute external processes (e.g. cargo), failing instead of doing so"
+    )]
+    no_exec: bool,
+
+    /// Fail on malformed entries in `Cargo.lock`
+    #[options(
+        no_short,
+        long = "strict-lockfile",
+        help = "fail on malformed entries in Cargo.lock rather than skipping them"
+    )]
+    strict_lockfile: bool,
+
+    /// Only report findings which are new relative to a baseline lockfile
+    #[options(
+        no_short,
+        long = "baseline",
+        meta = "PATH",
+        help = "only report findings for packages added or changed since this baseline Cargo.lock"
+    )]
+    baseline: Option<PathBuf>,
+
+    /// Audit all of the lockfiles in a directory tree
+    #[options(
+        no_short,
+        long = "recursive",
+        meta = "DIR",
+        help = "audit every Cargo.lock under this directory, producing one aggregated report"
+    )]
+    recursive: Option<PathBuf>,
+
+    /// File to append a summary of the audit to
+    #[options(
+        no_short,
+        long = "history-file",
+        meta = "PATH",
+        help = "append a summary of the audit to this newline-delimited JSON file"
+    )]
+    history_file: Option<PathBuf>,
+
+    /// Show timing and cache diagnostics
+    #[options(
+        no_short,
+        long = "stats",
+        help = "show timing and cache diagnostics (also embedded in JSON reports)"
+    )]
+    stats: bool,
+
+    /// Show advisory descriptions
+    #[options(
+        no_short,
+        long = "show-descriptions",
+        meta = "MODE",
+        help = "show advisory descriptions unless condensed: full, truncated, none (default: none)"
+    )]
+    show_descriptions: Option<DescriptionMode>,
+
+    /// Number of lines to truncate descriptions to
+    #[options(
+        no_short,
+        long = "description-lines",
+        meta = "COUNT",
+        help = "number of lines truncated descriptions are limited to (default: 5)"
+    )]
+    description_lines: Option<usize>,
+
+    /// Naming style of JSON report fields
+    #[options(
+        no_short,
+        long = "json-field-naming",
+        meta = "STYLE",
+        help = "field names in JSON reports: legacy, kebab-case, snake-case (default: legacy)"
+    )]
+    json_field_naming: Option<report::FieldNaming>,
+
+    /// Report the slowest advisory files to parse
+    #[options(
+        no_short,
+        long = "slow-parse-report",
+        meta = "COUNT",
+        help = "list the COUNT advisory files which took longest to parse"
+    )]
+    slow_parse_report: Option<usize>,
+
+    /// Include the hostname in JSON reports
+    #[options(
+        no_short,
+        long = "include-hostname",
+        help = "include the name of the host the audit ran on in JSON reports"
+    )]
+    include_hostname: bool,
+
+    /// Report when vulnerable dependencies were present in git history
+    #[options(
+        no_short,
+        long = "exposure",
+        help = "report the git commits during which vulnerable dependencies were present"
+    )]
+    exposure: bool,
+
+    /// Report `Cargo.toml` requirements which admit vulnerable releases
+    #[options(
+        no_short,
+        long = "requirements",
+        help = "report Cargo.toml version requirements which admit vulnerable releases, even if the locked versions are fine"
+    )]
+    requirements: bool,
+
     /// Vulnerability querying does not consider local crates
     #[options(
         no_short,
@@ -136,27 +445,71 @@ pub struct AuditCommand {
 }
 
 /// Subcommands of `cargo audit`
-#[cfg(feature = "fix")]
 #[derive(Command, Debug, Options, Runnable)]
 pub enum AuditSubcommand {
     /// `cargo audit fix` subcommand
+    #[cfg(feature = "fix")]
     #[options(help = "automatically upgrade vulnerable dependencies")]
     Fix(FixCommand),
+
+    /// `cargo audit badge` subcommand
+    #[options(help = "generate an SVG badge summarizing the audit")]
+    Badge(BadgeCommand),
+
+    /// `cargo audit completions` subcommand
+    #[options(help = "generate shell completions (bash, zsh, fish)")]
+    Completions(CompletionsCommand),
+
+    /// `cargo audit daemon` subcommand
+    #[options(help = "serve an HTTP API which audits lockfiles against an in-memory database")]
+    Daemon(DaemonCommand),
+
+    /// `cargo audit db` subcommand
+    #[options(help = "inspect the advisory database (e.g. recent changes)")]
+    Db(DbCommand),
+
+    /// `cargo audit ignore-check` subcommand
+    #[options(help = "report ignored advisories which no longer affect the lockfile")]
+    IgnoreCheck(IgnoreCheckCommand),
+
+    /// `cargo audit manpage` subcommand
+    #[options(help = "generate the cargo-audit(1) manpage")]
+    Manpage(ManpageCommand),
 }
 
 impl AuditCommand {
-    /// Get the color configuration
+    /// Get the color configuration, falling back to `CARGO_TERM_COLOR` if
+    /// `--color` isn't given
     pub fn color_config(&self) -> Option<ColorChoice> {
-        self.color.as_ref().map(|colors| match colors.as_ref() {
+        let color = self
+            .color
+            .clone()
+            .or_else(|| env::var("CARGO_TERM_COLOR").ok())?;
+
+        let choice = match color.as_ref() {
+            "auto" => ColorChoice::Auto,
             "always" => ColorChoice::Always,
             "never" => ColorChoice::Never,
-            _ => panic!("invalid color choice setting: {}", &colors),
-        })
+            other => {
+                eprintln!(
+                    "error: invalid color choice: {} (expected auto, always, or never)",
+                    other
+                );
+                exit(1);
+            }
+        };
+
+        Some(choice)
     }
 }
 
 impl Override<AuditConfig> for AuditCommand {
     fn override_config(&self, mut config: AuditConfig) -> Result<AuditConfig, FrameworkError> {
+        config.apply_env().unwrap_or_else(|e| {
+            status_err!("{}", e);
+            exit(1);
+        });
+
         if let Some(db) = &self.db {
             config.database.path = Some(db.into());
         }
@@ -171,8 +524,35 @@ impl Override<AuditConfig> for AuditCommand {
                 }));
         }
 
-        config.database.fetch |= !self.no_fetch;
+        for advisory_id in &self.only {
+            config
+                .advisories
+                .only
+                .push(advisory_id.parse().unwrap_or_else(|e| {
+                    status_err!("error parsing {}: {}", advisory_id, e);
+                    exit(1);
+                }));
+        }
+
+        config.advisories.overrides_as_warnings |= self.overrides_as_warnings;
+
+        for source in &self.ignore_source {
+            config
+                .advisories
+                .patched_forks
+                .push(source.parse().unwrap_or_else(|e| {
+                    status_err!("error parsing {}: {}", source, e);
+                    exit(1);
+                }));
+        }
+
+        if let Some(as_of) = &self.db_as_of {
+            config.database.as_of = Some(as_of.clone());
+        }
+
+        config.database.fetch |= !(self.no_fetch || env_flag(env_vars::NO_FETCH));
         config.database.stale |= self.stale;
+        config.database.no_repair |= self.no_repair;
 
         if let Some(target_arch) = self.target_arch {
             config.target.arch = Some(target_arch);
@@ -182,10 +562,38 @@ impl Override<AuditConfig> for AuditCommand {
             config.target.os = Some(target_os);
         }
 
+        config.target.triples.extend(self.target.iter().cloned());
+        config
+            .target
+            .exclude_arch
+            .extend(self.exclude_target_arch.iter().cloned());
+        config
+            .target
+            .exclude_os
+            .extend(self.exclude_target_os.iter().cloned());
+
+        for triple in &config.target.triples {
+            if Platform::find(triple).is_none() {
+                status_err!("unknown target triple: {}", triple);
+                exit(1);
+            }
+        }
+
         if let Some(url) = &self.url {
             config.database.url = Some(url.clone())
         }
 
+        if let Some(mirror) = &self.mirror {
+            config.database.mirror = Some(mirror.clone())
+        }
+
+        if let Some(digest) = &self.mirror_digest {
+            config.database.mirror_digest = Some(digest.clone())
+        }
+
+        config.database.embedded |= self.embedded_db;
+        config.packages.duplicates |= self.duplicates;
+
         if self.deny_warnings {
             // TODO(tarcieri): remove this in the next release of cargo-audit
             status_warn!("the --deny-warnings flag is deprecated. Please use: --deny warnings");
@@ -201,11 +609,54 @@ impl Override<AuditConfig> for AuditCommand {
         }
 
         config.output.quiet |= self.quiet;
+        config.output.verbose |= self.verbose;
+        config.output.condensed |= self.condensed;
+        config.output.exit_on_first |= self.exit_on_first;
+        config.output.stats |= self.stats;
+        config.output.hostname |= self.include_hostname;
+
+        if let Some(mode) = self.show_descriptions {
+            config.output.show_descriptions = mode;
+        }
+
+        if let Some(lines) = self.description_lines {
+            config.output.description_lines = Some(lines);
+        }
+
+        if let Some(naming) = self.json_field_naming {
+            config.output.field_naming = naming;
+        }
+
+        if let Some(count) = self.slow_parse_report {
+            config.output.slow_parse_report = Some(count);
+        }
+
+        if let Some(history_file) = &self.history_file {
+            config.output.history_file = Some(history_file.clone());
+        }
+
+        if let Some(format) = self.format {
+            config.output.format = format;
+        }
 
         if self.output_json {
             config.output.format = OutputFormat::Json;
         }
 
+        // Attestations are about a single lockfile
+        if self.recursive.is_some() && config.output.format == OutputFormat::Attestation {
+            status_err!("--recursive can't be used with the attestation output format");
+            exit(1);
+        }
+
+        config.lockfile.locked |= self.locked;
+        config.lockfile.no_cargo |= self.no_cargo;
+        config.lockfile.no_exec |= self.no_exec;
+        config.lockfile.strict |= self.strict_lockfile;
+
+        // Checks which need `cargo` are skipped, rather than failing
+        config.lockfile.no_cargo |= config.lockfile.no_exec;
+
         if self.no_local_crates {
             config.packages.source = Some(scope::Registry::Public)
         }
@@ -216,13 +667,13 @@ impl Override<AuditConfig> for AuditCommand {
 
 impl Runnable for AuditCommand {
     fn run(&self) {
-        #[cfg(feature = "fix")]
-        match &self.subcommand {
-            Some(AuditSubcommand::Fix(fix)) => {
-                fix.run();
-                exit(0)
-            }
-            None => (),
+        if app_config().lockfile.no_exec {
+            lockfile::forbid_exec();
+        }
+
+        if let Some(subcommand) = &self.subcommand {
+            subcommand.run();
+            exit(0)
         }
 
         if self.help {
@@ -235,7 +686,53 @@ impl Runnable for AuditCommand {
         }
 
         let lockfile_path = self.file.as_deref();
-        let report = self.auditor().audit(lockfile_path);
+
+        if self.exposure {
+            match self.auditor().exposure(lockfile_path) {
+                Ok(windows) => exit(if windows.iter().any(|w| w.resolved.is_none()) {
+                    1
+                } else {
+                    0
+                }),
+                Err(e) => {
+                    status_err!("{}", e);
+                    exit(2);
+                }
+            }
+        }
+
+        if self.requirements {
+            match self.auditor().loose_requirements(lockfile_path) {
+                Ok(loose) => exit(if loose.is_empty() { 0 } else { 1 }),
+                Err(e) => {
+                    status_err!("{}", e);
+                    exit(2);
+                }
+            }
+        }
+
+        if let Some(dir) = &self.recursive {
+            match self.auditor().audit_recursive(dir) {
+                Ok(aggregate) => exit(if aggregate.summary.vulnerabilities > 0 {
+                    1
+                } else {
+                    0
+                }),
+                Err(e) => {
+                    status_err!("{}", e);
+                    exit(2);
+                }
+            }
+        }
+
+        let report = match (&self.sbom, &self.vendor) {
+            (Some(sbom_path), _) => self.auditor().audit_sbom(sbom_path),
+            (None, Some(vendor_path)) => self.auditor().audit_vendor(vendor_path),
+            (None, None) if self.minimal_versions => {
+                self.auditor().audit_minimal_versions(lockfile_path)
+            }
+            (None, None) => self.auditor().audit(lockfile_path),
+        };
 
         match report {
             Ok(report) => {
@@ -256,6 +753,15 @@ impl AuditCommand {
     /// Initialize `Auditor`
     pub fn auditor(&self) -> Auditor {
         let config = app_config();
-        Auditor::new(&config)
+        let mut auditor = Auditor::new(&config);
+
+        if let Some(baseline_path) = &self.baseline {
+            auditor.set_baseline(baseline_path).unwrap_or_else(|e| {
+                status_err!("{}", e);
+                exit(2);
+            });
+        }
+
+        auditor
     }
 }
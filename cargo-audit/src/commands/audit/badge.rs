@@ -0,0 +1,57 @@
+//! The `cargo audit badge` subcommand
+
+use crate::{auditor::Auditor, badge::Badge, config::AuditConfig, prelude::*};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use std::{fs, path::PathBuf, process::exit};
+
+#[derive(Command, Default, Debug, Options)]
+pub struct BadgeCommand {
+    /// Get help information
+    #[options(short = "h", long = "help", help = "output help information and exit")]
+    help: bool,
+
+    /// Path to `Cargo.lock`
+    #[options(short = "f", long = "file", help = "Cargo lockfile to inspect")]
+    file: Option<PathBuf>,
+
+    /// Path to write the badge to
+    #[options(
+        short = "o",
+        long = "out",
+        meta = "PATH",
+        help = "file to write the SVG badge to (default: STDOUT)"
+    )]
+    out: Option<PathBuf>,
+}
+
+impl Runnable for BadgeCommand {
+    fn run(&self) {
+        if self.help {
+            Self::print_usage_and_exit(&[]);
+        }
+
+        let mut config = AuditConfig::clone(&app_config());
+
+        // The badge needs the full report, and it's the only output
+        config.output.exit_on_first = false;
+        config.output.quiet = true;
+
+        let report = Auditor::new(&config)
+            .report(self.file.as_deref())
+            .unwrap_or_else(|e| {
+                status_err!("{}", e);
+                exit(2);
+            });
+
+        let svg = Badge::from_report(&report).to_svg();
+
+        match &self.out {
+            Some(path) => fs::write(path, svg).unwrap_or_else(|e| {
+                status_err!("couldn't write badge to {}: {}", path.display(), e);
+                exit(1);
+            }),
+            None => print!("{}", svg),
+        }
+    }
+}
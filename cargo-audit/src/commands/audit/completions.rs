@@ -0,0 +1,176 @@
+//! The `cargo audit completions` subcommand
+
+use super::usage::{self, Flag, Subcommand, FLAGS};
+use crate::prelude::*;
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use std::process::exit;
+
+/// Name of the binary completions are generated for
+const BIN_NAME: &str = "cargo-audit";
+
+#[derive(Command, Default, Debug, Options)]
+pub struct CompletionsCommand {
+    /// Get help information
+    #[options(short = "h", long = "help", help = "output help information and exit")]
+    help: bool,
+
+    /// Shell to generate completions for
+    #[options(free, help = "shell to generate completions for: bash, zsh, fish")]
+    shell: Option<String>,
+}
+
+impl Runnable for CompletionsCommand {
+    fn run(&self) {
+        if self.help {
+            Self::print_usage_and_exit(&[]);
+        }
+
+        let commands = usage::subcommands();
+
+        let script = match self.shell.as_deref() {
+            Some("bash") => bash(FLAGS, &commands),
+            Some("zsh") => zsh(FLAGS, &commands),
+            Some("fish") => fish(FLAGS, &commands),
+            Some(other) => {
+                status_err!("unsupported shell: {} (expected bash, zsh, or fish)", other);
+                exit(1);
+            }
+            None => {
+                status_err!("no shell given (expected bash, zsh, or fish)");
+                exit(1);
+            }
+        };
+
+        print!("{}", script);
+    }
+}
+
+/// Generate completions for bash
+fn bash(flags: &[Flag], commands: &[Subcommand]) -> String {
+    let mut words = commands
+        .iter()
+        .map(|command| command.name.to_owned())
+        .collect::<Vec<_>>();
+
+    for flag in flags {
+        words.extend(flag.short.map(|short| format!("-{}", short)));
+        words.push(format!("--{}", flag.long));
+    }
+
+    let value_flags = flags
+        .iter()
+        .filter(|flag| flag.meta.is_some())
+        .flat_map(|flag| {
+            let short = flag.short.map(|short| format!("-{}", short));
+            short.into_iter().chain(Some(format!("--{}", flag.long)))
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        r#"_cargo_audit() {{
+    local cur prev
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+
+    case "$prev" in
+        {value_flags})
+            COMPREPLY=( $(compgen -f -- "$cur") )
+            return 0
+            ;;
+    esac
+
+    COMPREPLY=( $(compgen -W "{words}" -- "$cur") )
+}}
+
+complete -F _cargo_audit {bin}
+"#,
+        value_flags = value_flags.join("|"),
+        words = words.join(" "),
+        bin = BIN_NAME
+    )
+}
+
+/// Generate completions for zsh
+fn zsh(flags: &[Flag], commands: &[Subcommand]) -> String {
+    let mut out = format!(
+        "#compdef {}\n\n_cargo_audit() {{\n    _arguments \\\n",
+        BIN_NAME
+    );
+
+    for flag in flags {
+        let help = zsh_escape(flag.help);
+        let value = flag
+            .meta
+            .map(|meta| format!(":{}:_files", meta.to_lowercase()))
+            .unwrap_or_default();
+
+        let names = match flag.short {
+            Some(short) => format!("'(-{0} --{1})'{{-{0},--{1}}}'", short, flag.long),
+            None => format!("'--{}", flag.long),
+        };
+
+        out.push_str(&format!("        {}[{}]{}' \\\n", names, help, value));
+    }
+
+    if !commands.is_empty() {
+        let commands = commands
+            .iter()
+            .map(|command| {
+                let help = command.help.replace('"', "").replace('\'', "'\\''");
+                format!("{}\\:\"{}\"", command.name, help)
+            })
+            .collect::<Vec<_>>();
+
+        out.push_str(&format!("        '1: :(({}))' \\\n", commands.join(" ")));
+    }
+
+    out.push_str("        && return 0\n}\n\n_cargo_audit \"$@\"\n");
+    out
+}
+
+/// Escape text for use in a zsh `_arguments` description
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+/// Generate completions for fish
+fn fish(flags: &[Flag], commands: &[Subcommand]) -> String {
+    let mut out = String::new();
+
+    for command in commands {
+        out.push_str(&format!(
+            "complete -c {} -n '__fish_use_subcommand' -f -a {} -d '{}'\n",
+            BIN_NAME,
+            command.name,
+            fish_escape(command.help)
+        ));
+    }
+
+    for flag in flags {
+        let mut line = format!("complete -c {}", BIN_NAME);
+
+        if let Some(short) = flag.short {
+            line.push_str(&format!(" -s {}", short));
+        }
+
+        line.push_str(&format!(" -l {}", flag.long));
+
+        if flag.meta.is_some() {
+            line.push_str(" -r");
+        }
+
+        line.push_str(&format!(" -d '{}'\n", fish_escape(flag.help)));
+        out.push_str(&line);
+    }
+
+    out
+}
+
+/// Escape text for use in a single-quoted fish string
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
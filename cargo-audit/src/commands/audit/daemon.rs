@@ -0,0 +1,85 @@
+//! The `cargo audit daemon` subcommand
+
+use crate::{
+    auditor::{Auditor, LoadError},
+    config::AuditConfig,
+    daemon::Daemon,
+    prelude::*,
+};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use rustsec::{database::LoadProfile, report, Database};
+use std::process::exit;
+
+/// Address to listen on by default
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:7878";
+
+/// How often the advisory database is refreshed by default
+const DEFAULT_REFRESH_INTERVAL: &str = "1h";
+
+/// The `cargo audit daemon` subcommand
+#[derive(Command, Default, Debug, Options)]
+pub struct DaemonCommand {
+    /// Get help information
+    #[options(short = "h", long = "help", help = "output help information and exit")]
+    help: bool,
+
+    /// Address to listen on
+    #[options(
+        short = "l",
+        long = "listen",
+        meta = "ADDR",
+        help = "address to serve the HTTP API on (default: 127.0.0.1:7878)"
+    )]
+    listen: Option<String>,
+
+    /// How often to refresh the advisory database
+    #[options(
+        no_short,
+        long = "refresh-interval",
+        meta = "DURATION",
+        help = "how often to refresh the advisory database, e.g. 30m (default: 1h)"
+    )]
+    refresh_interval: Option<humantime::Duration>,
+}
+
+impl Runnable for DaemonCommand {
+    fn run(&self) {
+        if self.help {
+            Self::print_usage_and_exit(&[]);
+        }
+
+        let config = AuditConfig::clone(&app_config());
+        let listen = self.listen.as_deref().unwrap_or(DEFAULT_LISTEN_ADDR);
+        let refresh_interval = self
+            .refresh_interval
+            .unwrap_or_else(|| DEFAULT_REFRESH_INTERVAL.parse().unwrap());
+
+        let database = load_database(&config).unwrap_or_else(|e| {
+            status_err!("{}", e);
+            exit(1);
+        });
+
+        status_ok!("Loaded", "{} security advisories", database.iter().count());
+
+        let daemon = Daemon::new(Auditor::with_database(&config, database));
+        daemon.refresh_every(refresh_interval.into(), move || load_database(&config));
+
+        status_ok!("Listening", "on http://{}", listen);
+
+        daemon.serve(listen).unwrap_or_else(|e| {
+            status_err!("{}", e);
+            exit(1);
+        });
+    }
+}
+
+/// Load the advisory database the same way audits do, fetching it first
+/// unless fetching is disabled
+fn load_database(config: &AuditConfig) -> Result<Database, LoadError> {
+    Auditor::load_database(
+        config,
+        &mut report::Stats::default(),
+        &mut LoadProfile::new(),
+    )
+}
@@ -0,0 +1,36 @@
+//! The `cargo audit db` subcommand
+
+mod changes;
+
+use self::changes::ChangesCommand;
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+
+/// The `cargo audit db` subcommand
+#[derive(Command, Default, Debug, Options)]
+pub struct DbCommand {
+    /// Subcommand (e.g. `cargo audit db changes`)
+    #[options(command)]
+    subcommand: Option<DbSubcommand>,
+
+    /// Get help information
+    #[options(short = "h", long = "help", help = "output help information and exit")]
+    help: bool,
+}
+
+/// Subcommands of `cargo audit db`
+#[derive(Command, Debug, Options, Runnable)]
+pub enum DbSubcommand {
+    /// `cargo audit db changes` subcommand
+    #[options(help = "show advisories changed by the most recent database update")]
+    Changes(ChangesCommand),
+}
+
+impl Runnable for DbCommand {
+    fn run(&self) {
+        match &self.subcommand {
+            Some(subcommand) => subcommand.run(),
+            None => Self::print_usage_and_exit(&[]),
+        }
+    }
+}
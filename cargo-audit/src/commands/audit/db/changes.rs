@@ -0,0 +1,146 @@
+//! The `cargo audit db changes` subcommand
+
+use crate::prelude::*;
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use rustsec::{
+    database::advisory_id,
+    repository::git::{Change, Repository},
+    Database,
+};
+use std::process::exit;
+
+#[derive(Command, Default, Debug, Options)]
+pub struct ChangesCommand {
+    /// Get help information
+    #[options(short = "h", long = "help", help = "output help information and exit")]
+    help: bool,
+
+    /// Show changes made by the last N commits instead of the last update
+    #[options(
+        short = "n",
+        long = "commits",
+        meta = "N",
+        help = "show changes made by the last N commits instead of the last update"
+    )]
+    commits: Option<usize>,
+}
+
+impl Runnable for ChangesCommand {
+    fn run(&self) {
+        if self.help {
+            Self::print_usage_and_exit(&[]);
+        }
+
+        let config = app_config();
+        let path = config
+            .database
+            .path
+            .clone()
+            .unwrap_or_else(Repository::default_path);
+
+        let repo = Repository::open(&path).unwrap_or_else(|e| {
+            status_err!(
+                "couldn't open advisory database at {}: {}",
+                path.display(),
+                e
+            );
+            exit(1);
+        });
+
+        let from = self.base_commit(&repo).unwrap_or_else(|e| {
+            status_err!("couldn't read advisory database history: {}", e);
+            exit(1);
+        });
+
+        let changes = repo
+            .changed_files(from.as_deref(), "HEAD")
+            .unwrap_or_else(|e| {
+                status_err!("couldn't diff advisory database: {}", e);
+                exit(1);
+            });
+
+        let database = Database::open(&path).unwrap_or_else(|e| {
+            status_err!("error loading advisory database: {}", e);
+            exit(1);
+        });
+
+        if let Ok(commits) = repo.recent_commits(1) {
+            if let Some(head) = commits.first() {
+                status_ok!(
+                    "Changes",
+                    "{}..{} ({})",
+                    from.as_deref().map(short_id).unwrap_or("(empty)"),
+                    short_id(&head.commit_id),
+                    head.summary
+                );
+            }
+        }
+
+        let mut any_changes = false;
+
+        for (change, file) in &changes {
+            let id = match advisory_id(file) {
+                Some(id) => id,
+                None => continue,
+            };
+
+            any_changes = true;
+            let description = match database.get(&id) {
+                Some(advisory) => format!(
+                    "{} ({}): {}",
+                    id, advisory.metadata.package, advisory.metadata.title
+                ),
+                None => id.to_string(),
+            };
+
+            let description = match id.url() {
+                Some(url) if *change != Change::Deleted => format!("{} <{}>", description, url),
+                _ => description,
+            };
+
+            match change {
+                Change::Added => {
+                    status_ok!("Added", "{}", description);
+                }
+                Change::Modified => {
+                    status_ok!("Updated", "{}", description);
+                }
+                Change::Deleted => {
+                    status_warn!("removed {}", description);
+                }
+            }
+        }
+
+        if !any_changes {
+            status_ok!("Unchanged", "no advisories changed");
+        }
+    }
+}
+
+impl ChangesCommand {
+    /// Get the commit to show changes since: either `--commits` commits
+    /// before `HEAD`, or `HEAD` as of before the most recent update
+    fn base_commit(&self, repo: &Repository) -> Result<Option<String>, rustsec::Error> {
+        let n = match self.commits {
+            Some(n) => n,
+            None => match repo.previous_commit()? {
+                Some(commit_id) => return Ok(Some(commit_id)),
+                None => {
+                    status_warn!("no database update recorded; showing the latest commit");
+                    1
+                }
+            },
+        };
+
+        Ok(repo
+            .recent_commits(n + 1)?
+            .get(n)
+            .map(|commit| commit.commit_id.clone()))
+    }
+}
+
+/// Abbreviate a commit ID
+fn short_id(commit_id: &str) -> &str {
+    commit_id.get(..10).unwrap_or(commit_id)
+}
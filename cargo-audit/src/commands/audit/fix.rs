@@ -1,10 +1,16 @@
 //! The `cargo audit fix` subcommand
 
-use crate::{auditor::Auditor, lockfile, prelude::*};
+use crate::{auditor::Auditor, config::AuditConfig, lockfile, prelude::*};
 use abscissa_core::{Command, Runnable};
 use gumdrop::Options;
-use rustsec::fixer::Fixer;
+use rustsec::{
+    cargo_lock::{dependency::Tree, Dependency, Lockfile, Package},
+    fixer::Fixer,
+    Report,
+};
+use similar::TextDiff;
 use std::{
+    env, fs, io,
     path::{Path, PathBuf},
     process::exit,
 };
@@ -22,12 +28,25 @@ pub struct FixCommand {
     /// Perform a dry run
     #[options(no_short, long = "dry-run", help = "perform a dry run for the fix")]
     dry_run: bool,
+
+    /// Output format
+    #[options(
+        no_short,
+        long = "format",
+        meta = "FORMAT",
+        help = "output format: text (default), or patch to print the fix as a unified diff, restoring the original files afterwards"
+    )]
+    format: Option<String>,
 }
 
 impl FixCommand {
     /// Initialize `Auditor`
     pub fn auditor(&self) -> Auditor {
-        let config = app_config();
+        let mut config = AuditConfig::clone(&app_config());
+
+        // A full report is needed to know which dependencies to fix
+        config.output.exit_on_first = false;
+
         Auditor::new(&config)
     }
 
@@ -41,6 +60,114 @@ impl FixCommand {
     pub fn cargo_lock_path(&self) -> Option<&Path> {
         self.file.as_deref()
     }
+
+    /// Should the fix be printed as a patch rather than applied?
+    fn is_patch(&self) -> bool {
+        match self.format.as_deref() {
+            None | Some("text") => false,
+            Some("patch") => true,
+            Some(other) => {
+                status_err!("unsupported format: {} (expected text or patch)", other);
+                exit(1);
+            }
+        }
+    }
+
+    /// Attempt to fix all vulnerabilities in the report, warning about the
+    /// ones which can't be fixed
+    fn apply_fixes(&self, fixer: &mut Fixer, report: &Report, dry_run: bool) {
+        let lockfile_path = self
+            .cargo_lock_path()
+            .unwrap_or_else(|| Path::new("Cargo.lock"));
+        let tree = Lockfile::load(lockfile_path)
+            .and_then(|lockfile| lockfile.dependency_tree())
+            .ok();
+
+        for vulnerability in &report.vulnerabilities.list {
+            if let Err(e) = fixer.fix(vulnerability, dry_run) {
+                match tree
+                    .as_ref()
+                    .and_then(|tree| dependency_path(tree, &vulnerability.package))
+                {
+                    Some(path) => status_warn!("{} (depended on via {})", e, path),
+                    None => status_warn!("{}", e),
+                }
+            }
+        }
+    }
+
+    /// Apply the fix to the project, print the resulting changes to
+    /// `Cargo.toml` and `Cargo.lock` as a unified diff, and restore both
+    /// files afterwards.
+    ///
+    /// Fixing the project in place (rather than a copy of it) ensures path
+    /// dependencies, workspace members and symlinks resolve the same way as
+    /// for a real fix.
+    fn print_patch(&self, report: &Report) {
+        let mut fixer = Fixer::new(self.cargo_toml_path()).unwrap_or_else(|e| {
+            status_err!(
+                "couldn't load manifest from {}: {}",
+                self.cargo_toml_path().display(),
+                e
+            );
+            exit(1);
+        });
+
+        let paths = [
+            self.cargo_toml_path(),
+            self.cargo_lock_path()
+                .unwrap_or_else(|| Path::new("Cargo.lock"))
+                .to_owned(),
+        ];
+
+        let originals = paths
+            .iter()
+            .map(|path| fs::read(path).ok())
+            .collect::<Vec<_>>();
+
+        self.apply_fixes(&mut fixer, report, false);
+
+        let result = if app_config().lockfile.no_cargo {
+            status_warn!("not regenerating Cargo.lock as --no-cargo was passed");
+            Ok(())
+        } else {
+            lockfile::generate_for(&self.cargo_toml_path())
+        };
+
+        let updated = paths
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        for (path, original) in paths.iter().zip(&originals) {
+            if let Err(e) = restore(path, original.as_deref()) {
+                status_err!("couldn't restore {}: {}", path.display(), e);
+                exit(1);
+            }
+        }
+
+        if let Err(e) = result {
+            status_err!("{}", e);
+            exit(2);
+        }
+
+        let current_dir = env::current_dir().ok();
+
+        for ((path, original), new) in paths.iter().zip(&originals).zip(&updated) {
+            let old = original
+                .as_ref()
+                .map(|contents| String::from_utf8_lossy(contents).into_owned())
+                .unwrap_or_default();
+
+            // Paths in the patch are relative to the project directory
+            let path = current_dir
+                .as_ref()
+                .and_then(|dir| path.strip_prefix(dir).ok())
+                .unwrap_or(path);
+
+            print!("{}", unified_diff(path, &old, new));
+        }
+    }
 }
 
 impl Runnable for FixCommand {
@@ -49,6 +176,7 @@ impl Runnable for FixCommand {
             Self::print_usage_and_exit(&[]);
         }
 
+        let patch = self.is_patch();
         let report = self.auditor().audit(self.cargo_lock_path());
 
         let report = match report {
@@ -64,6 +192,11 @@ impl Runnable for FixCommand {
             }
         };
 
+        if patch {
+            self.print_patch(&report);
+            return;
+        }
+
         let mut fixer = Fixer::new(self.cargo_toml_path()).unwrap_or_else(|e| {
             status_err!(
                 "couldn't load manifest from {}: {}",
@@ -83,10 +216,11 @@ impl Runnable for FixCommand {
             dry_run_info
         );
 
-        for vulnerability in &report.vulnerabilities.list {
-            if let Err(e) = fixer.fix(vulnerability, dry_run) {
-                status_warn!("{}", e);
-            }
+        self.apply_fixes(&mut fixer, &report, dry_run);
+
+        if app_config().lockfile.no_cargo {
+            status_warn!("not regenerating Cargo.lock as --no-cargo was passed");
+            return;
         }
 
         if let Err(e) = lockfile::generate() {
@@ -95,3 +229,77 @@ impl Runnable for FixCommand {
         }
     }
 }
+
+/// Describe the shortest path from a root package to the given package
+fn dependency_path(tree: &Tree, package: &Package) -> Option<String> {
+    let node = *tree.nodes().get(&Dependency::from(package))?;
+    let path = tree.shortest_inverse_paths(node, 1).into_iter().next()?;
+
+    let names = path
+        .iter()
+        .rev()
+        .map(|&node| tree.graph()[node].name.as_str())
+        .collect::<Vec<_>>();
+
+    Some(names.join(" -> "))
+}
+
+/// Restore a file to its original contents, removing it if it didn't exist
+fn restore(path: &Path, original: Option<&[u8]>) -> io::Result<()> {
+    match original {
+        Some(contents) => fs::write(path, contents),
+        None => match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// Render the changes to the file at the given path as a unified diff which
+/// can be applied with `git apply` (or `patch -p1`)
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let path = path.display().to_string().replace('\\', "/");
+
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{restore, unified_diff};
+    use std::{fs, path::Path};
+
+    #[test]
+    fn diff_headers() {
+        let path = Path::new("Cargo.toml");
+        assert_eq!(unified_diff(path, "a = 1\n", "a = 1\n"), "");
+
+        let diff = unified_diff(
+            path,
+            "[dependencies]\nfoo = \"1.0\"\n",
+            "[dependencies]\nfoo = \"1.1\"\n",
+        );
+        assert!(diff.starts_with("--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1,2 +1,2 @@\n"));
+        assert!(diff.contains("\n-foo = \"1.0\"\n+foo = \"1.1\"\n"));
+    }
+
+    #[test]
+    fn restore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Cargo.lock");
+
+        fs::write(&path, "new").unwrap();
+        restore(&path, Some(b"old")).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+
+        restore(&path, None).unwrap();
+        assert!(!path.exists());
+        restore(&path, None).unwrap();
+    }
+}
@@ -0,0 +1,201 @@
+//! The `cargo audit ignore-check` subcommand
+
+use crate::{
+    auditor::Auditor,
+    commands::config_path,
+    config::AuditConfig,
+    error::{Error, ErrorKind},
+    prelude::*,
+};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+use rustsec::{advisory, report::IgnorePattern, Report};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// The `cargo audit ignore-check` subcommand
+#[derive(Command, Default, Debug, Options)]
+pub struct IgnoreCheckCommand {
+    /// Get help information
+    #[options(short = "h", long = "help", help = "output help information and exit")]
+    help: bool,
+
+    /// Path to `Cargo.lock`
+    #[options(short = "f", long = "file", help = "Cargo lockfile to inspect")]
+    file: Option<PathBuf>,
+
+    /// Remove stale ignores from `audit.toml`
+    #[options(
+        no_short,
+        long = "fix",
+        help = "remove ignores which no longer match anything from audit.toml"
+    )]
+    fix: bool,
+}
+
+impl Runnable for IgnoreCheckCommand {
+    fn run(&self) {
+        if self.help {
+            Self::print_usage_and_exit(&[]);
+        }
+
+        let mut config = AuditConfig::clone(&app_config());
+        let ignores = std::mem::take(&mut config.advisories.ignore);
+
+        if ignores.is_empty() {
+            status_ok!("Success", "no advisories are ignored");
+            return;
+        }
+
+        // Audit without any ignores to find out which advisories they match
+        config.output.exit_on_first = false;
+        config.output.quiet = true;
+
+        let report = Auditor::new(&config)
+            .report(self.file.as_deref())
+            .unwrap_or_else(|e| {
+                status_err!("{}", e);
+                exit(2);
+            });
+
+        let stale = stale_ignores(&ignores, &report);
+
+        if stale.is_empty() {
+            status_ok!(
+                "Success",
+                "all {} ignores match advisories affecting the lockfile",
+                ignores.len()
+            );
+            return;
+        }
+
+        for pattern in &stale {
+            status_warn!(
+                "`{}` is ignored, but doesn't match any advisory affecting the lockfile",
+                pattern
+            );
+        }
+
+        if !self.fix {
+            exit(1);
+        }
+
+        let path = config_path().unwrap_or_else(|| {
+            status_err!("no audit.toml to remove stale ignores from");
+            exit(1);
+        });
+
+        let removed = prune_ignores(&path, &stale).unwrap_or_else(|e| {
+            status_err!("couldn't update {}: {}", path.display(), e);
+            exit(1);
+        });
+
+        status_ok!(
+            "Removed",
+            "{} stale ignores from {}",
+            removed,
+            path.display()
+        );
+
+        // Ignores given via `--ignore` or `RUSTSEC_IGNORE` can't be removed
+        if removed < stale.len() {
+            status_warn!(
+                "{} stale ignores aren't in {} (i.e. they're set on the command line or in the environment)",
+                stale.len() - removed,
+                path.display()
+            );
+            exit(1);
+        }
+    }
+}
+
+/// Find the ignore patterns which don't match any of the advisories in a
+/// report generated without them
+fn stale_ignores<'a>(ignores: &'a [IgnorePattern], report: &Report) -> Vec<&'a IgnorePattern> {
+    let advisories = report
+        .vulnerabilities
+        .list
+        .iter()
+        .map(|vuln| &vuln.advisory)
+        .chain(report.accepted.iter().map(|a| &a.vulnerability.advisory))
+        .chain(
+            report
+                .warnings
+                .values()
+                .flatten()
+                .filter_map(|warning| warning.advisory.as_ref()),
+        )
+        .collect::<Vec<&advisory::Metadata>>();
+
+    ignores
+        .iter()
+        .filter(|pattern| !advisories.iter().any(|metadata| pattern.matches(metadata)))
+        .collect()
+}
+
+/// Remove the given ignore patterns from the `audit.toml` at the given path,
+/// preserving its formatting and comments, returning how many were removed
+fn prune_ignores(path: &Path, stale: &[&IgnorePattern]) -> Result<usize, Error> {
+    let mut doc = fs::read_to_string(path)?
+        .parse::<toml_edit::Document>()
+        .map_err(|e| Error::from(ErrorKind::Config.context(e)))?;
+
+    // Check the array exists first, since indexing mutably inserts tables
+    if doc["advisories"]["ignore"].as_array().is_none() {
+        return Ok(0);
+    }
+
+    let ignore = doc["advisories"]["ignore"].as_array_mut().unwrap();
+    let mut removed = 0;
+    let mut i = 0;
+
+    while i < ignore.len() {
+        let is_stale = ignore
+            .get(i)
+            .and_then(|value| value.as_str())
+            .map_or(false, |value| {
+                stale.iter().any(|pattern| pattern.to_string() == value)
+            });
+
+        if is_stale {
+            ignore.remove(i);
+            removed += 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    if removed > 0 {
+        fs::write(path, doc.to_string())?;
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prune_ignores;
+    use std::fs;
+
+    #[test]
+    fn prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.toml");
+        fs::write(
+            &path,
+            "[advisories]\n# Waivers\nignore = [\"RUSTSEC-2019-0001\", \"RUSTSEC-2020-0002\"]\n",
+        )
+        .unwrap();
+
+        let stale = "RUSTSEC-2019-0001".parse().unwrap();
+        assert_eq!(prune_ignores(&path, &[&stale]).unwrap(), 1);
+
+        let toml = fs::read_to_string(&path).unwrap();
+        assert!(toml.contains("# Waivers"));
+        assert!(!toml.contains("RUSTSEC-2019-0001"));
+        assert!(toml.contains("RUSTSEC-2020-0002"));
+    }
+}
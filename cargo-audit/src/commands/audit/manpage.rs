@@ -0,0 +1,101 @@
+//! The `cargo audit manpage` subcommand
+
+use super::usage::{self, FLAGS};
+use crate::commands::{CargoAuditCommand, CONFIG_FILE};
+use abscissa_core::{Command, Runnable};
+use gumdrop::Options;
+
+#[derive(Command, Default, Debug, Options)]
+pub struct ManpageCommand {
+    /// Get help information
+    #[options(short = "h", long = "help", help = "output help information and exit")]
+    help: bool,
+}
+
+impl Runnable for ManpageCommand {
+    fn run(&self) {
+        if self.help {
+            Self::print_usage_and_exit(&[]);
+        }
+
+        print!("{}", manpage());
+    }
+}
+
+/// Render the `cargo-audit(1)` manpage in roff format
+fn manpage() -> String {
+    let mut out = format!(
+        ".TH CARGO\\-AUDIT 1 \"\" \"cargo\\-audit {}\" \"Cargo Manual\"\n",
+        escape(CargoAuditCommand::version())
+    );
+
+    out.push_str(".SH NAME\n");
+    out.push_str(
+        "cargo\\-audit \\- audit Cargo.lock files for crates with security vulnerabilities\n",
+    );
+
+    out.push_str(".SH SYNOPSIS\n");
+    out.push_str("\\fBcargo audit\\fR [\\fIOPTIONS\\fR] [\\fICOMMAND\\fR]\n");
+
+    out.push_str(".SH DESCRIPTION\n");
+    out.push_str(
+        "Audit a project's Cargo.lock for crates with security vulnerabilities \
+         reported to the RustSec Advisory Database.\n",
+    );
+
+    out.push_str(".SH OPTIONS\n");
+
+    for flag in FLAGS {
+        let mut names = vec![];
+        names.extend(flag.short.map(|short| format!("\\fB\\-{}\\fR", short)));
+        names.push(format!("\\fB\\-\\-{}\\fR", escape(flag.long)));
+
+        out.push_str(".TP\n");
+        out.push_str(&names.join(", "));
+
+        if let Some(meta) = flag.meta {
+            out.push_str(&format!(" \\fI{}\\fR", escape(meta)));
+        }
+
+        out.push('\n');
+        out.push_str(&escape(flag.help));
+        out.push('\n');
+    }
+
+    let commands = usage::subcommands();
+
+    if !commands.is_empty() {
+        out.push_str(".SH COMMANDS\n");
+
+        for command in commands {
+            out.push_str(&format!(
+                ".TP\n\\fB{}\\fR\n{}\n",
+                escape(command.name),
+                escape(command.help)
+            ));
+        }
+    }
+
+    out.push_str(".SH FILES\n");
+    out.push_str(&format!(
+        ".TP\n\\fI.cargo/{0}\\fR, \\fI~/.cargo/{0}\\fR\nConfiguration file, \
+         looked up in the current project first\n",
+        escape(CONFIG_FILE)
+    ));
+
+    out.push_str(".SH SEE ALSO\n");
+    out.push_str("https://rustsec.org\n");
+    out
+}
+
+/// Escape text for use in roff
+fn escape(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+
+    // Lines starting with `.` or `'` would be interpreted as requests
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
@@ -0,0 +1,434 @@
+//! Flags and subcommands of `cargo audit`, from which shell completions and
+//! the manpage are generated.
+//!
+//! gumdrop doesn't expose the metadata of the options it parses, so it's
+//! declared here alongside `AuditCommand`. The tests below check it against
+//! the actual parser, so the two can't drift apart.
+
+/// Command-line flag
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Flag {
+    /// Short name of the flag (e.g. `f` for `-f`)
+    pub short: Option<char>,
+
+    /// Long name of the flag (e.g. `file` for `--file`)
+    pub long: &'static str,
+
+    /// Placeholder for the flag's value, if it takes one
+    pub meta: Option<&'static str>,
+
+    /// Description of the flag
+    pub help: &'static str,
+}
+
+/// Subcommand
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subcommand {
+    /// Name of the subcommand
+    pub name: &'static str,
+
+    /// Description of the subcommand
+    pub help: &'static str,
+}
+
+/// Flags of `cargo audit`, in the order they're declared in `AuditCommand`
+pub const FLAGS: &[Flag] = &[
+    Flag {
+        short: Some('h'),
+        long: "help",
+        meta: None,
+        help: "output help information and exit",
+    },
+    Flag {
+        short: None,
+        long: "version",
+        meta: None,
+        help: "output version and exit",
+    },
+    Flag {
+        short: Some('c'),
+        long: "color",
+        meta: Some("WHEN"),
+        help: "color configuration: auto, always, never (default: auto)",
+    },
+    Flag {
+        short: Some('d'),
+        long: "db",
+        meta: Some("DB"),
+        help: "advisory database path, either a git repo or a plain (e.g. vendored) directory (default: ~/.cargo/advisory-db)",
+    },
+    Flag {
+        short: None,
+        long: "db-as-of",
+        meta: Some("YYYY-MM-DD"),
+        help: "only consider advisories published on or before this date",
+    },
+    Flag {
+        short: Some('D'),
+        long: "deny",
+        meta: Some("DENY"),
+        help: "exit with an error on: warnings (any), unmaintained, unsound, yanked, duplicate, own-crate, malformed-lockfile",
+    },
+    Flag {
+        short: None,
+        long: "deny-warnings",
+        meta: None,
+        help: "deprecated legacy alternative to: --deny warnings",
+    },
+    Flag {
+        short: Some('f'),
+        long: "file",
+        meta: Some("FILE"),
+        help: "Cargo lockfile to inspect (or `-` for STDIN, default: Cargo.lock)",
+    },
+    Flag {
+        short: None,
+        long: "sbom",
+        meta: Some("PATH"),
+        help: "audit packages listed in a CycloneDX or SPDX JSON SBOM",
+    },
+    Flag {
+        short: None,
+        long: "vendor",
+        meta: Some("PATH"),
+        help: "audit crates in a `cargo vendor` directory",
+    },
+    Flag {
+        short: None,
+        long: "minimal-versions",
+        meta: None,
+        help: "audit the minimum versions of direct dependencies allowed by Cargo.toml rather than those in Cargo.lock",
+    },
+    Flag {
+        short: None,
+        long: "ignore",
+        meta: Some("ADVISORY_ID"),
+        help: "Advisory id or pattern (e.g. RUSTSEC-2019-*, package:foo-*) to ignore (can be specified multiple times)",
+    },
+    Flag {
+        short: None,
+        long: "only",
+        meta: Some("ADVISORY_ID"),
+        help: "only check for the advisory with this id, ignoring all others (can be specified multiple times)",
+    },
+    Flag {
+        short: None,
+        long: "ignore-source",
+        meta: Some("GIT_URL"),
+        help: "accept vulnerabilities in crates from a patched fork at the given git URL, optionally pinned to URL#REV (can be specified multiple times)",
+    },
+    Flag {
+        short: None,
+        long: "overrides-as-warnings",
+        meta: None,
+        help: "report vulnerabilities in crates overridden via [patch] or [replace] as warnings",
+    },
+    Flag {
+        short: Some('n'),
+        long: "no-fetch",
+        meta: None,
+        help: "do not perform a git fetch on the advisory DB",
+    },
+    Flag {
+        short: None,
+        long: "stale",
+        meta: None,
+        help: "allow stale database",
+    },
+    Flag {
+        short: None,
+        long: "no-repair",
+        meta: None,
+        help: "fail instead of re-cloning a corrupted or diverged advisory DB",
+    },
+    Flag {
+        short: None,
+        long: "target-arch",
+        meta: Some("TARGET-ARCH"),
+        help: "filter vulnerabilities by CPU (default: no filter)",
+    },
+    Flag {
+        short: None,
+        long: "target-os",
+        meta: Some("TARGET-OS"),
+        help: "filter vulnerabilities by OS (default: no filter)",
+    },
+    Flag {
+        short: None,
+        long: "target",
+        meta: Some("TRIPLE"),
+        help: "filter vulnerabilities by target triple (can be specified multiple times)",
+    },
+    Flag {
+        short: None,
+        long: "exclude-target-arch",
+        meta: Some("ARCH"),
+        help: "ignore vulnerabilities which only affect this CPU, taking precedence over --target-arch (can be specified multiple times)",
+    },
+    Flag {
+        short: None,
+        long: "exclude-target-os",
+        meta: Some("OS"),
+        help: "ignore vulnerabilities which only affect this OS, taking precedence over --target-os (can be specified multiple times)",
+    },
+    Flag {
+        short: Some('u'),
+        long: "url",
+        meta: Some("URL"),
+        help: "URL for advisory database git repo",
+    },
+    Flag {
+        short: None,
+        long: "mirror",
+        meta: Some("URL"),
+        help: "URL for a static file mirror of the advisory DB (instead of git)",
+    },
+    Flag {
+        short: None,
+        long: "mirror-digest",
+        meta: Some("SHA256"),
+        help: "expected digest of the mirror's manifest (printed by `rustsec-admin mirror`)",
+    },
+    Flag {
+        short: None,
+        long: "duplicates",
+        meta: None,
+        help: "warn about crates present in several versions in Cargo.lock",
+    },
+    Flag {
+        short: None,
+        long: "embedded-db",
+        meta: None,
+        help: "use the advisory DB snapshot embedded in this binary (requires the embedded-db feature)",
+    },
+    Flag {
+        short: Some('q'),
+        long: "quiet",
+        meta: None,
+        help: "Avoid printing unnecessary information",
+    },
+    Flag {
+        short: Some('v'),
+        long: "verbose",
+        meta: None,
+        help: "show full details and dependency trees for each finding, even with --condensed",
+    },
+    Flag {
+        short: None,
+        long: "condensed",
+        meta: None,
+        help: "show a single line for each finding rather than its full details",
+    },
+    Flag {
+        short: None,
+        long: "exit-on-first",
+        meta: None,
+        help: "exit as soon as the first denied finding is discovered",
+    },
+    Flag {
+        short: None,
+        long: "json",
+        meta: None,
+        help: "Output report in JSON format",
+    },
+    Flag {
+        short: None,
+        long: "format",
+        meta: Some("FORMAT"),
+        help: "output format: terminal, json, attestation (default: terminal)",
+    },
+    Flag {
+        short: None,
+        long: "locked",
+        meta: None,
+        help: "Require Cargo.lock to be up-to-date with Cargo.toml",
+    },
+    Flag {
+        short: None,
+        long: "no-cargo",
+        meta: None,
+        help: "never invoke cargo, skipping checks which require it",
+    },
+    Flag {
+        short: None,
+        long: "no-exec",
+        meta: None,
+        help: "never execute external processes (e.g. cargo), failing instead of doing so",
+    },
+    Flag {
+        short: None,
+        long: "strict-lockfile",
+        meta: None,
+        help: "fail on malformed entries in Cargo.lock rather than skipping them",
+    },
+    Flag {
+        short: None,
+        long: "baseline",
+        meta: Some("PATH"),
+        help: "only report findings for packages added or changed since this baseline Cargo.lock",
+    },
+    Flag {
+        short: None,
+        long: "recursive",
+        meta: Some("DIR"),
+        help: "audit every Cargo.lock under this directory, producing one aggregated report",
+    },
+    Flag {
+        short: None,
+        long: "history-file",
+        meta: Some("PATH"),
+        help: "append a summary of the audit to this newline-delimited JSON file",
+    },
+    Flag {
+        short: None,
+        long: "stats",
+        meta: None,
+        help: "show timing and cache diagnostics (also embedded in JSON reports)",
+    },
+    Flag {
+        short: None,
+        long: "show-descriptions",
+        meta: Some("MODE"),
+        help: "show advisory descriptions unless condensed: full, truncated, none (default: none)",
+    },
+    Flag {
+        short: None,
+        long: "description-lines",
+        meta: Some("COUNT"),
+        help: "number of lines truncated descriptions are limited to (default: 5)",
+    },
+    Flag {
+        short: None,
+        long: "json-field-naming",
+        meta: Some("STYLE"),
+        help: "field names in JSON reports: legacy, kebab-case, snake-case (default: legacy)",
+    },
+    Flag {
+        short: None,
+        long: "slow-parse-report",
+        meta: Some("COUNT"),
+        help: "list the COUNT advisory files which took longest to parse",
+    },
+    Flag {
+        short: None,
+        long: "include-hostname",
+        meta: None,
+        help: "include the name of the host the audit ran on in JSON reports",
+    },
+    Flag {
+        short: None,
+        long: "exposure",
+        meta: None,
+        help: "report the git commits during which vulnerable dependencies were present",
+    },
+    Flag {
+        short: None,
+        long: "requirements",
+        meta: None,
+        help: "report Cargo.toml version requirements which admit vulnerable releases, even if the locked versions are fine",
+    },
+    Flag {
+        short: None,
+        long: "no-local-crates",
+        meta: None,
+        help: "Vulnerability querying does not consider local crates",
+    },
+];
+
+/// Get the subcommands of `cargo audit`
+pub fn subcommands() -> Vec<Subcommand> {
+    let mut subcommands = vec![];
+
+    #[cfg(feature = "fix")]
+    subcommands.push(Subcommand {
+        name: "fix",
+        help: "automatically upgrade vulnerable dependencies",
+    });
+
+    subcommands.extend_from_slice(&[
+        Subcommand {
+            name: "badge",
+            help: "generate an SVG badge summarizing the audit",
+        },
+        Subcommand {
+            name: "completions",
+            help: "generate shell completions (bash, zsh, fish)",
+        },
+        Subcommand {
+            name: "daemon",
+            help: "serve an HTTP API which audits lockfiles against an in-memory database",
+        },
+        Subcommand {
+            name: "db",
+            help: "inspect the advisory database (e.g. recent changes)",
+        },
+        Subcommand {
+            name: "ignore-check",
+            help: "report ignored advisories which no longer affect the lockfile",
+        },
+        Subcommand {
+            name: "manpage",
+            help: "generate the cargo-audit(1) manpage",
+        },
+    ]);
+
+    subcommands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::AuditCommand, subcommands, FLAGS};
+    use gumdrop::Options;
+
+    /// Check that the given arguments name a flag of `cargo audit` which
+    /// takes a value if and only if `takes_value` is set
+    fn check_flag(arg: &str, takes_value: bool) {
+        match AuditCommand::parse_args_default(&[arg]) {
+            Ok(_) => assert!(!takes_value, "{} should take a value", arg),
+            Err(e) => assert!(
+                takes_value && e.to_string().starts_with("missing argument"),
+                "{}: {}",
+                arg,
+                e
+            ),
+        }
+    }
+
+    #[test]
+    fn flags_match_parser() {
+        for flag in FLAGS {
+            check_flag(&format!("--{}", flag.long), flag.meta.is_some());
+
+            if let Some(short) = flag.short {
+                check_flag(&format!("-{}", short), flag.meta.is_some());
+            }
+        }
+
+        // Every option the parser accepts is listed in its usage
+        let options = AuditCommand::usage()
+            .lines()
+            .filter(|line| line.trim_start().starts_with('-'))
+            .count();
+        assert_eq!(options, FLAGS.len());
+    }
+
+    #[test]
+    fn subcommands_match_parser() {
+        let subcommands = subcommands();
+
+        for subcommand in &subcommands {
+            assert!(
+                AuditCommand::command_usage(subcommand.name).is_some(),
+                "unknown subcommand: {}",
+                subcommand.name
+            );
+        }
+
+        let commands = AuditCommand::command_list()
+            .unwrap()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count();
+        assert_eq!(commands, subcommands.len());
+    }
+}
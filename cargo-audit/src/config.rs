@@ -1,5 +1,6 @@
 //! The `~/.cargo/audit.toml` configuration file
 
+use crate::presenter::Messages;
 use rustsec::warning;
 use rustsec::{
     advisory,
@@ -8,9 +9,88 @@ use rustsec::{
     report, Error, ErrorKind,
 };
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+/// Environment variables which override settings from the config file.
+///
+/// Command-line flags take precedence over environment variables, which in
+/// turn take precedence over `audit.toml`. Lists (e.g. advisories to ignore)
+/// are comma-separated and extend those in the config file, and flags are
+/// enabled by `1`, `true`, or `yes`.
+pub mod env_vars {
+    /// Path to the local advisory database (`--db`)
+    pub const DB_PATH: &str = "RUSTSEC_DB_PATH";
+
+    /// URL of the advisory database git repo (`--url`)
+    pub const DB_URL: &str = "RUSTSEC_DB_URL";
+
+    /// URL of a static file mirror of the advisory database (`--mirror`)
+    pub const DB_MIRROR: &str = "RUSTSEC_DB_MIRROR";
+
+    /// Expected digest of the mirror's manifest (`--mirror-digest`)
+    pub const DB_MIRROR_DIGEST: &str = "RUSTSEC_DB_MIRROR_DIGEST";
+
+    /// Only consider advisories published on or before this date (`--db-as-of`)
+    pub const DB_AS_OF: &str = "RUSTSEC_DB_AS_OF";
+
+    /// Don't fetch the advisory database (`--no-fetch`)
+    pub const NO_FETCH: &str = "RUSTSEC_NO_FETCH";
+
+    /// Allow a stale advisory database (`--stale`)
+    pub const STALE: &str = "RUSTSEC_STALE";
+
+    /// Don't re-clone a corrupted or diverged advisory database (`--no-repair`)
+    pub const NO_REPAIR: &str = "RUSTSEC_NO_REPAIR";
+
+    /// Advisory IDs or patterns to ignore (`--ignore`)
+    pub const IGNORE: &str = "RUSTSEC_IGNORE";
+
+    /// Git sources of patched forks to accept vulnerabilities from
+    /// (`--ignore-source`)
+    pub const IGNORE_SOURCE: &str = "RUSTSEC_IGNORE_SOURCE";
+
+    /// Report vulnerabilities in overridden crates as warnings
+    /// (`--overrides-as-warnings`)
+    pub const OVERRIDES_AS_WARNINGS: &str = "RUSTSEC_OVERRIDES_AS_WARNINGS";
+
+    /// Severity threshold to alert at (`severity_threshold`)
+    pub const SEVERITY_THRESHOLD: &str = "RUSTSEC_SEVERITY_THRESHOLD";
+
+    /// Warning kinds to exit with an error on (`--deny`)
+    pub const DENY: &str = "RUSTSEC_DENY";
+
+    /// Target triples to filter vulnerabilities by (`--target`)
+    pub const TARGET: &str = "RUSTSEC_TARGET";
+
+    /// Output format (`--format`)
+    pub const FORMAT: &str = "RUSTSEC_FORMAT";
+
+    /// Enable quiet mode (`--quiet`)
+    pub const QUIET: &str = "RUSTSEC_QUIET";
+
+    /// Show full details for each finding (`--verbose`)
+    pub const VERBOSE: &str = "RUSTSEC_VERBOSE";
+
+    /// Show a single line per finding (`--condensed`)
+    pub const CONDENSED: &str = "RUSTSEC_CONDENSED";
+
+    /// Require `Cargo.lock` to be up-to-date (`--locked`)
+    pub const LOCKED: &str = "RUSTSEC_LOCKED";
+
+    /// Never invoke cargo (`--no-cargo`)
+    pub const NO_CARGO: &str = "RUSTSEC_NO_CARGO";
+
+    /// Never execute external processes (`--no-exec`)
+    pub const NO_EXEC: &str = "RUSTSEC_NO_EXEC";
+
+    /// Fail on any malformed entry in `Cargo.lock` (`--strict-lockfile`)
+    pub const STRICT_LOCKFILE: &str = "RUSTSEC_STRICT_LOCKFILE";
+
+    /// Collect timing and cache diagnostics (`--stats`)
+    pub const STATS: &str = "RUSTSEC_STATS";
+}
+
 /// `cargo audit` configuration:
 ///
 /// An optional TOML config file located in `~/.cargo/audit.toml`
@@ -40,16 +120,120 @@ pub struct AuditConfig {
     /// Configuration for auditing for yanked crates
     #[serde(default)]
     pub yanked: YankedConfig,
+
+    /// `Cargo.lock`-related configuration
+    #[serde(default)]
+    pub lockfile: LockfileConfig,
 }
 
 impl AuditConfig {
+    /// Load configuration from the given `audit.toml` file.
+    ///
+    /// Unknown keys (e.g. typos like `ignroe`), values of the wrong type and
+    /// malformed advisory IDs are all rejected, with an error pointing at
+    /// the offending line of the file.
+    pub fn load_file(path: &Path) -> Result<Self, Error> {
+        let toml_string = rustsec::fs::read_to_string(path)?;
+
+        toml::from_str(&toml_string).map_err(|e| {
+            Error::new(
+                ErrorKind::Parse,
+                &describe_toml_error(path, &toml_string, &e),
+            )
+        })
+    }
+
+    /// Apply settings from `RUSTSEC_*` environment variables (see
+    /// [`env_vars`]) on top of the config file.
+    ///
+    /// [`env_vars::NO_FETCH`] isn't applied here as it's combined with the
+    /// `--no-fetch` flag (see [`env_flag`]).
+    pub fn apply_env(&mut self) -> Result<(), Error> {
+        if let Some(path) = env_var(env_vars::DB_PATH) {
+            self.database.path = Some(path.into());
+        }
+
+        if let Some(url) = env_var(env_vars::DB_URL) {
+            self.database.url = Some(url);
+        }
+
+        if let Some(mirror) = env_var(env_vars::DB_MIRROR) {
+            self.database.mirror = Some(mirror);
+        }
+
+        if let Some(digest) = env_var(env_vars::DB_MIRROR_DIGEST) {
+            self.database.mirror_digest = Some(digest);
+        }
+
+        if let Some(as_of) = env_var(env_vars::DB_AS_OF) {
+            self.database.as_of = Some(parse_env(env_vars::DB_AS_OF, &as_of)?);
+        }
+
+        self.database.stale |= env_flag(env_vars::STALE);
+        self.database.no_repair |= env_flag(env_vars::NO_REPAIR);
+
+        for pattern in env_list(env_vars::IGNORE) {
+            self.advisories
+                .ignore
+                .push(parse_env(env_vars::IGNORE, &pattern)?);
+        }
+
+        for source in env_list(env_vars::IGNORE_SOURCE) {
+            self.advisories
+                .patched_forks
+                .push(parse_env(env_vars::IGNORE_SOURCE, &source)?);
+        }
+
+        self.advisories.overrides_as_warnings |= env_flag(env_vars::OVERRIDES_AS_WARNINGS);
+
+        if let Some(threshold) = env_var(env_vars::SEVERITY_THRESHOLD) {
+            self.advisories.severity_threshold =
+                Some(parse_env(env_vars::SEVERITY_THRESHOLD, &threshold)?);
+        }
+
+        for kind in env_list(env_vars::DENY) {
+            match parse_env(env_vars::DENY, &kind)? {
+                DenyOption::Warnings => self.output.deny = DenyOption::all(),
+                other => self.output.deny.push(other),
+            }
+        }
+
+        self.target.triples.extend(env_list(env_vars::TARGET));
+
+        if let Some(format) = env_var(env_vars::FORMAT) {
+            self.output.format = parse_env(env_vars::FORMAT, &format)?;
+        }
+
+        self.output.quiet |= env_flag(env_vars::QUIET);
+        self.output.verbose |= env_flag(env_vars::VERBOSE);
+        self.output.condensed |= env_flag(env_vars::CONDENSED);
+        self.output.stats |= env_flag(env_vars::STATS);
+        self.lockfile.locked |= env_flag(env_vars::LOCKED);
+        self.lockfile.no_cargo |= env_flag(env_vars::NO_CARGO);
+        self.lockfile.no_exec |= env_flag(env_vars::NO_EXEC);
+        self.lockfile.strict |= env_flag(env_vars::STRICT_LOCKFILE);
+
+        Ok(())
+    }
+
     /// Get audit report settings from the configuration
     pub fn report_settings(&self) -> report::Settings {
         let mut settings = rustsec::report::Settings::default();
         settings.ignore = self.advisories.ignore.clone();
+        settings.only = self.advisories.only.clone();
         settings.severity = self.advisories.severity_threshold;
+        settings.severity_fallback = self.advisories.severity_fallback.clone();
+        settings.prerelease = self.advisories.prerelease;
+        settings.patched_forks = self.advisories.patched_forks.clone();
+        settings.overrides_as_warnings = self.advisories.overrides_as_warnings;
         settings.target_arch = self.target.arch;
         settings.target_os = self.target.os;
+        settings.targets = self.target.triples.clone();
+        settings.exclude_target_arch = self.target.exclude_arch.clone();
+        settings.exclude_target_os = self.target.exclude_os.clone();
+        settings.as_of = self.database.as_of.clone();
+        settings.owners = self.packages.owners.clone();
+        settings.promote = self.output.promote.clone();
 
         if let Some(source) = &self.packages.source {
             settings.package_scope = Some(source.clone().into());
@@ -86,7 +270,10 @@ impl AuditConfig {
                     insert_if_not_present(advisory::Informational::Unmaintained)
                 }
                 DenyOption::Unsound => insert_if_not_present(advisory::Informational::Unsound),
-                DenyOption::Yanked => continue,
+                DenyOption::Yanked
+                | DenyOption::Duplicate
+                | DenyOption::OwnCrate
+                | DenyOption::MalformedLockfile => continue,
             };
         }
 
@@ -94,13 +281,98 @@ impl AuditConfig {
     }
 }
 
+/// Describe an error parsing a TOML file, along with the line it occurred on
+fn describe_toml_error(path: &Path, toml_string: &str, error: &toml::de::Error) -> String {
+    let message = error.to_string();
+
+    let (line, col) = match error.line_col() {
+        Some(line_col) => line_col,
+        None => return format!("{}: {}", path.display(), message),
+    };
+
+    // The location is shown below instead
+    let message = match message.rfind(" at line ") {
+        Some(index) => &message[..index],
+        None => &message,
+    };
+
+    let source_line = toml_string.lines().nth(line).unwrap_or_default();
+    let line_number = (line + 1).to_string();
+    let gutter = " ".repeat(line_number.len());
+
+    format!(
+        "{message}\n{gutter}--> {path}:{line}:{col}\n{gutter} |\n{line} | {source}\n{gutter} | {caret:>width$}",
+        message = message,
+        gutter = gutter,
+        path = path.display(),
+        line = line_number,
+        col = col + 1,
+        source = source_line,
+        caret = "^",
+        width = col + 1
+    )
+}
+
+/// Get the value of an environment variable, treating empty values as unset
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Get a comma-separated list from an environment variable
+fn env_list(name: &str) -> Vec<String> {
+    env_var(name)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Is the flag set by the given environment variable enabled?
+pub fn env_flag(name: &str) -> bool {
+    match env_var(name) {
+        Some(value) => matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes"
+        ),
+        None => false,
+    }
+}
+
+/// Parse the value of an environment variable
+fn parse_env<T>(name: &str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.trim().parse().map_err(|e| {
+        Error::new(
+            ErrorKind::Parse,
+            &format!("invalid value for {}: {}: {}", name, value, e),
+        )
+    })
+}
+
 /// Advisory-related configuration.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct AdvisoryConfig {
-    /// Ignore advisories for the given IDs
+    /// Ignore advisories matching the given IDs or patterns (e.g.
+    /// `RUSTSEC-2019-*` or `package:foo-*`)
     #[serde(default)]
-    pub ignore: Vec<advisory::Id>,
+    pub ignore: Vec<report::IgnorePattern>,
+
+    /// Only check for the advisories with the given IDs, ignoring all
+    /// others (e.g. to find out whether a newly published advisory affects
+    /// any of many projects)
+    #[serde(default)]
+    pub only: Vec<advisory::Id>,
 
     /// Warn for the given types of informational advisories
     pub informational_warnings: Option<Vec<advisory::Informational>>,
@@ -110,6 +382,27 @@ pub struct AdvisoryConfig {
     /// Vulnerabilities with explicit CVSS info which have a severity below
     /// this threshold will be ignored.
     pub severity_threshold: Option<advisory::Severity>,
+
+    /// Severities to assume for advisories without CVSS info, by advisory
+    /// type (`vulnerability`, `unsound`, `unmaintained`, `notice`)
+    #[serde(default)]
+    pub severity_fallback: advisory::SeverityFallback,
+
+    /// How pre-release versions in the lockfile are matched against
+    /// advisories (`strict` or `permissive`)
+    #[serde(default)]
+    pub prerelease: advisory::PrereleaseMatching,
+
+    /// Git sources of patched forks (e.g. used via `[patch]`), whose
+    /// vulnerabilities are accepted rather than reported
+    #[serde(default)]
+    pub patched_forks: Vec<report::PatchedFork>,
+
+    /// Report vulnerabilities in crates overridden via `[patch]` or
+    /// `[replace]` as warnings, since their code may differ from the
+    /// registry release the advisory applies to
+    #[serde(default)]
+    pub overrides_as_warnings: bool,
 }
 
 /// Advisory Database configuration.
@@ -119,7 +412,10 @@ pub struct AdvisoryConfig {
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct DatabaseConfig {
-    /// Path to the local copy of advisory database's git repo (default: ~/.cargo/advisory-db)
+    /// Path to the local copy of advisory database's git repo (default: ~/.cargo/advisory-db).
+    ///
+    /// This may also be a plain directory which isn't a git checkout (e.g. a
+    /// vendored copy of the database), in which case it isn't fetched.
     pub path: Option<PathBuf>,
 
     /// URL to the advisory database's git repo (default: https://github.com/RustSec/advisory-db)
@@ -130,6 +426,30 @@ pub struct DatabaseConfig {
 
     /// Allow a stale advisory database? (i.e. one which hasn't been updated in 90 days)
     pub stale: bool,
+
+    /// Fail rather than re-cloning the advisory database when the local
+    /// checkout is corrupted or upstream history has diverged from it
+    /// (e.g. after a force push)
+    #[serde(default)]
+    pub no_repair: bool,
+
+    /// Only consider advisories published on or before this date
+    pub as_of: Option<advisory::Date>,
+
+    /// URL of a static file mirror of the advisory database to fetch instead
+    /// of the git repo (see `rustsec-admin mirror`)
+    pub mirror: Option<String>,
+
+    /// Expected digest of the mirror's manifest, as printed by
+    /// `rustsec-admin mirror`: the manifest is served by the mirror itself,
+    /// so this is what guarantees the mirror hasn't been tampered with
+    pub mirror_digest: Option<String>,
+
+    /// Use the snapshot of the advisory database embedded at build time
+    /// rather than fetching one (requires the `embedded-db` feature, with
+    /// which the snapshot is also used when no other database is available)
+    #[serde(default)]
+    pub embedded: bool,
 }
 
 /// Output configuration
@@ -147,14 +467,116 @@ pub struct OutputConfig {
     /// Enable quiet mode
     pub quiet: bool,
 
-    /// Show inverse dependency trees along with advisories (default: true)
+    /// Show inverse dependency trees along with advisories, unless output is
+    /// condensed (default: true)
     pub show_tree: Option<bool>,
+
+    /// Whether to show advisory descriptions along with findings, unless
+    /// output is condensed (default: none)
+    #[serde(default)]
+    pub show_descriptions: DescriptionMode,
+
+    /// Number of lines truncated descriptions are limited to (default: 5)
+    #[serde(default)]
+    pub description_lines: Option<usize>,
+
+    /// Show a condensed single line for each finding rather than its full
+    /// details and dependency tree
+    #[serde(default)]
+    pub condensed: bool,
+
+    /// Show full details for each finding even if `condensed` is set, along
+    /// with otherwise hidden diagnostics
+    #[serde(default)]
+    pub verbose: bool,
+
+    /// Stop at the first denied finding without generating a full report
+    /// (ignored when outputting JSON)
+    #[serde(default)]
+    pub exit_on_first: bool,
+
+    /// Message catalog used for terminal output (default: English)
+    #[serde(default)]
+    pub messages: Messages,
+
+    /// Rules which promote matching warnings to errors, e.g. only for
+    /// certain packages or direct dependencies
+    #[serde(default)]
+    pub promote: Vec<report::PromotionRule>,
+
+    /// File to append a summary of each audit to (see [`crate::history`])
+    #[serde(default)]
+    pub history_file: Option<PathBuf>,
+
+    /// Collect timing and cache diagnostics, which are printed after the
+    /// report and embedded in JSON reports
+    #[serde(default)]
+    pub stats: bool,
+
+    /// List this many of the advisory files which took longest to parse
+    /// while loading the advisory database
+    #[serde(default)]
+    pub slow_parse_report: Option<usize>,
+
+    /// Include the name of the host the audit ran on in JSON reports
+    #[serde(default)]
+    pub hostname: bool,
+
+    /// Naming style of the fields in JSON reports (default: legacy, a mix
+    /// of kebab-case and snake_case)
+    #[serde(default)]
+    pub field_naming: report::FieldNaming,
 }
 
 impl OutputConfig {
+    /// Are findings shown as a single line each?
+    pub fn is_condensed(&self) -> bool {
+        self.condensed && !self.verbose
+    }
+
     /// Is quiet mode enabled?
     pub fn is_quiet(&self) -> bool {
-        self.quiet || self.format == OutputFormat::Json
+        self.quiet || self.format.is_json()
+    }
+}
+
+/// Number of lines truncated advisory descriptions are limited to by default
+pub const DEFAULT_DESCRIPTION_LINES: usize = 5;
+
+/// Whether advisory descriptions are shown
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DescriptionMode {
+    /// Show descriptions in full
+    Full,
+
+    /// Show the first few lines of descriptions (see
+    /// [`OutputConfig::description_lines`])
+    Truncated,
+
+    /// Don't show descriptions
+    None,
+}
+
+impl Default for DescriptionMode {
+    fn default() -> Self {
+        DescriptionMode::None
+    }
+}
+
+impl FromStr for DescriptionMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "full" => Ok(DescriptionMode::Full),
+            "truncated" => Ok(DescriptionMode::Truncated),
+            "none" => Ok(DescriptionMode::None),
+            other => Err(Error::new(
+                ErrorKind::Parse,
+                &format!("invalid description mode: {}", other),
+            )),
+        }
     }
 }
 
@@ -176,6 +598,19 @@ pub enum DenyOption {
     /// Deny yanked dependency warnings
     #[serde(rename = "yanked")]
     Yanked,
+
+    /// Deny warnings about dependencies present in several versions
+    #[serde(rename = "duplicate")]
+    Duplicate,
+
+    /// Deny warnings about advisories affecting the project's own crates
+    #[serde(rename = "own-crate")]
+    OwnCrate,
+
+    /// Deny warnings about `Cargo.lock` entries which were skipped because
+    /// they couldn't be parsed
+    #[serde(rename = "malformed-lockfile")]
+    MalformedLockfile,
 }
 
 impl DenyOption {
@@ -186,6 +621,9 @@ impl DenyOption {
             DenyOption::Unmaintained,
             DenyOption::Unsound,
             DenyOption::Yanked,
+            DenyOption::Duplicate,
+            DenyOption::OwnCrate,
+            DenyOption::MalformedLockfile,
         ]
     }
     /// Get the warning::Kind that corresponds to self, if applicable
@@ -195,6 +633,9 @@ impl DenyOption {
             DenyOption::Unmaintained => Some(warning::Kind::Unmaintained),
             DenyOption::Unsound => Some(warning::Kind::Unsound),
             DenyOption::Yanked => Some(warning::Kind::Yanked),
+            DenyOption::Duplicate => Some(warning::Kind::Duplicate),
+            DenyOption::OwnCrate => Some(warning::Kind::OwnCrate),
+            DenyOption::MalformedLockfile => Some(warning::Kind::MalformedLockfile),
         }
     }
 }
@@ -208,6 +649,9 @@ impl FromStr for DenyOption {
             "unmaintained" => Ok(DenyOption::Unmaintained),
             "unsound" => Ok(DenyOption::Unsound),
             "yanked" => Ok(DenyOption::Yanked),
+            "duplicate" => Ok(DenyOption::Duplicate),
+            "own-crate" => Ok(DenyOption::OwnCrate),
+            "malformed-lockfile" => Ok(DenyOption::MalformedLockfile),
             other => Err(Error::new(
                 ErrorKind::Parse,
                 &format!("invalid deny option: {}", other),
@@ -226,6 +670,20 @@ pub enum OutputFormat {
     /// Display human-readable output to the terminal
     #[serde(rename = "terminal")]
     Terminal,
+
+    /// Display an in-toto attestation of the JSON report
+    #[serde(rename = "attestation")]
+    Attestation,
+}
+
+impl OutputFormat {
+    /// Is the output JSON (i.e. intended to be consumed by other tools)?
+    pub fn is_json(self) -> bool {
+        match self {
+            OutputFormat::Json | OutputFormat::Attestation => true,
+            OutputFormat::Terminal => false,
+        }
+    }
 }
 
 impl Default for OutputFormat {
@@ -234,6 +692,22 @@ impl Default for OutputFormat {
     }
 }
 
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "json" => Ok(OutputFormat::Json),
+            "terminal" => Ok(OutputFormat::Terminal),
+            "attestation" => Ok(OutputFormat::Attestation),
+            other => Err(Error::new(
+                ErrorKind::Parse,
+                &format!("invalid output format: {}", other),
+            )),
+        }
+    }
+}
+
 /// Target configuration
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -243,6 +717,22 @@ pub struct TargetConfig {
 
     /// Target OS to find vulnerabilities for
     pub os: Option<OS>,
+
+    /// Target triples to find vulnerabilities for (e.g.
+    /// `x86_64-unknown-linux-musl`), any of which may be affected
+    #[serde(default)]
+    pub triples: Vec<String>,
+
+    /// CPU architectures to ignore advisories for: advisories which only
+    /// affect these are ignored, even if they match the options above
+    #[serde(default)]
+    pub exclude_arch: Vec<Arch>,
+
+    /// Operating systems to ignore advisories for (e.g. `windows` for
+    /// server-only deployments): advisories which only affect these are
+    /// ignored, even if they match the options above
+    #[serde(default)]
+    pub exclude_os: Vec<OS>,
 }
 
 /// Packages configuration
@@ -251,6 +741,39 @@ pub struct TargetConfig {
 pub struct PackageConfig {
     /// Package scope which should be considered for querying for vulnerabilities.
     pub source: Option<scope::Registry>,
+
+    /// Teams which own packages, keyed by package name pattern (e.g. `tokio-*`)
+    #[serde(default)]
+    pub owners: report::Owners,
+
+    /// Warn about packages present in several versions in `Cargo.lock`
+    /// (default: false)
+    #[serde(default)]
+    pub duplicates: bool,
+}
+
+/// `Cargo.lock`-related configuration
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockfileConfig {
+    /// Refuse to audit a `Cargo.lock` which is out of sync with `Cargo.toml`
+    /// and never generate a missing one (default: false)
+    #[serde(default)]
+    pub locked: bool,
+
+    /// Never invoke `cargo`, skipping any checks which require it (default: false)
+    #[serde(default)]
+    pub no_cargo: bool,
+
+    /// Never execute any external processes, failing rather than doing so
+    /// (implies `no_cargo`, default: false)
+    #[serde(default)]
+    pub no_exec: bool,
+
+    /// Fail to audit a `Cargo.lock` containing any malformed entries, rather
+    /// than skipping them with a warning (default: false)
+    #[serde(default)]
+    pub strict: bool,
 }
 
 /// Configuration for auditing for yanked crates
@@ -264,6 +787,10 @@ pub struct YankedConfig {
     /// Should the crates.io index be updated before checking for yanked crates?
     #[serde(default = "default_true")]
     pub update_index: bool,
+
+    /// Protocol used to access the crates.io index
+    #[serde(default)]
+    pub protocol: IndexProtocol,
 }
 
 impl Default for YankedConfig {
@@ -271,10 +798,29 @@ impl Default for YankedConfig {
         Self {
             enabled: true,
             update_index: true,
+            protocol: IndexProtocol::default(),
         }
     }
 }
 
+/// Protocol used to access the crates.io index
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum IndexProtocol {
+    /// Local git checkout of the index
+    #[serde(rename = "git")]
+    Git,
+
+    /// Sparse HTTP index, which fetches only the entries being looked up
+    #[serde(rename = "sparse")]
+    Sparse,
+}
+
+impl Default for IndexProtocol {
+    fn default() -> Self {
+        IndexProtocol::Git
+    }
+}
+
 /// Helper function for returning a default of `true`
 fn default_true() -> bool {
     true
@@ -0,0 +1,522 @@
+//! Long-running audit service, which keeps the advisory database loaded in
+//! memory (refreshing it periodically) and audits lockfiles sent to it over
+//! HTTP.
+//!
+//! The API is deliberately minimal:
+//!
+//! - `POST /audit`: the request body is a `Cargo.lock` file, and the response
+//!   is the same JSON report `cargo audit --json` generates
+//! - `GET /health`: information about the loaded advisory database
+//!
+//! Errors are returned as JSON objects with an `error` message. The service
+//! doesn't support TLS or authentication, so it should only listen on
+//! loopback or otherwise trusted interfaces.
+
+use crate::{auditor::Auditor, prelude::*};
+use rustsec::{
+    error::{Error, ErrorKind},
+    report, Database,
+};
+use serde::Serialize;
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    str,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Maximum size of request bodies (i.e. lockfiles)
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// Maximum length of the request line and each header
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// Maximum number of connections handled at once, beyond which clients are
+/// turned away (each connection is handled by a thread of its own)
+const MAX_CONNECTIONS: usize = 64;
+
+/// How long to wait for clients to send their requests
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait for clients to receive responses
+const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Audit service
+pub struct Daemon {
+    /// Auditor generating reports, whose advisory database is replaced
+    /// whenever it's refreshed
+    auditor: Arc<Mutex<Auditor>>,
+
+    /// Number of connections currently being handled
+    connections: AtomicUsize,
+}
+
+impl Daemon {
+    /// Create a service which audits lockfiles with the given auditor
+    pub fn new(auditor: Auditor) -> Self {
+        Self {
+            auditor: Arc::new(Mutex::new(auditor)),
+            connections: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reload the advisory database with the given function every
+    /// `interval` in a background thread.
+    ///
+    /// If reloading fails, the previously loaded database keeps being used.
+    pub fn refresh_every<F, E>(&self, interval: Duration, load: F)
+    where
+        F: Fn() -> Result<Database, E> + Send + 'static,
+        E: fmt::Display,
+    {
+        let auditor = Arc::clone(&self.auditor);
+
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+
+            match load() {
+                Ok(refreshed) => {
+                    let count = refreshed.iter().count();
+                    auditor.lock().unwrap().set_database(refreshed);
+                    status_ok!("Refreshed", "advisory database ({} advisories)", count);
+                }
+                Err(e) => status_warn!("couldn't refresh advisory database: {}", e),
+            }
+        });
+    }
+
+    /// Serve requests on the given address until the process is terminated
+    pub fn serve(self, addr: impl ToSocketAddrs) -> Result<(), Error> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| Error::new(ErrorKind::Io, &format!("couldn't listen: {}", e)))?;
+
+        let daemon = Arc::new(self);
+
+        for stream in listener.incoming() {
+            // Errors accepting individual connections (e.g. the client
+            // disconnecting) shouldn't bring down the service
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+
+            let connection = match Connection::open(&daemon) {
+                Some(connection) => connection,
+                None => {
+                    // Clients slow to receive the rejection mustn't hold up
+                    // accepting other connections. It's small enough to fit
+                    // in the socket's send buffer, so it's written without
+                    // blocking (or not at all).
+                    let mut stream = stream;
+                    if stream.set_nonblocking(true).is_ok() {
+                        let _ = Response::error(503, "too many connections").write_to(&mut stream);
+                    }
+                    continue;
+                }
+            };
+
+            let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+
+            thread::spawn(move || connection.daemon.handle(stream));
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single connection
+    fn handle(&self, mut stream: TcpStream) {
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+        let response = match Request::read(&mut BufReader::new(&stream)) {
+            Ok(request) => self.respond(&request),
+            Err(response) => response,
+        };
+
+        // There's nothing to be done if the client went away
+        let _ = response.write_to(&mut stream);
+    }
+
+    /// Generate the response to a request
+    fn respond(&self, request: &Request) -> Response {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/audit") => self.audit(&request.body),
+            ("GET", "/health") => {
+                let auditor = self.auditor.lock().unwrap();
+                Response::json(200, &report::DatabaseInfo::new(auditor.database()))
+            }
+            (_, "/audit") | (_, "/health") => Response::error(405, "method not allowed"),
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    /// Audit the lockfile in a request body
+    fn audit(&self, body: &[u8]) -> Response {
+        let lockfile_toml = match str::from_utf8(body) {
+            Ok(body) => body,
+            Err(_) => return Response::error(400, "lockfile isn't valid UTF-8"),
+        };
+
+        match self.auditor.lock().unwrap().report_lockfile(lockfile_toml) {
+            Ok(report) => Response::json(200, &report),
+            Err(e) => Response::error(400, &format!("invalid lockfile: {}", e)),
+        }
+    }
+}
+
+/// Connection being handled, which counts towards [`MAX_CONNECTIONS`] until
+/// it's dropped
+struct Connection {
+    /// Service handling the connection
+    daemon: Arc<Daemon>,
+}
+
+impl Connection {
+    /// Start handling a connection, unless too many already are
+    fn open(daemon: &Arc<Daemon>) -> Option<Self> {
+        if daemon.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            daemon.connections.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+
+        Some(Self {
+            daemon: Arc::clone(daemon),
+        })
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.daemon.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// HTTP request
+#[derive(Debug)]
+struct Request {
+    /// Request method (e.g. `POST`)
+    method: String,
+
+    /// Requested path, without any query string
+    path: String,
+
+    /// Request body
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// Read a request, returning the response to send if it's invalid
+    fn read(reader: &mut impl BufRead) -> Result<Self, Response> {
+        let request_line = read_line(reader)?;
+        let mut parts = request_line.split_whitespace();
+
+        let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+                (method, target)
+            }
+            _ => return Err(Response::error(400, "malformed request line")),
+        };
+
+        let mut content_length = 0;
+
+        loop {
+            let header = read_line(reader)?;
+
+            if header.is_empty() {
+                break;
+            }
+
+            let mut header = header.splitn(2, ':');
+            let name = header.next().unwrap_or_default().trim();
+            let value = header.next().unwrap_or_default().trim();
+
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .parse()
+                    .map_err(|_| Response::error(400, "invalid Content-Length"))?;
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                return Err(Response::error(411, "Content-Length is required"));
+            }
+        }
+
+        if content_length > MAX_BODY_SIZE {
+            return Err(Response::error(413, "request body is too large"));
+        }
+
+        // Read the body as it arrives, rather than allocating however much
+        // the client claims to send up front
+        let mut body = vec![];
+        reader
+            .by_ref()
+            .take(content_length as u64)
+            .read_to_end(&mut body)
+            .map_err(|e| Response::error(400, &format!("couldn't read request body: {}", e)))?;
+
+        if body.len() < content_length {
+            return Err(Response::error(400, "unexpected end of request body"));
+        }
+
+        Ok(Self {
+            method: method.to_owned(),
+            path: target.splitn(2, '?').next().unwrap_or_default().to_owned(),
+            body,
+        })
+    }
+}
+
+/// Read a CRLF-terminated line of the request head
+fn read_line(reader: &mut impl BufRead) -> Result<String, Response> {
+    let mut line = String::new();
+
+    match reader
+        .by_ref()
+        .take(MAX_LINE_LENGTH as u64 + 1)
+        .read_line(&mut line)
+    {
+        Ok(0) => Err(Response::error(400, "unexpected end of request")),
+        Ok(_) if line.len() > MAX_LINE_LENGTH => {
+            Err(Response::error(400, "request header is too long"))
+        }
+        Ok(_) => Ok(line.trim_end_matches(&['\r', '\n'][..]).to_owned()),
+        Err(e) => Err(Response::error(
+            400,
+            &format!("couldn't read request: {}", e),
+        )),
+    }
+}
+
+/// HTTP response with a JSON body
+#[derive(Debug)]
+struct Response {
+    /// Status code
+    status: u16,
+
+    /// JSON body
+    body: String,
+}
+
+impl Response {
+    /// Create a response with the given value serialized as its body
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self { status, body },
+            Err(e) => Self::error(500, &format!("couldn't serialize response: {}", e)),
+        }
+    }
+
+    /// Create an error response
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+
+    /// Write the response to the client
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\
+             \r\n\
+             {}",
+            self.status,
+            reason_phrase(self.status),
+            self.body.len(),
+            self.body
+        )?;
+
+        writer.flush()
+    }
+}
+
+/// Get the reason phrase for an HTTP status code
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Connection, Daemon, Request, Response, MAX_CONNECTIONS};
+    use crate::{auditor::Auditor, config::AuditConfig};
+    use rustsec::{database::Source, Database};
+    use std::{path::Path, sync::Arc};
+
+    /// Advisory about versions of `base` before 1.2.3
+    const ADVISORY: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2001-2101"
+package = "base"
+date = "2001-02-03"
+
+[versions]
+patched = [">= 1.2.3"]
+```
+
+# All your base are belong to us
+
+You have no chance to survive.
+"#;
+
+    /// Lockfile containing a vulnerable version of `base`
+    const LOCKFILE: &str = r#"version = 3
+
+[[package]]
+name = "base"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    fn read(request: &str) -> Result<Request, Response> {
+        Request::read(&mut request.as_bytes())
+    }
+
+    fn daemon() -> Daemon {
+        let path = Path::new("crates/base/RUSTSEC-2001-2101.md");
+        let database =
+            Database::load_from_files(vec![(path, ADVISORY)], Source::directory(Path::new(".")))
+                .unwrap();
+
+        // Don't check for yanked crates, which needs the crates.io index
+        let mut config = AuditConfig::default();
+        config.yanked.enabled = false;
+
+        Daemon::new(Auditor::with_database(&config, database))
+    }
+
+    fn request(method: &str, path: &str, body: &[u8]) -> Request {
+        Request {
+            method: method.to_owned(),
+            path: path.to_owned(),
+            body: body.to_vec(),
+        }
+    }
+
+    fn json(response: &Response) -> serde_json::Value {
+        serde_json::from_str(&response.body).unwrap()
+    }
+
+    #[test]
+    fn audit_lockfile() {
+        let response = daemon().respond(&request("POST", "/audit", LOCKFILE.as_bytes()));
+        assert_eq!(response.status, 200);
+
+        let report = json(&response);
+        assert_eq!(report["vulnerabilities"]["found"], true);
+        assert_eq!(report["vulnerabilities"]["count"], 1);
+        assert_eq!(
+            report["vulnerabilities"]["list"][0]["advisory"]["id"],
+            "RUSTSEC-2001-2101"
+        );
+
+        // Reports are generated the same way as by `cargo audit --json`
+        assert!(report["tool"].is_object());
+    }
+
+    #[test]
+    fn audit_invalid_lockfile() {
+        let daemon = daemon();
+
+        let response = daemon.respond(&request("POST", "/audit", b"[[package]"));
+        assert_eq!(response.status, 400);
+        assert!(json(&response)["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("invalid lockfile"));
+
+        let response = daemon.respond(&request("POST", "/audit", &[0xff, 0xfe]));
+        assert_eq!(response.status, 400);
+    }
+
+    #[test]
+    fn respond_to_requests() {
+        let daemon = daemon();
+
+        let response = daemon.respond(&request("GET", "/health", b""));
+        assert_eq!(response.status, 200);
+        assert!(json(&response).is_object());
+
+        assert_eq!(daemon.respond(&request("GET", "/audit", b"")).status, 405);
+        assert_eq!(daemon.respond(&request("POST", "/health", b"")).status, 405);
+        assert_eq!(daemon.respond(&request("GET", "/", b"")).status, 404);
+    }
+
+    #[test]
+    fn limit_connections() {
+        let daemon = Arc::new(daemon());
+
+        let connections = (0..MAX_CONNECTIONS)
+            .map(|_| Connection::open(&daemon).unwrap())
+            .collect::<Vec<_>>();
+        assert!(Connection::open(&daemon).is_none());
+
+        drop(connections);
+        assert!(Connection::open(&daemon).is_some());
+    }
+
+    #[test]
+    fn read_request() {
+        let request = read(
+            "POST /audit?verbose HTTP/1.1\r\nHost: localhost\r\ncontent-length: 5\r\n\r\nhello",
+        )
+        .unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/audit");
+        assert_eq!(request.body, b"hello");
+    }
+
+    #[test]
+    fn reject_invalid_requests() {
+        assert_eq!(read("").unwrap_err().status, 400);
+        assert_eq!(read("GET /health\r\n\r\n").unwrap_err().status, 400);
+        assert_eq!(
+            read("POST /audit HTTP/1.1\r\nContent-Length: 999999999\r\n\r\n")
+                .unwrap_err()
+                .status,
+            413
+        );
+        assert_eq!(
+            read("POST /audit HTTP/1.1\r\nContent-Length: 10\r\n\r\nhello")
+                .unwrap_err()
+                .status,
+            400
+        );
+        assert_eq!(
+            read("POST /audit HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n")
+                .unwrap_err()
+                .status,
+            411
+        );
+    }
+
+    #[test]
+    fn write_response() {
+        let mut out = vec![];
+        Response::error(404, "not found")
+            .write_to(&mut out)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\
+             Content-Length: 21\r\nConnection: close\r\n\r\n{\"error\":\"not found\"}"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Snapshot of the advisory database embedded into the `cargo-audit` binary
+//! at build time (see `build.rs`), for auditing without network access.
+//!
+//! Enabled by the `embedded-db` feature. The embedded snapshot is used when
+//! no other database is available, or when it's newer than the one on disk.
+
+use rustsec::{
+    database::{Database, Source},
+    error::Error,
+};
+use std::{
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/embedded_db.rs"));
+}
+
+pub use self::generated::COMMIT_ID;
+
+/// Load the embedded advisory database snapshot
+pub fn load() -> Result<Database, Error> {
+    let files = generated::FILES
+        .iter()
+        .map(|(path, data)| (Path::new(*path), *data));
+
+    Database::load_from_files(files, Source::embedded(COMMIT_ID.map(ToOwned::to_owned)))
+}
+
+/// Time of the commit the embedded snapshot was taken at, if known
+pub fn timestamp() -> Option<SystemTime> {
+    generated::TIMESTAMP.map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Is the embedded snapshot newer than the given database? Databases without
+/// commit information (e.g. vendored copies) are assumed to be current.
+pub fn is_newer_than(database: &Database) -> bool {
+    match (timestamp(), database.latest_commit()) {
+        (Some(embedded), Some(commit)) => embedded > commit.timestamp,
+        _ => false,
+    }
+}
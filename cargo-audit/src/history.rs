@@ -0,0 +1,180 @@
+//! History of audit results, kept as an append-only file of
+//! newline-delimited JSON entries (one per run).
+//!
+//! Each entry summarizes a run compactly, including which advisories were
+//! newly found or resolved relative to the previous entry, so trends in a
+//! project's security debt can be graphed without any external
+//! infrastructure.
+
+use rustsec::{
+    error::{Error, ErrorKind},
+    Report,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet as Set,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::SystemTime,
+};
+
+/// Summary of a single audit run
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Entry {
+    /// When the audit was performed (RFC 3339)
+    pub timestamp: String,
+
+    /// Git commit of the advisory database the audit was performed against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database_commit: Option<String>,
+
+    /// Number of vulnerabilities found
+    pub vulnerabilities: usize,
+
+    /// Number of warnings found
+    pub warnings: usize,
+
+    /// IDs of all advisories which were found
+    #[serde(default)]
+    pub ids: Set<String>,
+
+    /// IDs of advisories which weren't found by the previous run
+    #[serde(default)]
+    pub new: Set<String>,
+
+    /// IDs of advisories which were found by the previous run, but not this one
+    #[serde(default)]
+    pub resolved: Set<String>,
+}
+
+impl Entry {
+    /// Summarize the given report, comparing it against the previous entry
+    pub fn new(report: &Report, previous: Option<&Entry>) -> Self {
+        let ids = report
+            .vulnerabilities
+            .list
+            .iter()
+            .map(|vuln| vuln.advisory.id.to_string())
+            .chain(
+                report
+                    .warnings
+                    .values()
+                    .flatten()
+                    .filter_map(|warning| warning.id().map(ToString::to_string)),
+            )
+            .collect::<Set<_>>();
+
+        let (new, resolved) = match previous {
+            Some(previous) => (
+                ids.difference(&previous.ids).cloned().collect(),
+                previous.ids.difference(&ids).cloned().collect(),
+            ),
+            None => (ids.clone(), Set::new()),
+        };
+
+        Self {
+            timestamp: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            database_commit: report.database.last_commit.clone(),
+            vulnerabilities: report.vulnerabilities.count,
+            warnings: report.warnings.values().map(Vec::len).sum(),
+            ids,
+            new,
+            resolved,
+        }
+    }
+}
+
+/// Append a summary of the given report to the history file at the given
+/// path (creating it if it doesn't exist), returning the new entry
+pub fn append(path: &Path, report: &Report) -> Result<Entry, Error> {
+    let previous = last_entry(path)?;
+    let entry = Entry::new(report, previous.as_ref());
+
+    let mut line = serde_json::to_string(&entry).map_err(|e| {
+        Error::new(
+            ErrorKind::Parse,
+            &format!("couldn't serialize history entry: {}", e),
+        )
+    })?;
+    line.push('\n');
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| history_error(path, e))?;
+
+    Ok(entry)
+}
+
+/// Read the last entry of the history file at the given path (if any)
+pub fn last_entry(path: &Path) -> Result<Option<Entry>, Error> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(history_error(path, e)),
+    };
+
+    match contents.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => serde_json::from_str(line).map(Some).map_err(|e| {
+            Error::new(
+                ErrorKind::Parse,
+                &format!("malformed history file {}: {}", path.display(), e),
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Error accessing the history file at the given path
+fn history_error(path: &Path, e: io::Error) -> Error {
+    Error::new(
+        ErrorKind::Io,
+        &format!("couldn't access history file {}: {}", path.display(), e),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append, last_entry, Entry};
+
+    #[test]
+    fn appends_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audits.ndjson");
+        assert_eq!(last_entry(&path).unwrap(), None);
+
+        let report: rustsec::Report = serde_json::from_str(REPORT).unwrap();
+        let first = append(&path, &report).unwrap();
+        assert_eq!(first.vulnerabilities, 0);
+        assert!(first.new.is_empty() && first.resolved.is_empty());
+
+        let mut previous = first.clone();
+        previous.ids.insert("RUSTSEC-2019-0001".to_owned());
+        let second = Entry::new(&report, Some(&previous));
+        assert_eq!(
+            second.resolved.into_iter().collect::<Vec<_>>(),
+            ["RUSTSEC-2019-0001"]
+        );
+
+        append(&path, &report).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        assert_eq!(last_entry(&path).unwrap().unwrap().ids, first.ids);
+    }
+
+    /// Minimal report without any findings
+    const REPORT: &str = r#"{
+        "database": {
+            "advisory-count": 0,
+            "last-commit": "0123456789abcdef0123456789abcdef01234567",
+            "last-updated": null
+        },
+        "lockfile": { "dependency-count": 0 },
+        "settings": { "ignore": [], "informational_warnings": [] },
+        "vulnerabilities": { "found": false, "count": 0, "list": [] },
+        "warnings": {}
+    }"#;
+}
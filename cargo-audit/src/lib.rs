@@ -21,13 +21,23 @@
 #![warn(missing_docs, rust_2018_idioms, trivial_casts, unused_qualifications)]
 
 pub mod application;
+pub mod attestation;
 pub mod auditor;
+pub mod badge;
 pub mod commands;
 pub mod config;
+pub mod daemon;
+#[cfg(feature = "embedded-db")]
+pub mod embedded;
 pub mod error;
+pub mod history;
 pub mod lockfile;
 mod prelude;
 pub mod presenter;
+pub mod progress;
+pub mod requirements;
+pub mod sbom;
+pub mod vendor;
 
 /// Current version of the `cargo-audit` crate
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
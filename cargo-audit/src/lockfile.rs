@@ -1,11 +1,69 @@
 //! Cargo.lock-related utilities
 
-use rustsec::error::{Error, ErrorKind};
-use std::process::Command;
+use rustsec::{
+    error::{Error, ErrorKind},
+    package,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Are external processes forbidden? (see [`forbid_exec`])
+static EXEC_FORBIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Forbid executing external processes for the rest of the run: anything
+/// which would need to invoke `cargo` fails instead (used by `--no-exec`).
+///
+/// `cargo` is the only external process `cargo audit` ever runs: the advisory
+/// DB and the crates.io index are fetched with `libgit2` or over HTTP, rather
+/// than with the `git` binary.
+pub fn forbid_exec() {
+    EXEC_FORBIDDEN.store(true, Ordering::SeqCst);
+}
+
+/// Have external processes been forbidden?
+pub fn is_exec_forbidden() -> bool {
+    EXEC_FORBIDDEN.load(Ordering::SeqCst)
+}
+
+/// Prepare to run the given `cargo` subcommand, failing if external
+/// processes have been forbidden
+fn cargo(subcommand: &str) -> Result<Command, Error> {
+    if is_exec_forbidden() {
+        return Err(Error::new(
+            ErrorKind::BadParam,
+            &format!(
+                "refusing to run `cargo {}` as --no-exec was passed",
+                subcommand
+            ),
+        ));
+    }
+
+    let mut command = Command::new("cargo");
+    command.arg(subcommand);
+    Ok(command)
+}
 
 /// Run `cargo generate-lockfile`
 pub fn generate() -> Result<(), Error> {
-    let status = Command::new("cargo").arg("generate-lockfile").status();
+    run_generate(&mut cargo("generate-lockfile")?)
+}
+
+/// Run `cargo generate-lockfile` for the given `Cargo.toml`
+pub fn generate_for(manifest_path: &Path) -> Result<(), Error> {
+    run_generate(
+        cargo("generate-lockfile")?
+            .arg("--manifest-path")
+            .arg(manifest_path),
+    )
+}
+
+/// Run the given `cargo generate-lockfile` command
+fn run_generate(command: &mut Command) -> Result<(), Error> {
+    let status = command.status();
 
     if let Err(e) = status {
         return Err(Error::new(
@@ -28,3 +86,181 @@ pub fn generate() -> Result<(), Error> {
     }
     Ok(())
 }
+
+/// Check whether `Cargo.lock` is in sync with the given `Cargo.toml` by
+/// running `cargo metadata --locked`, which fails if the lockfile would need
+/// to be updated.
+///
+/// `cargo metadata --locked` also fails if the manifest itself is invalid,
+/// so the manifest is checked with `cargo metadata --no-deps` first: only a
+/// failure of the former with a valid manifest means the lockfile is out of
+/// date.
+pub fn is_in_sync(manifest_path: &Path) -> Result<bool, Error> {
+    metadata(manifest_path)?;
+
+    let status = cargo("metadata")?
+        .arg("--locked")
+        .arg("--format-version=1")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Io,
+                &format!("couldn't run `cargo metadata`: {}", e),
+            )
+        })?;
+
+    Ok(status.success())
+}
+
+/// Get the names of the workspace members for the given `Cargo.toml` by
+/// running `cargo metadata --no-deps`
+pub fn workspace_members(manifest_path: &Path) -> Result<Vec<package::Name>, Error> {
+    // With `--no-deps`, `packages` contains only the workspace members
+    metadata(manifest_path)?["packages"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|package| package["name"].as_str())
+        .map(|name| name.parse().map_err(Error::from))
+        .collect()
+}
+
+/// Run `cargo metadata --no-deps` for the given `Cargo.toml`, returning its
+/// (JSON) output
+pub fn metadata(manifest_path: &Path) -> Result<serde_json::Value, Error> {
+    let output = cargo("metadata")?
+        .arg("--no-deps")
+        .arg("--format-version=1")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Io,
+                &format!("couldn't run `cargo metadata`: {}", e),
+            )
+        })?;
+
+    if !output.status.success() {
+        return Err(Error::new(
+            ErrorKind::Io,
+            &"non-zero exit status running `cargo metadata`",
+        ));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        Error::new(
+            ErrorKind::Parse,
+            &format!("couldn't parse `cargo metadata` output: {}", e),
+        )
+    })
+}
+
+/// Find all of the `Cargo.lock` files in the given directory tree, sorted
+/// by path.
+///
+/// Hidden directories (e.g. `.git`), `target` directories, and symlinks
+/// aren't searched.
+pub fn find_all(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut lockfiles = vec![];
+    let mut dirs = vec![dir.to_owned()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = fs::read_dir(&dir).map_err(|e| {
+            Error::new(
+                ErrorKind::Io,
+                &format!("couldn't read directory {}: {}", dir.display(), e),
+            )
+        })?;
+
+        for entry in entries {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+
+            if file_type.is_dir() {
+                if !file_name.starts_with('.') && file_name != "target" {
+                    dirs.push(entry.path());
+                }
+            } else if file_type.is_file() && file_name == "Cargo.lock" {
+                lockfiles.push(entry.path());
+            }
+        }
+    }
+
+    lockfiles.sort();
+    Ok(lockfiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_all, is_in_sync};
+    use std::fs;
+
+    #[test]
+    fn find_all_lockfiles() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for project in &["a", "b/nested", "a/target/c", ".hidden"] {
+            let project_dir = dir.path().join(project);
+            fs::create_dir_all(&project_dir).unwrap();
+            fs::write(project_dir.join("Cargo.lock"), "").unwrap();
+        }
+
+        let lockfiles = find_all(dir.path()).unwrap();
+
+        assert_eq!(
+            lockfiles,
+            vec![
+                dir.path().join("a/Cargo.lock"),
+                dir.path().join("b/nested/Cargo.lock"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lockfile_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("Cargo.toml");
+        let manifest = "[package]\nname = \"a\"\nversion = \"0.1.0\"\n";
+
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+        fs::write(&manifest_path, manifest).unwrap();
+        fs::write(
+            dir.path().join("Cargo.lock"),
+            "version = 3\n\n[[package]]\nname = \"a\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        assert!(is_in_sync(&manifest_path).unwrap());
+
+        // Adding a dependency (a path one, so the test doesn't need the
+        // network) makes the lockfile out of date
+        fs::create_dir_all(dir.path().join("b/src")).unwrap();
+        fs::write(dir.path().join("b/src/lib.rs"), "").unwrap();
+        fs::write(
+            dir.path().join("b/Cargo.toml"),
+            "[package]\nname = \"b\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            &manifest_path,
+            format!("{}\n[dependencies]\nb = {{ path = \"b\" }}\n", manifest),
+        )
+        .unwrap();
+
+        assert!(!is_in_sync(&manifest_path).unwrap());
+
+        // An invalid manifest is an error rather than an out of date lockfile
+        fs::write(&manifest_path, "[package").unwrap();
+        assert!(is_in_sync(&manifest_path).is_err());
+    }
+}
@@ -1,22 +1,51 @@
 //! Presenter for `rustsec::Report` information.
 
+mod messages;
+
+pub use self::messages::Messages;
+
+use self::messages::format_message;
 use crate::{
-    config::{DenyOption, OutputConfig, OutputFormat},
+    attestation,
+    config::{DenyOption, DescriptionMode, OutputConfig, OutputFormat, DEFAULT_DESCRIPTION_LINES},
     prelude::*,
+    requirements,
 };
 use abscissa_core::terminal::{
     self,
-    Color::{self, Red, Yellow},
+    Color::{self, Green, Red, Yellow},
+};
+use rustsec::{
+    advisory::Severity,
+    cargo_lock::{
+        dependency::{self, graph::EdgeDirection, Dependency},
+        package, Lockfile, Package,
+    },
+    database::LoadProfile,
+    dependency::Relationship,
+    exposure::{ExposureCommit, ExposureWindow},
+    report,
+    vulnerability::{FixAge, Remedy},
+    VersionReq,
 };
-use rustsec::cargo_lock::{
-    dependency::{self, graph::EdgeDirection, Dependency},
-    Lockfile, Package,
+use std::{
+    cmp,
+    collections::{BTreeMap as Map, BTreeSet as Set},
+    env, io, mem,
+    path::{Path, PathBuf},
+    time::Duration,
 };
-use std::{collections::BTreeSet as Set, io, path::Path};
 
 use std::io::Write as _;
 use std::string::ToString as _;
 
+/// Minimum width of attribute labels (e.g. `Crate:`), used for alignment
+const ATTR_WIDTH: usize = 14;
+
+/// Minimum width to wrap attribute values at: narrower terminals aren't
+/// worth wrapping for
+const MIN_WRAP_WIDTH: usize = 20;
+
 /// Vulnerability information presenter
 #[derive(Clone, Debug)]
 pub struct Presenter {
@@ -27,6 +56,19 @@ pub struct Presenter {
     /// Keep track of the warning kinds that correspond to deny-warnings options
     deny_warning_kinds: Set<rustsec::warning::Kind>,
 
+    /// Filesystem paths of packages (e.g. vendored crates) to display
+    package_paths: Map<Dependency, PathBuf>,
+
+    /// Names of packages with several versions in the lockfile, which need
+    /// to be qualified with a version when passed to `cargo update -p`
+    ambiguous_packages: Set<package::Name>,
+
+    /// Path to the lockfile currently being reported on
+    lockfile_path: PathBuf,
+
+    /// Width of the terminal, if STDOUT is a terminal
+    terminal_width: Option<usize>,
+
     /// Output configuration
     config: OutputConfig,
 }
@@ -41,30 +83,37 @@ impl Presenter {
                 .iter()
                 .filter_map(|k| k.get_warning_kind())
                 .collect(),
+            package_paths: Map::new(),
+            ambiguous_packages: Set::new(),
+            lockfile_path: PathBuf::new(),
+            terminal_width: terminal_width(),
             config: config.clone(),
         }
     }
 
+    /// Set the filesystem paths to display alongside packages
+    pub fn set_package_paths(&mut self, paths: Map<Dependency, PathBuf>) {
+        self.package_paths = paths;
+    }
+
     /// Information to display before a report is generated
     pub fn before_report(&mut self, lockfile_path: &Path, lockfile: &Lockfile) {
+        self.lockfile_path = lockfile_path.to_owned();
+
         if !self.config.is_quiet() {
             status_ok!(
                 "Scanning",
-                "{} for vulnerabilities ({} crate dependencies)",
-                lockfile_path.display(),
-                lockfile.packages.len(),
+                format_message(
+                    &self.config.messages.scanning,
+                    &[
+                        ("path", &lockfile_path.display()),
+                        ("count", &lockfile.packages.len())
+                    ]
+                )
             );
         }
     }
 
-    fn warning_word(&self, count: u64) -> &str {
-        if count != 1 {
-            "warnings"
-        } else {
-            "warning"
-        }
-    }
-
     /// Print the vulnerability report generated by an audit
     pub fn print_report(
         &mut self,
@@ -72,55 +121,126 @@ impl Presenter {
         self_advisories: &[rustsec::Advisory],
         lockfile: &Lockfile,
     ) {
-        if self.config.format == OutputFormat::Json {
-            serde_json::to_writer(io::stdout(), &report).unwrap();
-            io::stdout().flush().unwrap();
-            return;
+        match self.config.format {
+            OutputFormat::Json => {
+                serde_json::to_writer(io::stdout(), &self.config.field_naming.apply(&report))
+                    .unwrap();
+                io::stdout().flush().unwrap();
+                return;
+            }
+            OutputFormat::Attestation => {
+                let statement = attestation::Statement::new(&self.lockfile_path, lockfile, report);
+                serde_json::to_writer(io::stdout(), &statement).unwrap();
+                io::stdout().flush().unwrap();
+                return;
+            }
+            OutputFormat::Terminal => (),
         }
 
         // We'll set this to true if (e.g.) we see a warning and have deny-warnings enabled.
         // Once we've printed the whole report, we'll bail out of the whole program.
         let mut exit_with_failure = false;
 
-        let tree = lockfile
-            .dependency_tree()
-            .expect("invalid Cargo.lock dependency tree");
-
-        // Print out vulnerabilities and warnings
-        for vulnerability in &report.vulnerabilities.list {
-            self.print_vulnerability(vulnerability, &tree);
+        if self.print_findings(report, lockfile) && self.config.is_condensed() {
+            status_ok!("Hint", &self.config.messages.verbose_hint);
         }
 
-        for warnings in report.warnings.values() {
-            for warning in warnings.iter() {
-                self.print_warning(warning, &tree)
-            }
+        self.print_self_advisories(self_advisories);
+
+        for accepted in &report.accepted {
+            let vulnerability = &accepted.vulnerability;
+            status_ok!(
+                "Accepted",
+                format_message(
+                    &self.config.messages.accepted_patched_fork,
+                    &[
+                        ("id", &vulnerability.advisory.id),
+                        ("crate", &vulnerability.package.name),
+                        ("version", &vulnerability.package.version),
+                        ("fork", &accepted.patched_fork),
+                    ]
+                )
+            );
         }
 
-        // Print out any self-advisories
-        if !self_advisories.is_empty() {
-            let msg = "This copy of cargo-audit has known advisories!";
+        for notice in &report.notices {
+            status_warn!(notice);
+        }
 
-            if self.config.deny.contains(&DenyOption::Warnings) {
-                status_err!(msg);
-            } else {
-                status_warn!(msg);
-            }
+        if let Some(warnings) = report.warnings.get(&rustsec::warning::Kind::OwnCrate) {
+            for warning in warnings {
+                let id = match warning.id() {
+                    Some(id) => id,
+                    None => continue,
+                };
 
-            for advisory in self_advisories {
-                self.print_metadata(
-                    &advisory.metadata,
-                    self.warning_color(self.config.deny.contains(&DenyOption::Warnings)),
+                let message = format_message(
+                    &self.config.messages.own_crate_subject,
+                    &[
+                        ("crate", &warning.package.name),
+                        ("version", &warning.package.version),
+                        ("id", id),
+                    ],
                 );
+
+                if self.is_denied(warning) {
+                    status_err!(message);
+                } else {
+                    status_warn!(message);
+                }
             }
-            println!();
+        }
+
+        if let Some(stats) = &report.stats {
+            self.print_stats(stats, report.database.advisory_count);
         }
 
         if report.vulnerabilities.found {
             if report.vulnerabilities.count == 1 {
-                status_err!("1 vulnerability found!");
+                status_err!(&self.config.messages.vulnerability_found);
             } else {
-                status_err!("{} vulnerabilities found!", report.vulnerabilities.count);
+                status_err!(format_message(
+                    &self.config.messages.vulnerabilities_found,
+                    &[("count", &report.vulnerabilities.count)]
+                ));
+            }
+
+            self.print_remedy_summary(&report.vulnerabilities.list);
+
+            for member in &report.workspace_members {
+                if member.vulnerable_dependencies == 0 {
+                    continue;
+                }
+
+                let severity = member
+                    .highest_severity
+                    .map(|severity| severity.to_string())
+                    .unwrap_or_else(|| self.config.messages.unknown_severity.clone());
+
+                status_err!(format_message(
+                    &self.config.messages.workspace_member_summary,
+                    &[
+                        ("member", &member.name),
+                        ("count", &member.vulnerable_dependencies),
+                        ("severity", &severity),
+                    ]
+                ));
+            }
+
+            for target in &report.targets {
+                let message = format_message(
+                    &self.config.messages.target_summary,
+                    &[
+                        ("target", &target.target),
+                        ("count", &target.vulnerabilities.count),
+                    ],
+                );
+
+                if target.vulnerabilities.found {
+                    status_err!(&message);
+                } else {
+                    status_ok!("Target", &message);
+                }
             }
         }
 
@@ -128,35 +248,40 @@ impl Presenter {
         let mut num_denied: u64 = 0;
         let mut num_not_denied: u64 = 0;
 
-        for (kind, warnings) in report.warnings.iter() {
-            if self.deny_warning_kinds.contains(kind) {
-                num_denied += warnings.len() as u64;
+        for warning in report.warnings.values().flatten() {
+            if self.is_denied(warning) {
+                num_denied += 1;
             } else {
-                num_not_denied += warnings.len() as u64;
+                num_not_denied += 1;
             }
         }
 
         if num_denied > 0 || num_not_denied > 0 {
+            let messages = &self.config.messages;
+
             if num_denied > 0 {
-                status_err!(
-                    "{} denied {} found!",
-                    num_denied,
-                    self.warning_word(num_denied)
-                );
+                status_err!(format_message(
+                    &messages.denied_warnings_found,
+                    &[
+                        ("count", &num_denied),
+                        ("warnings", &messages.warning_word(num_denied))
+                    ]
+                ));
                 exit_with_failure = true;
             }
             if num_not_denied > 0 {
-                status_warn!(
-                    "{} allowed {} found",
-                    num_not_denied,
-                    self.warning_word(num_not_denied)
-                );
+                status_warn!(format_message(
+                    &messages.allowed_warnings_found,
+                    &[
+                        ("count", &num_not_denied),
+                        ("warnings", &messages.warning_word(num_not_denied))
+                    ]
+                ));
             }
         }
 
         if !self_advisories.is_empty() {
-            let upgrade_msg = "upgrade cargo-audit to the latest version: \
-                               cargo install --force cargo-audit";
+            let upgrade_msg = &self.config.messages.self_upgrade;
 
             if self.config.deny.contains(&DenyOption::Warnings) {
                 status_err!(upgrade_msg);
@@ -172,64 +297,620 @@ impl Presenter {
         }
     }
 
+    /// Print a report aggregated across several projects, where `lockfiles`
+    /// are the lockfiles of each of the projects in the report
+    pub fn print_aggregate_report(
+        &mut self,
+        aggregate: &report::AggregateReport,
+        lockfiles: &[Lockfile],
+        self_advisories: &[rustsec::Advisory],
+    ) {
+        // Attestations of aggregate reports are rejected by `AuditCommand`
+        if self.config.format.is_json() {
+            serde_json::to_writer(io::stdout(), &self.config.field_naming.apply(&aggregate))
+                .unwrap();
+            io::stdout().flush().unwrap();
+            return;
+        }
+
+        let mut any_findings = false;
+        let mut exit_with_failure = false;
+
+        for (project, lockfile) in aggregate.projects.iter().zip(lockfiles) {
+            let report = &project.report;
+            let warnings = report.warnings.values().map(Vec::len).sum::<usize>();
+
+            let color = if report.vulnerabilities.found {
+                Red
+            } else if warnings > 0 {
+                Yellow
+            } else {
+                Green
+            };
+
+            self.print_heading(
+                color,
+                format_message(
+                    &self.config.messages.project_summary,
+                    &[
+                        ("path", &project.lockfile.display()),
+                        ("vulnerabilities", &report.vulnerabilities.count),
+                        ("warnings", &warnings),
+                    ],
+                ),
+            );
+
+            any_findings |= self.print_findings(report, lockfile);
+
+            for notice in &report.notices {
+                status_warn!(notice);
+            }
+
+            exit_with_failure |= report
+                .warnings
+                .values()
+                .flatten()
+                .any(|warning| self.is_denied(warning));
+        }
+
+        if any_findings && self.config.is_condensed() {
+            status_ok!("Hint", &self.config.messages.verbose_hint);
+        }
+
+        self.print_self_advisories(self_advisories);
+
+        for notice in &aggregate.notices {
+            status_warn!(notice);
+        }
+
+        let summary = &aggregate.summary;
+        let summary_message = format_message(
+            &self.config.messages.aggregate_summary,
+            &[
+                ("projects", &summary.projects),
+                ("vulnerable", &summary.vulnerable_projects),
+                ("vulnerabilities", &summary.unique_vulnerabilities),
+                ("advisories", &summary.unique_advisories),
+                ("warnings", &summary.unique_warnings),
+            ],
+        );
+
+        if summary.vulnerable_projects > 0 {
+            status_err!(summary_message);
+        } else {
+            status_ok!("Summary", &summary_message);
+        }
+
+        if !self_advisories.is_empty() && self.config.deny.contains(&DenyOption::Warnings) {
+            status_err!(&self.config.messages.self_upgrade);
+            exit_with_failure = true;
+        }
+
+        if exit_with_failure {
+            std::process::exit(1);
+        }
+    }
+
+    /// Print the vulnerabilities (grouped by severity) and warnings in a
+    /// report, returning whether there were any findings to print
+    fn print_findings(&mut self, report: &rustsec::Report, lockfile: &Lockfile) -> bool {
+        // Trees are shown once per package within each report
+        self.displayed_packages.clear();
+
+        let tree = lockfile
+            .dependency_tree()
+            .expect("invalid Cargo.lock dependency tree");
+
+        let mut names = Set::new();
+        self.ambiguous_packages = lockfile
+            .packages
+            .iter()
+            .filter(|package| !names.insert(&package.name))
+            .map(|package| package.name.clone())
+            .collect();
+
+        // Print out vulnerabilities grouped by severity (most severe first,
+        // with unknown severities last), followed by warnings
+        let mut by_severity = Map::<cmp::Reverse<Option<Severity>>, Vec<_>>::new();
+
+        for vulnerability in &report.vulnerabilities.list {
+            let severity = vulnerability.severity.map(|info| info.severity);
+            by_severity
+                .entry(cmp::Reverse(severity))
+                .or_default()
+                .push(vulnerability);
+        }
+
+        for (cmp::Reverse(severity), vulnerabilities) in &by_severity {
+            let messages = &self.config.messages;
+            let severity = severity
+                .map(|severity| severity.to_string())
+                .unwrap_or_else(|| messages.unknown_severity.clone());
+
+            self.print_heading(
+                Red,
+                format_message(
+                    &messages.severity_group,
+                    &[("severity", &severity), ("count", &vulnerabilities.len())],
+                ),
+            );
+
+            for vulnerability in vulnerabilities {
+                if !self.config.is_condensed() {
+                    self.print_vulnerability(vulnerability, &tree);
+                } else {
+                    self.print_condensed_vulnerability(vulnerability);
+                }
+            }
+
+            if self.config.is_condensed() {
+                println!();
+            }
+        }
+
+        let warnings = report.warnings.values().flatten().collect::<Vec<_>>();
+
+        if !warnings.is_empty() {
+            let any_denied = warnings.iter().any(|warning| self.is_denied(warning));
+
+            self.print_heading(
+                self.warning_color(any_denied),
+                format_message(
+                    &self.config.messages.warnings_group,
+                    &[("count", &warnings.len())],
+                ),
+            );
+
+            for warning in &warnings {
+                if !self.config.is_condensed() {
+                    self.print_warning(warning, &tree);
+                } else {
+                    self.print_condensed_warning(warning);
+                }
+            }
+
+            if self.config.is_condensed() {
+                println!();
+            }
+        }
+
+        !(by_severity.is_empty() && warnings.is_empty())
+    }
+
+    /// Print any advisories about this copy of `cargo-audit`
+    fn print_self_advisories(&self, self_advisories: &[rustsec::Advisory]) {
+        if !self_advisories.is_empty() {
+            let msg = &self.config.messages.self_advisories;
+
+            if self.config.deny.contains(&DenyOption::Warnings) {
+                status_err!(msg);
+            } else {
+                status_warn!(msg);
+            }
+
+            for advisory in self_advisories {
+                self.print_metadata(
+                    &advisory.metadata,
+                    self.warning_color(self.config.deny.contains(&DenyOption::Warnings)),
+                );
+            }
+            println!();
+        }
+    }
+
+    /// Print the windows during which vulnerable dependencies were present
+    /// in the git history of the audited project
+    pub fn print_exposure_windows(&self, windows: &[ExposureWindow]) {
+        if self.config.format.is_json() {
+            serde_json::to_writer(io::stdout(), &self.config.field_naming.apply(&windows)).unwrap();
+            io::stdout().flush().unwrap();
+            return;
+        }
+
+        let messages = &self.config.messages;
+
+        if windows.is_empty() {
+            if !self.config.is_quiet() {
+                status_ok!("Success", &messages.no_exposure);
+            }
+            return;
+        }
+
+        for window in windows {
+            self.print_attr(Red, &messages.crate_label, &window.package);
+            self.print_attr(Red, &messages.version_label, &window.version.to_string());
+            self.print_attr(Red, &messages.id_label, &window.advisory);
+            self.print_attr(
+                Red,
+                &messages.introduced_label,
+                exposure_commit(&window.introduced),
+            );
+            self.print_attr(
+                Red,
+                &messages.resolved_label,
+                window
+                    .resolved
+                    .as_ref()
+                    .map(exposure_commit)
+                    .unwrap_or_else(|| messages.still_present.clone()),
+            );
+            println!();
+        }
+    }
+
+    /// Print the `Cargo.toml` requirements which admit vulnerable releases
+    pub fn print_loose_requirements(
+        &self,
+        loose: &[requirements::LooseRequirement],
+        notices: &[String],
+    ) {
+        if self.config.format.is_json() {
+            serde_json::to_writer(io::stdout(), &self.config.field_naming.apply(&loose)).unwrap();
+            io::stdout().flush().unwrap();
+            return;
+        }
+
+        let messages = &self.config.messages;
+
+        for notice in notices {
+            status_warn!(notice);
+        }
+
+        if loose.is_empty() {
+            if !self.config.is_quiet() {
+                status_ok!("Success", &messages.no_loose_requirements);
+            }
+            return;
+        }
+
+        for finding in loose {
+            let requirement = &finding.requirement;
+            let locked = finding
+                .locked
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| messages.not_locked.clone());
+
+            self.print_attr(Yellow, &messages.crate_label, &requirement.name);
+            self.print_attr(
+                Yellow,
+                &messages.requirement_label,
+                format!(
+                    "{} ({})",
+                    requirement.req,
+                    requirement.manifest_path.display()
+                ),
+            );
+            self.print_attr(Yellow, &messages.version_label, locked);
+            self.print_attr(Yellow, &messages.id_label, &finding.advisory);
+            self.print_attr(
+                Yellow,
+                &messages.admits_label,
+                format_message(
+                    &messages.vulnerable_releases,
+                    &[
+                        ("count", &finding.vulnerable.len()),
+                        ("earliest", &finding.vulnerable[0]),
+                        ("latest", &finding.vulnerable[finding.vulnerable.len() - 1]),
+                    ],
+                ),
+            );
+
+            let solution = match &finding.safe_minimum {
+                Some(version) => {
+                    format_message(&messages.raise_lower_bound, &[("version", version)])
+                }
+                None => messages.no_safe_upgrade.clone(),
+            };
+
+            self.print_attr(Yellow, &messages.solution_label, solution);
+            println!();
+        }
+
+        status_warn!(format_message(
+            &messages.loose_requirements_found,
+            &[("count", &loose.len())]
+        ));
+    }
+
+    /// Print the first denied finding when exiting early via `--exit-on-first`
+    pub fn print_first_finding(&self, finding: &report::Finding) {
+        let messages = &self.config.messages;
+
+        match finding {
+            report::Finding::Vulnerability(vulnerability) => {
+                self.print_attr(Red, &messages.crate_label, &vulnerability.package.name);
+                self.print_attr(
+                    Red,
+                    &messages.version_label,
+                    &vulnerability.package.version.to_string(),
+                );
+                self.print_metadata(&vulnerability.advisory, Red);
+                println!();
+                status_err!(&messages.first_vulnerability_found);
+            }
+            report::Finding::Warning(warning) => {
+                let kind = messages.warning_kind(&warning.kind);
+
+                self.print_attr(Red, &messages.crate_label, &warning.package.name);
+                self.print_attr(
+                    Red,
+                    &messages.version_label,
+                    &warning.package.version.to_string(),
+                );
+                self.print_attr(Red, &messages.warning_label, kind);
+
+                if let Some(metadata) = &warning.advisory {
+                    self.print_metadata(metadata, Red);
+                }
+
+                println!();
+                status_err!(format_message(
+                    &messages.first_denied_warning_found,
+                    &[("kind", &kind)]
+                ));
+            }
+        }
+    }
+
     /// Print information about the given vulnerability
     fn print_vulnerability(
         &mut self,
         vulnerability: &rustsec::Vulnerability,
         tree: &dependency::Tree,
     ) {
-        self.print_attr(Red, "Crate:        ", &vulnerability.package.name);
+        let messages = &self.config.messages;
+
+        self.print_attr(Red, &messages.crate_label, &vulnerability.package.name);
         self.print_attr(
             Red,
-            "Version:      ",
+            &messages.version_label,
             &vulnerability.package.version.to_string(),
         );
+        self.print_path(Red, &vulnerability.package);
+        self.print_owner(Red, vulnerability.owner.as_deref());
+        self.print_relationship(Red, vulnerability.relationship, vulnerability.depth);
+        self.print_review(Red, vulnerability.review);
         self.print_metadata(&vulnerability.advisory, Red);
+        self.print_override(Red, vulnerability.overridden.as_ref());
+        self.print_attr(Red, &messages.solution_label, self.solution(vulnerability));
+        self.print_fix_age(Red, vulnerability.fix_age.as_ref());
+        self.print_tree(Red, &vulnerability.package, tree);
+        println!();
+    }
+
+    /// Print a single line summarizing the given vulnerability
+    fn print_condensed_vulnerability(&self, vulnerability: &rustsec::Vulnerability) {
+        let line = format_message(
+            &self.config.messages.condensed_vulnerability,
+            &[
+                ("crate", &vulnerability.package.name),
+                ("version", &vulnerability.package.version),
+                ("title", &vulnerability.advisory.title),
+                ("solution", &self.solution(vulnerability)),
+            ],
+        );
+
+        self.print_attr(Red, &vulnerability.advisory.id.to_string(), line);
+    }
 
-        if vulnerability.versions.patched().is_empty() {
-            self.print_attr(Red, "Solution:     ", "No safe upgrade is available!");
+    /// Print a summary of how the given vulnerabilities can be fixed,
+    /// including the `cargo update` command which fixes the ones it can
+    fn print_remedy_summary(&self, vulnerabilities: &[rustsec::Vulnerability]) {
+        let messages = &self.config.messages;
+        let mut updatable = Set::new();
+        let mut fixable = 0;
+        let mut manual = 0;
+        let mut no_fix = 0;
+
+        for vulnerability in vulnerabilities {
+            match &vulnerability.remedy {
+                Some(Remedy::CargoUpdate { .. }) => {
+                    fixable += 1;
+                    updatable.insert(self.update_spec(&vulnerability.package));
+                }
+                Some(Remedy::ManualUpgrade { .. }) => manual += 1,
+                Some(Remedy::NoFix) => no_fix += 1,
+                None => (),
+            }
+        }
+
+        if fixable > 0 {
+            let command = updatable
+                .iter()
+                .fold(String::from("cargo update"), |cmd, spec| {
+                    format!("{} -p {}", cmd, spec)
+                });
+
+            status_warn!(format_message(
+                &messages.fixable_by_update,
+                &[("count", &fixable), ("command", &command)]
+            ));
+        }
+
+        if manual > 0 {
+            status_warn!(format_message(
+                &messages.needs_manual_upgrade,
+                &[("count", &manual)]
+            ));
+        }
+
+        if no_fix > 0 {
+            status_warn!(format_message(
+                &messages.no_fix_available,
+                &[("count", &no_fix)]
+            ));
+        }
+    }
+
+    /// Get the package ID spec to pass to `cargo update -p` for a package
+    fn update_spec(&self, package: &Package) -> String {
+        if self.ambiguous_packages.contains(&package.name) {
+            format!("{}:{}", package.name, package.version)
         } else {
-            self.print_attr(
-                Red,
-                "Solution:     ",
-                format!(
-                    "Upgrade to {}",
-                    vulnerability
-                        .versions
-                        .patched()
-                        .iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<_>>()
-                        .as_slice()
-                        .join(" OR ")
-                ),
-            );
+            package.name.to_string()
         }
+    }
 
-        self.print_tree(Red, &vulnerability.package, tree);
-        println!();
+    /// Get the suggested solution for a vulnerability
+    fn solution(&self, vulnerability: &rustsec::Vulnerability) -> String {
+        let messages = &self.config.messages;
+        let patched = vulnerability.versions.patched();
+
+        match &vulnerability.remedy {
+            Some(Remedy::CargoUpdate { version }) => {
+                return format_message(
+                    &messages.cargo_update_solution,
+                    &[
+                        ("package", &self.update_spec(&vulnerability.package)),
+                        ("version", version),
+                    ],
+                );
+            }
+            Some(Remedy::NoFix) => return messages.no_safe_upgrade.clone(),
+            _ => (),
+        }
+
+        if patched.is_empty() {
+            return messages.no_safe_upgrade.clone();
+        }
+
+        self.upgrade_to(patched)
+    }
+
+    /// Get the solution suggesting an upgrade to one of the given versions
+    fn upgrade_to(&self, patched: &[VersionReq]) -> String {
+        let messages = &self.config.messages;
+
+        format_message(
+            &messages.upgrade_to,
+            &[(
+                "versions",
+                &patched
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(&messages.or_separator),
+            )],
+        )
     }
 
     /// Print information about a given warning
     fn print_warning(&mut self, warning: &rustsec::Warning, tree: &dependency::Tree) {
-        let color = self.warning_color(self.deny_warning_kinds.contains(&warning.kind));
+        let color = self.warning_color(self.is_denied(warning));
+
+        let messages = &self.config.messages;
 
-        self.print_attr(color, "Crate:        ", &warning.package.name);
+        self.print_attr(color, &messages.crate_label, &warning.package.name);
         self.print_attr(
             color,
-            "Version:      ",
+            &messages.version_label,
             &warning.package.version.to_string(),
         );
-        self.print_attr(color, "Warning:      ", warning.kind.as_str());
+        self.print_attr(
+            color,
+            &messages.warning_label,
+            messages.warning_kind(&warning.kind),
+        );
+
+        self.print_path(color, &warning.package);
+        self.print_owner(color, warning.owner.as_deref());
+        self.print_relationship(color, warning.relationship, warning.depth);
+        self.print_review(color, warning.review);
 
         if let Some(metadata) = &warning.advisory {
             self.print_metadata(metadata, color)
         }
 
+        let aliases = warning.aliases();
+
+        if !aliases.is_empty() {
+            let aliases = aliases.iter().map(ToString::to_string).collect::<Vec<_>>();
+            self.print_attr(color, &messages.aliases_label, aliases.join(", "));
+        }
+
+        if let Some(info) = &warning.severity {
+            self.print_attr(color, &messages.severity_label, info.severity.to_string());
+        }
+
+        self.print_override(color, warning.overridden.as_ref());
+
+        if let Some(expected) = &warning.expected_checksum {
+            if let Some(checksum) = &warning.package.checksum {
+                self.print_attr(color, &messages.checksum_label, checksum.to_string());
+            }
+
+            self.print_attr(
+                color,
+                &messages.expected_checksum_label,
+                expected.to_string(),
+            );
+        }
+
+        if !warning.duplicate_versions.is_empty() {
+            let versions = warning
+                .duplicate_versions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+
+            self.print_attr(
+                color,
+                &messages.duplicate_versions_label,
+                versions.join(", "),
+            );
+        }
+
+        if let Some(entry) = &warning.malformed_entry {
+            self.print_attr(color, &messages.malformed_entry_label, entry);
+        }
+
+        let patched = warning.patched_versions();
+
+        if !patched.is_empty() {
+            self.print_attr(color, &messages.solution_label, self.upgrade_to(patched));
+        }
+
         self.print_tree(color, &warning.package, tree);
         println!();
     }
 
+    /// Print a single line summarizing the given warning
+    fn print_condensed_warning(&self, warning: &rustsec::Warning) {
+        let color = self.warning_color(self.is_denied(warning));
+        let messages = &self.config.messages;
+        let kind = messages.warning_kind(&warning.kind);
+
+        let args: &[(&str, &dyn ToString)] = &[
+            ("crate", &warning.package.name),
+            ("version", &warning.package.version),
+            ("kind", &kind),
+        ];
+
+        match &warning.advisory {
+            Some(metadata) => {
+                let mut args = args.to_vec();
+                args.push(("title", &metadata.title));
+
+                self.print_attr(
+                    color,
+                    &metadata.id.to_string(),
+                    format_message(&messages.condensed_advisory_warning, &args),
+                );
+            }
+            None => self.print_attr(
+                color,
+                kind,
+                format_message(&messages.condensed_warning, args),
+            ),
+        }
+    }
+
+    /// Is the given warning denied, either by kind or by a promotion rule?
+    fn is_denied(&self, warning: &rustsec::Warning) -> bool {
+        warning.promoted || self.deny_warning_kinds.contains(&warning.kind)
+    }
+
     /// Get the color to use when displaying warnings
     fn warning_color(&self, deny_warning: bool) -> Color {
         if deny_warning {
@@ -241,24 +922,238 @@ impl Presenter {
 
     /// Print a warning about a particular advisory
     fn print_metadata(&self, metadata: &rustsec::advisory::Metadata, color: Color) {
-        self.print_attr(color, "Title:        ", &metadata.title);
-        self.print_attr(color, "Date:         ", &metadata.date);
-        self.print_attr(color, "ID:           ", &metadata.id);
+        let messages = &self.config.messages;
+
+        self.print_attr(color, &messages.title_label, &metadata.title);
+        self.print_attr(color, &messages.date_label, &metadata.date);
+        self.print_attr(color, &messages.id_label, &metadata.id);
 
         if let Some(url) = metadata.id.url() {
-            self.print_attr(color, "URL:          ", &url);
+            self.print_attr(color, &messages.url_label, &url);
         } else if let Some(url) = &metadata.url {
-            self.print_attr(color, "URL:          ", url);
+            self.print_attr(color, &messages.url_label, url);
+        }
+
+        self.print_description(color, metadata);
+    }
+
+    /// Print the description of an advisory, if enabled (limited to the
+    /// configured number of lines when truncated)
+    fn print_description(&self, color: Color, metadata: &rustsec::advisory::Metadata) {
+        let limit = match self.config.show_descriptions {
+            DescriptionMode::Full => None,
+            DescriptionMode::Truncated => Some(
+                self.config
+                    .description_lines
+                    .unwrap_or(DEFAULT_DESCRIPTION_LINES),
+            ),
+            DescriptionMode::None => return,
+        };
+
+        let (lines, omitted) = truncate_lines(&metadata.description, limit);
+
+        self.print_heading(color, self.config.messages.description_label.clone());
+
+        for line in lines {
+            println!("{}", line);
+        }
+
+        if omitted > 0 {
+            println!(
+                "{}",
+                format_message(
+                    &self.config.messages.description_truncated,
+                    &[("count", &omitted)],
+                )
+            );
+        }
+    }
+
+    /// Print the filesystem path of a package, if known
+    fn print_path(&self, color: Color, package: &Package) {
+        if let Some(path) = self.package_paths.get(&Dependency::from(package)) {
+            self.print_attr(
+                color,
+                &self.config.messages.path_label,
+                path.display().to_string(),
+            );
+        }
+    }
+
+    /// Print timing and cache diagnostics for an audit
+    fn print_stats(&self, stats: &report::Stats, advisory_count: usize) {
+        let messages = &self.config.messages;
+        let millis = |ms: Option<u64>| {
+            ms.map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(|| messages.stats_skipped.clone())
+        };
+
+        status_ok!(
+            "Stats",
+            format_message(
+                &messages.stats_timing,
+                &[
+                    ("fetch", &millis(stats.fetch_ms)),
+                    ("index", &millis(stats.index_update_ms)),
+                    ("load", &millis(Some(stats.load_ms))),
+                    ("match", &millis(Some(stats.match_ms))),
+                ]
+            )
+        );
+        status_ok!(
+            "Stats",
+            format_message(
+                &messages.stats_cache,
+                &[
+                    ("hits", &stats.index_cache_hits),
+                    ("misses", &stats.index_cache_misses),
+                ]
+            )
+        );
+
+        let size = stats
+            .database_size
+            .map(|bytes| format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)))
+            .unwrap_or_else(|| "?".to_owned());
+
+        status_ok!(
+            "Stats",
+            format_message(
+                &messages.stats_database,
+                &[("count", &advisory_count), ("size", &size)]
+            )
+        );
+    }
+
+    /// Print the advisory files which took longest to parse while loading
+    /// the advisory database (`--slow-parse-report`)
+    pub fn print_slow_parse_report(&self, profile: &LoadProfile, count: usize) {
+        let messages = &self.config.messages;
+        let kib = |bytes: u64| format!("{:.1} KiB", bytes as f64 / 1024.0);
+        let millis = |elapsed: Duration| format!("{:.2}ms", elapsed.as_secs_f64() * 1000.0);
+
+        status_ok!(
+            "Profile",
+            format_message(
+                &messages.slow_parse_summary,
+                &[
+                    ("count", &profile.timings().len()),
+                    ("elapsed", &millis(profile.total_elapsed())),
+                    ("size", &kib(profile.total_size())),
+                ]
+            )
+        );
+
+        for timing in profile.slowest(count) {
+            status_ok!(
+                "Slow",
+                format_message(
+                    &messages.slow_parse_entry,
+                    &[
+                        ("path", &timing.path.display()),
+                        ("elapsed", &millis(timing.elapsed)),
+                        ("size", &kib(timing.size)),
+                    ]
+                )
+            );
+        }
+    }
+
+    /// Print how a package overrides the registry release, if it does
+    fn print_override(&self, color: Color, overridden: Option<&report::Override>) {
+        if let Some(overridden) = overridden {
+            self.print_attr(
+                color,
+                &self.config.messages.override_label,
+                overridden.to_string(),
+            );
+        }
+    }
+
+    /// Print the team which owns a package, if known
+    fn print_owner(&self, color: Color, owner: Option<&str>) {
+        if let Some(owner) = owner {
+            self.print_attr(color, &self.config.messages.owner_label, owner);
+        }
+    }
+
+    /// Print whether a package has been reviewed, if known
+    fn print_review(&self, color: Color, review: Option<report::ReviewStatus>) {
+        if let Some(status) = review {
+            let messages = &self.config.messages;
+            self.print_attr(
+                color,
+                &messages.review_label,
+                messages.review_status(status),
+            );
+        }
+    }
+
+    /// Print when the earliest fix for a vulnerability was released, if known
+    fn print_fix_age(&self, color: Color, fix_age: Option<&FixAge>) {
+        if let Some(fix_age) = fix_age {
+            let messages = &self.config.messages;
+            self.print_attr(
+                color,
+                &messages.fix_released_label,
+                format_message(
+                    &messages.fix_released,
+                    &[("version", &fix_age.version), ("days", &fix_age.days)],
+                ),
+            );
+        }
+    }
+
+    /// Print whether a package is a direct or transitive dependency, if known
+    fn print_relationship(
+        &self,
+        color: Color,
+        relationship: Option<Relationship>,
+        depth: Option<usize>,
+    ) {
+        if let (Some(relationship), Some(depth)) = (relationship, depth) {
+            let messages = &self.config.messages;
+            self.print_attr(
+                color,
+                &messages.dependency_label,
+                messages.relationship(relationship, depth),
+            );
         }
     }
 
     /// Display an attribute of a particular vulnerability
     fn print_attr(&self, color: Color, attr: &str, content: impl AsRef<str>) {
+        // Pad labels so values line up, leaving room for longer translations
+        let width = cmp::max(ATTR_WIDTH, attr.chars().count() + 1);
+        let content = content.as_ref();
+
+        // Wrap long values, indenting continuation lines to line up
+        let content = match self.terminal_width {
+            Some(terminal_width)
+                if terminal_width >= width + 1 + MIN_WRAP_WIDTH
+                    && content.chars().count() > terminal_width - width - 1 =>
+            {
+                let indent = format!("\n{:width$}", "", width = width + 1);
+                wrap(content, terminal_width - width - 1).join(&indent)
+            }
+            _ => content.to_owned(),
+        };
+
         terminal::status::Status::new()
             .bold()
             .color(color)
-            .status(attr)
-            .print_stdout(content.as_ref())
+            .status(format!("{:width$}", attr, width = width))
+            .print_stdout(content)
+            .unwrap();
+    }
+
+    /// Print a heading for a group of findings
+    fn print_heading(&self, color: Color, heading: String) {
+        terminal::status::Status::new()
+            .bold()
+            .color(color)
+            .status(heading)
+            .print_stdout("")
             .unwrap();
     }
 
@@ -276,7 +1171,7 @@ impl Presenter {
         terminal::status::Status::new()
             .bold()
             .color(color)
-            .status("Dependency tree:")
+            .status(self.config.messages.dependency_tree_label.clone())
             .print_stdout("")
             .unwrap();
 
@@ -285,3 +1180,90 @@ impl Presenter {
             .unwrap();
     }
 }
+
+/// Get the width of the terminal, or `None` if STDOUT isn't a terminal
+/// (in which case output isn't wrapped)
+fn terminal_width() -> Option<usize> {
+    if !atty::is(atty::Stream::Stdout) {
+        return None;
+    }
+
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(width), _)| usize::from(width))
+        .or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+}
+
+/// Get the lines of text (without leading or trailing blank lines), limited
+/// to `limit` lines if given, along with the number of lines omitted
+fn truncate_lines(text: &str, limit: Option<usize>) -> (Vec<&str>, usize) {
+    let mut lines = text.trim().lines().collect::<Vec<_>>();
+    let shown = limit.map_or(lines.len(), |limit| cmp::min(limit, lines.len()));
+    let omitted = lines.len() - shown;
+
+    lines.truncate(shown);
+    (lines, omitted)
+}
+
+/// Wrap text at word boundaries so lines are at most `width` characters,
+/// without breaking words which are longer than `width` (e.g. URLs)
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(mem::take(&mut line));
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+
+        line.push_str(word);
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Format a commit bounding an exposure window for display
+fn exposure_commit(commit: &ExposureCommit) -> String {
+    format!(
+        "{} ({})",
+        commit.commit_id,
+        humantime::format_rfc3339_seconds(commit.timestamp)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{truncate_lines, wrap};
+
+    #[test]
+    fn truncate_to_limit() {
+        let text = "\nfirst\n\nsecond\nthird\n\n";
+
+        assert_eq!(truncate_lines(text, Some(2)), (vec!["first", ""], 2));
+        assert_eq!(
+            truncate_lines(text, Some(10)),
+            (vec!["first", "", "second", "third"], 0)
+        );
+        assert_eq!(
+            truncate_lines(text, None),
+            (vec!["first", "", "second", "third"], 0)
+        );
+        assert_eq!(truncate_lines(text, Some(0)), (vec![], 4));
+    }
+
+    #[test]
+    fn wrap_at_word_boundaries() {
+        assert_eq!(
+            wrap("Use-after-free in  the\tfoo crate", 12),
+            vec!["Use-after-free", "in the foo", "crate"]
+        );
+        assert_eq!(wrap("", 12), vec![""]);
+    }
+}
@@ -0,0 +1,537 @@
+//! Message catalog for presenter output.
+//!
+//! All user-facing strings printed by the [`Presenter`][`super::Presenter`]
+//! are sourced from a [`Messages`] catalog. The default catalog is English,
+//! and translations can be supplied via the `[output.messages]` section of
+//! `audit.toml`. Any messages which are omitted fall back to English.
+//!
+//! Messages may contain `{placeholders}` which are substituted at runtime.
+
+use rustsec::{dependency::Relationship, report::ReviewStatus, warning};
+use serde::{Deserialize, Serialize};
+
+/// Catalog of messages displayed by the presenter
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Messages {
+    /// Label for the name of a crate
+    pub crate_label: String,
+
+    /// Label for the version of a crate
+    pub version_label: String,
+
+    /// Label for the filesystem path of a vendored crate
+    pub path_label: String,
+
+    /// Label for the team which owns a crate
+    pub owner_label: String,
+
+    /// Label for how a crate is related to the workspace (e.g. direct
+    /// or transitive dependency)
+    pub dependency_label: String,
+
+    /// Label for the title of an advisory
+    pub title_label: String,
+
+    /// Label for the date of an advisory
+    pub date_label: String,
+
+    /// Label for the ID of an advisory
+    pub id_label: String,
+
+    /// Label for the URL of an advisory
+    pub url_label: String,
+
+    /// Label for the other IDs an advisory is known by (e.g. CVEs)
+    pub aliases_label: String,
+
+    /// Label for the description of an advisory
+    pub description_label: String,
+
+    /// Note that an advisory description was truncated
+    /// (placeholder: `{count}`, the number of lines omitted)
+    pub description_truncated: String,
+
+    /// Label for the severity of an advisory
+    pub severity_label: String,
+
+    /// Label for how a crate overrides the registry release an advisory was
+    /// matched against (via `[patch]` or `[replace]`)
+    pub override_label: String,
+
+    /// Label for the suggested solution to a vulnerability
+    pub solution_label: String,
+
+    /// Label for when the earliest fix for a vulnerability was released
+    pub fix_released_label: String,
+
+    /// When the earliest fix for a vulnerability was released
+    /// (placeholders: `{version}`, `{days}`)
+    pub fix_released: String,
+
+    /// Label for the checksum of a package in `Cargo.lock`
+    pub checksum_label: String,
+
+    /// Label for the checksum of a package according to the registry index
+    pub expected_checksum_label: String,
+
+    /// Label for all of the versions of a package present in `Cargo.lock`,
+    /// for packages present in several versions
+    pub duplicate_versions_label: String,
+
+    /// Label for the `Cargo.lock` entry which was skipped because it
+    /// couldn't be parsed
+    pub malformed_entry_label: String,
+
+    /// Label for whether a package has been reviewed (according to
+    /// `cargo vet`)
+    pub review_label: String,
+
+    /// Review status of packages which were audited
+    pub review_audited: String,
+
+    /// Review status of packages which are exempted from auditing
+    pub review_exempted: String,
+
+    /// Review status of packages which violate the audit criteria
+    pub review_violation: String,
+
+    /// Review status of packages which haven't been audited
+    pub review_unreviewed: String,
+
+    /// Label for the kind of a warning
+    pub warning_label: String,
+
+    /// Label for the inverse dependency tree
+    pub dependency_tree_label: String,
+
+    /// Label for the commit which introduced a vulnerable dependency
+    pub introduced_label: String,
+
+    /// Label for the commit which removed a vulnerable dependency
+    pub resolved_label: String,
+
+    /// Displayed when a vulnerable dependency is present at `HEAD`
+    pub still_present: String,
+
+    /// Displayed when no vulnerable dependencies were found in git history
+    pub no_exposure: String,
+
+    /// Solution when no patched versions exist
+    pub no_safe_upgrade: String,
+
+    /// Label for a version requirement in `Cargo.toml`
+    pub requirement_label: String,
+
+    /// Label for the vulnerable releases admitted by a requirement
+    pub admits_label: String,
+
+    /// Vulnerable releases admitted by a requirement
+    /// (placeholders: `{count}`, `{earliest}`, `{latest}`)
+    pub vulnerable_releases: String,
+
+    /// Displayed in place of the locked version of a dependency which isn't
+    /// in `Cargo.lock`
+    pub not_locked: String,
+
+    /// Solution for requirements which admit vulnerable releases
+    /// (placeholders: `{version}`)
+    pub raise_lower_bound: String,
+
+    /// Displayed when no requirements admit vulnerable releases
+    pub no_loose_requirements: String,
+
+    /// Summary of requirements which admit vulnerable releases
+    /// (placeholders: `{count}`)
+    pub loose_requirements_found: String,
+
+    /// Solution when patched versions exist (placeholders: `{versions}`)
+    pub upgrade_to: String,
+
+    /// Separator between alternative patched versions
+    pub or_separator: String,
+
+    /// Message displayed before scanning a lockfile
+    /// (placeholders: `{path}`, `{count}`)
+    pub scanning: String,
+
+    /// Summary when a single vulnerability is found
+    pub vulnerability_found: String,
+
+    /// Summary when multiple vulnerabilities are found (placeholders: `{count}`)
+    pub vulnerabilities_found: String,
+
+    /// Summary when exiting at the first vulnerability found (`--exit-on-first`)
+    pub first_vulnerability_found: String,
+
+    /// Summary when exiting at the first denied warning found (`--exit-on-first`,
+    /// placeholders: `{kind}`)
+    pub first_denied_warning_found: String,
+
+    /// Per-workspace member summary of vulnerable dependencies
+    /// (placeholders: `{member}`, `{count}`, `{severity}`)
+    pub workspace_member_summary: String,
+
+    /// Summary of an advisory about one of the project's own crates
+    /// (placeholders: `{crate}`, `{version}`, `{id}`)
+    pub own_crate_subject: String,
+
+    /// Per-target summary of vulnerabilities, when auditing for several
+    /// targets (placeholders: `{target}`, `{count}`)
+    pub target_summary: String,
+
+    /// Displayed in place of a severity when none is known
+    pub unknown_severity: String,
+
+    /// Heading for a group of vulnerabilities with the same severity
+    /// (placeholders: `{severity}`, `{count}`)
+    pub severity_group: String,
+
+    /// Heading for the group of warnings (placeholders: `{count}`)
+    pub warnings_group: String,
+
+    /// Condensed single-line vulnerability entry
+    /// (placeholders: `{crate}`, `{version}`, `{title}`, `{solution}`)
+    pub condensed_vulnerability: String,
+
+    /// Condensed single-line warning entry
+    /// (placeholders: `{crate}`, `{version}`, `{kind}`)
+    pub condensed_warning: String,
+
+    /// Condensed single-line entry for a warning with an associated advisory
+    /// (placeholders: `{crate}`, `{version}`, `{kind}`, `{title}`)
+    pub condensed_advisory_warning: String,
+
+    /// Hint about displaying full details of condensed findings
+    pub verbose_hint: String,
+
+    /// Heading for each project in a recursive audit
+    /// (placeholders: `{path}`, `{vulnerabilities}`, `{warnings}`)
+    pub project_summary: String,
+
+    /// Summary of a recursive audit across all projects (placeholders:
+    /// `{projects}`, `{vulnerable}`, `{vulnerabilities}`, `{advisories}`,
+    /// `{warnings}`)
+    pub aggregate_summary: String,
+
+    /// Solution for vulnerabilities fixable by `cargo update`
+    /// (placeholders: `{package}`, `{version}`)
+    pub cargo_update_solution: String,
+
+    /// Summary of vulnerabilities fixable by `cargo update`
+    /// (placeholders: `{count}`, `{command}`)
+    pub fixable_by_update: String,
+
+    /// Summary of vulnerabilities which need a semver-incompatible upgrade
+    /// (placeholders: `{count}`)
+    pub needs_manual_upgrade: String,
+
+    /// Summary of vulnerabilities without any fix (placeholders: `{count}`)
+    pub no_fix_available: String,
+
+    /// Summary of denied warnings (placeholders: `{count}`, `{warnings}`)
+    pub denied_warnings_found: String,
+
+    /// Summary of allowed warnings (placeholders: `{count}`, `{warnings}`)
+    pub allowed_warnings_found: String,
+
+    /// Singular noun for "warning"
+    pub warning_singular: String,
+
+    /// Plural noun for "warnings"
+    pub warning_plural: String,
+
+    /// Displayed when `cargo-audit` itself has known advisories
+    pub self_advisories: String,
+
+    /// Advice to upgrade `cargo-audit` when it has known advisories
+    pub self_upgrade: String,
+
+    /// Displayed for vulnerabilities accepted since the crate comes from a
+    /// patched fork (placeholders: `{id}`, `{crate}`, `{version}`, `{fork}`)
+    pub accepted_patched_fork: String,
+
+    /// Timing diagnostics shown with `--stats` (placeholders: `{fetch}`,
+    /// `{index}`, `{load}`, `{match}`)
+    pub stats_timing: String,
+
+    /// Registry index cache diagnostics shown with `--stats` (placeholders:
+    /// `{hits}`, `{misses}`)
+    pub stats_cache: String,
+
+    /// Advisory database diagnostics shown with `--stats` (placeholders:
+    /// `{count}`, `{size}`)
+    pub stats_database: String,
+
+    /// Displayed in place of the time for phases which were skipped
+    pub stats_skipped: String,
+
+    /// Summary of the time taken to parse advisory files
+    /// (placeholders: `{count}`, `{elapsed}`, `{size}`)
+    pub slow_parse_summary: String,
+
+    /// Advisory file which was slow to parse
+    /// (placeholders: `{path}`, `{elapsed}`, `{size}`)
+    pub slow_parse_entry: String,
+
+    /// Name of the `notice` warning kind
+    pub notice: String,
+
+    /// Name of the `unmaintained` warning kind
+    pub unmaintained: String,
+
+    /// Name of the `unsound` warning kind
+    pub unsound: String,
+
+    /// Name of the `yanked` warning kind
+    pub yanked: String,
+
+    /// Name of the `overridden` warning kind
+    pub overridden: String,
+
+    /// Name of the `checksum-mismatch` warning kind
+    pub checksum_mismatch: String,
+
+    /// Name of the `duplicate` warning kind
+    pub duplicate: String,
+
+    /// Name of the `own-crate` warning kind
+    pub own_crate: String,
+
+    /// Name of the `malformed-lockfile` warning kind
+    pub malformed_lockfile: String,
+
+    /// Displayed for crates which are workspace members
+    pub workspace_crate: String,
+
+    /// Displayed for direct dependencies of workspace members
+    pub direct_dependency: String,
+
+    /// Displayed for transitive dependencies (placeholders: `{depth}`)
+    pub transitive_dependency: String,
+}
+
+impl Messages {
+    /// Get the localized name for a warning kind
+    pub fn warning_kind<'a>(&'a self, kind: &'a warning::Kind) -> &'a str {
+        match kind {
+            warning::Kind::Notice => &self.notice,
+            warning::Kind::Unmaintained => &self.unmaintained,
+            warning::Kind::Unsound => &self.unsound,
+            warning::Kind::Yanked => &self.yanked,
+            warning::Kind::Overridden => &self.overridden,
+            warning::Kind::ChecksumMismatch => &self.checksum_mismatch,
+            warning::Kind::Duplicate => &self.duplicate,
+            warning::Kind::OwnCrate => &self.own_crate,
+            warning::Kind::MalformedLockfile => &self.malformed_lockfile,
+            other => other.as_str(),
+        }
+    }
+
+    /// Get the localized description of a review status
+    pub fn review_status(&self, status: ReviewStatus) -> &str {
+        match status {
+            ReviewStatus::Audited => &self.review_audited,
+            ReviewStatus::Exempted => &self.review_exempted,
+            ReviewStatus::Violation => &self.review_violation,
+            ReviewStatus::Unreviewed => &self.review_unreviewed,
+        }
+    }
+
+    /// Get the localized description of how a crate at the given depth is
+    /// related to the workspace
+    pub fn relationship(&self, relationship: Relationship, depth: usize) -> String {
+        match relationship {
+            Relationship::Workspace => self.workspace_crate.clone(),
+            Relationship::Direct => self.direct_dependency.clone(),
+            Relationship::Transitive => {
+                format_message(&self.transitive_dependency, &[("depth", &depth)])
+            }
+        }
+    }
+
+    /// Get the localized noun for "warning(s)" given a count
+    pub fn warning_word(&self, count: u64) -> &str {
+        if count != 1 {
+            &self.warning_plural
+        } else {
+            &self.warning_singular
+        }
+    }
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            crate_label: "Crate:".to_owned(),
+            version_label: "Version:".to_owned(),
+            path_label: "Path:".to_owned(),
+            owner_label: "Owner:".to_owned(),
+            dependency_label: "Dependency:".to_owned(),
+            title_label: "Title:".to_owned(),
+            date_label: "Date:".to_owned(),
+            id_label: "ID:".to_owned(),
+            url_label: "URL:".to_owned(),
+            aliases_label: "Aliases:".to_owned(),
+            description_label: "Description:".to_owned(),
+            description_truncated: "... ({count} more lines)".to_owned(),
+            severity_label: "Severity:".to_owned(),
+            override_label: "Override:".to_owned(),
+            solution_label: "Solution:".to_owned(),
+            fix_released_label: "Fix released:".to_owned(),
+            fix_released: "{version}, {days} days ago".to_owned(),
+            checksum_label: "Checksum:".to_owned(),
+            expected_checksum_label: "Expected:".to_owned(),
+            duplicate_versions_label: "Versions:".to_owned(),
+            malformed_entry_label: "Skipped:".to_owned(),
+            review_label: "Review:".to_owned(),
+            review_audited: "audited".to_owned(),
+            review_exempted: "exempted (not audited)".to_owned(),
+            review_violation: "violates audit criteria".to_owned(),
+            review_unreviewed: "unreviewed".to_owned(),
+            warning_label: "Warning:".to_owned(),
+            dependency_tree_label: "Dependency tree:".to_owned(),
+            introduced_label: "Introduced:".to_owned(),
+            resolved_label: "Resolved:".to_owned(),
+            still_present: "still present".to_owned(),
+            no_exposure: "no vulnerable dependencies found in git history".to_owned(),
+            no_safe_upgrade: "No safe upgrade is available!".to_owned(),
+            requirement_label: "Requirement:".to_owned(),
+            admits_label: "Admits:".to_owned(),
+            vulnerable_releases: "{count} vulnerable releases ({earliest} to {latest})".to_owned(),
+            not_locked: "not locked".to_owned(),
+            raise_lower_bound: "Require at least {version}".to_owned(),
+            no_loose_requirements: "no dependency requirements admit vulnerable releases"
+                .to_owned(),
+            loose_requirements_found: "{count} dependency requirements admit vulnerable releases!"
+                .to_owned(),
+            upgrade_to: "Upgrade to {versions}".to_owned(),
+            or_separator: " OR ".to_owned(),
+            scanning: "{path} for vulnerabilities ({count} crate dependencies)".to_owned(),
+            vulnerability_found: "1 vulnerability found!".to_owned(),
+            vulnerabilities_found: "{count} vulnerabilities found!".to_owned(),
+            first_vulnerability_found: "vulnerability found! (stopped at first denied finding)"
+                .to_owned(),
+            first_denied_warning_found:
+                "denied {kind} warning found! (stopped at first denied finding)".to_owned(),
+            workspace_member_summary:
+                "{member}: {count} vulnerable dependencies (highest severity: {severity})"
+                    .to_owned(),
+            own_crate_subject: "you are the subject of {id}: {crate} {version} is affected"
+                .to_owned(),
+            target_summary: "{target}: {count} vulnerabilities".to_owned(),
+            unknown_severity: "unknown".to_owned(),
+            severity_group: "{severity} severity ({count})".to_owned(),
+            warnings_group: "warnings ({count})".to_owned(),
+            condensed_vulnerability: "{crate} {version}: {title} ({solution})".to_owned(),
+            condensed_warning: "{crate} {version}: {kind}".to_owned(),
+            condensed_advisory_warning: "{crate} {version}: {kind}: {title}".to_owned(),
+            verbose_hint: "run with --verbose for full details and dependency trees".to_owned(),
+            project_summary: "{path}: {vulnerabilities} vulnerabilities, {warnings} warnings"
+                .to_owned(),
+            aggregate_summary: "{projects} projects audited, {vulnerable} vulnerable: \
+                                {vulnerabilities} unique vulnerabilities \
+                                ({advisories} advisories), {warnings} unique warnings"
+                .to_owned(),
+            cargo_update_solution: "Run `cargo update -p {package}` (fixed in {version})"
+                .to_owned(),
+            fixable_by_update: "{count} fixable by `cargo update`: {command}".to_owned(),
+            needs_manual_upgrade: "{count} need a manual (semver-incompatible) upgrade".to_owned(),
+            no_fix_available: "{count} have no fix available".to_owned(),
+            denied_warnings_found: "{count} denied {warnings} found!".to_owned(),
+            allowed_warnings_found: "{count} allowed {warnings} found".to_owned(),
+            warning_singular: "warning".to_owned(),
+            warning_plural: "warnings".to_owned(),
+            self_advisories: "This copy of cargo-audit has known advisories!".to_owned(),
+            self_upgrade: "upgrade cargo-audit to the latest version: \
+                           cargo install --force cargo-audit"
+                .to_owned(),
+            accepted_patched_fork: "{id} in {crate} {version} (accepted via patched fork {fork})"
+                .to_owned(),
+            stats_timing: "fetch {fetch}, index update {index}, load {load}, match {match}"
+                .to_owned(),
+            stats_cache: "index cache {hits} hits, {misses} misses".to_owned(),
+            stats_database: "advisory database {count} advisories, {size} on disk".to_owned(),
+            stats_skipped: "skipped".to_owned(),
+            slow_parse_summary: "parsed {count} advisory files ({size}) in {elapsed}".to_owned(),
+            slow_parse_entry: "{elapsed} {path} ({size})".to_owned(),
+            notice: "notice".to_owned(),
+            unmaintained: "unmaintained".to_owned(),
+            unsound: "unsound".to_owned(),
+            yanked: "yanked".to_owned(),
+            overridden: "overridden".to_owned(),
+            checksum_mismatch: "checksum mismatch".to_owned(),
+            duplicate: "duplicate".to_owned(),
+            own_crate: "own crate".to_owned(),
+            malformed_lockfile: "malformed lockfile".to_owned(),
+            workspace_crate: "workspace member".to_owned(),
+            direct_dependency: "direct".to_owned(),
+            transitive_dependency: "transitive (depth {depth})".to_owned(),
+        }
+    }
+}
+
+/// Substitute `{name}` placeholders in a message template.
+///
+/// The template is scanned once, so placeholders appearing in substituted
+/// values are left as they are. Unknown placeholders are kept verbatim.
+pub fn format_message(template: &str, args: &[(&str, &dyn ToString)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = match rest.find('}') {
+            Some(end) => end,
+            None => break,
+        };
+
+        let name = &rest[1..end];
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => result.push_str(&value.to_string()),
+            None => result.push_str(&rest[..=end]),
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_message, Messages};
+
+    #[test]
+    fn substitutes_placeholders() {
+        let messages = Messages::default();
+        assert_eq!(
+            format_message(
+                &messages.denied_warnings_found,
+                &[("count", &2), ("warnings", &messages.warning_word(2))]
+            ),
+            "2 denied warnings found!"
+        );
+    }
+
+    #[test]
+    fn substitutes_placeholders_once() {
+        assert_eq!(
+            format_message(
+                "{name} {version} {unknown}",
+                &[("name", &"{version}"), ("version", &"1.0.0")]
+            ),
+            "{version} 1.0.0 {unknown}"
+        );
+    }
+
+    #[test]
+    fn partial_translation_falls_back_to_english() {
+        let messages: Messages = toml::from_str("crate_label = \"Paquete:\"").unwrap();
+        assert_eq!(messages.crate_label, "Paquete:");
+        assert_eq!(messages.version_label, "Version:");
+    }
+}
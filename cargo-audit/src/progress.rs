@@ -0,0 +1,64 @@
+//! Progress bar displayed while loading the advisory database
+
+use rustsec::database::LoadProgress;
+use std::io::{self, Write};
+
+/// Width of the bar itself (excluding the label and counts)
+const BAR_WIDTH: usize = 30;
+
+/// Progress bar rendered to STDERR, which is only displayed when STDERR is
+/// a terminal (so as not to clutter logs)
+#[derive(Debug)]
+pub struct ProgressBar {
+    /// Should the progress bar be displayed?
+    enabled: bool,
+}
+
+impl ProgressBar {
+    /// Create a new progress bar, which is only displayed if `enabled` is
+    /// true and STDERR is a terminal
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: enabled && atty::is(atty::Stream::Stderr),
+        }
+    }
+
+    /// Update the progress bar from an advisory database loading event
+    pub fn update(&mut self, progress: LoadProgress<'_>) {
+        if !self.enabled {
+            return;
+        }
+
+        match progress {
+            LoadProgress::Parsed { parsed, total, .. } => {
+                let filled = if total == 0 {
+                    BAR_WIDTH
+                } else {
+                    parsed * BAR_WIDTH / total
+                };
+
+                eprint!(
+                    "\r{:>12} [{}{}] {}/{} advisories",
+                    "Loading",
+                    "=".repeat(filled),
+                    " ".repeat(BAR_WIDTH - filled),
+                    parsed,
+                    total
+                );
+
+                if parsed == total {
+                    self.clear();
+                }
+            }
+            LoadProgress::Error { .. } => self.clear(),
+            _ => (),
+        }
+
+        io::stderr().flush().ok();
+    }
+
+    /// Erase the progress bar
+    fn clear(&self) {
+        eprint!("\r{:width$}\r", "", width = BAR_WIDTH + 48);
+    }
+}
@@ -0,0 +1,348 @@
+//! Version requirements on dependencies declared in `Cargo.toml`
+
+use crate::lockfile;
+use rustsec::{
+    advisory,
+    database::Query,
+    error::{Error, ErrorKind},
+    lockfile::Lockfile,
+    package::{self, source::CRATES_IO_INDEX},
+    registry, report,
+    version::cmp_precedence,
+    Database, Version, VersionReq,
+};
+use serde::Serialize;
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+};
+
+/// Requirement of a workspace member on a crates.io dependency
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Requirement {
+    /// Workspace member declaring the requirement
+    pub member: package::Name,
+
+    /// `Cargo.toml` of the workspace member
+    pub manifest_path: PathBuf,
+
+    /// Name of the dependency (rather than any name it's renamed to)
+    pub name: package::Name,
+
+    /// Version requirement on the dependency
+    pub req: VersionReq,
+}
+
+impl Requirement {
+    /// Find the earliest non-yanked release of the dependency which meets
+    /// the requirement, i.e. the version Cargo's (unstable) minimal-versions
+    /// resolution would pick
+    pub fn minimal_version(&self, index: &registry::Index) -> Result<Option<Version>, Error> {
+        Ok(index
+            .releases(&self.name)?
+            .into_iter()
+            .filter(|release| !release.is_yanked && self.req.matches(&release.version))
+            .map(|release| release.version)
+            .min_by(cmp_precedence))
+    }
+
+    /// Find the version of the dependency locked in the given lockfile which
+    /// meets the requirement, if any
+    pub fn locked_version(&self, lockfile: &Lockfile) -> Option<Version> {
+        lockfile
+            .packages
+            .iter()
+            .find(|package| package.name == self.name && self.req.matches(&package.version))
+            .map(|package| package.version.clone())
+    }
+}
+
+/// Requirement which admits releases affected by an advisory, even though
+/// the version it's locked to (if any) isn't affected
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LooseRequirement {
+    /// The requirement
+    pub requirement: Requirement,
+
+    /// ID of the advisory
+    pub advisory: advisory::Id,
+
+    /// Version the dependency is locked to
+    pub locked: Option<Version>,
+
+    /// Non-yanked releases admitted by the requirement which are vulnerable,
+    /// in ascending order
+    pub vulnerable: Vec<Version>,
+
+    /// Earliest admitted release newer than all of the vulnerable ones, which
+    /// would make a safe lower bound for the requirement
+    pub safe_minimum: Option<Version>,
+}
+
+/// Find the requirements which admit releases affected by advisories in the
+/// database (other than ignored ones).
+///
+/// Requirements whose locked version is itself vulnerable are skipped, since
+/// auditing the lockfile already reports those.
+pub fn find_loose(
+    requirements: &[Requirement],
+    database: &Database,
+    index: &registry::Index,
+    lockfile: Option<&Lockfile>,
+    settings: &report::Settings,
+) -> Result<Vec<LooseRequirement>, Error> {
+    let mut loose = vec![];
+
+    for requirement in requirements {
+        let advisories = database
+            .query(
+                &Query::crate_scope()
+                    .package(requirement.name.clone())
+                    .prerelease_matching(settings.prerelease),
+            )
+            .into_iter()
+            .filter(|advisory| {
+                !settings
+                    .ignore
+                    .iter()
+                    .any(|pattern| pattern.matches(&advisory.metadata))
+            })
+            .collect::<Vec<_>>();
+
+        if advisories.is_empty() {
+            continue;
+        }
+
+        let mut admitted = index
+            .releases(&requirement.name)?
+            .into_iter()
+            .filter(|release| !release.is_yanked && requirement.req.matches(&release.version))
+            .map(|release| release.version)
+            .collect::<Vec<_>>();
+
+        admitted.sort_by(cmp_precedence);
+
+        let locked = lockfile.and_then(|lockfile| requirement.locked_version(lockfile));
+
+        for advisory in advisories {
+            let is_vulnerable = |version: &Version| {
+                advisory
+                    .versions
+                    .is_vulnerable_with(version, settings.prerelease)
+            };
+
+            if locked.as_ref().map_or(false, is_vulnerable) {
+                continue;
+            }
+
+            let vulnerable = admitted
+                .iter()
+                .filter(|version| is_vulnerable(version))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let latest_vulnerable = match vulnerable.last() {
+                Some(version) => version,
+                None => continue,
+            };
+
+            let safe_minimum = admitted
+                .iter()
+                .find(|version| cmp_precedence(version, latest_vulnerable) == Ordering::Greater)
+                .cloned();
+
+            loose.push(LooseRequirement {
+                requirement: requirement.clone(),
+                advisory: advisory.metadata.id.clone(),
+                locked: locked.clone(),
+                vulnerable,
+                safe_minimum,
+            });
+        }
+    }
+
+    Ok(loose)
+}
+
+/// Get the requirements of the workspace members of the given `Cargo.toml`
+/// on crates.io dependencies, from `cargo metadata --no-deps`.
+///
+/// Dev-dependencies (which don't affect dependents), path and git
+/// dependencies, and ones from other registries are skipped.
+pub fn load(manifest_path: &Path) -> Result<Vec<Requirement>, Error> {
+    let metadata = lockfile::metadata(manifest_path)?;
+    let mut requirements = vec![];
+
+    // With `--no-deps`, `packages` contains only the workspace members
+    for member in metadata["packages"].as_array().into_iter().flatten() {
+        let member_name = member["name"]
+            .as_str()
+            .unwrap_or_default()
+            .parse::<package::Name>()?;
+        let member_manifest = PathBuf::from(member["manifest_path"].as_str().unwrap_or_default());
+
+        for dependency in member["dependencies"].as_array().into_iter().flatten() {
+            let is_crates_io = dependency["source"].as_str().map_or(false, |source| {
+                source == format!("registry+{}", CRATES_IO_INDEX)
+            });
+
+            if !is_crates_io || dependency["kind"].as_str() == Some("dev") {
+                continue;
+            }
+
+            let name = dependency["name"]
+                .as_str()
+                .unwrap_or_default()
+                .parse::<package::Name>()?;
+            let req = dependency["req"]
+                .as_str()
+                .unwrap_or("*")
+                .parse::<VersionReq>()
+                .map_err(|e| {
+                    Error::new(
+                        ErrorKind::Parse,
+                        &format!("invalid requirement on {} in {}: {}", name, member_name, e),
+                    )
+                })?;
+
+            requirements.push(Requirement {
+                member: member_name.clone(),
+                manifest_path: member_manifest.clone(),
+                name,
+                req,
+            });
+        }
+    }
+
+    Ok(requirements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Requirement;
+    use rustsec::{
+        lockfile::Lockfile,
+        package,
+        registry::{Backend, Index, IndexPackage},
+        Error,
+    };
+    use std::path::{Path, PathBuf};
+
+    /// Releases of `base64` (some of them yanked) in the fake registry index
+    const RELEASES: &[(&str, bool)] = &[
+        ("0.9.0", false),
+        ("0.9.1", true),
+        ("0.9.2", false),
+        ("0.9.3", false),
+        ("0.10.0-beta.1", false),
+        ("0.10.0", false),
+    ];
+
+    /// Registry index containing only the releases above
+    struct FixtureBackend;
+
+    impl Backend for FixtureBackend {
+        fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, Error> {
+            Ok(RELEASES
+                .iter()
+                .map(|&(version, is_yanked)| IndexPackage {
+                    package: package.clone(),
+                    version: version.parse().unwrap(),
+                    is_yanked,
+                    checksum: None,
+                    published: None,
+                })
+                .collect())
+        }
+    }
+
+    /// Requirement of the `example` package on `base64`
+    fn requirement(req: &str) -> Requirement {
+        Requirement {
+            member: "example".parse().unwrap(),
+            manifest_path: PathBuf::from("Cargo.toml"),
+            name: "base64".parse().unwrap(),
+            req: req.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn load() {
+        let manifest_path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/support/requirements/Cargo.toml");
+
+        let mut requirements = super::load(&manifest_path)
+            .unwrap()
+            .iter()
+            .map(|requirement| {
+                (
+                    requirement.member.to_string(),
+                    requirement.name.to_string(),
+                    requirement.req.to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        requirements.sort();
+
+        // Renamed dependencies are listed by their own name, while path and
+        // dev-dependencies are skipped
+        let expected = [
+            ("local", "base64", "<0.5.2"),
+            ("requirements", "base64", "^0.13"),
+            ("requirements", "smallvec", "^1.6.1"),
+        ];
+
+        assert_eq!(
+            requirements,
+            expected
+                .iter()
+                .map(|&(member, name, req)| (member.to_owned(), name.to_owned(), req.to_owned()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn minimal_version() {
+        let index = Index::with_backend(FixtureBackend);
+        let minimal_version = |req| requirement(req).minimal_version(&index).unwrap();
+
+        // Yanked releases are never picked
+        assert_eq!(minimal_version("^0.9.1"), Some("0.9.2".parse().unwrap()));
+        assert_eq!(minimal_version("0.9"), Some("0.9.0".parse().unwrap()));
+        assert_eq!(
+            minimal_version(">=0.10.0-beta.1"),
+            Some("0.10.0-beta.1".parse().unwrap())
+        );
+        assert_eq!(minimal_version(">0.9.3"), Some("0.10.0".parse().unwrap()));
+        assert_eq!(minimal_version("1"), None);
+    }
+
+    #[test]
+    fn locked_version() {
+        let lockfile: Lockfile = r#"
+[[package]]
+name = "base64"
+version = "0.9.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "base64"
+version = "0.13.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            requirement("0.13").locked_version(&lockfile),
+            Some("0.13.0".parse().unwrap())
+        );
+        assert_eq!(
+            requirement("^0.9.1").locked_version(&lockfile),
+            Some("0.9.3".parse().unwrap())
+        );
+        assert_eq!(requirement("1").locked_version(&lockfile), None);
+    }
+}
@@ -0,0 +1,215 @@
+//! Software Bill of Materials (SBOM) input.
+//!
+//! Extracts `pkg:cargo` package URLs (purls) from CycloneDX or SPDX JSON
+//! documents and converts them into a [`Lockfile`] which can be audited
+//! like any other.
+
+use rustsec::{
+    cargo_lock::{Metadata, Patch, ResolveVersion},
+    error::{Error, ErrorKind},
+    fs,
+    lockfile::Lockfile,
+    package::{source::CRATES_IO_INDEX, Package, SourceId},
+};
+use serde_json::Value;
+use std::{collections::BTreeSet as Set, path::Path};
+
+/// Prefix of package URLs for Cargo packages
+const CARGO_PURL_PREFIX: &str = "pkg:cargo/";
+
+/// Load an SBOM from the given path
+pub fn load(path: &Path) -> Result<Lockfile, Error> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/// Parse a CycloneDX or SPDX JSON document, returning a [`Lockfile`]
+/// containing all of the Cargo packages it references
+pub fn parse(json: &str) -> Result<Lockfile, Error> {
+    let document: Value = serde_json::from_str(json)
+        .map_err(|e| Error::new(ErrorKind::Parse, &format!("invalid SBOM: {}", e)))?;
+
+    if document.get("bomFormat").and_then(Value::as_str) != Some("CycloneDX")
+        && document.get("spdxVersion").is_none()
+    {
+        return Err(Error::new(
+            ErrorKind::Parse,
+            &"unrecognized SBOM format (expected CycloneDX or SPDX JSON)",
+        ));
+    }
+
+    let mut purls = Set::new();
+    collect_purls(&document, &mut purls);
+
+    let packages = purls
+        .iter()
+        .map(|purl| parse_purl(purl))
+        .collect::<Result<Set<_>, _>>()?
+        .into_iter()
+        .collect();
+
+    Ok(Lockfile {
+        version: ResolveVersion::default(),
+        packages,
+        root: None,
+        metadata: Metadata::default(),
+        patch: Patch::default(),
+    })
+}
+
+/// Recursively find all Cargo purls in a document.
+///
+/// CycloneDX documents contain a `purl` field on each component, whereas
+/// SPDX documents list purls as `externalRefs` on each package.
+fn collect_purls<'a>(value: &'a Value, purls: &mut Set<&'a str>) {
+    match value {
+        Value::Object(object) => {
+            let purl = match object.get("referenceType").and_then(Value::as_str) {
+                Some("purl") => object.get("referenceLocator"),
+                _ => object.get("purl"),
+            };
+
+            if let Some(purl) = purl.and_then(Value::as_str) {
+                if purl.starts_with(CARGO_PURL_PREFIX) {
+                    purls.insert(purl);
+                }
+            }
+
+            for child in object.values() {
+                collect_purls(child, purls);
+            }
+        }
+        Value::Array(array) => {
+            for child in array {
+                collect_purls(child, purls);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Parse a `pkg:cargo/name@version` purl into a [`Package`].
+///
+/// Packages are assumed to come from crates.io unless a `repository_url`
+/// qualifier is present.
+fn parse_purl(purl: &str) -> Result<Package, Error> {
+    let invalid = || Error::new(ErrorKind::Parse, &format!("invalid cargo purl: {}", purl));
+
+    // Remove the subpath, which isn't meaningful for auditing
+    let purl = purl.splitn(2, '#').next().unwrap();
+    let mut parts = purl[CARGO_PURL_PREFIX.len()..].splitn(2, '?');
+    let mut name_version = parts.next().unwrap().splitn(2, '@');
+    let name = percent_decode(name_version.next().unwrap()).ok_or_else(invalid)?;
+    let version = percent_decode(name_version.next().ok_or_else(invalid)?).ok_or_else(invalid)?;
+
+    let mut registry = CRATES_IO_INDEX.to_owned();
+
+    for qualifier in parts.next().unwrap_or_default().split('&') {
+        let mut kv = qualifier.splitn(2, '=');
+
+        if kv.next() == Some("repository_url") {
+            registry = percent_decode(kv.next().unwrap_or_default()).ok_or_else(invalid)?;
+        }
+    }
+
+    Ok(Package {
+        name: name.parse()?,
+        version: version.parse()?,
+        source: Some(SourceId::from_url(&format!("registry+{}", registry))?),
+        checksum: None,
+        dependencies: vec![],
+        replace: None,
+    })
+}
+
+/// Decode `%XX` escapes in a purl component
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, parse};
+    use rustsec::error::ErrorKind;
+
+    #[test]
+    fn parse_cyclonedx() {
+        let lockfile = parse(
+            r#"{
+                "bomFormat": "CycloneDX",
+                "specVersion": "1.3",
+                "components": [
+                    { "name": "smallvec", "purl": "pkg:cargo/smallvec@0.6.9" },
+                    { "name": "base64", "purl": "pkg:cargo/base64@0.5.1",
+                      "components": [{ "purl": "pkg:cargo/semver@1.0.0%2Bbuild" }] },
+                    { "name": "left-pad", "purl": "pkg:npm/left-pad@1.3.0" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let names = lockfile
+            .packages
+            .iter()
+            .map(|pkg| format!("{} {}", pkg.name, pkg.version))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            ["base64 0.5.1", "semver 1.0.0+build", "smallvec 0.6.9"]
+        );
+        assert!(lockfile.packages[0]
+            .source
+            .as_ref()
+            .unwrap()
+            .is_default_registry());
+    }
+
+    #[test]
+    fn parse_spdx() {
+        let lockfile = parse(
+            r#"{
+                "spdxVersion": "SPDX-2.2",
+                "packages": [{
+                    "name": "smallvec",
+                    "externalRefs": [{
+                        "referenceCategory": "PACKAGE-MANAGER",
+                        "referenceType": "purl",
+                        "referenceLocator": "pkg:cargo/smallvec@0.6.9"
+                    }]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(lockfile.packages.len(), 1);
+        assert_eq!(lockfile.packages[0].name.as_str(), "smallvec");
+    }
+
+    #[test]
+    fn reject_unknown_format() {
+        assert!(parse(r#"{ "components": [] }"#).is_err());
+    }
+
+    #[test]
+    fn error_kinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sbom.json");
+        assert_eq!(load(&path).unwrap_err().kind(), ErrorKind::Io);
+
+        std::fs::write(&path, "{").unwrap();
+        assert_eq!(load(&path).unwrap_err().kind(), ErrorKind::Parse);
+    }
+}
@@ -0,0 +1,184 @@
+//! Support for auditing `cargo vendor` directories.
+//!
+//! Each crate in a vendor directory contains its (normalized) `Cargo.toml`
+//! along with a `.cargo-checksum.json` file. These are used to reconstruct
+//! the list of vendored packages when the original `Cargo.lock` is
+//! unavailable.
+
+use rustsec::{
+    cargo_lock::{Dependency, Metadata, Patch, ResolveVersion},
+    error::{Error, ErrorKind},
+    fs,
+    lockfile::Lockfile,
+    package::{source::CRATES_IO_INDEX, Checksum, Package, SourceId},
+};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap as Map,
+    path::{Path, PathBuf},
+};
+
+/// Name of the checksum file `cargo vendor` places in each crate directory
+const CHECKSUM_FILE: &str = ".cargo-checksum.json";
+
+/// Packages found in a vendor directory
+#[derive(Clone, Debug)]
+pub struct VendorDir {
+    /// Lockfile containing the vendored packages
+    pub lockfile: Lockfile,
+
+    /// Filesystem paths of each vendored package
+    pub paths: Map<Dependency, PathBuf>,
+}
+
+/// Subset of a vendored crate's `Cargo.toml`
+#[derive(Deserialize)]
+struct Manifest {
+    package: ManifestPackage,
+}
+
+/// `[package]` section of a vendored crate's `Cargo.toml`
+#[derive(Deserialize)]
+struct ManifestPackage {
+    name: String,
+    version: String,
+}
+
+/// Subset of a vendored crate's `.cargo-checksum.json`
+#[derive(Deserialize)]
+struct ChecksumFile {
+    /// Checksum of the `.crate` file (absent for git dependencies)
+    package: Option<String>,
+}
+
+impl VendorDir {
+    /// Scan the given `cargo vendor` directory.
+    ///
+    /// Vendor directories don't record the source of their crates. Crates
+    /// with a package checksum are assumed to originate from crates.io, while
+    /// those without one came from git (or another source without `.crate`
+    /// files) and are treated like local packages, i.e. they have no source.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let source = SourceId::from_url(&format!("registry+{}", CRATES_IO_INDEX))?;
+        let mut packages = vec![];
+        let mut paths = Map::new();
+
+        for dir_entry in fs::read_dir(path)? {
+            let crate_path = dir_entry?.path();
+
+            if !crate_path.join(CHECKSUM_FILE).exists() {
+                continue;
+            }
+
+            let package = load_package(&crate_path, &source).map_err(|e| {
+                Error::new(
+                    ErrorKind::Parse,
+                    &format!(
+                        "couldn't load vendored crate {}: {}",
+                        crate_path.display(),
+                        e
+                    ),
+                )
+            })?;
+
+            paths.insert(Dependency::from(&package), crate_path);
+            packages.push(package);
+        }
+
+        if packages.is_empty() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                &format!("no vendored crates found in {}", path.display()),
+            ));
+        }
+
+        packages.sort();
+
+        let lockfile = Lockfile {
+            version: ResolveVersion::default(),
+            packages,
+            root: None,
+            metadata: Metadata::default(),
+            patch: Patch::default(),
+        };
+
+        Ok(Self { lockfile, paths })
+    }
+}
+
+/// Load a single vendored crate
+fn load_package(crate_path: &Path, source: &SourceId) -> Result<Package, Error> {
+    let manifest: Manifest = toml::from_str(&fs::read_to_string(crate_path.join("Cargo.toml"))?)
+        .map_err(|e| Error::new(ErrorKind::Parse, &e))?;
+
+    let checksum: ChecksumFile =
+        serde_json::from_str(&fs::read_to_string(crate_path.join(CHECKSUM_FILE))?)
+            .map_err(|e| Error::new(ErrorKind::Parse, &e))?;
+
+    Ok(Package {
+        name: manifest.package.name.parse()?,
+        version: manifest.package.version.parse()?,
+        source: checksum.package.as_ref().map(|_| source.clone()),
+        checksum: checksum
+            .package
+            .map(|c| c.parse::<Checksum>())
+            .transpose()?,
+        dependencies: vec![],
+        replace: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VendorDir;
+    use rustsec::cargo_lock::Dependency;
+    use std::fs;
+
+    #[test]
+    fn load_vendor_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let crate_path = dir.path().join("base64");
+        fs::create_dir(&crate_path).unwrap();
+        fs::write(
+            crate_path.join("Cargo.toml"),
+            "[package]\nname = \"base64\"\nversion = \"0.5.1\"\n",
+        )
+        .unwrap();
+        fs::write(
+            crate_path.join(".cargo-checksum.json"),
+            format!(r#"{{"files":{{}},"package":"{}"}}"#, "0".repeat(64)),
+        )
+        .unwrap();
+
+        // Crates without a package checksum come from git, not crates.io
+        let git_crate_path = dir.path().join("fork");
+        fs::create_dir(&git_crate_path).unwrap();
+        fs::write(
+            git_crate_path.join("Cargo.toml"),
+            "[package]\nname = \"fork\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            git_crate_path.join(".cargo-checksum.json"),
+            r#"{"files":{},"package":null}"#,
+        )
+        .unwrap();
+
+        // Directories which aren't vendored crates are ignored
+        fs::create_dir(dir.path().join("not-a-crate")).unwrap();
+
+        let vendor_dir = VendorDir::load(dir.path()).unwrap();
+        let packages = &vendor_dir.lockfile.packages;
+        assert_eq!(packages.len(), 2);
+
+        assert_eq!(packages[0].name.as_str(), "base64");
+        assert!(packages[0].source.as_ref().unwrap().is_default_registry());
+        assert_eq!(
+            vendor_dir.paths[&Dependency::from(&packages[0])],
+            crate_path
+        );
+
+        assert_eq!(packages[1].name.as_str(), "fork");
+        assert!(packages[1].source.is_none());
+    }
+}
@@ -31,18 +31,25 @@ pub static RUNNER: Lazy<CmdRunner> = Lazy::new(|| {
     runner
 });
 
+/// Get the path to an integration test example project's Cargo.lock file
+fn lockfile_path(project: &str) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "support",
+        project,
+        "Cargo.lock",
+    ]
+    .iter()
+    .collect()
+}
+
 /// Get a `CmdRunner` configured to point at a project with or without vulns
 fn new_cmd_runner(project: &str) -> CmdRunner {
     let mut runner = RUNNER.clone();
 
-    let tests_data_dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "support"]
-        .iter()
-        .collect();
-
     // Point at the integration test example project's Cargo.lock file.
-    runner
-        .arg("--file")
-        .arg(tests_data_dir.join(project).join("Cargo.lock"));
+    runner.arg("--file").arg(lockfile_path(project));
 
     runner
 }
@@ -144,6 +151,80 @@ fn advisories_found_json() {
     assert_eq!(advisory_id, "RUSTSEC-2017-0004");
 }
 
+#[test]
+fn advisories_found_json_typed() {
+    let mut runner = vulnerable_cmd_runner();
+    runner.arg("--json");
+
+    let mut process = runner.run();
+    let json = get_advisories_json(&mut process);
+    process.wait().unwrap().expect_code(1);
+
+    let report: rustsec::Report = serde_json::from_value(json).unwrap();
+    assert!(report.vulnerabilities.found);
+    assert_eq!(
+        report.vulnerabilities.list[0].advisory.id.as_str(),
+        "RUSTSEC-2017-0004"
+    );
+}
+
+#[test]
+fn advisories_found_json_field_naming() {
+    for (naming, expected, unexpected) in &[
+        (
+            "kebab-case",
+            "/settings/target-arch",
+            "/settings/target_arch",
+        ),
+        (
+            "snake-case",
+            "/database/advisory_count",
+            "/database/advisory-count",
+        ),
+    ] {
+        let mut runner = vulnerable_cmd_runner();
+        runner.arg("--json").arg("--json-field-naming").arg(*naming);
+
+        let mut process = runner.run();
+        let json = get_advisories_json(&mut process);
+        process.wait().unwrap().expect_code(1);
+
+        assert!(json.pointer(expected).is_some(), "{} missing", expected);
+        assert!(json.pointer(unexpected).is_none(), "{} present", unexpected);
+
+        // Renamed reports can still be read back
+        let report: rustsec::Report = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            report.vulnerabilities.list[0].advisory.id.as_str(),
+            "RUSTSEC-2017-0004"
+        );
+        assert!(report.settings.target_arch.is_none());
+        assert!(report.database.advisory_count > 0);
+    }
+}
+
+/// the embedded snapshot (built from `tests/support/embedded-db` in CI) is
+/// used instead of the database on disk when requested
+#[cfg(feature = "embedded-db")]
+#[test]
+fn advisories_found_embedded_db() {
+    let mut runner = vulnerable_cmd_runner();
+    runner.arg("--json").arg("--embedded-db");
+
+    let mut process = runner.run();
+    let json = get_advisories_json(&mut process);
+    process.wait().unwrap().expect_code(1);
+
+    assert_eq!(json.pointer("/database/advisory-count").unwrap(), 1);
+    assert_eq!(
+        json.pointer("/vulnerabilities/list/0/advisory/id")
+            .unwrap()
+            .as_str()
+            .unwrap(),
+        "RUSTSEC-2017-0004"
+    );
+}
+
 #[test]
 fn version() {
     let mut runner = RUNNER.clone();
@@ -152,6 +233,27 @@ fn version() {
     process.wait().unwrap().expect_success();
 }
 
+#[test]
+fn advisories_found_only_ids_json() {
+    for (id, count) in &[("RUSTSEC-2017-0004", 1), ("RUSTSEC-2016-0001", 0)] {
+        let mut runner = vulnerable_cmd_runner();
+        runner.arg("--json");
+        runner.arg("--only").arg(*id);
+
+        let mut process = runner.run();
+        let json = get_advisories_json(&mut process);
+        process.wait().unwrap();
+
+        assert_eq!(
+            json.pointer("/vulnerabilities/count")
+                .unwrap()
+                .as_u64()
+                .unwrap(),
+            *count
+        );
+    }
+}
+
 #[test]
 fn advisories_found_but_ignored_json() {
     let mut runner = vulnerable_cmd_runner();
@@ -170,3 +272,274 @@ fn advisories_found_but_ignored_json() {
         0
     );
 }
+
+/// With `--no-cargo` (or `--no-exec`, which implies it), checks which need
+/// `cargo` are skipped and reported as notices instead, without running it
+#[cfg(unix)]
+#[test]
+fn no_cargo_notices_json() {
+    use std::{fs, os::unix::fs::PermissionsExt, process::Command};
+
+    // The only thing on the `PATH` is a `cargo` which records being run
+    let bin_dir = TempDir::new().unwrap();
+    let marker = bin_dir.path().join("cargo-was-run");
+    let cargo = bin_dir.path().join("cargo");
+    fs::write(
+        &cargo,
+        format!("#!/bin/sh\n: > '{}'\nexit 1\n", marker.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+
+    // Not shared with `RUNNER`, as this doesn't run under its mutex
+    let db_dir = TempDir::new().unwrap();
+
+    for flag in &["--no-cargo", "--no-exec"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_cargo-audit"))
+            .arg("audit")
+            .arg("--db")
+            .arg(db_dir.path())
+            .arg("--file")
+            .arg(lockfile_path("no_vulns"))
+            .args(&["--json", "--locked", flag])
+            .env("PATH", bin_dir.path())
+            .output()
+            .unwrap();
+
+        assert!(
+            output.status.success(),
+            "audit with {} failed: {}",
+            flag,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(!marker.exists(), "cargo was run with {}", flag);
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(json.pointer("/lockfile/in-sync").is_none());
+
+        let notices = json.pointer("/notices").unwrap().as_array().unwrap();
+        assert!(notices.iter().any(|notice| notice
+            .as_str()
+            .unwrap()
+            .starts_with("skipped checking whether")));
+    }
+}
+
+/// A local mirror whose manifest doesn't have the pinned digest is rejected
+#[test]
+fn mirror_digest_mismatch() {
+    let mirror_dir = TempDir::new().unwrap();
+    std::fs::write(mirror_dir.path().join("README.md"), "# Mirror").unwrap();
+
+    let manifest = rustsec::repository::mirror::Manifest::generate(mirror_dir.path()).unwrap();
+    manifest.save(mirror_dir.path()).unwrap();
+
+    let mut runner = CmdRunner::default();
+    runner
+        .arg("audit")
+        .arg("--db")
+        .arg(mirror_dir.path())
+        .args(&["--mirror", "https://mirror.example.com", "--no-fetch"])
+        .arg("--mirror-digest")
+        .arg("0".repeat(64))
+        .arg("--file")
+        .arg(lockfile_path("no_vulns"))
+        .capture_stdout()
+        .capture_stderr()
+        .status()
+        .expect_code(1);
+}
+
+/// Findings about packages which are unchanged from the baseline lockfile
+/// are omitted
+#[test]
+fn baseline_unchanged_json() {
+    let mut runner = vulnerable_cmd_runner();
+    runner
+        .arg("--json")
+        .arg("--baseline")
+        .arg(lockfile_path("base64_vuln"));
+
+    let mut process = runner.run();
+    let json = get_advisories_json(&mut process);
+    process.wait().unwrap().expect_success();
+
+    assert_eq!(
+        json.pointer("/vulnerabilities/count")
+            .unwrap()
+            .as_u64()
+            .unwrap(),
+        0
+    );
+
+    let notices = json.pointer("/notices").unwrap().as_array().unwrap();
+    assert!(notices
+        .iter()
+        .any(|notice| notice.as_str().unwrap().starts_with("omitted ")));
+}
+
+/// Findings about packages which were added since the baseline lockfile are
+/// still reported
+#[test]
+fn baseline_added_json() {
+    let mut runner = vulnerable_cmd_runner();
+    runner
+        .arg("--json")
+        .arg("--baseline")
+        .arg(lockfile_path("no_vulns"));
+
+    let mut process = runner.run();
+    let json = get_advisories_json(&mut process);
+    process.wait().unwrap().expect_code(1);
+
+    assert_eq!(
+        json.pointer("/vulnerabilities/count")
+            .unwrap()
+            .as_u64()
+            .unwrap(),
+        1
+    );
+}
+
+/// `--baseline` with a lockfile that doesn't exist is an error
+#[test]
+fn baseline_missing() {
+    let mut runner = secure_cmd_runner();
+    runner.arg("--baseline").arg(lockfile_path("empty"));
+    runner.status().expect_code(2);
+}
+
+/// Findings are shown in full, along with their dependency trees, by default
+#[test]
+fn full_output_by_default() {
+    let runner = vulnerable_cmd_runner();
+    let mut process = runner.run();
+    let stdout = (&mut **process.stdout())
+        .lines()
+        .collect::<Result<Vec<_>, _>>();
+    process.wait().unwrap().expect_code(1);
+
+    assert!(stdout
+        .unwrap()
+        .iter()
+        .any(|line| line.contains("Dependency tree:")));
+}
+
+/// With `--condensed`, findings are shown as a single line without their
+/// dependency trees
+#[test]
+fn condensed_output() {
+    let mut runner = vulnerable_cmd_runner();
+    runner.arg("--condensed");
+
+    let mut process = runner.run();
+    let stdout = (&mut **process.stdout())
+        .lines()
+        .collect::<Result<Vec<_>, _>>();
+    process.wait().unwrap().expect_code(1);
+
+    let stdout = stdout.unwrap();
+    assert!(!stdout.iter().any(|line| line.contains("Dependency tree:")));
+    assert!(stdout
+        .iter()
+        .any(|line| line.contains("RUSTSEC-") && line.contains("base64 0.5.1")));
+}
+
+/// With `--show-descriptions truncated`, only the first lines of advisory
+/// descriptions are shown, followed by how many lines were omitted
+#[test]
+fn truncated_descriptions() {
+    let mut runner = vulnerable_cmd_runner();
+    runner.args(&[
+        "--show-descriptions",
+        "truncated",
+        "--description-lines",
+        "1",
+    ]);
+
+    let mut process = runner.run();
+    let stdout = (&mut **process.stdout())
+        .lines()
+        .collect::<Result<Vec<_>, _>>();
+    process.wait().unwrap().expect_code(1);
+
+    let stdout = stdout.unwrap();
+    assert!(stdout.iter().any(|line| line.contains("Description:")));
+    assert!(stdout
+        .iter()
+        .any(|line| line.starts_with("... (") && line.ends_with(" more lines)")));
+}
+
+/// Descriptions aren't shown by default
+#[test]
+fn no_descriptions_by_default() {
+    let runner = vulnerable_cmd_runner();
+    let mut process = runner.run();
+    let stdout = (&mut **process.stdout())
+        .lines()
+        .collect::<Result<Vec<_>, _>>();
+    process.wait().unwrap().expect_code(1);
+
+    assert!(!stdout
+        .unwrap()
+        .iter()
+        .any(|line| line.contains("Description:")));
+}
+
+/// `--format attestation` wraps the report in an in-toto statement about the
+/// lockfile
+#[test]
+fn attestation_json() {
+    let mut runner = secure_cmd_runner();
+    runner.args(&["--format", "attestation"]);
+
+    let mut process = runner.run();
+    let json = get_advisories_json(&mut process);
+    process.wait().unwrap().expect_success();
+
+    assert_eq!(
+        json.pointer("/predicateType").unwrap().as_str().unwrap(),
+        "https://rustsec.org/attestation/audit/v1"
+    );
+    assert!(json.pointer("/subject/0/digest/sha256").is_some());
+    assert!(json.pointer("/predicate/report/database").is_some());
+    assert!(json.pointer("/predicate/database").is_none());
+}
+
+/// Attestations are about a single lockfile, so can't be generated for
+/// aggregate reports
+#[test]
+fn recursive_attestation_rejected() {
+    let support_dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "support"]
+        .iter()
+        .collect();
+
+    let mut runner = RUNNER.clone();
+    runner
+        .args(&["--format", "attestation", "--recursive"])
+        .arg(support_dir)
+        .status()
+        .expect_code(1);
+}
+
+/// A vendored copy of the advisory DB (i.e. one which isn't a git checkout)
+/// is audited against without being fetched
+#[test]
+fn vendored_db() {
+    let db_dir = TempDir::new().unwrap();
+    std::fs::write(db_dir.path().join("README.md"), "# Vendored").unwrap();
+
+    let mut runner = CmdRunner::default();
+    runner
+        .arg("audit")
+        .arg("--db")
+        .arg(db_dir.path())
+        .arg("--file")
+        .arg(lockfile_path("no_vulns"))
+        .capture_stdout()
+        .capture_stderr()
+        .status()
+        .expect_success();
+
+    assert!(!db_dir.path().join(".git").exists());
+}
@@ -18,3 +18,131 @@ fn parse_audit_toml_example() {
         "https://github.com/RustSec/advisory-db.git"
     );
 }
+
+/// Ensure description modes are parsed from config files and flags alike
+#[test]
+fn parse_description_mode() {
+    use cargo_audit::config::DescriptionMode;
+
+    let config: AuditConfig = toml::from_str("").unwrap();
+    assert_eq!(config.output.show_descriptions, DescriptionMode::None);
+    assert_eq!(config.output.description_lines, None);
+
+    let config: AuditConfig = toml::from_str(
+        "[output]\nquiet = false\nshow_descriptions = \"truncated\"\ndescription_lines = 3\n",
+    )
+    .unwrap();
+    assert_eq!(config.output.show_descriptions, DescriptionMode::Truncated);
+    assert_eq!(config.output.description_lines, Some(3));
+
+    assert_eq!(
+        "full".parse::<DescriptionMode>().unwrap(),
+        DescriptionMode::Full
+    );
+    assert!("some".parse::<DescriptionMode>().is_err());
+    assert!(toml::from_str::<AuditConfig>(
+        "[output]\nquiet = false\nshow_descriptions = \"some\"\n"
+    )
+    .is_err());
+}
+
+/// Ensure typos in config files are reported along with their location
+#[test]
+fn reject_unknown_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.toml");
+    fs::write(&path, "[advisories]\nignroe = [\"RUSTSEC-2019-0001\"]\n").unwrap();
+
+    let message = AuditConfig::load_file(&path).unwrap_err().to_string();
+    assert!(message.contains("unknown field `ignroe`"));
+    assert!(message.contains("--> "));
+    assert!(message.contains(" | ^"));
+}
+
+/// Ensure malformed advisory IDs in config files are rejected
+#[test]
+fn reject_invalid_advisory_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.toml");
+    fs::write(&path, "[advisories]\nignore = [\"RUSTSEC-19-1\"]\n").unwrap();
+
+    let message = AuditConfig::load_file(&path).unwrap_err().to_string();
+    assert!(message.contains("RUSTSEC-19-1"));
+    assert!(message.contains("audit.toml:"));
+}
+
+/// Ensure `RUSTSEC_*` environment variables are parsed and take precedence
+/// over (or extend) the config file.
+///
+/// Environment variables are process-wide, so all of the cases are checked
+/// by a single test.
+#[test]
+fn apply_env() {
+    use cargo_audit::config::{env_vars, DenyOption, OutputFormat};
+    use rustsec::advisory::Severity;
+    use std::env;
+
+    let config_toml = r#"
+        [advisories]
+        ignore = ["RUSTSEC-2019-0001"]
+        severity_threshold = "low"
+
+        [database]
+        url = "https://example.com/config-advisory-db.git"
+        fetch = true
+        stale = false
+
+        [output]
+        format = "terminal"
+        quiet = false
+    "#;
+
+    env::set_var(env_vars::DB_URL, "https://example.com/env-advisory-db.git");
+    env::set_var(env_vars::IGNORE, "RUSTSEC-2020-0002, RUSTSEC-2021-0003");
+    env::set_var(env_vars::SEVERITY_THRESHOLD, "high");
+    env::set_var(env_vars::DENY, "unmaintained,yanked");
+    env::set_var(env_vars::FORMAT, "json");
+    env::set_var(env_vars::QUIET, "TRUE");
+    env::set_var(env_vars::STALE, "0");
+    env::set_var(env_vars::LOCKED, " ");
+
+    let mut config: AuditConfig = toml::from_str(config_toml).unwrap();
+    let result = config.apply_env();
+
+    // Invalid values are reported along with the variable they came from
+    env::set_var(env_vars::SEVERITY_THRESHOLD, "severe");
+    let mut invalid: AuditConfig = toml::from_str(config_toml).unwrap();
+    let message = invalid.apply_env().unwrap_err().to_string();
+
+    for name in &[
+        env_vars::DB_URL,
+        env_vars::IGNORE,
+        env_vars::SEVERITY_THRESHOLD,
+        env_vars::DENY,
+        env_vars::FORMAT,
+        env_vars::QUIET,
+        env_vars::STALE,
+        env_vars::LOCKED,
+    ] {
+        env::remove_var(name);
+    }
+
+    result.unwrap();
+    assert_eq!(
+        config.database.url.unwrap(),
+        "https://example.com/env-advisory-db.git"
+    );
+    assert_eq!(config.advisories.ignore.len(), 3);
+    assert_eq!(config.advisories.severity_threshold, Some(Severity::High));
+    assert_eq!(
+        config.output.deny,
+        [DenyOption::Unmaintained, DenyOption::Yanked]
+    );
+    assert_eq!(config.output.format, OutputFormat::Json);
+    assert!(config.output.quiet);
+    assert!(!config.database.stale);
+    assert!(!config.lockfile.locked);
+
+    assert!(message.contains(env_vars::SEVERITY_THRESHOLD));
+    assert!(message.contains("severe"));
+}
@@ -0,0 +1,34 @@
+//! Tests for forbidding external processes (`--no-exec`).
+//!
+//! These live in their own test binary, as forbidding external processes
+//! lasts for the rest of the process.
+
+use cargo_audit::lockfile;
+use std::fs;
+
+/// Once external processes are forbidden, anything which would run `cargo`
+/// fails without running it
+#[test]
+fn cargo_is_not_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest_path = dir.path().join("Cargo.toml");
+
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+    fs::write(
+        &manifest_path,
+        "[package]\nname = \"a\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+
+    assert!(!lockfile::is_exec_forbidden());
+    lockfile::forbid_exec();
+    assert!(lockfile::is_exec_forbidden());
+
+    let error = lockfile::generate_for(&manifest_path).unwrap_err();
+    assert!(error.to_string().contains("--no-exec"));
+    assert!(!dir.path().join("Cargo.lock").exists());
+
+    assert!(lockfile::is_in_sync(&manifest_path).is_err());
+    assert!(lockfile::workspace_members(&manifest_path).is_err());
+}
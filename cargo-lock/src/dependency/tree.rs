@@ -10,7 +10,12 @@ use super::{
     Dependency,
 };
 use crate::{error::Error, lockfile::Lockfile, Map};
-use std::{collections::BTreeSet as Set, io};
+use petgraph::algo::tarjan_scc;
+use std::{
+    cmp::Reverse,
+    collections::{btree_map::Entry, BTreeSet as Set, BinaryHeap, VecDeque},
+    io,
+};
 
 /// Dependency tree computed from a `Cargo.lock` file
 #[derive(Clone, Debug)]
@@ -77,6 +82,179 @@ impl Tree {
         self.graph.externals(EdgeDirection::Incoming).collect()
     }
 
+    /// Is the package at the given [`NodeIndex`] a root package?
+    pub fn is_root(&self, node_index: NodeIndex) -> bool {
+        self.dependents(node_index).is_empty()
+    }
+
+    /// Get the packages which directly depend on the package at the given
+    /// [`NodeIndex`]
+    pub fn dependents(&self, node_index: NodeIndex) -> Vec<NodeIndex> {
+        let mut dependents = self
+            .graph
+            .neighbors_directed(node_index, EdgeDirection::Incoming)
+            .collect::<Vec<_>>();
+
+        // Packages may depend on the same package more than once
+        // (e.g. with different renames), so deduplicate
+        dependents.sort();
+        dependents.dedup();
+        dependents
+    }
+
+    /// Get the transitive dependencies of the package at the given
+    /// [`NodeIndex`], including the package itself
+    pub fn transitive_dependencies(&self, node_index: NodeIndex) -> Set<NodeIndex> {
+        let mut visited = Set::new();
+        let mut stack = vec![node_index];
+
+        while let Some(node) = stack.pop() {
+            if visited.insert(node) {
+                stack.extend(self.graph.neighbors_directed(node, EdgeDirection::Outgoing));
+            }
+        }
+
+        visited
+    }
+
+    /// Get at most `limit` of the inverse dependency paths from the package
+    /// at the given [`NodeIndex`] to a root package, shortest first.
+    ///
+    /// Each path begins with the given package and ends with a root, or
+    /// with a package whose only dependents are already on the path (i.e.
+    /// one in a dependency cycle, which is possible via dev-dependencies).
+    /// Cycles are never followed more than once.
+    ///
+    /// Paths are explored in order of their shortest possible length, so
+    /// only as much of the graph is visited as is needed to find them,
+    /// even when the total number of paths is huge.
+    pub fn shortest_inverse_paths(
+        &self,
+        node_index: NodeIndex,
+        limit: usize,
+    ) -> Vec<Vec<NodeIndex>> {
+        let mut paths = vec![];
+
+        if limit == 0 {
+            return paths;
+        }
+
+        // Lower bound on the length of any complete path continuing from the
+        // given one, so paths are found in order of length
+        let bounds = self.path_length_bounds();
+        let estimate = |path: &[NodeIndex]| {
+            let last = path.last().unwrap();
+            path.len() + bounds.get(last).copied().unwrap_or(0)
+        };
+
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((estimate(&[node_index]), vec![node_index])));
+
+        while let Some(Reverse((_, path))) = queue.pop() {
+            let dependents = self.unvisited_dependents(&path);
+
+            if dependents.is_empty() {
+                paths.push(path);
+
+                if paths.len() == limit {
+                    break;
+                }
+
+                continue;
+            }
+
+            for dependent in dependents {
+                let mut next = path.clone();
+                next.push(dependent);
+                queue.push(Reverse((estimate(&next), next)));
+            }
+        }
+
+        paths
+    }
+
+    /// Get the root packages which (transitively) depend on the package at
+    /// the given [`NodeIndex`]
+    pub fn roots_of(&self, node_index: NodeIndex) -> Set<NodeIndex> {
+        let mut visited = Set::new();
+        let mut roots = Set::new();
+        let mut stack = vec![node_index];
+
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+
+            let dependents = self.dependents(node);
+
+            if dependents.is_empty() {
+                roots.insert(node);
+            }
+
+            stack.extend(dependents);
+        }
+
+        roots
+    }
+
+    /// Get the dependents of the last package on an inverse dependency path
+    /// which aren't already on it
+    fn unvisited_dependents(&self, path: &[NodeIndex]) -> Vec<NodeIndex> {
+        let last = *path.last().unwrap();
+
+        self.dependents(last)
+            .into_iter()
+            .filter(|dependent| !path.contains(dependent))
+            .collect()
+    }
+
+    /// Get a lower bound on the number of further packages on any inverse
+    /// dependency path continuing from each package: the minimum number of
+    /// dependency edges between it and a package a path can end with
+    fn path_length_bounds(&self) -> Map<NodeIndex, usize> {
+        let mut ends = self.roots();
+
+        // Paths can also end with a package in a dependency cycle if all of
+        // its dependents are in the cycle too (i.e. strongly connected to
+        // it), since they may all be on the path already
+        for component in tarjan_scc(&self.graph) {
+            if component.len() < 2 {
+                continue;
+            }
+
+            for &node in &component {
+                if self
+                    .dependents(node)
+                    .iter()
+                    .all(|dependent| component.contains(dependent))
+                {
+                    ends.push(node);
+                }
+            }
+        }
+
+        let mut bounds = Map::new();
+        let mut queue = VecDeque::new();
+
+        for node in ends {
+            bounds.insert(node, 0);
+            queue.push_back(node);
+        }
+
+        while let Some(node) = queue.pop_front() {
+            let distance = bounds[&node] + 1;
+
+            for dependency in self.graph.neighbors_directed(node, EdgeDirection::Outgoing) {
+                if let Entry::Vacant(entry) = bounds.entry(dependency) {
+                    entry.insert(distance);
+                    queue.push_back(dependency);
+                }
+            }
+        }
+
+        bounds
+    }
+
     /// Get the `petgraph` dependency graph.
     pub fn graph(&self) -> &Graph {
         &self.graph
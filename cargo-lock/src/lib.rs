@@ -187,7 +187,7 @@ pub mod patch;
 pub use self::{
     dependency::Dependency,
     error::{Error, ErrorKind},
-    lockfile::{Lockfile, ResolveVersion},
+    lockfile::{Lockfile, ResolveVersion, SkippedEntry},
     metadata::Metadata,
     package::{Checksum, Name, Package, SourceId, Version},
     patch::Patch,
@@ -1,9 +1,10 @@
 //! Parser for `Cargo.lock` files
 
 pub(crate) mod encoding;
+mod lenient;
 pub mod version;
 
-pub use self::version::ResolveVersion;
+pub use self::{lenient::SkippedEntry, version::ResolveVersion};
 
 use self::encoding::EncodableLockfile;
 use crate::{
@@ -50,6 +51,15 @@ impl Lockfile {
         }
     }
 
+    /// Parse a `Cargo.lock` file leniently: packages, checksums,
+    /// dependencies, and sections which can't be parsed are skipped rather
+    /// than failing the whole file, and returned along with the lockfile.
+    ///
+    /// Files which aren't valid TOML still fail to parse.
+    pub fn parse_lenient(toml_string: &str) -> Result<(Self, Vec<SkippedEntry>), Error> {
+        lenient::parse(toml_string)
+    }
+
     /// Get the dependency tree for this `Lockfile`. Returns an error if the
     /// contents of this lockfile aren't well structured.
     ///
@@ -0,0 +1,182 @@
+//! Lenient `Cargo.lock` parsing, which skips entries that can't be parsed
+//! rather than failing on them
+
+use super::{encoding::EncodableLockfile, Lockfile};
+use crate::{error::Error, Checksum, Dependency};
+use std::{
+    convert::TryInto,
+    fmt::{self, Display},
+    mem,
+};
+use toml::Value;
+
+/// Entry of a `Cargo.lock` file which was skipped by [`Lockfile::parse_lenient`]
+/// because it couldn't be parsed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SkippedEntry {
+    /// Description of the entry (e.g. ``checksum of package `foo 1.0.0` ``)
+    pub entry: String,
+
+    /// Why the entry couldn't be parsed
+    pub reason: String,
+
+    /// Package the entry belongs to, if it can be identified (i.e. for
+    /// packages whose name and version parse, and their checksums and
+    /// dependencies)
+    pub package: Option<Dependency>,
+}
+
+impl Display for SkippedEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "skipped {}: {}", self.entry, self.reason)
+    }
+}
+
+/// Parse a lockfile, skipping any packages, checksums, dependencies, and
+/// sections which can't be parsed
+pub(super) fn parse(toml_string: &str) -> Result<(Lockfile, Vec<SkippedEntry>), Error> {
+    // Files which aren't valid TOML at all can't be recovered from
+    let mut table: toml::value::Table = toml::from_str(toml_string)?;
+    let mut skipped = vec![];
+
+    if let Some(Value::Array(packages)) = table.get_mut("package") {
+        for (i, mut package) in mem::take(packages).into_iter().enumerate() {
+            if parse_package(&mut package, i, &mut skipped) {
+                packages.push(package);
+            }
+        }
+    }
+
+    for section in &["root", "metadata", "patch"] {
+        if let Some(value) = table.get(*section) {
+            if let Err(e) = check_section(section, value.clone()) {
+                table.remove(*section);
+                skipped.push(SkippedEntry {
+                    entry: format!("[{}] section", section),
+                    reason: e.to_string(),
+                    package: None,
+                });
+            }
+        }
+    }
+
+    let mut raw_lockfile: EncodableLockfile = Value::Table(table).try_into()?;
+
+    // Dependencies on skipped packages can't be resolved
+    let resolvable = raw_lockfile
+        .package
+        .iter()
+        .map(|package| {
+            package
+                .dependencies
+                .iter()
+                .map(|dep| dep.resolve(&raw_lockfile.package))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for (package, resolved) in raw_lockfile.package.iter_mut().zip(resolvable) {
+        let mut resolved = resolved.into_iter();
+        let owner = Dependency {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            source: package.source.clone(),
+        };
+
+        package.dependencies.retain(|dep| match resolved.next() {
+            Some(Err(e)) => {
+                skipped.push(SkippedEntry {
+                    entry: format!(
+                        "dependency `{}` of package `{} {}`",
+                        dep.name, owner.name, owner.version
+                    ),
+                    reason: e.to_string(),
+                    package: Some(owner.clone()),
+                });
+                false
+            }
+            _ => true,
+        });
+    }
+
+    Ok((raw_lockfile.try_into()?, skipped))
+}
+
+/// Check whether a package can be parsed, discarding its checksum if that's
+/// what's malformed. Returns whether the package should be kept.
+fn parse_package(package: &mut Value, index: usize, skipped: &mut Vec<SkippedEntry>) -> bool {
+    let error = match package
+        .clone()
+        .try_into::<super::encoding::EncodablePackage>()
+    {
+        Ok(_) => return true,
+        Err(e) => e,
+    };
+
+    let description = describe_package(package, index);
+
+    let bad_checksum = package
+        .get("checksum")
+        .and_then(Value::as_str)
+        .and_then(|checksum| checksum.parse::<Checksum>().err());
+
+    if let Some(e) = bad_checksum {
+        if let Some(table) = package.as_table_mut() {
+            table.remove("checksum");
+        }
+
+        skipped.push(SkippedEntry {
+            entry: format!("checksum of {}", description),
+            reason: e.to_string(),
+            package: identify_package(package),
+        });
+
+        return parse_package(package, index, skipped);
+    }
+
+    skipped.push(SkippedEntry {
+        entry: description,
+        reason: error.to_string(),
+        package: identify_package(package),
+    });
+
+    false
+}
+
+/// Check whether a top-level section of the lockfile can be parsed
+fn check_section(section: &str, value: Value) -> Result<(), toml::de::Error> {
+    match section {
+        "root" => value
+            .try_into::<super::encoding::EncodablePackage>()
+            .map(|_| ()),
+        "metadata" => value.try_into::<crate::Metadata>().map(|_| ()),
+        _ => value.try_into::<crate::Patch>().map(|_| ()),
+    }
+}
+
+/// Describe a package by its name and version (as far as they're known)
+fn describe_package(package: &Value, index: usize) -> String {
+    let field = |name: &str| package.get(name).and_then(Value::as_str);
+
+    match (field("name"), field("version")) {
+        (Some(name), Some(version)) => format!("package `{} {}`", name, version),
+        (Some(name), None) => format!("package `{}`", name),
+        _ => format!("package #{}", index + 1),
+    }
+}
+
+/// Identify a package by its name, version, and source, if they all parse
+fn identify_package(package: &Value) -> Option<Dependency> {
+    let field = |name: &str| package.get(name).and_then(Value::as_str);
+
+    let source = match field("source") {
+        Some(source) => Some(source.parse().ok()?),
+        None => None,
+    };
+
+    Some(Dependency {
+        name: field("name")?.parse().ok()?,
+        version: field("version")?.parse().ok()?,
+        source,
+    })
+}
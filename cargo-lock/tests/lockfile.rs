@@ -121,4 +121,189 @@ mod tree {
 
         assert_eq!(tree.nodes().len(), 472);
     }
+
+    /// Find inverse dependency paths and roots in a small dependency graph
+    #[test]
+    fn inverse_paths_and_roots() {
+        let lockfile: Lockfile = r#"
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["left", "right"]
+
+            [[package]]
+            name = "left"
+            version = "0.1.0"
+            dependencies = ["leaf"]
+
+            [[package]]
+            name = "right"
+            version = "0.1.0"
+            dependencies = ["left"]
+
+            [[package]]
+            name = "leaf"
+            version = "0.1.0"
+        "#
+        .parse()
+        .unwrap();
+
+        let tree = lockfile.dependency_tree().unwrap();
+        let node = |name: &str| {
+            tree.nodes()
+                .iter()
+                .find(|(dep, _)| dep.name.as_str() == name)
+                .map(|(_, &node)| node)
+                .unwrap()
+        };
+
+        assert_eq!(tree.roots(), [node("app")]);
+        assert!(tree.is_root(node("app")));
+        assert!(!tree.is_root(node("leaf")));
+        assert_eq!(tree.transitive_dependencies(node("app")).len(), 4);
+        assert_eq!(
+            tree.shortest_inverse_paths(node("leaf"), 10),
+            [
+                vec![node("leaf"), node("left"), node("app")],
+                vec![node("leaf"), node("left"), node("right"), node("app")],
+            ]
+        );
+        assert!(tree.roots_of(node("leaf")).contains(&node("app")));
+    }
+
+    /// Find the shortest inverse dependency paths in a graph with a
+    /// (dev-dependency) cycle
+    #[test]
+    fn shortest_inverse_paths_with_cycle() {
+        let lockfile: Lockfile = r#"
+            [[package]]
+            name = "app"
+            version = "0.1.0"
+            dependencies = ["left", "middle", "right"]
+
+            [[package]]
+            name = "left"
+            version = "0.1.0"
+            dependencies = ["leaf"]
+
+            [[package]]
+            name = "middle"
+            version = "0.1.0"
+            dependencies = ["right"]
+
+            [[package]]
+            name = "right"
+            version = "0.1.0"
+            dependencies = ["left"]
+
+            [[package]]
+            name = "leaf"
+            version = "0.1.0"
+            dependencies = ["tester"]
+
+            [[package]]
+            name = "tester"
+            version = "0.1.0"
+            dependencies = ["leaf"]
+        "#
+        .parse()
+        .unwrap();
+
+        let tree = lockfile.dependency_tree().unwrap();
+        let node = |name: &str| {
+            tree.nodes()
+                .iter()
+                .find(|(dep, _)| dep.name.as_str() == name)
+                .map(|(_, &node)| node)
+                .unwrap()
+        };
+
+        // The cycle through `tester` ends at `tester` rather than looping
+        assert_eq!(
+            tree.shortest_inverse_paths(node("leaf"), 2),
+            [
+                vec![node("leaf"), node("tester")],
+                vec![node("leaf"), node("left"), node("app")],
+            ]
+        );
+        assert_eq!(tree.shortest_inverse_paths(node("leaf"), 0).len(), 0);
+        assert_eq!(
+            tree.shortest_inverse_paths(node("leaf"), 10),
+            [
+                vec![node("leaf"), node("tester")],
+                vec![node("leaf"), node("left"), node("app")],
+                vec![node("leaf"), node("left"), node("right"), node("app")],
+                vec![
+                    node("leaf"),
+                    node("left"),
+                    node("right"),
+                    node("middle"),
+                    node("app")
+                ],
+            ]
+        );
+    }
+}
+
+/// Ensure malformed entries are skipped when parsing leniently
+#[test]
+fn parse_lenient() {
+    let toml = r#"
+version = 3
+
+[[package]]
+name = "a"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "not-a-checksum"
+dependencies = ["b"]
+
+[[package]]
+name = "b"
+version = "not-a-version"
+
+[[package]]
+name = "c"
+version = "1.0.0"
+"#;
+
+    assert!(toml.parse::<Lockfile>().is_err());
+
+    let (lockfile, skipped) = Lockfile::parse_lenient(toml).unwrap();
+    let names = lockfile
+        .packages
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["a", "c"]);
+    assert_eq!(lockfile.packages[0].checksum, None);
+    assert!(lockfile.packages[0].dependencies.is_empty());
+
+    let entries = skipped
+        .iter()
+        .map(|skipped| skipped.entry.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        entries,
+        [
+            "checksum of package `a 1.0.0`",
+            "package `b not-a-version`",
+            "dependency `b` of package `a 1.0.0`"
+        ]
+    );
+
+    let packages = skipped
+        .iter()
+        .map(|skipped| {
+            skipped
+                .package
+                .as_ref()
+                .map(|package| package.name.as_str())
+        })
+        .collect::<Vec<_>>();
+
+    // `b` can't be identified, as its version doesn't parse
+    assert_eq!(packages, [Some("a"), None, Some("a")]);
 }
@@ -0,0 +1,18 @@
+//! Fuzz the Markdown advisory parser, which should never panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rustsec::Advisory;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let Ok(advisory) = s.parse::<Advisory>() {
+            // Exercise the version matching logic on successfully parsed advisories
+            let _ = advisory
+                .versions
+                .is_vulnerable(&rustsec::Version::new(0, 0, 0));
+            let _ = advisory.severity();
+        }
+    }
+});
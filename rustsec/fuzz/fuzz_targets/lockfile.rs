@@ -0,0 +1,12 @@
+//! Fuzz the `Cargo.lock` parser, which should never panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rustsec::lockfile::Lockfile;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = s.parse::<Lockfile>();
+    }
+});
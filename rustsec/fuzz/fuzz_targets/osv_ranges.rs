@@ -0,0 +1,44 @@
+//! Fuzz the conversion of `patched`/`unaffected` version requirements into
+//! OSV ranges, which should never panic.
+//!
+//! Input is a newline-delimited list of version requirements. Lines prefixed
+//! with `!` are treated as unaffected, all others as patched.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rustsec::{advisory::Versions, osv, Version, VersionReq};
+
+fuzz_target!(|data: &[u8]| {
+    let s = match std::str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+
+    let mut patched = vec![];
+    let mut unaffected = vec![];
+
+    for line in s.lines() {
+        if let Some(req) = line.strip_prefix('!') {
+            match VersionReq::parse(req) {
+                Ok(req) => unaffected.push(req),
+                Err(_) => return,
+            }
+        } else {
+            match VersionReq::parse(line) {
+                Ok(req) => patched.push(req),
+                Err(_) => return,
+            }
+        }
+    }
+
+    if let Ok(versions) = Versions::new(patched, unaffected) {
+        for range in osv::ranges_for_advisory(&versions) {
+            for version in range.introduced().into_iter().chain(range.fixed()) {
+                let _ = versions.is_vulnerable(version);
+            }
+        }
+
+        let _ = versions.is_vulnerable(&Version::new(0, 0, 0));
+    }
+});
@@ -3,27 +3,47 @@
 pub mod affected;
 pub mod category;
 pub mod date;
+pub(crate) mod display;
 pub mod id;
 pub mod informational;
 pub mod keyword;
 pub mod linter;
 pub mod metadata;
 pub mod parser;
+#[cfg(feature = "cvss")]
+pub mod severity;
 pub mod versions;
 
+mod writer;
+
 pub use self::{
-    affected::Affected, category::Category, date::Date, id::Id, informational::Informational,
-    keyword::Keyword, linter::Linter, metadata::Metadata, versions::Versions,
+    affected::Affected,
+    category::Category,
+    date::Date,
+    id::Id,
+    informational::Informational,
+    keyword::Keyword,
+    linter::Linter,
+    metadata::Metadata,
+    versions::{GitRange, PrereleaseMatching, Versions},
 };
+
+#[cfg(feature = "cvss")]
+pub use self::severity::{AdvisoryType, SeverityFallback, SeverityInfo};
+#[cfg(feature = "cvss")]
 pub use cvss::Severity;
 
 use crate::{
-    error::{Error, ErrorKind},
+    error::{AdvisoryError, Error, ErrorKind},
     fs,
 };
 use serde::{Deserialize, Serialize};
 use std::{path::Path, str::FromStr};
 
+/// Version of the advisory file format (Markdown with TOML front matter,
+/// a.k.a. the "V3 advisory format") which is parsed and written
+pub const FORMAT_VERSION: u32 = 3;
+
 /// RustSec Security Advisories
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Advisory {
@@ -43,12 +63,13 @@ impl Advisory {
     pub fn load_file(path: impl AsRef<Path>) -> Result<Self, Error> {
         let path = path.as_ref();
 
-        let advisory_data = fs::read_to_string(path)
-            .map_err(|e| format_err!(ErrorKind::Io, "couldn't open {}: {}", path.display(), e))?;
+        let advisory_data = fs::read_to_string(path).map_err(|e| {
+            format_err!(ErrorKind::Io, "couldn't open {}: {}", path.display(), e).with_source(e)
+        })?;
 
-        advisory_data
-            .parse()
-            .map_err(|e| format_err!(ErrorKind::Parse, "error parsing {}: {}", path.display(), e))
+        advisory_data.parse().map_err(|e: Error| {
+            format_err!(ErrorKind::Parse, "error parsing {}: {}", path.display(), e).with_source(e)
+        })
     }
 
     /// Get advisory ID
@@ -72,14 +93,54 @@ impl Advisory {
     }
 
     /// Get the severity of this advisory if it has a CVSS v3 associated
+    #[cfg(feature = "cvss")]
     pub fn severity(&self) -> Option<Severity> {
         self.metadata.cvss.as_ref().map(|cvss| cvss.severity())
     }
 
+    /// Get the kind of informational advisory this is, if it's informational
+    pub fn informational(&self) -> Option<&Informational> {
+        self.metadata.informational.as_ref()
+    }
+
+    /// Get the date the vulnerability was discovered, if known
+    pub fn discovered(&self) -> Option<&Date> {
+        self.metadata.discovered.as_ref()
+    }
+
+    /// Get the date the vulnerability was reported to the crate's
+    /// maintainers, if known
+    pub fn reported(&self) -> Option<&Date> {
+        self.metadata.reported.as_ref()
+    }
+
+    /// Get the date the first patched version was released, if any (see
+    /// [`Versions::patched`] for the patched versions themselves)
+    pub fn patched_date(&self) -> Option<&Date> {
+        self.metadata.patched.as_ref()
+    }
+
     /// Whether the advisory has been withdrawn, i.e. soft-deleted
     pub fn withdrawn(&self) -> bool {
         self.metadata.withdrawn.is_some()
     }
+
+    /// Serialize this advisory in the canonical Markdown format (i.e. TOML
+    /// front matter followed by the title and description).
+    ///
+    /// Keys are written in a stable order and empty optional keys are
+    /// omitted, so rewriting an advisory file produces a minimal diff.
+    pub fn to_toml_string(&self) -> Result<String, Error> {
+        writer::write(self)
+    }
+
+    /// Render this advisory as human-readable text wrapped to fit within
+    /// `width` columns: its title, ID, crate, date, URL, severity, and
+    /// patched versions, laid out the same way `cargo audit` displays
+    /// them, followed by its description
+    pub fn display_report(&self, width: usize) -> String {
+        display::report(self, width)
+    }
 }
 
 impl FromStr for Advisory {
@@ -98,17 +159,11 @@ impl FromStr for Advisory {
         let mut advisory: Self = toml::from_str(&front_matter)?;
 
         if !advisory.metadata.title.is_empty() {
-            fail!(
-                ErrorKind::Parse,
-                "invalid `title` attribute in advisory TOML"
-            );
+            return Err(AdvisoryError::InvalidAttribute("title").into());
         }
 
         if !advisory.metadata.description.is_empty() {
-            fail!(
-                ErrorKind::Parse,
-                "invalid `description` attribute in advisory TOML"
-            );
+            return Err(AdvisoryError::InvalidAttribute("description").into());
         }
 
         advisory.metadata.title = parts.title.to_owned();
@@ -2,10 +2,14 @@
 //! of impacted systems/functions/usages.
 
 use crate::{
-    error::{Error, ErrorKind},
+    error::{AdvisoryError, Error, ErrorKind},
     Map,
 };
-use platforms::target::{Arch, OS};
+#[cfg(feature = "platforms")]
+use platforms::{
+    target::{Arch, Env, OS},
+    Platform,
+};
 use semver::VersionReq;
 use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
@@ -20,13 +24,37 @@ use std::{
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Affected {
     /// CPU architectures that this vulnerability is specific to
+    #[cfg(feature = "platforms")]
     #[serde(default)]
     pub arch: Vec<Arch>,
 
     /// Operating systems that this vulnerability is specific to
+    #[cfg(feature = "platforms")]
     #[serde(default)]
     pub os: Vec<OS>,
 
+    /// Target environments (i.e. `cfg(target_env)`, e.g. `musl`) that this
+    /// vulnerability is specific to
+    #[cfg(feature = "platforms")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<Env>,
+
+    /// CPU architectures, kept as strings without the `platforms` feature so
+    /// that re-serializing the advisory doesn't lose them
+    #[cfg(not(feature = "platforms"))]
+    #[serde(default)]
+    arch: Vec<String>,
+
+    /// Operating systems, kept as strings without the `platforms` feature
+    #[cfg(not(feature = "platforms"))]
+    #[serde(default)]
+    os: Vec<String>,
+
+    /// Target environments, kept as strings without the `platforms` feature
+    #[cfg(not(feature = "platforms"))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    env: Vec<String>,
+
     /// Paths to types and/or functions containing vulnerable code, enumerated
     /// as canonical Rust paths (i.e. starting with the crate name), sans any
     /// path parameters.
@@ -36,6 +64,20 @@ pub struct Affected {
     pub functions: Map<FunctionPath, Vec<VersionReq>>,
 }
 
+#[cfg(feature = "platforms")]
+impl Affected {
+    /// Is the given platform affected? Empty lists of architectures,
+    /// operating systems, or environments match any platform.
+    pub fn affects_platform(&self, platform: &Platform) -> bool {
+        (self.arch.is_empty() || self.arch.contains(&platform.target_arch))
+            && (self.os.is_empty() || self.os.contains(&platform.target_os))
+            && (self.env.is_empty()
+                || platform
+                    .target_env
+                    .map_or(false, |env| self.env.contains(&env)))
+    }
+}
+
 /// Canonical Rust Paths (sans parameters) to vulnerable types and/or functions
 /// affected by a particular advisory.
 /// <https://doc.rust-lang.org/reference/paths.html#canonical-paths>
@@ -97,11 +139,10 @@ impl FromStr for FunctionPath {
         if segments.len() >= 2 {
             Ok(FunctionPath(segments))
         } else {
-            fail!(
-                ErrorKind::Parse,
-                "paths must start with the crate name (i.e. minimum two segments): '{}'",
-                path
-            )
+            Err(invalid_path(
+                "paths must start with the crate name (i.e. minimum two segments)",
+                path,
+            ))
         }
     }
 }
@@ -158,35 +199,45 @@ fn validate_identifier(identifier: &str) -> Result<(), Error> {
     if let Some(first_char) = chars.next() {
         match first_char {
             'A'..='Z' | 'a'..='z' | '_' | '<' => (),
-            _ => fail!(
-                ErrorKind::Parse,
-                "invalid character at start of ident: '{}'",
-                identifier
-            ),
+            _ => {
+                return Err(invalid_path(
+                    "invalid character at start of ident",
+                    identifier,
+                ))
+            }
         }
     } else {
-        fail!(ErrorKind::Parse, "empty identifier in affected path");
+        return Err(Error::new(
+            ErrorKind::Parse,
+            &"empty identifier in affected path",
+        ));
     }
 
     for c in chars {
         match c {
             'A'..='Z' | 'a'..='z' | '0'..='9' | '_' | '<' | '>' | ',' => (),
-            '(' | ')' => fail!(
-                ErrorKind::Parse,
-                "omit parameters when specifying affected paths: '{}'",
-                identifier
-            ),
-            _ => fail!(
-                ErrorKind::Parse,
-                "invalid character in identifier: '{}'",
-                identifier
-            ),
+            '(' | ')' => {
+                return Err(invalid_path(
+                    "omit parameters when specifying affected paths",
+                    identifier,
+                ))
+            }
+            _ => return Err(invalid_path("invalid character in identifier", identifier)),
         }
     }
 
     Ok(())
 }
 
+/// Error for an invalid path of an affected function
+fn invalid_path(reason: &'static str, path: &str) -> Error {
+    AdvisoryError::InvalidAffectedPath {
+        reason,
+        path: path.to_owned(),
+    }
+    .into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::FunctionPath;
@@ -1,6 +1,6 @@
 //! Advisory dates
 
-use crate::error::{Error, ErrorKind};
+use crate::error::{AdvisoryError, Error};
 
 use serde::{de, Deserialize, Serialize};
 use std::str::FromStr;
@@ -79,15 +79,20 @@ macro_rules! check_date_part {
     ($name:expr, $string:expr, $parts:expr, $len:expr, $min:expr, $max:expr) => {
         let part = $parts
             .next()
-            .ok_or_else(|| format_err!(ErrorKind::Parse, "invalid date: {}", $string))?;
+            .ok_or_else(|| AdvisoryError::InvalidDate($string.to_owned()))?;
+
+        let malformed = || AdvisoryError::MalformedDate {
+            component: $name,
+            date: $string.to_owned(),
+        };
 
         if part.len() != $len {
-            fail!(ErrorKind::Parse, "malformed {}: {}", $name, $string);
+            return Err(malformed().into());
         }
 
         match part.parse::<u32>() {
             Ok($min..=$max) => (),
-            _ => fail!(ErrorKind::Parse, "malformed {}: {}", $name, $string),
+            _ => return Err(malformed().into()),
         }
     };
 }
@@ -102,7 +107,7 @@ fn validate_date(string: &str) -> Result<(), Error> {
     check_date_part!("day", string, parts, 2, 1, 31);
 
     if parts.next().is_some() {
-        fail!(ErrorKind::Parse, "invalid date: {}", string)
+        return Err(AdvisoryError::InvalidDate(string.to_owned()).into());
     }
 
     Ok(())
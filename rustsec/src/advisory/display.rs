@@ -0,0 +1,153 @@
+//! Rendering advisories as human-readable text, laid out the same way
+//! `cargo audit` displays its findings.
+//!
+//! The labels are always in English: `cargo audit` renders its own output
+//! (so it can be customized and translated) and only shares the layout.
+
+use super::Advisory;
+use std::{cmp, fmt::Write, mem};
+
+/// Minimum width of attribute labels (e.g. `Crate:`), used for alignment
+const LABEL_WIDTH: usize = 14;
+
+/// Minimum width to wrap attribute values at: narrower outputs aren't
+/// worth wrapping for
+const MIN_WRAP_WIDTH: usize = 20;
+
+/// Render an advisory as a block of `Label: value` lines followed by its
+/// description, wrapped to fit within `width` columns
+pub(crate) fn report(advisory: &Advisory, width: usize) -> String {
+    let metadata = &advisory.metadata;
+    let mut out = String::new();
+
+    attr(&mut out, "Title:", metadata.title.trim(), width);
+    attr(&mut out, "ID:", metadata.id.as_str(), width);
+    attr(&mut out, "Crate:", metadata.package.as_str(), width);
+    attr(&mut out, "Date:", metadata.date.as_str(), width);
+
+    if let Some(url) = metadata.id.url() {
+        attr(&mut out, "URL:", &url, width);
+    } else if let Some(url) = &metadata.url {
+        attr(&mut out, "URL:", url.as_str(), width);
+    }
+
+    #[cfg(feature = "cvss")]
+    {
+        if let Some(cvss) = &metadata.cvss {
+            let score = cvss.score();
+            let severity = format!("{:.1} ({})", score.value(), score.severity());
+            attr(&mut out, "Severity:", &severity, width);
+        }
+    }
+
+    let patched = advisory.versions.patched();
+    let solution = if patched.is_empty() {
+        "No safe upgrade is available!".to_owned()
+    } else {
+        let versions = patched.iter().map(ToString::to_string).collect::<Vec<_>>();
+        format!("Upgrade to {}", versions.join(" OR "))
+    };
+    attr(&mut out, "Solution:", &solution, width);
+
+    for paragraph in metadata.description.trim().split("\n\n") {
+        out.push('\n');
+
+        // Code blocks and lists are laid out by hand, so leave them as-is
+        let preformatted = paragraph
+            .lines()
+            .any(|line| line.starts_with("```") || line.starts_with("    "))
+            || paragraph.trim_start().starts_with("- ")
+            || paragraph.trim_start().starts_with("* ");
+
+        if preformatted {
+            out.push_str(paragraph);
+            out.push('\n');
+        } else {
+            for line in wrap(paragraph, width) {
+                writeln!(out, "{}", line).unwrap();
+            }
+        }
+    }
+
+    out
+}
+
+/// Append a `Label: value` line to `out`
+fn attr(out: &mut String, label: &str, value: &str, width: usize) {
+    let label = pad_label(label);
+    let value = wrap_value(label.chars().count(), value, Some(width));
+    writeln!(out, "{} {}", label, value).unwrap();
+}
+
+/// Pad an attribute label so the values following it line up, leaving room
+/// for labels longer than [`LABEL_WIDTH`] (e.g. translations)
+fn pad_label(label: &str) -> String {
+    let width = cmp::max(LABEL_WIDTH, label.chars().count() + 1);
+    format!("{:width$}", label, width = width)
+}
+
+/// Wrap an attribute value which follows a label of the given width so each
+/// line fits within `width` columns (if given), indenting continuation lines
+/// to line up with the first
+fn wrap_value(label_width: usize, value: &str, width: Option<usize>) -> String {
+    match width {
+        Some(width)
+            if width >= label_width + 1 + MIN_WRAP_WIDTH
+                && value.chars().count() > width - label_width - 1 =>
+        {
+            let indent = format!("\n{:width$}", "", width = label_width + 1);
+            wrap(value, width - label_width - 1).join(&indent)
+        }
+        _ => value.to_owned(),
+    }
+}
+
+/// Wrap text at word boundaries so lines are at most `width` characters,
+/// without breaking words which are longer than `width` (e.g. URLs)
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(mem::take(&mut line));
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+
+        line.push_str(word);
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wrap, wrap_value};
+
+    #[test]
+    fn wrap_at_word_boundaries() {
+        assert_eq!(
+            wrap("Use-after-free in  the\tfoo crate", 12),
+            vec!["Use-after-free", "in the foo", "crate"]
+        );
+        assert_eq!(wrap("", 12), vec![""]);
+    }
+
+    #[test]
+    fn wrap_values_after_label() {
+        let value = "Use-after-free in the foo crate when parsing untrusted input";
+        assert_eq!(
+            wrap_value(14, value, Some(40)),
+            "Use-after-free in the foo\n               crate when parsing\n               untrusted input"
+        );
+        assert_eq!(wrap_value(14, value, Some(30)), value);
+        assert_eq!(wrap_value(14, value, None), value);
+    }
+}
@@ -1,9 +1,10 @@
 //! Advisory identifiers
 
 use super::date::{YEAR_MAX, YEAR_MIN};
-use crate::error::{Error, ErrorKind};
+use crate::error::{AdvisoryError, Error};
 use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
+    cmp::Ordering,
     fmt::{self, Display},
     str::FromStr,
 };
@@ -11,8 +12,11 @@ use std::{
 /// Placeholder advisory name: shouldn't be used until an ID is assigned
 pub const PLACEHOLDER: &str = "RUSTSEC-0000-0000";
 
-/// An identifier for an individual advisory
-#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+/// An identifier for an individual advisory.
+///
+/// IDs are ordered by kind, then by year and number (numerically), so e.g.
+/// `CVE-2017-999` sorts before `CVE-2017-1000168`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Id {
     /// An autodetected identifier kind
     kind: Kind,
@@ -25,6 +29,22 @@ pub struct Id {
 }
 
 impl Id {
+    /// Get the next `RUSTSEC` advisory ID to assign for the given year,
+    /// i.e. the one following the highest existing `RUSTSEC` ID for that year
+    pub fn next_id<'a, I>(year: u32, existing: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = &'a Id>,
+    {
+        let highest = existing
+            .into_iter()
+            .filter(|id| id.is_rustsec() && id.year() == Some(year))
+            .filter_map(Id::numerical_part)
+            .max()
+            .unwrap_or_default();
+
+        format!("RUSTSEC-{}-{:04}", year, highest + 1).parse()
+    }
+
     /// Get a string reference to this advisory ID
     pub fn as_str(&self) -> &str {
         self.string.as_ref()
@@ -140,7 +160,12 @@ impl FromStr for Id {
         // Ensure known advisory types are well-formed
         let year = match kind {
             Kind::RUSTSEC | Kind::CVE | Kind::TALOS => Some(parse_year(advisory_id)?),
-            _ => None,
+            Kind::GHSA => {
+                validate_ghsa(advisory_id)?;
+                None
+            }
+            // Other schemes following the `PREFIX-YYYY-NNNN` convention
+            Kind::Other => parse_year(advisory_id).ok(),
         };
 
         Ok(Self {
@@ -151,6 +176,22 @@ impl FromStr for Id {
     }
 }
 
+impl Ord for Id {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.kind
+            .cmp(&other.kind)
+            .then_with(|| self.year.cmp(&other.year))
+            .then_with(|| self.numerical_part().cmp(&other.numerical_part()))
+            .then_with(|| self.string.cmp(&other.string))
+    }
+}
+
+impl PartialOrd for Id {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Into<String> for Id {
     fn into(self) -> String {
         self.string
@@ -212,37 +253,48 @@ fn parse_year(advisory_id: &str) -> Result<u32, Error> {
     let mut parts = advisory_id.split('-');
     parts.next().unwrap();
 
-    let year = match parts.next().unwrap().parse::<u32>() {
+    let year = match parts.next().unwrap_or_default().parse::<u32>() {
         Ok(n) => match n {
             YEAR_MIN..=YEAR_MAX => n,
-            _ => fail!(
-                ErrorKind::Parse,
-                "out-of-range year in advisory ID: {}",
-                advisory_id
-            ),
+            _ => return Err(AdvisoryError::IdYearOutOfRange(advisory_id.to_owned()).into()),
         },
-        _ => fail!(
-            ErrorKind::Parse,
-            "malformed year in advisory ID: {}",
-            advisory_id
-        ),
+        _ => return Err(AdvisoryError::MalformedIdYear(advisory_id.to_owned()).into()),
     };
 
     if let Some(num) = parts.next() {
         if num.parse::<u32>().is_err() {
-            fail!(ErrorKind::Parse, "malformed advisory ID: {}", advisory_id);
+            return Err(AdvisoryError::MalformedId(advisory_id.to_owned()).into());
         }
     } else {
-        fail!(ErrorKind::Parse, "incomplete advisory ID: {}", advisory_id);
+        return Err(AdvisoryError::IncompleteId(advisory_id.to_owned()).into());
     }
 
     if parts.next().is_some() {
-        fail!(ErrorKind::Parse, "malformed advisory ID: {}", advisory_id);
+        return Err(AdvisoryError::MalformedId(advisory_id.to_owned()).into());
     }
 
     Ok(year)
 }
 
+/// Ensure a GHSA identifier is of the form `GHSA-xxxx-xxxx-xxxx`
+fn validate_ghsa(advisory_id: &str) -> Result<(), Error> {
+    let parts = advisory_id.split('-').skip(1).collect::<Vec<_>>();
+
+    let valid = parts.len() == 3
+        && parts.iter().all(|part| {
+            part.len() == 4
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c.is_ascii_lowercase())
+        });
+
+    if !valid {
+        return Err(AdvisoryError::MalformedGhsaId(advisory_id.to_owned()).into());
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Id, Kind, PLACEHOLDER};
@@ -308,6 +360,53 @@ mod tests {
         assert_eq!(talos_id.numerical_part().unwrap(), 0468);
     }
 
+    #[test]
+    fn malformed_ghsa_id_test() {
+        assert!("GHSA-4mmc-49vf".parse::<Id>().is_err());
+        assert!("GHSA-4MMC-49VF-JMCP".parse::<Id>().is_err());
+    }
+
+    #[test]
+    fn other_scheme_id_test() {
+        let other_id = "OSV-2021-123".parse::<Id>().unwrap();
+        assert!(other_id.is_other());
+        assert_eq!(other_id.year(), Some(2021));
+        assert_eq!(other_id.numerical_part(), Some(123));
+    }
+
+    #[test]
+    fn ordering_test() {
+        let mut ids = ["CVE-2017-1000168", "CVE-2018-0001", "CVE-2017-999"]
+            .iter()
+            .map(|id| id.parse::<Id>().unwrap())
+            .collect::<Vec<_>>();
+        ids.sort();
+
+        let ids = ids.iter().map(Id::as_str).collect::<Vec<_>>();
+        assert_eq!(ids, ["CVE-2017-999", "CVE-2017-1000168", "CVE-2018-0001"]);
+    }
+
+    #[test]
+    fn next_id_test() {
+        let ids = [
+            "RUSTSEC-2021-0009",
+            "RUSTSEC-2021-0010",
+            "RUSTSEC-2020-0042",
+        ]
+        .iter()
+        .map(|id| id.parse::<Id>().unwrap())
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            Id::next_id(2021, &ids).unwrap().as_str(),
+            "RUSTSEC-2021-0011"
+        );
+        assert_eq!(
+            Id::next_id(2022, &ids).unwrap().as_str(),
+            "RUSTSEC-2022-0001"
+        );
+    }
+
     #[test]
     fn other_id_test() {
         let other_id = EXAMPLE_UNKNOWN_ID.parse::<Id>().unwrap();
@@ -211,7 +211,7 @@ impl Linter {
                         }
                     }
                     "aliases" | "cvss" | "keywords" | "package" | "references" | "related"
-                    | "title" | "description" => (),
+                    | "title" | "description" | "discovered" | "reported" | "patched" => (),
                     _ => self.errors.push(Error {
                         kind: ErrorKind::key(key),
                         section: Some("advisory"),
@@ -226,6 +226,44 @@ impl Linter {
                 message: Some("expected table"),
             });
         }
+
+        self.lint_timeline();
+    }
+
+    /// Ensure the incident timeline dates are in order: a vulnerability must
+    /// be discovered before it's reported, and reported before it's patched
+    /// or the advisory is issued (patches may be released either before or
+    /// after the advisory)
+    fn lint_timeline(&mut self) {
+        let metadata = &self.advisory.metadata;
+        let timeline = [
+            ("discovered", metadata.discovered.as_ref()),
+            ("reported", metadata.reported.as_ref()),
+            ("patched", metadata.patched.as_ref()),
+            ("date", Some(&metadata.date)),
+        ];
+
+        for (i, &(name, date)) in timeline.iter().enumerate() {
+            for &(later_name, later_date) in &timeline[(i + 1)..] {
+                if name == "patched" && later_name == "date" {
+                    continue;
+                }
+
+                if let (Some(date), Some(later_date)) = (date, later_date) {
+                    if date > later_date {
+                        self.errors.push(Error {
+                            kind: ErrorKind::value(name, date.as_str()),
+                            section: Some("advisory"),
+                            message: Some(
+                                "dates must be ordered: discovered <= reported <= patched, \
+                                 and reported <= date",
+                            ),
+                        });
+                        return;
+                    }
+                }
+            }
+        }
     }
 
     /// Lint the `[versions]` section of an advisory
@@ -233,7 +271,7 @@ impl Linter {
         if let Some(table) = versions.as_table() {
             for (key, _) in table {
                 match key.as_str() {
-                    "patched" | "unaffected" => (),
+                    "patched" | "unaffected" | "git" => (),
                     _ => self.errors.push(Error {
                         kind: ErrorKind::key(key),
                         section: Some("versions"),
@@ -362,6 +400,24 @@ impl ErrorKind {
             value: value.into(),
         }
     }
+
+    /// Identifier of the lint rule which detected this kind of error
+    /// (e.g. for machine-readable output)
+    pub fn rule_id(&self) -> &'static str {
+        match self {
+            ErrorKind::Malformed => "malformed",
+            ErrorKind::InvalidKey { .. } => "invalid-key",
+            ErrorKind::InvalidValue { .. } => "invalid-value",
+        }
+    }
+
+    /// Name of the key the error is about, if any
+    pub fn key_name(&self) -> Option<&str> {
+        match self {
+            ErrorKind::Malformed => None,
+            ErrorKind::InvalidKey { name } | ErrorKind::InvalidValue { name, .. } => Some(name),
+        }
+    }
 }
 
 impl fmt::Display for ErrorKind {
@@ -27,6 +27,19 @@ pub struct Metadata {
     /// Date this advisory was officially issued
     pub date: Date,
 
+    /// Date the vulnerability was discovered (if known)
+    #[serde(default)]
+    pub discovered: Option<Date>,
+
+    /// Date the vulnerability was reported to the crate's maintainers
+    /// (if known)
+    #[serde(default)]
+    pub reported: Option<Date>,
+
+    /// Date the first patched version was released (if any)
+    #[serde(default)]
+    pub patched: Option<Date>,
+
     /// Advisory IDs in other databases which point to the same advisory
     #[serde(default)]
     pub aliases: Vec<Id>,
@@ -57,8 +70,14 @@ pub struct Metadata {
     /// ```text
     /// CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N
     /// ```
+    #[cfg(feature = "cvss")]
     pub cvss: Option<cvss::v3::Base>,
 
+    /// CVSS vector string, kept as-is without the `cvss` feature so that
+    /// re-serializing the advisory doesn't lose it
+    #[cfg(not(feature = "cvss"))]
+    cvss: Option<String>,
+
     /// Informational advisories can be used to warn users about issues
     /// affecting a particular crate without failing the build.
     pub informational: Option<Informational>,
@@ -1,7 +1,7 @@
 //! Support for parsing advisories from a Markdown file
 //! (a.k.a. "V3 advisory format")
 
-use crate::error::{Error, ErrorKind};
+use crate::error::{AdvisoryError, Error};
 
 /// Parts of a parsed advisory
 #[derive(Copy, Clone, Debug)]
@@ -23,42 +23,29 @@ impl<'a> Parts<'a> {
     /// Parse a Markdown advisory into its component parts
     pub fn parse(advisory_data: &'a str) -> Result<Self, Error> {
         if !advisory_data.starts_with("```toml") {
-            let context = if advisory_data.len() > 20 {
-                &advisory_data[..20]
-            } else {
-                advisory_data
+            // Truncate on a character boundary to avoid panicking on multibyte input
+            let context = match advisory_data.char_indices().nth(20) {
+                Some((end, _)) => &advisory_data[..end],
+                None => advisory_data,
             };
 
-            fail!(
-                ErrorKind::Parse,
-                "unexpected start of advisory: \"{}\"",
-                context
-            )
+            return Err(AdvisoryError::UnexpectedStart(context.to_owned()).into());
         }
 
-        let toml_end = advisory_data.find("\n```").ok_or_else(|| {
-            format_err!(
-                ErrorKind::Parse,
-                "couldn't find end of TOML front matter in advisory"
-            )
-        })?;
+        let toml_end = advisory_data
+            .find("\n```")
+            .ok_or(AdvisoryError::MissingFrontMatterEnd)?;
 
         let front_matter = advisory_data[7..toml_end].trim_start().trim_end();
         let markdown = advisory_data[(toml_end + 4)..].trim_start();
 
         if !markdown.starts_with("# ") {
-            fail!(
-                ErrorKind::Parse,
-                "Expected # header after TOML front matter"
-            );
+            return Err(AdvisoryError::MissingTitle.into());
         }
 
-        let next_newline = markdown.find('\n').ok_or_else(|| {
-            format_err!(
-                ErrorKind::Parse,
-                "no Markdown body (i.e. description) found"
-            )
-        })?;
+        let next_newline = markdown
+            .find('\n')
+            .ok_or(AdvisoryError::MissingDescription)?;
 
         let title = markdown[2..next_newline].trim_end();
         let description = markdown[(next_newline + 1)..].trim_start().trim_end();
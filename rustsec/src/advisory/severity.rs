@@ -0,0 +1,131 @@
+//! Severity of advisories, including severities derived from the type of
+//! advisory when no CVSS information is available.
+
+use super::{Informational, Metadata, Severity};
+use crate::{
+    error::{Error, ErrorKind},
+    Map,
+};
+use serde::{de, ser, Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Fallback severities for advisories without CVSS information, keyed by
+/// advisory type
+pub type SeverityFallback = Map<AdvisoryType, Severity>;
+
+/// Type of an advisory, for the purposes of a [`SeverityFallback`]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum AdvisoryType {
+    /// Advisory for a vulnerability, i.e. one which isn't informational
+    Vulnerability,
+
+    /// Informational `notice` advisory
+    Notice,
+
+    /// Informational advisory for an `unmaintained` crate
+    Unmaintained,
+
+    /// Informational advisory for an `unsound` crate
+    Unsound,
+}
+
+impl AdvisoryType {
+    /// Get the type of the given advisory, or `None` if it's an
+    /// informational advisory of an unknown kind
+    pub fn of(metadata: &Metadata) -> Option<Self> {
+        match &metadata.informational {
+            None => Some(AdvisoryType::Vulnerability),
+            Some(Informational::Notice) => Some(AdvisoryType::Notice),
+            Some(Informational::Unmaintained) => Some(AdvisoryType::Unmaintained),
+            Some(Informational::Unsound) => Some(AdvisoryType::Unsound),
+            Some(_) => None,
+        }
+    }
+
+    /// Get a `str` representing this advisory type
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdvisoryType::Vulnerability => "vulnerability",
+            AdvisoryType::Notice => "notice",
+            AdvisoryType::Unmaintained => "unmaintained",
+            AdvisoryType::Unsound => "unsound",
+        }
+    }
+}
+
+impl fmt::Display for AdvisoryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AdvisoryType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s {
+            "vulnerability" => AdvisoryType::Vulnerability,
+            "notice" => AdvisoryType::Notice,
+            "unmaintained" => AdvisoryType::Unmaintained,
+            "unsound" => AdvisoryType::Unsound,
+            other => fail!(ErrorKind::Parse, "invalid advisory type: {}", other),
+        })
+    }
+}
+
+// Advisory types are used as map keys, which TOML only supports as strings
+impl<'de> Deserialize<'de> for AdvisoryType {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use de::Error;
+        let string = String::deserialize(deserializer)?;
+        string.parse().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for AdvisoryType {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_str().serialize(serializer)
+    }
+}
+
+/// Where the severity of an advisory came from
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SeveritySource {
+    /// Computed from the CVSS vector declared in the advisory
+    Declared,
+
+    /// Derived from the advisory type using a [`SeverityFallback`]
+    Derived,
+}
+
+/// Severity of an advisory along with where it came from
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SeverityInfo {
+    /// Qualitative severity
+    pub severity: Severity,
+
+    /// Whether the severity was declared or derived
+    pub source: SeveritySource,
+}
+
+impl SeverityInfo {
+    /// Determine the severity of an advisory, falling back to the severity
+    /// for its type if it has no CVSS information
+    pub fn new(metadata: &Metadata, fallback: &SeverityFallback) -> Option<Self> {
+        if let Some(cvss) = &metadata.cvss {
+            return Some(Self {
+                severity: cvss.severity(),
+                source: SeveritySource::Declared,
+            });
+        }
+
+        AdvisoryType::of(metadata)
+            .and_then(|advisory_type| fallback.get(&advisory_type))
+            .map(|&severity| Self {
+                severity,
+                source: SeveritySource::Derived,
+            })
+    }
+}
@@ -8,8 +8,9 @@ use std::convert::{TryFrom, TryInto};
 
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::{osv, Error};
+use crate::{error::AdvisoryError, osv, Error};
 
 /// The `[versions]` subsection of an advisory: future home to information
 /// about which versions are patched and/or unaffected.
@@ -22,17 +23,51 @@ pub struct Versions {
     /// Versions which were never affected in the first place
     #[serde(default)]
     unaffected: Vec<VersionReq>,
+
+    /// Ranges of affected commits in git repositories, for vulnerabilities
+    /// which are (so far) only fixed on a git branch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    git: Vec<GitRange>,
 }
 
 impl Versions {
     /// Is the given version of a package vulnerable?
+    ///
+    /// Pre-releases are matched according to [`PrereleaseMatching::Strict`].
     pub fn is_vulnerable(&self, version: &Version) -> bool {
-        for range in osv::ranges_for_advisory(self).iter() {
-            if range.affects(version) {
-                return true;
-            }
-        }
-        false
+        self.is_vulnerable_with(version, PrereleaseMatching::Strict)
+    }
+
+    /// Is the given version of a package vulnerable, matching pre-releases
+    /// according to the given rules?
+    pub fn is_vulnerable_with(&self, version: &Version, prerelease: PrereleaseMatching) -> bool {
+        let ranges = osv::ranges_for_advisory(self);
+        is_affected(&ranges, version, prerelease)
+    }
+
+    /// Select the versions affected by the advisory from a list of versions
+    /// (e.g. all published releases of a crate, from the registry index),
+    /// keeping their order.
+    ///
+    /// Pre-releases are matched according to [`PrereleaseMatching::Strict`].
+    pub fn affected_subset(&self, versions: &[Version]) -> Vec<Version> {
+        self.affected_subset_with(versions, PrereleaseMatching::Strict)
+    }
+
+    /// Select the versions affected by the advisory from a list of versions,
+    /// matching pre-releases according to the given rules
+    pub fn affected_subset_with(
+        &self,
+        versions: &[Version],
+        prerelease: PrereleaseMatching,
+    ) -> Vec<Version> {
+        let ranges = osv::ranges_for_advisory(self);
+
+        versions
+            .iter()
+            .filter(|version| is_affected(&ranges, version, prerelease))
+            .cloned()
+            .collect()
     }
 
     /// Creates a new `[versions]` entry.
@@ -41,6 +76,7 @@ impl Versions {
         RawVersions {
             patched,
             unaffected,
+            git: vec![],
         }
         .try_into()
     }
@@ -54,6 +90,115 @@ impl Versions {
     pub fn unaffected(&self) -> &[VersionReq] {
         self.unaffected.as_slice()
     }
+
+    /// Ranges of affected commits in git repositories.
+    ///
+    /// These are informational (e.g. for OSV export): they aren't taken into
+    /// account when determining whether a version is vulnerable.
+    pub fn git(&self) -> &[GitRange] {
+        self.git.as_slice()
+    }
+
+    /// Set the ranges of affected commits in git repositories
+    pub fn with_git(mut self, git: Vec<GitRange>) -> Result<Self, Error> {
+        for range in &git {
+            range.validate()?;
+        }
+
+        self.git = git;
+        Ok(self)
+    }
+}
+
+/// Range of affected commits in a git repository, e.g. for a fix which is
+/// only available on a branch and hasn't been released yet
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GitRange {
+    /// URL of the git repository
+    pub repo: Url,
+
+    /// Full hash of the commit which introduced the vulnerability (if
+    /// omitted, all commits before the fix are affected)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introduced: Option<String>,
+
+    /// Full hash of the commit which fixed the vulnerability (if omitted,
+    /// it's unfixed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+}
+
+impl GitRange {
+    /// Check that the range has at least one bound, and that its bounds are
+    /// full commit hashes
+    fn validate(&self) -> Result<(), Error> {
+        if self.introduced.is_none() && self.fixed.is_none() {
+            return Err(AdvisoryError::UnboundedGitRange(self.repo.to_string()).into());
+        }
+
+        for commit in self.introduced.iter().chain(self.fixed.iter()) {
+            if commit.len() != 40 || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(AdvisoryError::InvalidGitCommit {
+                    repo: self.repo.to_string(),
+                    commit: commit.clone(),
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Rules for matching pre-release versions (e.g. `1.0.0-alpha.2`) against
+/// the `[versions]` of an advisory.
+///
+/// Semver requirements (e.g. `>= 1.0.0`) never match pre-releases unless
+/// they name a pre-release of the same version, which would make every
+/// pre-release look vulnerable (or unaffected, depending on how the
+/// requirements are checked). Instead, versions are always compared using
+/// [SemVer 2.0 precedence](https://semver.org/#spec-item-11), under which
+/// `1.0.0-alpha.2` sorts after `0.9.9` and before `1.0.0`. These rules only
+/// differ in how pre-releases of a patched release are treated.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrereleaseMatching {
+    /// Pre-releases are matched by precedence alone: with `patched = [">= 1.0.0"]`,
+    /// `1.0.0-alpha.2` is vulnerable since it precedes the fix (default)
+    Strict,
+
+    /// Pre-releases are only vulnerable if the release they precede is too:
+    /// with `patched = [">= 1.0.0"]`, `1.0.0-alpha.2` is assumed to contain
+    /// the fix. Findings are a subset of those under [`Self::Strict`].
+    Permissive,
+}
+
+impl Default for PrereleaseMatching {
+    fn default() -> Self {
+        PrereleaseMatching::Strict
+    }
+}
+
+/// Is the given version within any of the given affected ranges?
+fn is_affected(
+    ranges: &[osv::OsvRange],
+    version: &Version,
+    prerelease: PrereleaseMatching,
+) -> bool {
+    let affects = |v: &Version| ranges.iter().any(|range| range.affects(v));
+
+    match prerelease {
+        PrereleaseMatching::Strict => affects(version),
+        PrereleaseMatching::Permissive => {
+            affects(version) && (version.pre.is_empty() || affects(&release_of(version)))
+        }
+    }
+}
+
+/// Get the release a pre-release version precedes (e.g. `1.0.0` for
+/// `1.0.0-alpha.2`)
+fn release_of(version: &Version) -> Version {
+    Version::new(version.major, version.minor, version.patch)
 }
 
 impl TryFrom<RawVersions> for Versions {
@@ -64,7 +209,9 @@ impl TryFrom<RawVersions> for Versions {
         Ok(Versions {
             patched: raw.patched,
             unaffected: raw.unaffected,
-        })
+            git: vec![],
+        }
+        .with_git(raw.git)?)
     }
 }
 
@@ -75,9 +222,114 @@ pub(crate) struct RawVersions {
 
     #[serde(default)]
     pub unaffected: Vec<VersionReq>,
+
+    #[serde(default)]
+    pub git: Vec<GitRange>,
 }
 
 fn validate_ranges(versions: &RawVersions) -> Result<(), Error> {
     let _ = osv::ranges_for_unvalidated_advisory(versions)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{GitRange, PrereleaseMatching, Versions};
+    use semver::{Version, VersionReq};
+
+    fn versions(patched: &[&str], unaffected: &[&str]) -> Versions {
+        let parse = |reqs: &[&str]| reqs.iter().map(|r| VersionReq::parse(r).unwrap()).collect();
+        Versions::new(parse(patched), parse(unaffected)).unwrap()
+    }
+
+    fn is_vulnerable(versions: &Versions, version: &str, prerelease: PrereleaseMatching) -> bool {
+        versions.is_vulnerable_with(&Version::parse(version).unwrap(), prerelease)
+    }
+
+    #[test]
+    fn git_ranges() {
+        let range = |fixed: &str| GitRange {
+            repo: "https://github.com/example/example".parse().unwrap(),
+            introduced: None,
+            fixed: Some(fixed.to_owned()),
+        };
+
+        let fixed = "0123456789abcdef0123456789abcdef01234567";
+        let versions = versions(&[], &[]).with_git(vec![range(fixed)]).unwrap();
+        assert_eq!(versions.git()[0].fixed.as_deref(), Some(fixed));
+
+        assert!(versions.clone().with_git(vec![range("0123abc")]).is_err());
+    }
+
+    #[test]
+    fn strict_prereleases() {
+        let versions = versions(&[">= 1.0.0"], &["< 0.5.0"]);
+        let strict = PrereleaseMatching::Strict;
+
+        assert!(is_vulnerable(&versions, "1.0.0-alpha.2", strict));
+        assert!(is_vulnerable(&versions, "0.6.0-rc.1", strict));
+        assert!(!is_vulnerable(&versions, "0.5.0-rc.1", strict));
+        assert!(!is_vulnerable(&versions, "1.1.0-alpha.1", strict));
+        assert!(!is_vulnerable(&versions, "1.0.0", strict));
+    }
+
+    #[test]
+    fn permissive_prereleases() {
+        let versions = versions(&[">= 1.0.0"], &["< 0.5.0"]);
+        let permissive = PrereleaseMatching::Permissive;
+
+        assert!(!is_vulnerable(&versions, "1.0.0-alpha.2", permissive));
+        assert!(is_vulnerable(&versions, "0.6.0-rc.1", permissive));
+        assert!(is_vulnerable(&versions, "0.9.0", permissive));
+        assert!(!is_vulnerable(&versions, "0.5.0-rc.1", permissive));
+    }
+
+    #[test]
+    fn affected_subset() {
+        let versions = versions(&[">= 1.2.0"], &["< 1.0.0"]);
+        let published = [
+            "0.9.0",
+            "1.0.0",
+            "1.1.0-rc.1",
+            "1.1.0",
+            "1.2.0-alpha.1",
+            "1.2.0",
+        ]
+        .iter()
+        .map(|v| Version::parse(v).unwrap())
+        .collect::<Vec<_>>();
+
+        let affected = |prerelease| {
+            versions
+                .affected_subset_with(&published, prerelease)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            affected(PrereleaseMatching::Strict),
+            ["1.0.0", "1.1.0-rc.1", "1.1.0", "1.2.0-alpha.1"]
+        );
+        assert_eq!(
+            affected(PrereleaseMatching::Permissive),
+            ["1.0.0", "1.1.0-rc.1", "1.1.0"]
+        );
+    }
+
+    #[test]
+    fn prerelease_fix() {
+        // Fixes released as a pre-release apply to later pre-releases either way
+        let versions = versions(&[">= 1.0.0-beta.1"], &[]);
+
+        for prerelease in &[PrereleaseMatching::Strict, PrereleaseMatching::Permissive] {
+            assert!(!is_vulnerable(&versions, "1.0.0-beta.2", *prerelease));
+        }
+
+        assert!(is_vulnerable(
+            &versions,
+            "1.0.0-alpha.1",
+            PrereleaseMatching::Strict
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+//! Support for writing advisories back out as Markdown files in the
+//! canonical "V3 advisory format"
+
+use super::Advisory;
+use crate::error::{Error, ErrorKind};
+use std::fmt::Write;
+use toml::value::{Table, Value};
+
+/// Keys of the `[advisory]` table in the order they're written
+const ADVISORY_KEYS: &[&str] = &[
+    "id",
+    "package",
+    "date",
+    "discovered",
+    "reported",
+    "patched",
+    "url",
+    "references",
+    "categories",
+    "keywords",
+    "aliases",
+    "related",
+    "cvss",
+    "informational",
+    "yanked",
+    "withdrawn",
+];
+
+/// Keys of the `[versions]` table in the order they're written
+const VERSIONS_KEYS: &[&str] = &["patched", "unaffected", "git"];
+
+/// Keys of the `[affected]` table in the order they're written
+const AFFECTED_KEYS: &[&str] = &["arch", "os", "env", "functions"];
+
+/// Keys which are written even when their value is empty
+const REQUIRED_KEYS: &[&str] = &["patched"];
+
+/// Keys which are never written to the TOML front matter: the title and
+/// description live in the Markdown, and the collection is inferred from
+/// the advisory's location
+const OMITTED_KEYS: &[&str] = &["title", "description", "collection"];
+
+/// Serialize an advisory as Markdown with TOML front matter
+pub(crate) fn write(advisory: &Advisory) -> Result<String, Error> {
+    let mut metadata = to_table(&advisory.metadata)?;
+
+    // `yanked` isn't retained when parsing, but must accompany `withdrawn`
+    if metadata.contains_key("withdrawn") {
+        metadata.insert("yanked".to_owned(), Value::Boolean(true));
+    }
+
+    let mut out = String::from("```toml\n");
+    write_table(&mut out, "advisory", &metadata, ADVISORY_KEYS)?;
+    write_table(
+        &mut out,
+        "versions",
+        &to_table(&advisory.versions)?,
+        VERSIONS_KEYS,
+    )?;
+
+    if let Some(affected) = &advisory.affected {
+        let affected = to_table(affected)?;
+
+        if affected.values().any(|value| !is_empty(value)) {
+            write_table(&mut out, "affected", &affected, AFFECTED_KEYS)?;
+        }
+    }
+
+    write!(
+        out,
+        "```\n\n# {}\n\n{}\n",
+        advisory.metadata.title.trim(),
+        advisory.metadata.description.trim()
+    )?;
+
+    Ok(out)
+}
+
+/// Convert a section of an advisory into a TOML table
+fn to_table<T: serde::Serialize>(section: &T) -> Result<Table, Error> {
+    match Value::try_from(section) {
+        Ok(Value::Table(table)) => Ok(table),
+        Ok(other) => fail!(
+            ErrorKind::Parse,
+            "expected advisory section to serialize as a table, got: {}",
+            other.type_str()
+        ),
+        Err(e) => fail!(ErrorKind::Parse, "couldn't serialize advisory: {}", e),
+    }
+}
+
+/// Write a TOML table, emitting the keys in `order` first and then any
+/// other keys alphabetically (so unknown keys are never silently dropped)
+fn write_table(out: &mut String, name: &str, table: &Table, order: &[&str]) -> Result<(), Error> {
+    if !out.ends_with("```toml\n") {
+        out.push('\n');
+    }
+
+    writeln!(out, "[{}]", name)?;
+
+    let remaining = table
+        .keys()
+        .map(String::as_str)
+        .filter(|key| !order.contains(key));
+
+    for key in order.iter().copied().chain(remaining) {
+        if OMITTED_KEYS.contains(&key) {
+            continue;
+        }
+
+        if let Some(value) = table.get(key) {
+            if is_empty(value) && !REQUIRED_KEYS.contains(&key) {
+                continue;
+            }
+
+            writeln!(out, "{} = {}", format_key(key), format_value(value))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Format a TOML value, rendering tables inline
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Table(table) => {
+            if table.is_empty() {
+                return "{}".to_owned();
+            }
+
+            let entries = table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", format_key(key), format_value(value)))
+                .collect::<Vec<_>>();
+
+            format!("{{ {} }}", entries.join(", "))
+        }
+        Value::Array(array) if array.iter().any(Value::is_table) => {
+            let elements = array.iter().map(format_value).collect::<Vec<_>>();
+            format!("[{}]", elements.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Format a TOML key, quoting it unless it's a valid bare key
+fn format_key(key: &str) -> String {
+    let is_bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if is_bare {
+        key.to_owned()
+    } else {
+        Value::String(key.to_owned()).to_string()
+    }
+}
+
+/// Is the given value an empty string, array, or table?
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.is_empty(),
+        Value::Array(array) => array.is_empty(),
+        Value::Table(table) => table.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_key;
+
+    #[test]
+    fn quoted_keys() {
+        assert_eq!(format_key("patched"), "patched");
+        assert_eq!(
+            format_key("mycrate::vulnerable_fn"),
+            "\"mycrate::vulnerable_fn\""
+        );
+    }
+}
@@ -2,12 +2,23 @@
 
 use crate::error::{Error, ErrorKind};
 use serde::{de, ser, Deserialize, Serialize};
-use std::{fmt, str::FromStr};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str,
+    str::FromStr,
+};
 
 /// Collections of packages (`crates` vs `rust`).
 ///
 /// Advisories are either filed against crates published to https://crates.io
-/// or packages provided by the Rust language itself (e.g. `std`, `rustdoc`)
+/// or packages provided by the Rust language itself (e.g. `std`, `rustdoc`).
+///
+/// Databases in the same format which track other kinds of packages (e.g.
+/// internal firmware components) can add collections of their own, which
+/// are named after the directory containing them and declared in the
+/// database's [`COLLECTIONS_FILE`](crate::database::COLLECTIONS_FILE).
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum Collection {
     /// Crates published through crates.io
@@ -15,10 +26,13 @@ pub enum Collection {
 
     /// Rust core vulnerabilities
     Rust,
+
+    /// Collection which isn't part of the RustSec advisory database
+    Custom(CustomName),
 }
 
 impl Collection {
-    /// Get all collections as a slice
+    /// Get all built-in collections as a slice
     pub fn all() -> &'static [Self] {
         &[Collection::Crates, Collection::Rust]
     }
@@ -28,8 +42,14 @@ impl Collection {
         match self {
             Collection::Crates => "crates",
             Collection::Rust => "rust",
+            Collection::Custom(name) => name.as_str(),
         }
     }
+
+    /// Is this one of the collections of the RustSec advisory database?
+    pub fn is_builtin(&self) -> bool {
+        !matches!(self, Collection::Custom(_))
+    }
 }
 
 impl fmt::Display for Collection {
@@ -45,7 +65,10 @@ impl FromStr for Collection {
         Ok(match s {
             "crates" => Collection::Crates,
             "rust" => Collection::Rust,
-            other => fail!(ErrorKind::Parse, "invalid package type: {}", other),
+            other => match CustomName::new(other) {
+                Some(name) => Collection::Custom(name),
+                None => fail!(ErrorKind::Parse, "invalid package type: {}", other),
+            },
         })
     }
 }
@@ -64,6 +87,93 @@ impl Serialize for Collection {
     }
 }
 
+/// Name of a custom [`Collection`].
+///
+/// Names are stored inline (so collections are `Copy`), and are at most
+/// [`CustomName::MAX_LEN`] bytes long.
+#[derive(Copy, Clone)]
+pub struct CustomName {
+    /// Bytes of the name, followed by zeroes
+    bytes: [u8; CustomName::MAX_LEN],
+
+    /// Length of the name in bytes
+    len: u8,
+}
+
+impl CustomName {
+    /// Maximum length of a custom collection name in bytes
+    pub const MAX_LEN: usize = 64;
+
+    /// Create a custom collection name, if the given name is valid.
+    ///
+    /// Collections are directories in the database, so their names must be
+    /// usable as such: they consist of ASCII alphanumerics, `-`, `_` and `.`,
+    /// and mustn't be empty or start with `.` (e.g. `.git`).
+    fn new(name: &str) -> Option<Self> {
+        if name.is_empty()
+            || name.len() > Self::MAX_LEN
+            || name.starts_with('.')
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            return None;
+        }
+
+        let mut bytes = [0; Self::MAX_LEN];
+        bytes[..name.len()].copy_from_slice(name.as_bytes());
+
+        Some(Self {
+            bytes,
+            len: name.len() as u8,
+        })
+    }
+
+    /// Get the name as a `str`
+    pub fn as_str(&self) -> &str {
+        // Names are validated to be ASCII when they're created
+        str::from_utf8(&self.bytes[..self.len as usize]).unwrap()
+    }
+}
+
+impl fmt::Debug for CustomName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for CustomName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq for CustomName {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for CustomName {}
+
+impl Hash for CustomName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialOrd for CustomName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CustomName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Collection;
@@ -83,7 +193,34 @@ mod tests {
     }
 
     #[test]
-    fn parse_other() {
-        assert!("foobar".parse::<Collection>().is_err());
+    fn parse_custom() {
+        let custom_kind = "firmware".parse::<Collection>().unwrap();
+        assert_eq!("firmware", custom_kind.as_str());
+        assert_eq!(custom_kind, "firmware".parse::<Collection>().unwrap());
+        assert_ne!(custom_kind, "hardware".parse::<Collection>().unwrap());
+        assert!(!custom_kind.is_builtin());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("".parse::<Collection>().is_err());
+        assert!(".git".parse::<Collection>().is_err());
+        assert!("foo/bar".parse::<Collection>().is_err());
+        assert!("a".repeat(65).parse::<Collection>().is_err());
+    }
+
+    #[test]
+    fn custom_ordering() {
+        let mut collections = ["zzz", "aaa", "rust", "crates"]
+            .iter()
+            .map(|name| name.parse::<Collection>().unwrap())
+            .collect::<Vec<_>>();
+        collections.sort();
+
+        let names = collections
+            .iter()
+            .map(Collection::as_str)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["crates", "rust", "aaa", "zzz"]);
     }
 }
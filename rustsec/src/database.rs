@@ -2,100 +2,326 @@
 
 mod entries;
 mod index;
+mod profile;
+mod progress;
 mod query;
+mod source;
 
 pub mod scope;
 
-pub use self::query::Query;
+pub use self::{
+    profile::{LoadProfile, ParseTiming},
+    progress::LoadProgress,
+    query::Query,
+    source::{LoadMethod, Source},
+};
 
-use self::{entries::Entries, index::Index};
+use self::{
+    entries::{Entries, Slot},
+    index::Index,
+};
 use crate::{
     advisory::{self, Advisory},
     collection::Collection,
     error::Error,
     fs,
     lockfile::Lockfile,
+    package,
     vulnerability::Vulnerability,
+    Map, Set, Version,
+};
+use serde::Deserialize;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Instant,
 };
-use std::path::Path;
 
-#[cfg(feature = "git")]
+#[cfg(feature = "db-git")]
 use crate::repository::git;
 
 /// Iterator over entries in the database
 pub type Iter<'a> = std::slice::Iter<'a, Advisory>;
 
-/// Database of RustSec security advisories, indexed both by ID and collection
-#[derive(Debug)]
+/// Name of the file at the root of a database which declares the custom
+/// collections it contains besides the built-in ones, e.g.
+/// `collections = ["firmware"]`.
+///
+/// Other top-level directories (e.g. documentation) aren't collections.
+pub const COLLECTIONS_FILE: &str = "collections.toml";
+
+/// Maximum number of distinct queries about a single package whose results
+/// are memoized (beyond which the oldest are evicted)
+const MAX_CACHED_QUERIES: usize = 64;
+
+/// Results of previous queries about each package
+type QueryCache = Map<package::Name, Vec<(Query, Vec<Slot>)>>;
+
+/// Database of RustSec security advisories, indexed both by ID and collection.
+///
+/// Databases are cheap to clone, since clones share the (immutable)
+/// advisories, and can be queried from many threads at once. Results of
+/// queries about particular packages are memoized, and the memoized results
+/// are shared between clones as well.
+#[derive(Clone, Debug)]
 pub struct Database {
     /// All advisories in the database
-    advisories: Entries,
+    advisories: Arc<Entries>,
+
+    /// Index of the packages in each collection
+    indexes: Arc<Map<Collection, Index>>,
 
-    /// Index of Rust core vulnerabilities
-    rust_index: Index,
+    /// Memoized results of queries about particular packages
+    cache: Arc<Mutex<QueryCache>>,
 
-    /// Index of third party crates
-    crate_index: Index,
+    /// Where the database was loaded from
+    source: Source,
 
     /// Information about the last git commit to the database
-    #[cfg(feature = "git")]
-    latest_commit: Option<git::Commit>,
+    #[cfg(feature = "db-git")]
+    latest_commit: Option<Arc<git::Commit>>,
 }
 
 impl Database {
+    /// Create a database from loaded advisories and their indexes
+    fn new(advisories: Entries, indexes: Map<Collection, Index>, source: Source) -> Self {
+        Self {
+            advisories: Arc::new(advisories),
+            indexes: Arc::new(indexes),
+            cache: Arc::new(Mutex::new(Map::new())),
+            source,
+            #[cfg(feature = "db-git")]
+            latest_commit: None,
+        }
+    }
+
     /// Open [`Database`] located at the given local path
     pub fn open(path: &Path) -> Result<Self, Error> {
+        Self::open_with_progress(path, |_| ())
+    }
+
+    /// Open [`Database`] located at the given local path, invoking the given
+    /// callback as advisory files are discovered and parsed
+    pub fn open_with_progress(
+        path: &Path,
+        mut progress: impl FnMut(LoadProgress<'_>),
+    ) -> Result<Self, Error> {
         let mut advisory_paths = vec![];
 
-        for collection in Collection::all() {
+        for collection in Self::find_collections(path)? {
             let collection_path = path.join(collection.as_str());
 
-            if let Ok(collection_entry) = fs::read_dir(&collection_path) {
-                for dir_entry in collection_entry {
-                    for advisory_entry in fs::read_dir(dir_entry?.path())? {
-                        advisory_paths.push(advisory_entry?.path().to_owned());
+            for dir_entry in fs::read_dir(&collection_path)? {
+                let package_path = dir_entry?.path();
+
+                // Files alongside package directories (e.g. a README) aren't
+                // part of the collection
+                if !package_path.is_dir() {
+                    continue;
+                }
+
+                for advisory_entry in fs::read_dir(&package_path)? {
+                    let advisory_path = advisory_entry?.path();
+
+                    // Only the built-in collections are known to contain
+                    // nothing but advisories
+                    if collection.is_builtin()
+                        || advisory_path.extension().and_then(|ext| ext.to_str()) == Some("md")
+                    {
+                        advisory_paths.push(advisory_path);
                     }
                 }
             }
         }
 
-        let mut advisories = Entries::new();
-        let mut rust_index = Index::new();
-        let mut crate_index = Index::new();
+        let total = advisory_paths.len();
+        progress(LoadProgress::Discovered { total });
 
-        for path in &advisory_paths {
-            if let Some(slot) = advisories.load_file(path)? {
+        let mut advisories = Entries::new();
+        let mut indexes = Map::new();
+
+        for (i, path) in advisory_paths.iter().enumerate() {
+            let started = Instant::now();
+            let loaded = advisories.load_file(path).map_err(|error| {
+                progress(LoadProgress::Error {
+                    path,
+                    error: &error,
+                });
+                error
+            })?;
+            let elapsed = started.elapsed();
+
+            progress(LoadProgress::Parsed {
+                path,
+                parsed: i + 1,
+                total,
+                size: fs::metadata(path).map(|m| m.len()).unwrap_or_default(),
+                elapsed,
+            });
+
+            if let Some(slot) = loaded {
                 let advisory = advisories.get(slot).unwrap();
-                match advisory.metadata.collection.unwrap() {
-                    Collection::Crates => {
-                        crate_index.insert(&advisory.metadata.package, slot);
-                    }
-                    Collection::Rust => {
-                        rust_index.insert(&advisory.metadata.package, slot);
-                    }
-                }
+                let collection = advisory.metadata.collection.unwrap();
+                indexes
+                    .entry(collection)
+                    .or_insert_with(Index::new)
+                    .insert(&advisory.metadata.package, slot);
             }
         }
 
-        Ok(Self {
-            advisories,
-            crate_index,
-            rust_index,
-            #[cfg(feature = "git")]
-            latest_commit: None,
-        })
+        Ok(Self::new(advisories, indexes, Source::directory(path)))
+    }
+
+    /// Find the collections in the database located at the given path, i.e.
+    /// the built-in ones and those declared in its [`COLLECTIONS_FILE`]
+    /// which have a directory, with the built-in collections first.
+    fn find_collections(path: &Path) -> Result<Vec<Collection>, Error> {
+        let collections_file_path = path.join(COLLECTIONS_FILE);
+
+        let collections_file = if collections_file_path.is_file() {
+            Some(fs::read_to_string(&collections_file_path)?)
+        } else {
+            None
+        };
+
+        Ok(declared_collections(collections_file.as_deref())?
+            .into_iter()
+            .filter(|collection| path.join(collection.as_str()).is_dir())
+            .collect())
     }
 
     /// Load [`Database`] from the given [`git::Repository`]
-    #[cfg(feature = "git")]
+    #[cfg(feature = "db-git")]
     pub fn load_from_repo(repo: &git::Repository) -> Result<Self, Error> {
-        let mut db = Self::open(repo.path())?;
-        db.latest_commit = Some(repo.latest_commit()?);
+        Self::load_from_repo_with_progress(repo, |_| ())
+    }
+
+    /// Load [`Database`] from the given [`git::Repository`], invoking the
+    /// given callback as advisory files are discovered and parsed
+    #[cfg(feature = "db-git")]
+    pub fn load_from_repo_with_progress(
+        repo: &git::Repository,
+        progress: impl FnMut(LoadProgress<'_>),
+    ) -> Result<Self, Error> {
+        let mut db = Self::open_with_progress(repo.path(), progress)?;
+        let latest_commit = repo.latest_commit()?;
+        db.source = Source::git(repo.path(), latest_commit.commit_id.clone());
+        db.latest_commit = Some(Arc::new(latest_commit));
+        Ok(db)
+    }
+
+    /// Load [`Database`] as of the given revision (e.g. a commit hash, a tag,
+    /// or `HEAD`) of the given [`git::Repository`].
+    ///
+    /// Advisories are read directly from git's object database, so this
+    /// works for bare clones and doesn't touch the working tree, making it
+    /// possible to audit against any historical state of the database.
+    #[cfg(feature = "db-git")]
+    pub fn load_from_commit(repo: &git::Repository, rev: &str) -> Result<Self, Error> {
+        let commit = repo.find_commit(rev)?;
+        let files = repo.files_at(&commit.commit_id)?;
+        let mut advisories = Entries::new();
+        let mut indexes = Map::new();
+
+        let collections_file_path = Path::new(COLLECTIONS_FILE);
+        let collections_file = if files.iter().any(|path| path == collections_file_path) {
+            let data = repo.read_file_at(&commit.commit_id, collections_file_path)?;
+            Some(std::str::from_utf8(&data)?.to_owned())
+        } else {
+            None
+        };
+
+        let collections = declared_collections(collections_file.as_deref())?;
+
+        for path in files {
+            let mut components = path.iter().filter_map(|c| c.to_str());
+
+            let collection = match (components.next(), components.next(), components.next()) {
+                (Some(collection), Some(_package), Some(_file)) if components.next().is_none() => {
+                    match collection.parse::<Collection>() {
+                        Ok(collection) if collections.contains(&collection) => collection,
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            // Only the built-in collections are known to contain nothing
+            // but advisories
+            if !collection.is_builtin()
+                && path.extension().and_then(|ext| ext.to_str()) != Some("md")
+            {
+                continue;
+            }
+
+            let data = repo.read_file_at(&commit.commit_id, &path)?;
+            let data = String::from_utf8(data).map_err(|e| {
+                format_err!(
+                    crate::ErrorKind::Parse,
+                    "error parsing {}: {}",
+                    path.display(),
+                    e
+                )
+            })?;
+
+            insert_file(&mut advisories, &mut indexes, &path, &data)?;
+        }
+
+        let mut db = Self::new(
+            advisories,
+            indexes,
+            Source::git(repo.path(), commit.commit_id.clone()),
+        );
+        db.latest_commit = Some(Arc::new(commit));
         Ok(db)
     }
 
+    /// Load [`Database`] from the contents of advisory files, given along with
+    /// their paths relative to the root of the database (e.g.
+    /// `crates/base64/RUSTSEC-2017-0004.md`).
+    ///
+    /// This makes it possible to load a database which isn't on disk, such as
+    /// a snapshot embedded in a binary. Files outside of collections are
+    /// skipped, as they would be when opening a database directory.
+    pub fn load_from_files<'a>(
+        files: impl IntoIterator<Item = (&'a Path, &'a str)>,
+        source: Source,
+    ) -> Result<Self, Error> {
+        let files = files.into_iter().collect::<Vec<_>>();
+        let mut advisories = Entries::new();
+        let mut indexes = Map::new();
+
+        let collections_file = files
+            .iter()
+            .find(|(path, _)| *path == Path::new(COLLECTIONS_FILE))
+            .map(|(_, data)| *data);
+
+        let collections = declared_collections(collections_file)?;
+
+        for (path, data) in files {
+            let mut components = path.iter().filter_map(|c| c.to_str());
+
+            let collection = match (components.next(), components.next(), components.next()) {
+                (Some(collection), Some(_package), Some(_file)) if components.next().is_none() => {
+                    collection.parse::<Collection>().ok()
+                }
+                _ => None,
+            };
+
+            if let Some(collection) = collection {
+                if collections.contains(&collection)
+                    && path.extension().and_then(|ext| ext.to_str()) == Some("md")
+                {
+                    insert_file(&mut advisories, &mut indexes, path, data)?;
+                }
+            }
+        }
+
+        Ok(Self::new(advisories, indexes, source))
+    }
+
     /// Fetch the default advisory database from GitHub
-    #[cfg(feature = "git")]
+    #[cfg(feature = "db-git")]
     pub fn fetch() -> Result<Self, Error> {
         git::Repository::fetch_default_repo().and_then(|repo| Self::load_from_repo(&repo))
     }
@@ -105,30 +331,117 @@ impl Database {
         self.advisories.find_by_id(id)
     }
 
-    /// Query the database according to the given query object
+    /// Query the database according to the given query object.
+    ///
+    /// Results of queries about a particular package are memoized.
     pub fn query(&self, query: &Query) -> Vec<&Advisory> {
-        // Use indexes if we know a package name and collection
-        if let Some(name) = &query.package {
-            if let Some(collection) = query.collection {
-                return match collection {
-                    Collection::Crates => self.crate_index.get(name),
-                    Collection::Rust => self.rust_index.get(name),
-                }
+        let name = match (&query.package, &query.ids) {
+            (Some(name), _) => name,
+            (None, Some(ids)) => {
+                return ids
+                    .iter()
+                    .filter_map(|id| self.get(id))
+                    .filter(|advisory| query.matches(advisory))
+                    .collect()
+            }
+            (None, None) => {
+                return self
+                    .iter()
+                    .filter(|advisory| query.matches(advisory))
+                    .collect()
+            }
+        };
+
+        if let Some(slots) = self.cached_query(name, query) {
+            return self.get_slots(&slots);
+        }
+
+        // Use indexes if we know the collection
+        let slots: Vec<Slot> = match &query.collection {
+            Some(collection) => self
+                .indexes
+                .get(collection)
+                .and_then(|index| index.get(name))
                 .map(|slots| {
                     slots
-                        .map(|slot| self.advisories.get(*slot).unwrap())
-                        .filter(|advisory| query.matches(advisory))
+                        .filter(|slot| query.matches(self.advisories.get(**slot).unwrap()))
+                        .cloned()
                         .collect()
                 })
-                .unwrap_or_else(Vec::new);
-            }
+                .unwrap_or_default(),
+            None => self
+                .indexes
+                .values()
+                .filter_map(|index| index.get(name))
+                .flatten()
+                .filter(|slot| query.matches(self.advisories.get(**slot).unwrap()))
+                .cloned()
+                .collect::<Set<_>>()
+                .into_iter()
+                .collect(),
+        };
+
+        let advisories = self.get_slots(&slots);
+        self.cache_query(name, query, slots);
+        advisories
+    }
+
+    /// Look up the memoized result of a query about the given package
+    fn cached_query(&self, name: &package::Name, query: &Query) -> Option<Vec<Slot>> {
+        self.cache
+            .lock()
+            .unwrap()
+            .get(name)?
+            .iter()
+            .find(|(cached, _)| cached == query)
+            .map(|(_, slots)| slots.clone())
+    }
+
+    /// Memoize the result of a query about the given package
+    fn cache_query(&self, name: &package::Name, query: &Query, slots: Vec<Slot>) {
+        let mut cache = self.cache.lock().unwrap();
+        let entries = cache.entry(name.clone()).or_insert_with(Vec::new);
+
+        if entries.len() >= MAX_CACHED_QUERIES {
+            entries.remove(0);
         }
 
-        self.iter()
-            .filter(|advisory| query.matches(advisory))
+        entries.push((query.clone(), slots));
+    }
+
+    /// Get the advisories in the given slots
+    fn get_slots(&self, slots: &[Slot]) -> Vec<&Advisory> {
+        slots
+            .iter()
+            .map(|slot| self.advisories.get(*slot).unwrap())
             .collect()
     }
 
+    /// Find advisories for a package identified by its name, version, and
+    /// source, without needing a [`Lockfile`].
+    ///
+    /// Useful for consumers which get dependency information from somewhere
+    /// other than `Cargo.lock`, e.g. SBOMs, registries, or `cargo metadata`.
+    ///
+    /// Local packages never match. Like [`Database::vulnerabilities`], this
+    /// treats packages without a source as local, since `Cargo.lock` doesn't
+    /// record the source of path dependencies. Sources other than lockfiles
+    /// (e.g. `cargo metadata`) do, so packages with a `path` source are also
+    /// treated as local.
+    pub fn query_package(
+        &self,
+        name: &package::Name,
+        version: &Version,
+        source: Option<&package::SourceId>,
+    ) -> Vec<&Advisory> {
+        match source {
+            Some(source) if !source.is_path() => {
+                self.query(&Query::crate_scope().package_version(name.clone(), version.clone()))
+            }
+            _ => vec![],
+        }
+    }
+
     /// Find vulnerabilities in the provided `Lockfile` which match a given query.
     // TODO(tarcieri): move `package_scope` into `Query`?
     pub fn query_vulnerabilities(
@@ -137,28 +450,45 @@ impl Database {
         query: &Query,
         package_scope: impl Into<scope::Package>,
     ) -> Vec<Vulnerability> {
-        let package_scope = package_scope.into();
-        let mut vulns = vec![];
-
-        for package in &lockfile.packages {
-            if package_scope.is_remote() && package.source.is_none() {
-                continue;
-            }
-
-            let advisories = self.query(
-                &query
-                    .clone()
-                    .package_version(package.name.clone(), package.version.clone()),
-            );
-
-            vulns.extend(
-                advisories
-                    .iter()
-                    .map(|advisory| Vulnerability::new(advisory, package)),
-            );
-        }
+        self.iter_vulnerabilities(lockfile, query, package_scope.into())
+            .collect()
+    }
 
-        vulns
+    /// Lazily find vulnerabilities in the provided `Lockfile` which match a
+    /// given query, e.g. to stop after the first vulnerability is found.
+    pub fn iter_vulnerabilities<'a>(
+        &'a self,
+        lockfile: &'a Lockfile,
+        query: &'a Query,
+        package_scope: scope::Package,
+    ) -> impl Iterator<Item = Vulnerability> + 'a {
+        // Only the packages which the advisories a query is restricted to are
+        // about can match it, so there's no need to query for the others
+        let names = query.ids.as_ref().map(|ids| {
+            ids.iter()
+                .filter_map(|id| self.get(id))
+                .map(|advisory| &advisory.metadata.package)
+                .collect::<Set<_>>()
+        });
+
+        lockfile
+            .packages
+            .iter()
+            .filter(move |package| !(package_scope.is_remote() && package.source.is_none()))
+            .filter(move |package| {
+                names
+                    .as_ref()
+                    .map_or(true, |names| names.contains(&package.name))
+            })
+            .flat_map(move |package| {
+                self.query(
+                    &query
+                        .clone()
+                        .package_version(package.name.clone(), package.version.clone()),
+                )
+                .into_iter()
+                .map(move |advisory| Vulnerability::new(advisory, package))
+            })
     }
 
     /// Scan for vulnerabilities in the provided `Lockfile`.
@@ -171,10 +501,49 @@ impl Database {
         self.advisories.iter()
     }
 
+    /// Iterate over the advisories in the given [`Collection`]
+    pub fn iter_collection(&self, collection: Collection) -> impl Iterator<Item = &Advisory> {
+        self.iter()
+            .filter(move |advisory| advisory.metadata.collection == Some(collection))
+    }
+
+    /// Get the collections which advisories in the database are filed in,
+    /// including custom ones, sorted with the built-in collections first
+    pub fn collections(&self) -> Vec<Collection> {
+        self.indexes.keys().copied().collect()
+    }
+
+    /// Get the distinct set of crate names with advisories filed against them
+    /// (i.e. packages in [`Collection::Crates`]), sorted by name
+    pub fn packages(&self) -> Vec<&package::Name> {
+        self.collection_packages(Collection::Crates)
+    }
+
+    /// Get the distinct set of package names with advisories filed against
+    /// them in the given [`Collection`], sorted by name
+    pub fn collection_packages(&self, collection: Collection) -> Vec<&package::Name> {
+        self.indexes
+            .get(&collection)
+            .map(|index| index.packages().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get information about where the database was loaded from
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+
+    /// Replace the information about where the database was loaded from,
+    /// e.g. to record the URL it was fetched from
+    pub fn with_source(mut self, source: Source) -> Self {
+        self.source = source;
+        self
+    }
+
     /// Get information about the latest commit to the repo
-    #[cfg(feature = "git")]
+    #[cfg(feature = "db-git")]
     pub fn latest_commit(&self) -> Option<&git::Commit> {
-        self.latest_commit.as_ref()
+        self.latest_commit.as_deref()
     }
 }
 
@@ -184,6 +553,97 @@ impl IntoIterator for Database {
     type IntoIter = std::vec::IntoIter<Advisory>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.advisories.into_iter()
+        // Advisories shared with clones of the database need to be copied
+        match Arc::try_unwrap(self.advisories) {
+            Ok(advisories) => advisories.into_iter(),
+            Err(advisories) => advisories.iter().cloned().collect::<Vec<_>>().into_iter(),
+        }
     }
 }
+
+/// Parse the advisory file at the given path (relative to the root of the
+/// database) and add it to the given entries and indexes
+/// Contents of a database's [`COLLECTIONS_FILE`]
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CollectionsFile {
+    /// Custom collections in the database
+    collections: Vec<Collection>,
+}
+
+/// Get the collections of a database, given the contents of its
+/// [`COLLECTIONS_FILE`] (if it has one): the built-in collections, followed
+/// by the custom ones it declares
+fn declared_collections(collections_file: Option<&str>) -> Result<Vec<Collection>, Error> {
+    let mut collections = Collection::all().to_vec();
+
+    if let Some(collections_file) = collections_file {
+        let file: CollectionsFile = toml::from_str(collections_file).map_err(|e| {
+            format_err!(
+                crate::ErrorKind::Parse,
+                "error parsing {}: {}",
+                COLLECTIONS_FILE,
+                e
+            )
+        })?;
+
+        collections.extend(file.collections);
+    }
+
+    collections.sort();
+    collections.dedup();
+    Ok(collections)
+}
+
+fn insert_file(
+    advisories: &mut Entries,
+    indexes: &mut Map<Collection, Index>,
+    path: &Path,
+    data: &str,
+) -> Result<(), Error> {
+    let advisory = data.parse::<Advisory>().map_err(|e| {
+        format_err!(
+            crate::ErrorKind::Parse,
+            "error parsing {}: {}",
+            path.display(),
+            e
+        )
+        .with_source(e)
+    })?;
+
+    if let Some(slot) = advisories.insert(path, advisory)? {
+        let advisory = advisories.get(slot).unwrap();
+        let collection = advisory.metadata.collection.unwrap();
+        indexes
+            .entry(collection)
+            .or_insert_with(Index::new)
+            .insert(&advisory.metadata.package, slot);
+    }
+
+    Ok(())
+}
+
+/// Get the ID of the advisory in the file at the given path (relative to the
+/// root of the database), if it's an advisory file, i.e. one named
+/// `<collection>/<package>/<id>.md`
+pub fn advisory_id(path: &Path) -> Option<advisory::Id> {
+    let mut components = path.iter();
+
+    match (
+        components.next(),
+        components.next(),
+        components.next(),
+        components.next(),
+    ) {
+        (Some(collection), Some(_package), Some(_file), None) => {
+            collection.to_str()?.parse::<Collection>().ok()?;
+        }
+        _ => return None,
+    }
+
+    if path.extension()? != "md" {
+        return None;
+    }
+
+    path.file_stem()?.to_str()?.parse().ok()
+}
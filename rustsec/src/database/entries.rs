@@ -4,7 +4,7 @@ use super::Iter;
 use crate::{
     advisory::{self, Advisory},
     collection::Collection,
-    error::{Error, ErrorKind},
+    error::{DatabaseError, Error},
     map, Map,
 };
 use std::{
@@ -34,82 +34,66 @@ impl Entries {
     }
 
     /// Load an advisory from a file and insert it into the database entry table
-    // TODO(tarcieri): factor more of this into `advisory.rs`?
     pub fn load_file(&mut self, path: &Path) -> Result<Option<Slot>, Error> {
-        let mut advisory = Advisory::load_file(path)?;
+        let advisory = Advisory::load_file(path)?;
+        self.insert(path, advisory)
+    }
 
+    /// Insert an advisory into the database entry table, validating it
+    /// against the path (relative to the database root, or absolute) of the
+    /// file it was loaded from
+    // TODO(tarcieri): factor more of this into `advisory.rs`?
+    pub fn insert(&mut self, path: &Path, mut advisory: Advisory) -> Result<Option<Slot>, Error> {
         // TODO(tarcieri): deprecate and remove legacy TOML-based advisory format
         let expected_filename = match path.extension().and_then(|ext| ext.to_str()) {
             Some("md") => OsString::from(format!("{}.md", advisory.metadata.id)),
-            _ => fail!(
-                ErrorKind::Repo,
-                "unexpected file extension: {}",
-                path.display()
-            ),
+            _ => return Err(DatabaseError::UnexpectedExtension(path.to_owned()).into()),
         };
 
         // Ensure advisory has the correct filename
         if path.file_name().unwrap() != expected_filename {
-            fail!(
-                ErrorKind::Repo,
-                "expected {} to be named {:?}",
-                path.display(),
-                expected_filename
-            );
+            return Err(DatabaseError::MisnamedAdvisory {
+                path: path.to_owned(),
+                expected: expected_filename.to_string_lossy().into_owned(),
+            }
+            .into());
         }
 
         // Ensure advisory is in a directory named after its package
-        let package_dir = path.parent().ok_or_else(|| {
-            format_err!(
-                ErrorKind::Repo,
-                "advisory has no parent dir: {}",
-                path.display()
-            )
-        })?;
+        let package_dir = path
+            .parent()
+            .ok_or_else(|| DatabaseError::MissingParentDir(path.to_owned()))?;
 
         if package_dir.file_name().unwrap() != OsStr::new(advisory.metadata.package.as_str()) {
-            fail!(
-                ErrorKind::Repo,
-                "expected {} to be in {} directory (instead of \"{:?}\")",
-                advisory.metadata.id,
-                advisory.metadata.package,
-                package_dir
-            );
+            return Err(DatabaseError::MisplacedAdvisory {
+                id: advisory.metadata.id.to_string(),
+                package: advisory.metadata.package.to_string(),
+                dir: package_dir.to_owned(),
+            }
+            .into());
         }
 
         // Get the collection this advisory is part of
         let collection_dir = package_dir
             .parent()
-            .ok_or_else(|| {
-                format_err!(
-                    ErrorKind::Repo,
-                    "advisory has no collection: {}",
-                    path.display()
-                )
-            })?
+            .ok_or_else(|| DatabaseError::MissingCollection(path.to_owned()))?
             .file_name()
             .unwrap();
 
-        let collection = if collection_dir == OsStr::new(Collection::Crates.as_str()) {
-            Collection::Crates
-        } else if collection_dir == OsStr::new(Collection::Rust.as_str()) {
-            Collection::Rust
-        } else {
-            fail!(
-                ErrorKind::Repo,
-                "invalid package collection: {:?}",
-                collection_dir
-            );
-        };
+        let collection = collection_dir
+            .to_str()
+            .and_then(|name| name.parse::<Collection>().ok())
+            .ok_or_else(|| {
+                DatabaseError::InvalidCollection(collection_dir.to_string_lossy().into_owned())
+            })?;
 
         match advisory.metadata.collection {
             Some(c) => {
                 if c != collection {
-                    fail!(
-                        ErrorKind::Parse,
-                        "collection mismatch for {}",
-                        &advisory.metadata.id
-                    );
+                    return Err(DatabaseError::CollectionMismatch(
+                        advisory.metadata.id.to_string(),
+                    )
+                    .into());
                 }
             }
             None => advisory.metadata.collection = Some(collection),
@@ -123,17 +107,19 @@ impl Entries {
 
         let id = advisory.metadata.id.clone();
         let slot = Slot(self.advisories.len());
-        self.advisories.push(advisory);
 
+        // Check for duplicates before inserting, so the table is unchanged
+        // by advisories which fail to insert
         match self.index.entry(id) {
             map::Entry::Vacant(entry) => {
                 entry.insert(slot);
             }
             map::Entry::Occupied(entry) => {
-                fail!(ErrorKind::Parse, "duplicate advisory ID: {}", entry.key())
+                return Err(DatabaseError::DuplicateId(entry.key().to_string()).into());
             }
         }
 
+        self.advisories.push(advisory);
         Ok(Some(slot))
     }
 
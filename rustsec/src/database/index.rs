@@ -29,4 +29,9 @@ impl Index {
     pub fn get(&self, key: &package::Name) -> Option<Iter<'_, Slot>> {
         self.0.get(key).map(|set| set.iter())
     }
+
+    /// Get an iterator over all package names in the index
+    pub fn packages(&self) -> map::Keys<'_, package::Name, Set<Slot>> {
+        self.0.keys()
+    }
 }
@@ -0,0 +1,106 @@
+//! Profiling how long advisory files take to load
+
+use super::LoadProgress;
+use std::{path::PathBuf, time::Duration};
+
+/// How long a single advisory file took to parse
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseTiming {
+    /// Path to the advisory file
+    pub path: PathBuf,
+
+    /// Size of the advisory file in bytes
+    pub size: u64,
+
+    /// Time taken to parse (and validate) the advisory file
+    pub elapsed: Duration,
+}
+
+/// Parse durations and sizes of advisory files, recorded from the
+/// [`LoadProgress`] events reported while loading a database, e.g. to find
+/// the advisories which are slowest to load
+#[derive(Clone, Debug, Default)]
+pub struct LoadProfile {
+    /// Timings of each advisory file, in the order they were parsed
+    timings: Vec<ParseTiming>,
+}
+
+impl LoadProfile {
+    /// Create an empty profile
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a loading event (only [`LoadProgress::Parsed`] events are of
+    /// interest)
+    pub fn record(&mut self, progress: &LoadProgress<'_>) {
+        if let LoadProgress::Parsed {
+            path,
+            size,
+            elapsed,
+            ..
+        } = progress
+        {
+            self.timings.push(ParseTiming {
+                path: path.to_path_buf(),
+                size: *size,
+                elapsed: *elapsed,
+            });
+        }
+    }
+
+    /// Timings of all advisory files, in the order they were parsed
+    pub fn timings(&self) -> &[ParseTiming] {
+        &self.timings
+    }
+
+    /// Get the `n` advisory files which took longest to parse, slowest first
+    pub fn slowest(&self, n: usize) -> Vec<&ParseTiming> {
+        let mut timings = self.timings.iter().collect::<Vec<_>>();
+        timings.sort_by(|a, b| b.elapsed.cmp(&a.elapsed).then_with(|| a.path.cmp(&b.path)));
+        timings.truncate(n);
+        timings
+    }
+
+    /// Total time spent parsing advisory files
+    pub fn total_elapsed(&self) -> Duration {
+        self.timings.iter().map(|timing| timing.elapsed).sum()
+    }
+
+    /// Total size of the advisory files in bytes
+    pub fn total_size(&self) -> u64 {
+        self.timings.iter().map(|timing| timing.size).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadProfile, LoadProgress};
+    use std::{path::Path, time::Duration};
+
+    #[test]
+    fn slowest() {
+        let mut profile = LoadProfile::new();
+        profile.record(&LoadProgress::Discovered { total: 3 });
+
+        for (i, (name, millis)) in [("a.md", 3), ("b.md", 40), ("c.md", 7)].iter().enumerate() {
+            profile.record(&LoadProgress::Parsed {
+                path: Path::new(name),
+                parsed: i + 1,
+                total: 3,
+                size: 1000 * *millis,
+                elapsed: Duration::from_millis(*millis),
+            });
+        }
+
+        let slowest = profile
+            .slowest(2)
+            .into_iter()
+            .map(|timing| timing.path.to_str().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(slowest, ["b.md", "c.md"]);
+        assert_eq!(profile.total_elapsed(), Duration::from_millis(50));
+        assert_eq!(profile.total_size(), 50_000);
+    }
+}
@@ -0,0 +1,43 @@
+//! Progress reporting while loading the advisory database
+
+use crate::error::Error;
+use std::{path::Path, time::Duration};
+
+/// Events reported while loading a [`Database`][`super::Database`] via
+/// [`Database::open_with_progress`][`super::Database::open_with_progress`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LoadProgress<'a> {
+    /// All advisory files have been discovered
+    Discovered {
+        /// Total number of advisory files which will be parsed
+        total: usize,
+    },
+
+    /// An advisory file was parsed
+    Parsed {
+        /// Path to the advisory file
+        path: &'a Path,
+
+        /// Number of advisory files parsed so far (including this one)
+        parsed: usize,
+
+        /// Total number of advisory files which will be parsed
+        total: usize,
+
+        /// Size of the advisory file in bytes
+        size: u64,
+
+        /// Time taken to parse the advisory file
+        elapsed: Duration,
+    },
+
+    /// An advisory file couldn't be loaded, aborting loading the database
+    Error {
+        /// Path to the advisory file
+        path: &'a Path,
+
+        /// Error which occurred (also returned from loading the database)
+        error: &'a Error,
+    },
+}
@@ -1,16 +1,22 @@
 //! Queries against the RustSec database
 //!
 use crate::{
-    advisory::{Advisory, Severity},
+    advisory::{
+        Advisory, Date, Id, Informational, PrereleaseMatching, Severity, SeverityFallback,
+        SeverityInfo,
+    },
     collection::Collection,
     database::scope,
     package,
 };
-use platforms::target::{Arch, OS};
+use platforms::{
+    target::{Arch, OS},
+    Platform,
+};
 use semver::Version;
 
 /// Queries against the RustSec database
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Query {
     /// Collection to query against
     pub(super) collection: Option<Collection>,
@@ -21,18 +27,36 @@ pub struct Query {
     /// Version of a package to search for
     version: Option<Version>,
 
+    /// Rules for matching pre-release versions
+    prerelease: PrereleaseMatching,
+
     /// Severity threshold (i.e. minimum severity)
     severity: Option<Severity>,
 
+    /// Severities for advisories without CVSS information, by advisory type
+    severity_fallback: SeverityFallback,
+
     /// Target architecture
     target_arch: Option<Arch>,
 
     /// Target operating system
     target_os: Option<OS>,
 
+    /// Target platforms (any of which may be affected)
+    targets: Vec<&'static Platform>,
+
+    /// Excluded target architectures
+    excluded_arch: Vec<Arch>,
+
+    /// Excluded target operating systems
+    excluded_os: Vec<OS>,
+
     /// Year associated with the advisory ID
     year: Option<u32>,
 
+    /// Only consider advisories as they were on this date
+    as_of: Option<Date>,
+
     /// Query for withdrawn advisories
     /// (i.e. advisories which were soft-deleted from the database,
     /// as opposed to yanked crates)
@@ -41,8 +65,14 @@ pub struct Query {
     /// Query for informational advisories
     informational: Option<bool>,
 
+    /// Kinds of informational advisories to query for (any of which match)
+    informational_kinds: Vec<Informational>,
+
     /// Scope of packages which should be considered for audit
     package_scope: Option<scope::Package>,
+
+    /// IDs of the only advisories to query for
+    pub(super) ids: Option<Vec<Id>>,
 }
 
 impl Query {
@@ -86,6 +116,13 @@ impl Query {
         self
     }
 
+    /// Set the rules for matching pre-release versions against advisories
+    /// (default: [`PrereleaseMatching::Strict`])
+    pub fn prerelease_matching(mut self, prerelease: PrereleaseMatching) -> Self {
+        self.prerelease = prerelease;
+        self
+    }
+
     /// Set minimum severity threshold according to the CVSS
     /// Qualitative Severity Rating Scale.
     ///
@@ -96,6 +133,17 @@ impl Query {
         self
     }
 
+    /// Set fallback severities to use for advisories which lack CVSS
+    /// information, keyed by advisory type (see
+    /// [`AdvisoryType`][crate::advisory::AdvisoryType]).
+    ///
+    /// Advisories without CVSS information whose type has no fallback will
+    /// always match regardless of the severity threshold.
+    pub fn severity_fallback(mut self, fallback: SeverityFallback) -> Self {
+        self.severity_fallback = fallback;
+        self
+    }
+
     /// Set target architecture
     pub fn target_arch(mut self, arch: Arch) -> Self {
         self.target_arch = Some(arch);
@@ -108,12 +156,49 @@ impl Query {
         self
     }
 
+    /// Add a target platform. When several targets are added, advisories
+    /// affecting any one of them match.
+    pub fn target(mut self, platform: &'static Platform) -> Self {
+        self.targets.push(platform);
+        self
+    }
+
+    /// Exclude a target architecture: advisories which only affect excluded
+    /// architectures are omitted, while advisories which aren't specific to
+    /// any architecture still match.
+    ///
+    /// Exclusions take precedence over [`Query::target_arch`] and
+    /// [`Query::target`].
+    pub fn exclude_target_arch(mut self, arch: Arch) -> Self {
+        self.excluded_arch.push(arch);
+        self
+    }
+
+    /// Exclude a target operating system: advisories which only affect
+    /// excluded operating systems are omitted, while advisories which aren't
+    /// specific to any operating system still match.
+    ///
+    /// Exclusions take precedence over [`Query::target_os`] and
+    /// [`Query::target`].
+    pub fn exclude_target_os(mut self, os: OS) -> Self {
+        self.excluded_os.push(os);
+        self
+    }
+
     /// Query for vulnerabilities occurring in a specific year.
     pub fn year(mut self, year: u32) -> Self {
         self.year = Some(year);
         self
     }
 
+    /// Query the database as it would have appeared on the given date:
+    /// advisories published after this date are omitted, and advisories
+    /// withdrawn after this date are treated as not (yet) withdrawn.
+    pub fn as_of(mut self, date: Date) -> Self {
+        self.as_of = Some(date);
+        self
+    }
+
     /// Query for withdrawn advisories.
     ///
     /// By default they will be omitted from query results.
@@ -129,8 +214,34 @@ impl Query {
         self
     }
 
+    /// Query for informational advisories of a specific kind (e.g.
+    /// [`Informational::Unmaintained`]), omitting all other advisories.
+    ///
+    /// When several kinds are added, advisories of any of them match.
+    pub fn informational_kind(mut self, kind: Informational) -> Self {
+        self.informational = Some(true);
+        self.informational_kinds.push(kind);
+        self
+    }
+
+    /// Only query for the advisories with the given IDs (e.g. to check
+    /// whether particular advisories affect a project), omitting all others.
+    ///
+    /// Queries restricted to a few IDs are cheap, since only the packages
+    /// those advisories are about need to be considered.
+    pub fn only_ids(mut self, ids: &[Id]) -> Self {
+        self.ids = Some(ids.to_vec());
+        self
+    }
+
     /// Does this query match a given advisory?
     pub fn matches(&self, advisory: &Advisory) -> bool {
+        if let Some(ids) = &self.ids {
+            if !ids.contains(&advisory.metadata.id) {
+                return false;
+            }
+        }
+
         if let Some(collection) = self.collection {
             if Some(collection) != advisory.metadata.collection {
                 return false;
@@ -144,14 +255,17 @@ impl Query {
         }
 
         if let Some(version) = &self.version {
-            if !advisory.versions.is_vulnerable(version) {
+            if !advisory
+                .versions
+                .is_vulnerable_with(version, self.prerelease)
+            {
                 return false;
             }
         }
 
         if let Some(severity_threshold) = self.severity {
-            if let Some(advisory_severity) = advisory.severity() {
-                if advisory_severity < severity_threshold {
+            if let Some(info) = SeverityInfo::new(&advisory.metadata, &self.severity_fallback) {
+                if info.severity < severity_threshold {
                     return false;
                 }
             }
@@ -169,6 +283,29 @@ impl Query {
                     return false;
                 }
             }
+
+            if !affected.arch.is_empty()
+                && affected
+                    .arch
+                    .iter()
+                    .all(|arch| self.excluded_arch.contains(arch))
+            {
+                return false;
+            }
+
+            if !affected.os.is_empty() && affected.os.iter().all(|os| self.excluded_os.contains(os))
+            {
+                return false;
+            }
+
+            if !self.targets.is_empty()
+                && !self
+                    .targets
+                    .iter()
+                    .any(|platform| affected.affects_platform(platform))
+            {
+                return false;
+            }
         }
 
         if let Some(query_year) = self.year {
@@ -179,8 +316,19 @@ impl Query {
             }
         }
 
+        if let Some(as_of) = &self.as_of {
+            if &advisory.metadata.date > as_of {
+                return false;
+            }
+        }
+
         if let Some(withdrawn) = self.withdrawn {
-            if withdrawn != advisory.metadata.withdrawn.is_some() {
+            let is_withdrawn = match (&advisory.metadata.withdrawn, &self.as_of) {
+                (Some(withdrawn_date), Some(as_of)) => withdrawn_date <= as_of,
+                (withdrawn_date, _) => withdrawn_date.is_some(),
+            };
+
+            if withdrawn != is_withdrawn {
                 return false;
             }
         }
@@ -191,6 +339,13 @@ impl Query {
             }
         }
 
+        if !self.informational_kinds.is_empty() {
+            match &advisory.metadata.informational {
+                Some(kind) if self.informational_kinds.contains(kind) => (),
+                _ => return false,
+            }
+        }
+
         true
     }
 }
@@ -0,0 +1,114 @@
+//! Where an advisory database was loaded from
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use url::Url;
+
+/// Description of where a [`Database`][`super::Database`] was loaded from,
+/// for databases which may not have any git history (e.g. mirrors or
+/// vendored copies)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Source {
+    /// How the database was loaded
+    pub method: LoadMethod,
+
+    /// URL the database was loaded from: the remote it was fetched from, or
+    /// a `file://` URL for local directories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Identifier of the loaded snapshot of the database, i.e. the git
+    /// commit ID or the digest of a mirror's manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
+}
+
+/// How an advisory database was loaded
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadMethod {
+    /// Loaded from a git checkout
+    Git,
+
+    /// Loaded from a static file mirror
+    Mirror,
+
+    /// Loaded from a plain directory (e.g. a vendored copy)
+    Directory,
+
+    /// Loaded from a snapshot embedded in the running binary
+    Embedded,
+}
+
+impl Source {
+    /// Database loaded from a plain directory at the given path
+    pub fn directory(path: &Path) -> Self {
+        Self {
+            method: LoadMethod::Directory,
+            url: file_url(path),
+            snapshot: None,
+        }
+    }
+
+    /// Database loaded from the git checkout at the given path, at the given
+    /// commit
+    pub fn git(path: &Path, commit_id: impl Into<String>) -> Self {
+        Self {
+            method: LoadMethod::Git,
+            url: file_url(path),
+            snapshot: Some(commit_id.into()),
+        }
+    }
+
+    /// Database loaded from a static file mirror whose manifest has the
+    /// given digest
+    pub fn mirror(digest: impl Into<String>) -> Self {
+        Self {
+            method: LoadMethod::Mirror,
+            url: None,
+            snapshot: Some(digest.into()),
+        }
+    }
+
+    /// Database loaded from a snapshot embedded in the running binary, taken
+    /// at the given git commit (if known)
+    pub fn embedded(commit_id: Option<String>) -> Self {
+        Self {
+            method: LoadMethod::Embedded,
+            url: None,
+            snapshot: commit_id,
+        }
+    }
+
+    /// Set the URL the database was fetched from
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// Get the `file://` URL for a local directory, if it can be expressed as one
+fn file_url(path: &Path) -> Option<String> {
+    let path = path.canonicalize().ok()?;
+    Url::from_directory_path(path).ok().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadMethod, Source};
+
+    #[test]
+    fn serialize() {
+        let source = Source::mirror("abc123").url("https://example.com/advisory-db");
+
+        assert_eq!(
+            toml::to_string(&source).unwrap(),
+            "method = \"mirror\"\nurl = \"https://example.com/advisory-db\"\nsnapshot = \"abc123\"\n"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = Source::directory(dir.path());
+        assert_eq!(source.method, LoadMethod::Directory);
+        assert!(source.url.unwrap().starts_with("file://"));
+    }
+}
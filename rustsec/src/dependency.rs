@@ -0,0 +1,199 @@
+//! Relationships between workspace members and the packages they depend on,
+//! computed from the dependency graph of a lockfile
+
+use crate::{lockfile::Lockfile, package, Map, Set, Version};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, fmt};
+
+/// How a package is related to the workspace whose lockfile is audited
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relationship {
+    /// The package is a member of the workspace itself
+    Workspace,
+
+    /// The package is depended on directly by a workspace member
+    Direct,
+
+    /// The package is only depended on via other dependencies
+    Transitive,
+}
+
+impl Relationship {
+    /// Classify a package by its minimum depth in the dependency graph
+    pub fn from_depth(depth: usize) -> Self {
+        match depth {
+            0 => Relationship::Workspace,
+            1 => Relationship::Direct,
+            _ => Relationship::Transitive,
+        }
+    }
+
+    /// Get a `str` representing this relationship
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Relationship::Workspace => "workspace",
+            Relationship::Direct => "direct",
+            Relationship::Transitive => "transitive",
+        }
+    }
+}
+
+impl fmt::Display for Relationship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Minimum depth of each package in the dependency graph of a lockfile,
+/// keyed by name and version.
+///
+/// Workspace members have depth 0, their direct dependencies have depth 1,
+/// and so on. Members are the local packages (i.e. ones without a source)
+/// whose names are in `members`, or if that's empty, the local packages no
+/// other package depends on, since path dependencies are local too. Packages
+/// which can't be reached from a member (e.g. in a lockfile synthesized from
+/// an SBOM) have no depth.
+pub(crate) fn depths<'a>(
+    lockfile: &'a Lockfile,
+    members: &[package::Name],
+) -> Map<(&'a package::Name, &'a Version), usize> {
+    let mut depths = Map::new();
+    let mut queue = VecDeque::new();
+
+    let dependents = lockfile
+        .packages
+        .iter()
+        .flat_map(|package| &package.dependencies)
+        .map(|dependency| (&dependency.name, &dependency.version))
+        .collect::<Set<_>>();
+
+    for package in &lockfile.packages {
+        let is_member = if members.is_empty() {
+            !dependents.contains(&(&package.name, &package.version))
+        } else {
+            members.contains(&package.name)
+        };
+
+        if package.source.is_none() && is_member {
+            depths.insert((&package.name, &package.version), 0);
+            queue.push_back(package);
+        }
+    }
+
+    let packages = lockfile
+        .packages
+        .iter()
+        .map(|package| ((&package.name, &package.version), package))
+        .collect::<Map<_, _>>();
+
+    while let Some(package) = queue.pop_front() {
+        let depth = depths[&(&package.name, &package.version)] + 1;
+
+        for dependency in &package.dependencies {
+            let key = (&dependency.name, &dependency.version);
+
+            if depths.contains_key(&key) {
+                continue;
+            }
+
+            if let Some((&key, &dependency)) = packages.get_key_value(&key) {
+                depths.insert(key, depth);
+                queue.push_back(dependency);
+            }
+        }
+    }
+
+    depths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{depths, Relationship};
+    use crate::{lockfile::Lockfile, package};
+
+    /// Get the depth of the package with the given name
+    fn depth(lockfile: &Lockfile, members: &[package::Name], name: &str) -> Option<usize> {
+        depths(lockfile, members)
+            .iter()
+            .find(|((n, _), _)| n.as_str() == name)
+            .map(|(_, &depth)| depth)
+    }
+
+    #[test]
+    fn minimum_depths() {
+        let lockfile: Lockfile = r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["direct", "transitive"]
+
+[[package]]
+name = "direct"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["transitive"]
+
+[[package]]
+name = "transitive"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["deep"]
+
+[[package]]
+name = "deep"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(depth(&lockfile, &[], "app"), Some(0));
+        assert_eq!(depth(&lockfile, &[], "direct"), Some(1));
+        assert_eq!(depth(&lockfile, &[], "transitive"), Some(1));
+        assert_eq!(depth(&lockfile, &[], "deep"), Some(2));
+
+        assert_eq!(Relationship::from_depth(1), Relationship::Direct);
+        assert_eq!(Relationship::from_depth(2), Relationship::Transitive);
+    }
+
+    #[test]
+    fn path_dependencies() {
+        let lockfile: Lockfile = r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["lib"]
+
+[[package]]
+name = "lib"
+version = "0.1.0"
+dependencies = ["vendored"]
+
+[[package]]
+name = "vendored"
+version = "1.0.0"
+dependencies = ["registry"]
+
+[[package]]
+name = "registry"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#
+        .parse()
+        .unwrap();
+
+        // Without a list of members, only the root package is one
+        assert_eq!(depth(&lockfile, &[], "app"), Some(0));
+        assert_eq!(depth(&lockfile, &[], "lib"), Some(1));
+        assert_eq!(depth(&lockfile, &[], "vendored"), Some(2));
+        assert_eq!(depth(&lockfile, &[], "registry"), Some(3));
+
+        // `vendored` is a path dependency of the `app` and `lib` workspace
+        let members = ["app".parse().unwrap(), "lib".parse().unwrap()];
+        assert_eq!(depth(&lockfile, &members, "app"), Some(0));
+        assert_eq!(depth(&lockfile, &members, "lib"), Some(0));
+        assert_eq!(depth(&lockfile, &members, "vendored"), Some(1));
+        assert_eq!(depth(&lockfile, &members, "registry"), Some(2));
+    }
+}
@@ -3,6 +3,7 @@
 use std::{
     fmt::{self, Display},
     io,
+    path::PathBuf,
     str::Utf8Error,
 };
 use thiserror::Error;
@@ -30,6 +31,9 @@ macro_rules! fail {
     };
 }
 
+/// Boxed error which caused an [`Error`]
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
 /// Error type
 #[derive(Debug)]
 pub struct Error {
@@ -38,6 +42,12 @@ pub struct Error {
 
     /// Message providing additional information
     msg: String,
+
+    /// Typed description of the error (if any)
+    details: Option<Details>,
+
+    /// Underlying error which caused this one (if any)
+    source: Option<BoxError>,
 }
 
 impl Error {
@@ -46,13 +56,41 @@ impl Error {
         Self {
             kind,
             msg: description.to_string(),
+            details: None,
+            source: None,
         }
     }
 
+    /// Attach the underlying error which caused this one, making it available
+    /// via [`std::error::Error::source`]
+    pub fn with_source(mut self, source: impl Into<BoxError>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
     /// Obtain the inner `ErrorKind` for this error
     pub fn kind(&self) -> ErrorKind {
         self.kind
     }
+
+    /// Get the typed description of this error, which says what went wrong
+    /// and in which part of the crate, if there is one.
+    ///
+    /// Errors which wrap another `rustsec` error (e.g. to add the path of the
+    /// file being parsed) return the details of the wrapped error.
+    pub fn details(&self) -> Option<&Details> {
+        self.details
+            .as_ref()
+            .or_else(|| self.source_as::<Error>().and_then(Error::details))
+    }
+
+    /// Attempt to downcast the underlying source of this error to a
+    /// concrete error type (e.g. `git2::Error` or `std::io::Error`)
+    pub fn source_as<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        self.source
+            .as_ref()
+            .and_then(|source| source.downcast_ref())
+    }
 }
 
 impl Display for Error {
@@ -61,7 +99,13 @@ impl Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source.as_ref() as &(dyn std::error::Error + 'static))
+    }
+}
 
 /// Custom error type for this library
 #[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
@@ -101,9 +145,453 @@ pub enum ErrorKind {
     Version,
 }
 
+/// Typed description of an error, grouped by the module it originated in,
+/// for telling errors apart programmatically (e.g. a network failure from a
+/// malformed advisory) without matching on messages
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum Details {
+    /// Malformed advisory
+    #[error(transparent)]
+    Advisory(#[from] AdvisoryError),
+
+    /// Advisory database whose layout is inconsistent with its advisories
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+
+    /// Failure fetching or reading the advisory database repository or a
+    /// mirror of it
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+
+    /// Failure querying the crates.io index
+    #[error(transparent)]
+    Registry(#[from] RegistryError),
+
+    /// Unsupported version requirement or range
+    #[error(transparent)]
+    Version(#[from] VersionError),
+}
+
+impl Details {
+    /// Get the [`ErrorKind`] corresponding to these details
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Details::Advisory(error) => error.kind(),
+            Details::Database(error) => error.kind(),
+            Details::Repository(error) => error.kind(),
+            Details::Registry(error) => error.kind(),
+            Details::Version(_) => ErrorKind::BadParam,
+        }
+    }
+}
+
+impl From<Details> for Error {
+    fn from(details: Details) -> Self {
+        Self {
+            kind: details.kind(),
+            msg: details.to_string(),
+            details: Some(details),
+            source: None,
+        }
+    }
+}
+
+/// Implement `From` for the given error types via [`Details`]
+macro_rules! impl_from_details {
+    ($($error:ty),+) => {
+        $(
+            impl From<$error> for Error {
+                fn from(other: $error) -> Self {
+                    Details::from(other).into()
+                }
+            }
+        )+
+    };
+}
+
+impl_from_details!(
+    AdvisoryError,
+    DatabaseError,
+    RepositoryError,
+    RegistryError,
+    VersionError
+);
+
+/// Malformed advisories
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum AdvisoryError {
+    /// Advisory ID whose year is outside of the supported range
+    #[error("out-of-range year in advisory ID: {0}")]
+    IdYearOutOfRange(String),
+
+    /// Advisory ID whose year isn't a number
+    #[error("malformed year in advisory ID: {0}")]
+    MalformedIdYear(String),
+
+    /// Advisory ID which isn't of the form `PREFIX-YEAR-NUMBER`
+    #[error("malformed advisory ID: {0}")]
+    MalformedId(String),
+
+    /// Advisory ID which is missing its number
+    #[error("incomplete advisory ID: {0}")]
+    IncompleteId(String),
+
+    /// GHSA ID which isn't of the form `GHSA-xxxx-xxxx-xxxx`
+    #[error("malformed GHSA ID: {0}")]
+    MalformedGhsaId(String),
+
+    /// Date which isn't of the form `YYYY-MM-DD`
+    #[error("invalid date: {0}")]
+    InvalidDate(String),
+
+    /// Date with an out of range or malformed component (e.g. `month`)
+    #[error("malformed {component}: {date}")]
+    MalformedDate {
+        /// Malformed component of the date
+        component: &'static str,
+
+        /// The date
+        date: String,
+    },
+
+    /// Advisory which doesn't start with TOML front matter
+    #[error("unexpected start of advisory: \"{0}\"")]
+    UnexpectedStart(String),
+
+    /// Advisory whose TOML front matter isn't terminated
+    #[error("couldn't find end of TOML front matter in advisory")]
+    MissingFrontMatterEnd,
+
+    /// Advisory without a Markdown title after its front matter
+    #[error("Expected # header after TOML front matter")]
+    MissingTitle,
+
+    /// Advisory without a Markdown description
+    #[error("no Markdown body (i.e. description) found")]
+    MissingDescription,
+
+    /// Attribute which must come from the Markdown body (e.g. `title`) in
+    /// the TOML front matter
+    #[error("invalid `{0}` attribute in advisory TOML")]
+    InvalidAttribute(&'static str),
+
+    /// Malformed path of an affected function
+    #[error("{reason}: '{path}'")]
+    InvalidAffectedPath {
+        /// Why the path is invalid
+        reason: &'static str,
+
+        /// The path (or path segment)
+        path: String,
+    },
+
+    /// Git commit range without any bounds
+    #[error("git range for {0} must have an introduced or fixed commit")]
+    UnboundedGitRange(String),
+
+    /// Git commit range with a bound which isn't a full commit hash
+    #[error("invalid commit hash in git range for {repo}: {commit} (must be 40 hex digits)")]
+    InvalidGitCommit {
+        /// URL of the repository
+        repo: String,
+
+        /// The invalid commit hash
+        commit: String,
+    },
+}
+
+impl AdvisoryError {
+    /// Get the [`ErrorKind`] corresponding to this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            AdvisoryError::UnboundedGitRange(_) | AdvisoryError::InvalidGitCommit { .. } => {
+                ErrorKind::BadParam
+            }
+            _ => ErrorKind::Parse,
+        }
+    }
+}
+
+/// Inconsistencies between advisories and the layout of the database
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum DatabaseError {
+    /// Advisory file with an extension other than `.md`
+    #[error("unexpected file extension: {}", .0.display())]
+    UnexpectedExtension(PathBuf),
+
+    /// Advisory file which isn't named after the advisory's ID
+    #[error("expected {} to be named {expected:?}", .path.display())]
+    MisnamedAdvisory {
+        /// Path of the advisory file
+        path: PathBuf,
+
+        /// Expected name of the file
+        expected: String,
+    },
+
+    /// Advisory file which isn't in a package directory
+    #[error("advisory has no parent dir: {}", .0.display())]
+    MissingParentDir(PathBuf),
+
+    /// Advisory file which isn't in the directory named after its package
+    #[error("expected {id} to be in {package} directory (instead of \"{:?}\")", .dir)]
+    MisplacedAdvisory {
+        /// ID of the advisory
+        id: String,
+
+        /// Package the advisory is about
+        package: String,
+
+        /// Directory the advisory is in
+        dir: PathBuf,
+    },
+
+    /// Advisory file which isn't in a collection directory
+    #[error("advisory has no collection: {}", .0.display())]
+    MissingCollection(PathBuf),
+
+    /// Advisory file in a directory which isn't a valid collection name
+    #[error("invalid package collection: {0:?}")]
+    InvalidCollection(String),
+
+    /// Advisory whose `collection` doesn't match the directory it's in
+    #[error("collection mismatch for {0}")]
+    CollectionMismatch(String),
+
+    /// Several advisories with the same ID
+    #[error("duplicate advisory ID: {0}")]
+    DuplicateId(String),
+}
+
+impl DatabaseError {
+    /// Get the [`ErrorKind`] corresponding to this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DatabaseError::CollectionMismatch(_) | DatabaseError::DuplicateId(_) => {
+                ErrorKind::Parse
+            }
+            _ => ErrorKind::Repo,
+        }
+    }
+}
+
+/// Failures fetching or reading the advisory database repository, or a
+/// static mirror of it
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum RepositoryError {
+    /// Repository URL which doesn't use HTTPS
+    #[error("expected {0} to start with https://")]
+    InsecureUrl(String),
+
+    /// HTTPS URL when built without the `git-https` feature
+    #[error(
+        "can't fetch {0}: rustsec was built without the `git-https` feature \
+         (fetch the advisory DB from a mirror instead)"
+    )]
+    HttpsUnsupported(String),
+
+    /// Checkout path without a parent directory
+    #[error("invalid directory: {}", .0.display())]
+    InvalidDirectory(PathBuf),
+
+    /// Upstream history which doesn't descend from the local checkout
+    #[error("upstream history has diverged: {0}")]
+    Diverged(String),
+
+    /// Latest commit without a signature
+    #[error("no signature on commit {commit_id}: {summary} ({author})")]
+    Unsigned {
+        /// Commit hash
+        commit_id: String,
+
+        /// First line of the commit message
+        summary: String,
+
+        /// Commit author
+        author: String,
+    },
+
+    /// Repository whose latest commit is too old
+    #[error("repository is stale (last commit: {0})")]
+    Stale(String),
+
+    /// Repository in the middle of an operation (e.g. a merge)
+    #[error("bad repository state: {0}")]
+    BadState(String),
+
+    /// Repository without a `HEAD` target
+    #[error("no ref target for: {}", .0.display())]
+    NoRefTarget(PathBuf),
+
+    /// Commit without a (UTF-8) message
+    #[error("no commit summary for {0}")]
+    NoCommitSummary(String),
+
+    /// Path which isn't a file at a revision
+    #[error("{} is not a file at {rev}", .path.display())]
+    NotAFile {
+        /// Path relative to the repository root
+        path: PathBuf,
+
+        /// The revision
+        rev: String,
+    },
+
+    /// Path which isn't relative to the repository root
+    #[error("{} is not a relative path", .0.display())]
+    NotRelative(PathBuf),
+
+    /// Failure authenticating to a remote
+    #[error("{0}")]
+    Authentication(String),
+
+    /// Network failure fetching a mirror
+    #[error("error fetching {url}: {reason}")]
+    Fetch {
+        /// URL being fetched
+        url: String,
+
+        /// Description of the failure
+        reason: String,
+    },
+
+    /// Mirror manifest which couldn't be parsed
+    #[error("invalid manifest: {0}")]
+    InvalidManifest(String),
+
+    /// Mirror manifest containing a path outside of the mirror
+    #[error("invalid path in manifest: {0}")]
+    InvalidManifestPath(String),
+
+    /// Mirror manifest whose digest doesn't match the pinned one
+    #[error("manifest digest mismatch: expected {expected}, got {actual}")]
+    DigestMismatch {
+        /// Pinned digest of the manifest
+        expected: String,
+
+        /// Actual digest of the manifest
+        actual: String,
+    },
+
+    /// Mirrored file whose checksum doesn't match the manifest
+    #[error("checksum mismatch for {path}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// Path of the file within the mirror
+        path: String,
+
+        /// Checksum according to the manifest
+        expected: String,
+
+        /// Actual checksum of the file
+        actual: String,
+    },
+}
+
+impl RepositoryError {
+    /// Get the [`ErrorKind`] corresponding to this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            RepositoryError::InsecureUrl(_)
+            | RepositoryError::HttpsUnsupported(_)
+            | RepositoryError::InvalidDirectory(_)
+            | RepositoryError::NotRelative(_)
+            | RepositoryError::InvalidManifestPath(_) => ErrorKind::BadParam,
+            RepositoryError::InvalidManifest(_) => ErrorKind::Parse,
+            _ => ErrorKind::Repo,
+        }
+    }
+}
+
+/// Failures querying the crates.io index
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum RegistryError {
+    /// Local copy of the index which hasn't been fetched
+    #[error("crates.io registry has not been fetched yet")]
+    NotFetched,
+
+    /// Package (or release) which isn't in the index
+    #[error("no results for: {0}")]
+    NotFound(String),
+
+    /// Network failure fetching from the index
+    #[error("error fetching {url}: {reason}")]
+    Fetch {
+        /// URL being fetched
+        url: String,
+
+        /// Description of the failure
+        reason: String,
+    },
+
+    /// Index entry which couldn't be parsed
+    #[error("malformed index entry in {location}: {reason}")]
+    MalformedEntry {
+        /// Where the entry is (e.g. its URL)
+        location: String,
+
+        /// Description of the problem
+        reason: String,
+    },
+}
+
+impl RegistryError {
+    /// Get the [`ErrorKind`] corresponding to this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            RegistryError::NotFound(_) => ErrorKind::NotFound,
+            _ => ErrorKind::Registry,
+        }
+    }
+}
+
+/// Version requirements and ranges which can't be used in advisories
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum VersionError {
+    /// Requirement which can't be converted into a range of versions
+    #[error("{reason}: {req}")]
+    UnsupportedRequirement {
+        /// Why the requirement is unsupported
+        reason: &'static str,
+
+        /// The requirement
+        req: String,
+    },
+
+    /// Requirement using an operator which isn't supported
+    #[error("Unsupported operator in version specification: '{0}'")]
+    UnsupportedOperator(String),
+
+    /// Requirement combining a selector which defines both bounds of a
+    /// range (e.g. `^1.0`) with other selectors
+    #[error("{0}")]
+    CompoundSelector(&'static str),
+
+    /// Range whose start is after its end
+    #[error("Invalid range: start must be <= end; if equal, both bounds must be inclusive")]
+    InvalidRange,
+
+    /// Requirements whose ranges overlap
+    #[error("Overlapping version ranges: {0} and {1}")]
+    OverlappingRanges(String, String),
+
+    /// Version which can't be incremented
+    #[error("Version out of range: {0}")]
+    OutOfRange(String),
+
+    /// OSV range or events which don't describe a valid range
+    #[error("{0}")]
+    InvalidOsvRange(String),
+}
+
 impl From<Utf8Error> for Error {
     fn from(other: Utf8Error) -> Self {
-        format_err!(ErrorKind::Parse, &other)
+        format_err!(ErrorKind::Parse, &other).with_source(other)
     }
 }
 
@@ -116,44 +604,94 @@ impl From<cargo_edit::Error> for Error {
 
 impl From<cargo_lock::Error> for Error {
     fn from(other: cargo_lock::Error) -> Self {
-        format_err!(ErrorKind::Io, &other)
+        format_err!(ErrorKind::Io, &other).with_source(other)
     }
 }
 
 impl From<fmt::Error> for Error {
     fn from(other: fmt::Error) -> Self {
-        format_err!(ErrorKind::Io, &other)
+        format_err!(ErrorKind::Io, &other).with_source(other)
     }
 }
 
-#[cfg(feature = "git")]
+#[cfg(feature = "db-git")]
 impl From<git2::Error> for Error {
     fn from(other: git2::Error) -> Self {
-        format_err!(ErrorKind::Repo, &other)
+        format_err!(ErrorKind::Repo, &other).with_source(other)
     }
 }
 
 impl From<io::Error> for Error {
     fn from(other: io::Error) -> Self {
-        format_err!(ErrorKind::Io, &other)
+        format_err!(ErrorKind::Io, &other).with_source(other)
     }
 }
 
-#[cfg(feature = "git")]
+#[cfg(feature = "db-git")]
 impl From<crates_index::Error> for Error {
     fn from(other: crates_index::Error) -> Self {
-        format_err!(ErrorKind::Registry, "{}", other)
+        format_err!(ErrorKind::Registry, "{}", other).with_source(other)
     }
 }
 
 impl From<semver::Error> for Error {
     fn from(other: semver::Error) -> Self {
-        format_err!(ErrorKind::Version, &other)
+        format_err!(ErrorKind::Version, &other).with_source(other)
     }
 }
 
 impl From<toml::de::Error> for Error {
     fn from(other: toml::de::Error) -> Self {
-        format_err!(ErrorKind::Parse, &other)
+        format_err!(ErrorKind::Parse, &other).with_source(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdvisoryError, Details, Error, ErrorKind, RegistryError};
+    use std::{error::Error as _, io};
+
+    #[test]
+    fn source_chain() {
+        let io_error = io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused");
+        let error = Error::from(io_error);
+        assert_eq!(error.kind(), ErrorKind::Io);
+
+        let source = error.source_as::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::ConnectionRefused);
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn no_source() {
+        let error = format_err!(ErrorKind::Parse, "bad advisory");
+        assert!(error.source().is_none());
+        assert!(error.source_as::<io::Error>().is_none());
+    }
+
+    #[test]
+    fn typed_details() {
+        let error = Error::from(AdvisoryError::MalformedId("RUSTSEC-X".to_owned()));
+        assert_eq!(error.kind(), ErrorKind::Parse);
+        assert_eq!(
+            error.to_string(),
+            "parse error: malformed advisory ID: RUSTSEC-X"
+        );
+
+        match error.details() {
+            Some(Details::Advisory(AdvisoryError::MalformedId(id))) => assert_eq!(id, "RUSTSEC-X"),
+            other => panic!("unexpected details: {:?}", other),
+        }
+
+        // Details of wrapped errors are available from the wrapper
+        let wrapper =
+            format_err!(ErrorKind::Parse, "error parsing foo.md: {}", &error).with_source(error);
+        assert!(matches!(
+            wrapper.details(),
+            Some(Details::Advisory(AdvisoryError::MalformedId(_)))
+        ));
+
+        let not_found = Error::from(RegistryError::NotFound("foo".to_owned()));
+        assert_eq!(not_found.kind(), ErrorKind::NotFound);
     }
 }
@@ -0,0 +1,202 @@
+//! Exposure windows: periods of a project's git history during which a
+//! vulnerable version of a dependency was present in its `Cargo.lock`.
+//!
+//! Useful for incident response, i.e. answering "since when were we
+//! affected, and when did we stop being affected?"
+
+use crate::{
+    advisory,
+    error::{Error, ErrorKind},
+    fs,
+    lockfile::Lockfile,
+    package, report, Database, Map, Set, Version,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    path::Path,
+    str,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Period during which a vulnerable package version was present in a lockfile
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ExposureWindow {
+    /// ID of the advisory the package is vulnerable to
+    pub advisory: advisory::Id,
+
+    /// Name of the vulnerable package
+    pub package: package::Name,
+
+    /// Vulnerable version of the package
+    pub version: Version,
+
+    /// First commit in which the vulnerable version was present
+    pub introduced: ExposureCommit,
+
+    /// First commit in which the vulnerable version was no longer present
+    /// (`None` if it is still present at `HEAD`)
+    pub resolved: Option<ExposureCommit>,
+}
+
+/// Commit marking the start or end of an [`ExposureWindow`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ExposureCommit {
+    /// ID (i.e. SHA-1 hash) of the commit
+    #[serde(alias = "commit-id")]
+    pub commit_id: String,
+
+    /// Commit timestamp
+    #[serde(with = "humantime_serde")]
+    pub timestamp: SystemTime,
+}
+
+impl ExposureCommit {
+    /// Create an [`ExposureCommit`] from a git commit
+    fn new(commit: &git2::Commit<'_>) -> Result<Self, Error> {
+        let seconds = u64::try_from(commit.time().seconds()).map_err(|_| {
+            format_err!(
+                ErrorKind::Repo,
+                "commit {} has a timestamp before the Unix epoch",
+                commit.id()
+            )
+        })?;
+
+        Ok(Self {
+            commit_id: commit.id().to_string(),
+            timestamp: UNIX_EPOCH + Duration::from_secs(seconds),
+        })
+    }
+}
+
+/// Key identifying a vulnerable package version
+type Key = (advisory::Id, package::Name, Version);
+
+/// Walk the first-parent git history of the repository containing the given
+/// `Cargo.lock`, computing the windows during which each vulnerable package
+/// version was present.
+///
+/// Vulnerabilities are filtered according to the given report settings.
+/// Revisions of the lockfile which fail to parse are skipped.
+pub fn exposure_windows(
+    db: &Database,
+    lockfile_path: &Path,
+    settings: &report::Settings,
+) -> Result<Vec<ExposureWindow>, Error> {
+    let lockfile_path = fs::canonicalize(lockfile_path)?;
+    let repo = git2::Repository::discover(lockfile_path.parent().unwrap_or(&lockfile_path))?;
+
+    let workdir = repo.workdir().ok_or_else(|| {
+        format_err!(
+            ErrorKind::Repo,
+            "bare repository: {}",
+            repo.path().display()
+        )
+    })?;
+
+    let relative_path = lockfile_path
+        .strip_prefix(fs::canonicalize(workdir)?)
+        .map_err(|_| {
+            format_err!(
+                ErrorKind::BadParam,
+                "{} is not inside {}",
+                lockfile_path.display(),
+                workdir.display()
+            )
+        })?
+        .to_owned();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    revwalk.simplify_first_parent()?;
+    revwalk.push_head()?;
+
+    let query = settings.query();
+    let package_scope = settings.package_scope.as_ref().cloned().unwrap_or_default();
+
+    let mut open = Map::<Key, ExposureCommit>::new();
+    let mut windows = vec![];
+    let mut last_blob = None;
+
+    for commit_id in revwalk {
+        let commit = repo.find_commit(commit_id?)?;
+        let blob_id = commit
+            .tree()?
+            .get_path(&relative_path)
+            .ok()
+            .map(|entry| entry.id());
+
+        // Skip commits which didn't modify the lockfile
+        if last_blob.is_some() && blob_id == last_blob {
+            continue;
+        }
+
+        last_blob = blob_id;
+
+        let present = match blob_id {
+            Some(id) => {
+                let blob = repo.find_blob(id)?;
+                match str::from_utf8(blob.content())
+                    .ok()
+                    .and_then(|s| s.parse::<Lockfile>().ok())
+                {
+                    Some(lockfile) => db
+                        .query_vulnerabilities(&lockfile, &query, package_scope.clone())
+                        .into_iter()
+                        .filter(|vuln| !settings.is_ignored(&vuln.advisory))
+                        .map(|vuln| (vuln.advisory.id, vuln.package.name, vuln.package.version))
+                        .collect::<Set<Key>>(),
+                    None => continue,
+                }
+            }
+            None => Set::new(),
+        };
+
+        let resolved_keys = open
+            .keys()
+            .filter(|key| !present.contains(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in resolved_keys {
+            let introduced = open.remove(&key).unwrap();
+            windows.push(window(key, introduced, Some(ExposureCommit::new(&commit)?)));
+        }
+
+        for key in present {
+            if !open.contains_key(&key) {
+                open.insert(key, ExposureCommit::new(&commit)?);
+            }
+        }
+    }
+
+    for (key, introduced) in open {
+        windows.push(window(key, introduced, None));
+    }
+
+    windows.sort_by(|a, b| {
+        (&a.advisory, &a.package, &a.version, a.introduced.timestamp).cmp(&(
+            &b.advisory,
+            &b.package,
+            &b.version,
+            b.introduced.timestamp,
+        ))
+    });
+
+    Ok(windows)
+}
+
+/// Construct an [`ExposureWindow`] for the given key
+fn window(
+    (advisory, package, version): Key,
+    introduced: ExposureCommit,
+    resolved: Option<ExposureCommit>,
+) -> ExposureWindow {
+    ExposureWindow {
+        advisory,
+        package,
+        version,
+        introduced,
+        resolved,
+    }
+}
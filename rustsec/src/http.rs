@@ -0,0 +1,28 @@
+//! HTTP(S) client used for fetching from static file mirrors and the sparse
+//! registry index.
+//!
+//! The TLS backend is selected at compile time: `rustls` (the default) needs
+//! no system libraries, while `native-tls` uses the platform's TLS library
+//! (e.g. OpenSSL on Linux) and takes precedence if both are enabled.
+
+use crate::error::Error;
+#[cfg(feature = "native-tls")]
+use crate::error::ErrorKind;
+#[cfg(feature = "native-tls")]
+use std::sync::Arc;
+
+/// Create an HTTP agent using the configured TLS backend
+pub(crate) fn agent() -> Result<ureq::Agent, Error> {
+    let builder = ureq::AgentBuilder::new();
+
+    #[cfg(feature = "native-tls")]
+    let builder = {
+        let connector = native_tls_crate::TlsConnector::new().map_err(|e| {
+            format_err!(ErrorKind::Io, "couldn't initialize native TLS: {}", e).with_source(e)
+        })?;
+
+        builder.tls_connector(Arc::new(connector))
+    };
+
+    Ok(builder.build())
+}
@@ -3,10 +3,29 @@
 //! This crate is primarily intended for use with the cargo-audit tool:
 //!
 //! <https://crates.io/crates/cargo-audit>
+//!
+//! ## Cargo features
+//!
+//! Advisory parsing and version matching are always available. Everything
+//! else is opt-in so that consumers which only parse advisories can avoid
+//! pulling in git, HTTP, and crates.io index dependencies:
+//!
+//! - `parser`: advisory parsing only (no additional dependencies)
+//! - `report`: [`Database`], [`Report`], and [`Vulnerability`], including
+//!   CVSS severities and platform-specific filtering
+//! - `db-git` (default): fetching the advisory DB and crates.io index via git
+//! - `osv`: exporting advisories in the OSV format
+//!
+//! Without `report`, CVSS vectors and affected platforms in advisories aren't
+//! parsed, but are kept as-is so that [`Advisory::to_toml_string`] still
+//! writes them back out.
+//!
+//! The `git` and `osv-I-know-this-is-unstable` features are retained as
+//! aliases of `db-git` and `osv` respectively.
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/RustSec/logos/main/rustsec-logo-lg.png",
-    html_root_url = "https://docs.rs/rustsec/0.24.2"
+    html_root_url = "https://docs.rs/rustsec/0.25.0"
 )]
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms, unused_qualifications)]
@@ -16,35 +35,47 @@ pub mod error;
 
 pub mod advisory;
 pub mod collection;
+#[cfg(feature = "report")]
 pub mod database;
+pub mod dependency;
+#[cfg(feature = "db-git")]
+pub mod exposure;
+#[cfg(any(feature = "mirror", feature = "sparse"))]
+mod http;
 pub mod osv;
+#[cfg(feature = "report")]
 pub mod report;
 pub mod repository;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod version;
+#[cfg(feature = "report")]
 pub mod vulnerability;
 pub mod warning;
 
 #[cfg(feature = "fix")]
 pub mod fixer;
 
-#[cfg(feature = "git")]
+#[cfg(feature = "db-git")]
 pub mod registry;
 
 pub use cargo_lock::{self, lockfile, package};
 pub use fs_err as fs;
+#[cfg(feature = "platforms")]
 pub use platforms;
 pub use semver::{self, Version, VersionReq};
 
 pub use crate::{
     advisory::Advisory,
     collection::Collection,
-    database::Database,
     error::{Error, ErrorKind},
-    report::Report,
-    vulnerability::Vulnerability,
     warning::Warning,
 };
 
-#[cfg(feature = "git")]
+#[cfg(feature = "report")]
+pub use crate::{database::Database, report::Report, vulnerability::Vulnerability};
+
+#[cfg(feature = "db-git")]
 pub use crate::repository::git::Repository;
 
 // Use BTreeMap and BTreeSet as our map and set types
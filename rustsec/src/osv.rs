@@ -6,10 +6,12 @@
 //! which `semver` crate does not allow doing directly.
 //! See https://github.com/dtolnay/semver/issues/172
 
-#[cfg(feature = "osv-I-know-this-is-unstable")]
+#[cfg(feature = "osv")]
 mod osv_advisory;
-#[cfg(feature = "osv-I-know-this-is-unstable")]
-pub use osv_advisory::OsvAdvisory;
+#[cfg(feature = "osv")]
+pub use osv_advisory::{
+    OsvAdvisory, OsvAttribution, RUSTSEC_LICENSE, RUSTSEC_SOURCE, SCHEMA_VERSION,
+};
 
 // The rest are enabled unconditionally because the OSV range format
 // is used for determining whether a given version is affected or not
@@ -18,6 +20,7 @@ mod osv_range;
 mod ranges_for_advisory;
 mod unaffected_range;
 
-pub use osv_range::OsvRange;
+pub use osv_range::{events_to_ranges, ranges_to_events, OsvEvent, OsvRange};
 pub use ranges_for_advisory::ranges_for_advisory;
 pub(crate) use ranges_for_advisory::ranges_for_unvalidated_advisory;
+pub(crate) use unaffected_range::UnaffectedRange;
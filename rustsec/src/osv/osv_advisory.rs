@@ -1,20 +1,31 @@
-use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
 use serde::Serialize;
 use url::Url;
 
-use super::{ranges_for_advisory, OsvRange};
+use super::{ranges_for_advisory, ranges_to_events, OsvEvent};
 
 use crate::{
     advisory::{affected::FunctionPath, Affected, Category, Id, Informational},
     repository::git::{GitModificationTimes, GitPath},
-    Advisory,
+    version::cmp_precedence,
+    Advisory, Version,
 };
 
 const ECOSYSTEM: &str = "crates.io";
 
+/// Version of the OSV schema exported advisories conform to
+pub const SCHEMA_VERSION: &str = "1.2.0";
+
+/// SPDX identifier of the license of the RustSec advisory database
+pub const RUSTSEC_LICENSE: &str = "CC0-1.0";
+
+/// URL of the RustSec advisory database's source repository
+pub const RUSTSEC_SOURCE: &str = "https://github.com/rustsec/advisory-db";
+
 /// Security advisory in the format defined by https://github.com/google/osv
 #[derive(Debug, Clone, Serialize)]
 pub struct OsvAdvisory {
+    schema_version: &'static str,
     id: Id,
     modified: String,  // maybe add an rfc3339 newtype?
     published: String, // maybe add an rfc3339 newtype?
@@ -22,12 +33,10 @@ pub struct OsvAdvisory {
     withdrawn: Option<String>, // maybe add an rfc3339 newtype?
     aliases: Vec<Id>,
     related: Vec<Id>,
-    package: OsvPackage,
     summary: String,
     details: String,
-    affects: OsvAffected,
+    affected: Vec<OsvAffected>,
     references: Vec<OsvReference>,
-    ecosystem_specific: OsvEcosystemSpecific,
     database_specific: OsvDatabaseSpecific,
 }
 
@@ -51,34 +60,46 @@ impl From<&cargo_lock::Name> for OsvPackage {
     }
 }
 
+/// Affected package, along with the ranges of its affected versions
 #[derive(Debug, Clone, Serialize)]
 pub struct OsvAffected {
-    // Other fields are specified, but we never use them.
-    // Ranges alone are sufficient.
+    package: OsvPackage,
     ranges: Vec<OsvJsonRange>,
+    /// Published versions within the ranges, for consumers which don't
+    /// implement SemVer (only populated given the crate's releases)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    versions: Vec<String>,
+    ecosystem_specific: OsvEcosystemSpecific,
 }
 
-/// Same as `OsvRange`, but also has `type` field specified
-/// which is required in the OSV JSON representation.
+/// Range of affected versions (or commits, for `GIT` ranges) in the OSV
+/// JSON `events` representation
 #[derive(Debug, Clone, Serialize)]
 pub struct OsvJsonRange {
     // 'type' is a reserved keyword in Rust
     #[serde(rename = "type")]
     kind: &'static str,
+    /// Repository the commits of `GIT` ranges are in
     #[serde(skip_serializing_if = "Option::is_none")]
-    introduced: Option<semver::Version>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    fixed: Option<semver::Version>,
+    repo: Option<Url>,
+    events: Vec<OsvJsonEvent>,
 }
 
-impl From<OsvRange> for OsvJsonRange {
-    fn from(range: OsvRange) -> Self {
-        OsvJsonRange {
-            kind: "SEMVER",
-            introduced: range.introduced,
-            fixed: range.fixed,
-        }
-    }
+/// Event in a range: either a version or a git commit
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum OsvJsonEvent {
+    Semver(OsvEvent),
+    Git(OsvGitEvent),
+}
+
+/// Event in a `GIT` range, i.e. an object with exactly one field whose
+/// value is a commit hash (or `"0"` for `introduced`, meaning all commits)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsvGitEvent {
+    Introduced(String),
+    Fixed(String),
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -118,6 +139,8 @@ pub struct OsvEcosystemSpecific {
 pub struct OsvEcosystemSpecificAffected {
     arch: Vec<platforms::target::Arch>,
     os: Vec<platforms::target::OS>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    env: Vec<platforms::target::Env>,
     /// We include function names only in order to allow changing
     /// the way versions are specified without an API break
     functions: Vec<FunctionPath>,
@@ -128,6 +151,7 @@ impl From<Affected> for OsvEcosystemSpecificAffected {
         OsvEcosystemSpecificAffected {
             arch: a.arch,
             os: a.os,
+            env: a.env,
             functions: a.functions.into_iter().map(|(f, _v)| f).collect(),
         }
     }
@@ -138,11 +162,57 @@ pub struct OsvDatabaseSpecific {
     categories: Vec<Category>,
     cvss: Option<cvss::v3::Base>,
     informational: Option<Informational>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    discovered: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reported: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    patched: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attribution: Option<OsvAttribution>,
+}
+
+/// Licensing and attribution information about the database an advisory was
+/// exported from, for redistributors of exported advisories
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct OsvAttribution {
+    /// SPDX identifier of the database's license (e.g. `CC0-1.0` or `CC-BY-4.0`)
+    pub license: String,
+
+    /// URL of the database's source repository
+    pub source: String,
+
+    /// Git commit of the database the advisory was exported from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// Attribution notice to reproduce along with the advisory (as required
+    /// by licenses such as CC-BY)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+}
+
+impl OsvAttribution {
+    /// Attribution for advisories exported from the given commit of the
+    /// RustSec advisory database
+    pub fn rustsec(commit: Option<String>) -> Self {
+        Self {
+            license: RUSTSEC_LICENSE.to_owned(),
+            source: RUSTSEC_SOURCE.to_owned(),
+            commit,
+            notice: None,
+        }
+    }
 }
 
 impl OsvAdvisory {
     /// Converts a single RustSec advisory to OSV format.
     /// `path` is the path to the advisory file. It must be relative to the git repository root.
+    ///
+    /// The `modified` timestamp is the time of the latest commit which
+    /// touched the advisory file (or its publication date if it hasn't been
+    /// committed yet), so exporting an unchanged advisory always produces
+    /// identical output.
     pub fn from_rustsec(
         advisory: Advisory,
         mod_times: &GitModificationTimes,
@@ -168,30 +238,63 @@ impl OsvAdvisory {
         // other references
         reference_urls.extend(metadata.references.into_iter());
 
+        let published = rustsec_date_to_rfc3339(&metadata.date);
+        let modified = match mod_times.get(path) {
+            Some(time) => git2_time_to_rfc3339(time),
+            None => published.clone(),
+        };
+
         OsvAdvisory {
+            schema_version: SCHEMA_VERSION,
             id: metadata.id,
-            modified: git2_time_to_rfc3339(mod_times.for_path(path)),
-            published: rustsec_date_to_rfc3339(&metadata.date),
-            affects: OsvAffected {
-                ranges: json_ranges_for_advisory(&advisory.versions),
-            },
+            modified,
+            published,
             withdrawn: metadata.withdrawn.map(|d| rustsec_date_to_rfc3339(&d)),
             aliases: metadata.aliases,
             related: metadata.related,
-            package: (&metadata.package).into(),
             summary: metadata.title,
             details: metadata.description,
+            affected: vec![OsvAffected {
+                package: (&metadata.package).into(),
+                ranges: json_ranges_for_advisory(&advisory.versions),
+                versions: vec![],
+                ecosystem_specific: OsvEcosystemSpecific {
+                    affects: advisory.affected.unwrap_or_default().into(),
+                },
+            }],
             references: osv_references(reference_urls),
-            ecosystem_specific: OsvEcosystemSpecific {
-                affects: advisory.affected.unwrap_or_default().into(),
-            },
             database_specific: OsvDatabaseSpecific {
                 categories: metadata.categories,
                 cvss: metadata.cvss,
                 informational: metadata.informational,
+                discovered: metadata.discovered.map(|d| rustsec_date_to_rfc3339(&d)),
+                reported: metadata.reported.map(|d| rustsec_date_to_rfc3339(&d)),
+                patched: metadata.patched.map(|d| rustsec_date_to_rfc3339(&d)),
+                attribution: None,
             },
         }
     }
+
+    /// Set the explicit list of affected versions, given the affected
+    /// subset of the crate's published releases (see
+    /// [`Versions::affected_subset`][crate::advisory::Versions::affected_subset]).
+    /// The versions are listed in ascending order.
+    pub fn with_affected_versions(mut self, mut versions: Vec<Version>) -> Self {
+        versions.sort_by(cmp_precedence);
+
+        for affected in &mut self.affected {
+            affected.versions = versions.iter().map(ToString::to_string).collect();
+        }
+
+        self
+    }
+
+    /// Include licensing and attribution information about the database
+    /// the advisory was exported from in `database_specific`
+    pub fn with_attribution(mut self, attribution: OsvAttribution) -> Self {
+        self.database_specific.attribution = Some(attribution);
+        self
+    }
 }
 
 fn osv_references(references: Vec<Url>) -> Vec<OsvReference> {
@@ -212,23 +315,82 @@ fn guess_url_kind(url: &Url) -> OsvReferenceKind {
     }
 }
 
-/// Like ``ranges_for_advisory``, but also converts from ``OsvRange`` to ``OsvJsonRange``
+/// Like ``ranges_for_advisory``, but converts the ``OsvRange``s into a single
+/// ``OsvJsonRange`` with ``events`` in ascending order, followed by a ``GIT``
+/// range for each of the advisory's git ranges.
 /// Assumes that the input has already been validated; panics if passed an invalid advisory.
 fn json_ranges_for_advisory(versions: &crate::advisory::Versions) -> Vec<OsvJsonRange> {
-    ranges_for_advisory(versions)
-        .into_iter()
-        .map(|x| x.into())
-        .collect()
+    let events = ranges_to_events(&ranges_for_advisory(versions));
+    let mut ranges = vec![];
+
+    if !events.is_empty() {
+        ranges.push(OsvJsonRange {
+            kind: "SEMVER",
+            repo: None,
+            events: events.into_iter().map(OsvJsonEvent::Semver).collect(),
+        });
+    }
+
+    for git in versions.git() {
+        let introduced = git.introduced.clone().unwrap_or_else(|| "0".to_owned());
+        let mut events = vec![OsvJsonEvent::Git(OsvGitEvent::Introduced(introduced))];
+
+        if let Some(fixed) = &git.fixed {
+            events.push(OsvJsonEvent::Git(OsvGitEvent::Fixed(fixed.clone())));
+        }
+
+        ranges.push(OsvJsonRange {
+            kind: "GIT",
+            repo: Some(git.repo.clone()),
+            events,
+        });
+    }
+
+    ranges
 }
 
 fn git2_time_to_rfc3339(time: &git2::Time) -> String {
     let unix_timestamp = time.seconds();
     let time = NaiveDateTime::from_timestamp(unix_timestamp, 0);
-    DateTime::<Utc>::from_utc(time, Utc).to_rfc3339()
+    utc_to_rfc3339(DateTime::<Utc>::from_utc(time, Utc))
 }
 
 fn rustsec_date_to_rfc3339(d: &crate::advisory::Date) -> String {
     let pub_date: NaiveDate = NaiveDate::from_ymd(d.year() as i32, d.month(), d.day());
     let pub_time = NaiveDateTime::new(pub_date, NaiveTime::from_hms(12, 0, 0));
-    DateTime::<Utc>::from_utc(pub_time, Utc).to_rfc3339()
+    utc_to_rfc3339(DateTime::<Utc>::from_utc(pub_time, Utc))
+}
+
+/// Format a timestamp the way the OSV schema recommends (e.g.
+/// `2021-01-01T12:00:00Z`), regardless of the local time zone of the commit
+fn utc_to_rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{git2_time_to_rfc3339, rustsec_date_to_rfc3339, OsvAttribution};
+
+    #[test]
+    fn rfc3339_timestamps() {
+        // Commit times are normalized to UTC regardless of their offset
+        assert_eq!(
+            git2_time_to_rfc3339(&git2::Time::new(1_609_459_200, 120)),
+            "2021-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            rustsec_date_to_rfc3339(&"2021-01-01".parse().unwrap()),
+            "2021-01-01T12:00:00Z"
+        );
+    }
+
+    #[test]
+    fn rustsec_attribution() {
+        let attribution = OsvAttribution::rustsec(Some("abc123".to_owned()));
+
+        assert_eq!(
+            toml::to_string(&attribution).unwrap(),
+            "license = \"CC0-1.0\"\nsource = \"https://github.com/rustsec/advisory-db\"\ncommit = \"abc123\"\n"
+        );
+    }
 }
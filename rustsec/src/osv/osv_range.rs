@@ -1,27 +1,400 @@
+use crate::{
+    error::{Error, VersionError},
+    version::cmp_precedence,
+};
 use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, convert::TryFrom};
 
 /// A range of affected versions.
 /// If any of the bounds is unspecified, that means ALL versions
 /// in that direction are affected.
 ///
 /// This format is defined by https://github.com/google/osv
-#[derive(Debug, Clone)]
+///
+/// Serializes as an `{ introduced, fixed }` pair. Use [`OsvRange::events`] or
+/// [`ranges_to_events`] to obtain the `events` representation used by
+/// OSV JSON documents instead.
+///
+/// Ranges are ordered by their start, and then by their end.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize, Serialize)]
 pub struct OsvRange {
     /// Inclusive
-    pub introduced: Option<Version>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    introduced: Option<Version>,
     /// Exclusive
-    pub fixed: Option<Version>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fixed: Option<Version>,
 }
 
 impl OsvRange {
-    /// Returns true if the given version is affected
+    /// Create a new range, checking that it isn't empty
+    pub fn new(introduced: Option<Version>, fixed: Option<Version>) -> Result<Self, Error> {
+        if let (Some(start), Some(end)) = (&introduced, &fixed) {
+            if cmp_precedence(start, end) != Ordering::Less {
+                return Err(invalid_range(format!(
+                    "empty OSV range: introduced {} is not before fixed {}",
+                    start, end
+                )));
+            }
+        }
+
+        Ok(Self { introduced, fixed })
+    }
+
+    /// First affected version (inclusive), or `None` if all versions before
+    /// the end of the range are affected
+    pub fn introduced(&self) -> Option<&Version> {
+        self.introduced.as_ref()
+    }
+
+    /// First unaffected version after the range (exclusive), or `None` if
+    /// all versions after the start of the range are affected
+    pub fn fixed(&self) -> Option<&Version> {
+        self.fixed.as_ref()
+    }
+
+    /// Returns the smallest range covering both ranges, or `None` if they
+    /// neither overlap nor are adjacent
+    pub fn union(&self, other: &OsvRange) -> Option<OsvRange> {
+        if !starts_before_end(self, other) || !starts_before_end(other, self) {
+            return None;
+        }
+
+        Some(OsvRange {
+            introduced: match (&self.introduced, &other.introduced) {
+                (Some(a), Some(b)) => Some(earliest(a, b).clone()),
+                _ => None,
+            },
+            fixed: match (&self.fixed, &other.fixed) {
+                (Some(a), Some(b)) => Some(latest(a, b).clone()),
+                _ => None,
+            },
+        })
+    }
+
+    /// Returns the range of versions affected by both ranges, or `None` if
+    /// they don't overlap
+    pub fn intersect(&self, other: &OsvRange) -> Option<OsvRange> {
+        let introduced = match (&self.introduced, &other.introduced) {
+            (Some(a), Some(b)) => Some(latest(a, b)),
+            (a, b) => a.as_ref().or_else(|| b.as_ref()),
+        };
+
+        let fixed = match (&self.fixed, &other.fixed) {
+            (Some(a), Some(b)) => Some(earliest(a, b)),
+            (a, b) => a.as_ref().or_else(|| b.as_ref()),
+        };
+
+        OsvRange::new(introduced.cloned(), fixed.cloned()).ok()
+    }
+
+    /// Returns true if the given version is affected (ignoring any build
+    /// metadata)
     pub fn affects(&self, v: &Version) -> bool {
         (match &self.introduced {
             None => true,
-            Some(start_v) => v >= start_v,
+            Some(start_v) => cmp_precedence(v, start_v) != Ordering::Less,
         }) && (match &self.fixed {
             None => true,
-            Some(end_v) => v < end_v,
+            Some(end_v) => cmp_precedence(v, end_v) == Ordering::Less,
+        })
+    }
+
+    /// Returns this range in the OSV `events` representation
+    pub fn events(&self) -> Vec<OsvEvent> {
+        let mut events = vec![OsvEvent::Introduced(self.introduced.clone())];
+
+        if let Some(fixed) = &self.fixed {
+            events.push(OsvEvent::Fixed(fixed.clone()));
+        }
+
+        events
+    }
+}
+
+impl Ord for OsvRange {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Unbounded starts sort first, and unbounded ends sort last. Build
+        // metadata only breaks ties, to stay consistent with `Eq`.
+        let start = match (&self.introduced, &other.introduced) {
+            (Some(a), Some(b)) => cmp_versions(a, b),
+            (a, b) => a.cmp(b),
+        };
+
+        start.then_with(|| match (&self.fixed, &other.fixed) {
+            (Some(a), Some(b)) => cmp_versions(a, b),
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
         })
     }
 }
+
+impl PartialOrd for OsvRange {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Does range `a` start no later than range `b` ends?
+fn starts_before_end(a: &OsvRange, b: &OsvRange) -> bool {
+    match (&a.introduced, &b.fixed) {
+        (Some(start), Some(end)) => cmp_precedence(start, end) != Ordering::Greater,
+        _ => true,
+    }
+}
+
+/// Get the earlier of two versions (ignoring build metadata)
+fn earliest<'a>(a: &'a Version, b: &'a Version) -> &'a Version {
+    if cmp_precedence(b, a) == Ordering::Less {
+        b
+    } else {
+        a
+    }
+}
+
+/// Get the later of two versions (ignoring build metadata)
+fn latest<'a>(a: &'a Version, b: &'a Version) -> &'a Version {
+    if cmp_precedence(b, a) == Ordering::Greater {
+        b
+    } else {
+        a
+    }
+}
+
+/// Compare versions by precedence, and then by build metadata
+fn cmp_versions(a: &Version, b: &Version) -> Ordering {
+    cmp_precedence(a, b).then_with(|| a.cmp(b))
+}
+
+/// Converts a list of non-overlapping ranges into a single list of OSV
+/// `events` in ascending version order
+pub fn ranges_to_events(ranges: &[OsvRange]) -> Vec<OsvEvent> {
+    let mut ranges = ranges.to_vec();
+
+    ranges.sort();
+    ranges.iter().flat_map(OsvRange::events).collect()
+}
+
+/// Converts a list of OSV `events` in ascending order back into ranges.
+///
+/// `last_affected` events can't be represented as an [`OsvRange`] (whose
+/// end is exclusive) and result in an error.
+pub fn events_to_ranges(events: &[OsvEvent]) -> Result<Vec<OsvRange>, Error> {
+    let mut ranges = vec![];
+    let mut start: Option<Option<Version>> = None;
+
+    for event in events {
+        match (event, start.take()) {
+            (OsvEvent::Introduced(v), None) => start = Some(v.clone()),
+            (OsvEvent::Fixed(v), Some(introduced)) => {
+                ranges.push(OsvRange::new(introduced, Some(v.clone()))?)
+            }
+            (OsvEvent::LastAffected(v), _) => {
+                return Err(invalid_range(format!(
+                    "unsupported OSV event: last_affected {}",
+                    v
+                )))
+            }
+            (OsvEvent::Introduced(v), Some(_)) => {
+                return Err(invalid_range(format!(
+                    "OSV event introduced {} before previous range was fixed",
+                    v.as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_else(|| "0".into())
+                )))
+            }
+            (OsvEvent::Fixed(v), None) => {
+                return Err(invalid_range(format!(
+                    "OSV event fixed {} without introduced",
+                    v
+                )))
+            }
+        }
+    }
+
+    if let Some(introduced) = start {
+        ranges.push(OsvRange::new(introduced, None)?);
+    }
+
+    Ok(ranges)
+}
+
+/// Error for OSV ranges or events which don't describe a valid range
+fn invalid_range(msg: String) -> Error {
+    VersionError::InvalidOsvRange(msg).into()
+}
+
+/// Event in the OSV `events` representation of a range
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "RawOsvEvent", into = "RawOsvEvent")]
+pub enum OsvEvent {
+    /// Versions starting at this one are affected.
+    /// `None` means all versions (serialized as `"0"`).
+    Introduced(Option<Version>),
+
+    /// Versions starting at this one are no longer affected
+    Fixed(Version),
+
+    /// Last version which is affected
+    LastAffected(Version),
+}
+
+/// Serialized form of an [`OsvEvent`]: an object with exactly one field
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct RawOsvEvent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    introduced: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fixed: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_affected: Option<String>,
+}
+
+impl TryFrom<RawOsvEvent> for OsvEvent {
+    type Error = String;
+
+    fn try_from(raw: RawOsvEvent) -> Result<Self, String> {
+        let parse = |v: String| v.parse::<Version>().map_err(|e| e.to_string());
+
+        match (raw.introduced, raw.fixed, raw.last_affected) {
+            (Some(v), None, None) if v == "0" => Ok(OsvEvent::Introduced(None)),
+            (Some(v), None, None) => Ok(OsvEvent::Introduced(Some(parse(v)?))),
+            (None, Some(v), None) => Ok(OsvEvent::Fixed(parse(v)?)),
+            (None, None, Some(v)) => Ok(OsvEvent::LastAffected(parse(v)?)),
+            _ => Err("OSV event must have exactly one of: introduced, fixed, last_affected".into()),
+        }
+    }
+}
+
+impl From<OsvEvent> for RawOsvEvent {
+    fn from(event: OsvEvent) -> Self {
+        match event {
+            OsvEvent::Introduced(v) => RawOsvEvent {
+                introduced: Some(v.map(|v| v.to_string()).unwrap_or_else(|| "0".into())),
+                ..Default::default()
+            },
+            OsvEvent::Fixed(v) => RawOsvEvent {
+                fixed: Some(v.to_string()),
+                ..Default::default()
+            },
+            OsvEvent::LastAffected(v) => RawOsvEvent {
+                last_affected: Some(v.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{events_to_ranges, ranges_to_events, OsvEvent, OsvRange, RawOsvEvent};
+    use semver::Version;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn events_ascending() {
+        let ranges = [
+            OsvRange::new(Some(Version::parse("2.0.0").unwrap()), None).unwrap(),
+            OsvRange::new(None, Some(Version::parse("1.2.3").unwrap())).unwrap(),
+        ];
+
+        assert_eq!(
+            ranges_to_events(&ranges),
+            [
+                OsvEvent::Introduced(None),
+                OsvEvent::Fixed(Version::parse("1.2.3").unwrap()),
+                OsvEvent::Introduced(Some(Version::parse("2.0.0").unwrap())),
+            ]
+        );
+    }
+
+    #[test]
+    fn unbounded_introduced_is_zero() {
+        let raw = RawOsvEvent::from(OsvEvent::Introduced(None));
+        assert_eq!(raw.introduced.as_deref(), Some("0"));
+        assert_eq!(OsvEvent::try_from(raw).unwrap(), OsvEvent::Introduced(None));
+    }
+
+    #[test]
+    fn reject_ambiguous_event() {
+        let raw = RawOsvEvent {
+            introduced: Some("1.0.0".into()),
+            fixed: Some("2.0.0".into()),
+            last_affected: None,
+        };
+        assert!(OsvEvent::try_from(raw).is_err());
+    }
+
+    fn range(introduced: Option<&str>, fixed: Option<&str>) -> OsvRange {
+        OsvRange::new(
+            introduced.map(|v| v.parse().unwrap()),
+            fixed.map(|v| v.parse().unwrap()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reject_empty_range() {
+        assert!(OsvRange::new(
+            Some(Version::parse("1.0.0").unwrap()),
+            Some(Version::parse("1.0.0").unwrap())
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn union() {
+        let a = range(None, Some("1.0.0"));
+        let b = range(Some("1.0.0"), Some("2.0.0"));
+        let c = range(Some("3.0.0"), None);
+
+        assert_eq!(a.union(&b), Some(range(None, Some("2.0.0"))));
+        assert_eq!(a.union(&c), None);
+
+        // Build metadata doesn't affect whether ranges are adjacent
+        let d = range(None, Some("1.0.0+a"));
+        let e = range(Some("1.0.0+b"), Some("2.0.0"));
+        assert_eq!(d.union(&e), Some(range(None, Some("2.0.0"))));
+        assert_eq!(e.union(&d), Some(range(None, Some("2.0.0"))));
+    }
+
+    #[test]
+    fn intersect() {
+        let a = range(None, Some("2.0.0"));
+        let b = range(Some("1.0.0"), None);
+        let c = range(Some("2.0.0"), None);
+
+        assert_eq!(a.intersect(&b), Some(range(Some("1.0.0"), Some("2.0.0"))));
+        assert_eq!(a.intersect(&c), None);
+    }
+
+    #[test]
+    fn ordering() {
+        let mut ranges = vec![
+            range(Some("1.0.0"), None),
+            range(Some("1.0.0"), Some("1.5.0")),
+            range(None, Some("0.5.0")),
+        ];
+        ranges.sort();
+
+        assert_eq!(
+            ranges,
+            [
+                range(None, Some("0.5.0")),
+                range(Some("1.0.0"), Some("1.5.0")),
+                range(Some("1.0.0"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let ranges = vec![range(None, Some("1.0.0")), range(Some("2.0.0"), None)];
+        assert_eq!(
+            events_to_ranges(&ranges_to_events(&ranges)).unwrap(),
+            ranges
+        );
+    }
+}
@@ -5,7 +5,7 @@ use semver::{Prerelease, Version};
 
 use crate::advisory::versions::RawVersions;
 use crate::advisory::Versions;
-use crate::Error;
+use crate::{error::VersionError, version, Error};
 
 use super::osv_range::OsvRange;
 use super::unaffected_range::{Bound, UnaffectedRange};
@@ -45,10 +45,7 @@ fn unaffected_to_osv_ranges(
 
     // Edge case: no unaffected ranges specified. That means that ALL versions are affected.
     if unaffected.is_empty() {
-        return Ok(vec![OsvRange {
-            introduced: None,
-            fixed: None,
-        }]);
+        return Ok(vec![OsvRange::new(None, None)?]);
     }
 
     // Verify that the incoming ranges do not overlap. This is required for the correctness of the algoritm.
@@ -57,10 +54,7 @@ fn unaffected_to_osv_ranges(
     for (idx, a) in unaffected[..unaffected.len() - 1].iter().enumerate() {
         for b in unaffected[idx + 1..].iter() {
             if a.overlaps(b) {
-                fail!(
-                    crate::ErrorKind::BadParam,
-                    format!("Overlapping version ranges: {} and {}", a, b)
-                );
+                return Err(VersionError::OverlappingRanges(a.to_string(), b.to_string()).into());
             }
         }
     }
@@ -70,13 +64,14 @@ fn unaffected_to_osv_ranges(
     let mut unaffected = unaffected.to_vec();
     use std::cmp::Ordering;
     unaffected.sort_unstable_by(|a, b| {
-        match (a.start().version(), b.start().version()) {
-            (None, _) => Ordering::Less,
-            (_, None) => Ordering::Greater,
-            (Some(v1), Some(v2)) => {
-                assert!(v1 != v2); // should be already ruled out by overlap checks, but verify just in case
-                v1.cmp(v2)
-            }
+        match (a.start(), b.start()) {
+            (Bound::Unbounded, _) => Ordering::Less,
+            (_, Bound::Unbounded) => Ordering::Greater,
+            // Non-overlapping ranges may still start at the same version, e.g. `=1.0` and `>1.0`,
+            // in which case the inclusive bound comes first
+            (Bound::Inclusive(v1), Bound::Exclusive(v2)) if v1 == v2 => Ordering::Less,
+            (Bound::Exclusive(v1), Bound::Inclusive(v2)) if v1 == v2 => Ordering::Greater,
+            (start1, start2) => start1.version().cmp(&start2.version()),
         }
     });
 
@@ -85,14 +80,8 @@ fn unaffected_to_osv_ranges(
     // Handle the start bound of the first element, since it's not handled by the main loop
     match &unaffected.first().unwrap().start() {
         Bound::Unbounded => {} // Nothing to do
-        Bound::Exclusive(v) => result.push(OsvRange {
-            introduced: None,
-            fixed: Some(increment(v)),
-        }),
-        Bound::Inclusive(v) => result.push(OsvRange {
-            introduced: None,
-            fixed: Some(v.clone()),
-        }),
+        Bound::Exclusive(v) => result.push(OsvRange::new(None, Some(increment(v)?))?),
+        Bound::Inclusive(v) => result.push(OsvRange::new(None, Some(v.clone()))?),
     }
 
     // Iterate over pairs of UnaffectedRange and turn the space between each pair into an OsvRange
@@ -101,30 +90,24 @@ fn unaffected_to_osv_ranges(
             // ranges are ordered, so Unbounded can only appear in the first or last element, which are handled outside the loop
             Bound::Unbounded => unreachable!(),
             Bound::Exclusive(v) => v.clone(),
-            Bound::Inclusive(v) => increment(v),
+            Bound::Inclusive(v) => increment(v)?,
         };
         let end = match &r[1].start() {
             Bound::Unbounded => unreachable!(),
-            Bound::Exclusive(v) => increment(v),
+            Bound::Exclusive(v) => increment(v)?,
             Bound::Inclusive(v) => v.clone(),
         };
-        result.push(OsvRange {
-            introduced: Some(start),
-            fixed: Some(end),
-        });
+        // Adjacent ranges such as `=1.0` and `>1.0` leave no gap between them
+        if version::cmp_precedence(&start, &end) == Ordering::Less {
+            result.push(OsvRange::new(Some(start), Some(end))?);
+        }
     }
 
     // Handle the end bound of the last element, since it's not handled by the main loop
     match &unaffected.last().unwrap().end() {
         Bound::Unbounded => {} // Nothing to do
-        Bound::Exclusive(v) => result.push(OsvRange {
-            introduced: Some(v.clone()),
-            fixed: None,
-        }),
-        Bound::Inclusive(v) => result.push(OsvRange {
-            introduced: Some(increment(v)),
-            fixed: None,
-        }),
+        Bound::Exclusive(v) => result.push(OsvRange::new(Some(v.clone()), None)?),
+        Bound::Inclusive(v) => result.push(OsvRange::new(Some(increment(v)?), None)?),
     }
 
     Ok(result)
@@ -134,15 +117,18 @@ fn unaffected_to_osv_ranges(
 /// [the SemVer 2.0 precedence rules](https://semver.org/#spec-item-11).
 /// This is not the intutive "increment": this function returns a pre-release version!
 /// E.g. "1.2.3" is transformed to "1.2.4-0".
-fn increment(v: &Version) -> Version {
-    let mut v = v.clone();
-    v.build = Default::default(); // Clear any build metadata, it's not used to determine precedence
+/// Errors if the patch version is already at its maximum value.
+fn increment(v: &Version) -> Result<Version, Error> {
+    let mut v = version::normalize(v); // Build metadata isn't used to determine precedence
     if v.pre.is_empty() {
         // Not a pre-release.
         // Increment the last version and add "0" as pre-release specifier.
         // E.g. "1.2.3" is transformed to "1.2.4-0".
         // This seems to be the lowest possible version that's above 1.2.3 according to semver 2.0 spec
-        v.patch += 1;
+        v.patch = v
+            .patch
+            .checked_add(1)
+            .ok_or_else(|| VersionError::OutOfRange(v.to_string()))?;
         v.pre = Prerelease::new("0").unwrap();
     } else {
         // It's a pre-release.
@@ -156,18 +142,19 @@ fn increment(v: &Version) -> Version {
         let incremented = v.pre.to_string() + ".0";
         v.pre = Prerelease::new(&incremented).unwrap();
     }
-    v
+    Ok(v)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::increment;
-    use semver::Version;
+    use super::{increment, unaffected_to_osv_ranges, OsvRange, Versions};
+    use crate::osv::{ranges_to_events, OsvEvent};
+    use semver::{Version, VersionReq};
 
     #[test]
     fn increment_simple() {
         let input = Version::parse("1.2.3").unwrap();
-        let incremented = increment(&input);
+        let incremented = increment(&input).unwrap();
         assert!(incremented > input);
         let expected = Version::parse("1.2.4-0").unwrap();
         assert_eq!(expected, incremented);
@@ -176,7 +163,7 @@ mod tests {
     #[test]
     fn increment_prerelease_numeric() {
         let input = Version::parse("1.2.3-9").unwrap();
-        let incremented = increment(&input);
+        let incremented = increment(&input).unwrap();
         assert!(incremented > input);
         let intuitively_next = Version::parse("1.2.3-10").unwrap();
         assert!(incremented < intuitively_next);
@@ -189,7 +176,7 @@ mod tests {
     #[test]
     fn increment_prerelease_numeric_multipart() {
         let input = Version::parse("1.2.3-4.5.6").unwrap();
-        let incremented = increment(&input);
+        let incremented = increment(&input).unwrap();
         assert!(incremented > input);
         let intuitively_next = Version::parse("1.2.3-4.5.7").unwrap();
         assert!(incremented < intuitively_next);
@@ -200,7 +187,7 @@ mod tests {
     #[test]
     fn increment_prerelease_alphanumeric() {
         let input = Version::parse("1.2.3-alpha1").unwrap();
-        let incremented = increment(&input);
+        let incremented = increment(&input).unwrap();
         assert!(incremented > input);
         let intuitively_next = Version::parse("1.2.3-alpha2").unwrap();
         assert!(incremented < intuitively_next);
@@ -213,9 +200,38 @@ mod tests {
     #[test]
     fn increment_prerelease_textual_multipart() {
         let input = Version::parse("1.2.3-alpha.1.foo").unwrap();
-        let incremented = increment(&input);
+        let incremented = increment(&input).unwrap();
         assert!(incremented > input);
         let expected = Version::parse("1.2.3-alpha.1.foo.0").unwrap();
         assert_eq!(expected, incremented);
     }
+
+    #[test]
+    fn adjacent_ranges_with_same_start() {
+        let unaffected = [VersionReq::parse("=1.0.0").unwrap()];
+        let patched = [VersionReq::parse(">1.0.0").unwrap()];
+        let ranges = unaffected_to_osv_ranges(&unaffected, &patched).unwrap();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].introduced(), None);
+        assert_eq!(ranges[0].fixed(), Some(&Version::parse("1.0.0").unwrap()));
+    }
+
+    #[test]
+    fn everything_affected() {
+        let ranges = unaffected_to_osv_ranges(&[], &[]).unwrap();
+        assert_eq!(ranges, vec![OsvRange::new(None, None).unwrap()]);
+
+        assert_eq!(ranges_to_events(&ranges), vec![OsvEvent::Introduced(None)]);
+
+        let versions = Versions::new(vec![], vec![]).unwrap();
+        assert!(versions.is_vulnerable(&Version::new(0, 0, 0)));
+        assert!(versions.is_vulnerable(&Version::parse("1.0.0-alpha").unwrap()));
+        assert!(versions.is_vulnerable(&Version::new(u64::MAX, 0, 0)));
+    }
+
+    #[test]
+    fn increment_overflow() {
+        let input = Version::new(1, 2, u64::MAX);
+        assert!(increment(&input).is_err());
+    }
 }
@@ -2,11 +2,14 @@
 //! Cargo-style version selectors (`>=`, `^`, `<`, etc) to OSV ranges.
 //! It is an implementation detail and is not exported outside OSV module.
 
-use std::{convert::TryFrom, fmt::Display};
+use std::{cmp::Ordering, convert::TryFrom, fmt::Display};
 
 use semver::{Comparator, Op, Prerelease, Version};
 
-use crate::{Error, ErrorKind::BadParam};
+use crate::{
+    error::{Error, VersionError},
+    version::cmp_precedence,
+};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub(crate) enum Bound {
@@ -66,10 +69,7 @@ impl UnaffectedRange {
         if start.less_or_equal(&end) {
             Ok(UnaffectedRange { start, end })
         } else {
-            Err(format_err!(
-                BadParam,
-                "Invalid range: start must be <= end; if equal, both bounds must be inclusive"
-            ))
+            Err(VersionError::InvalidRange.into())
         }
     }
 
@@ -85,6 +85,24 @@ impl UnaffectedRange {
         // range check for well-formed ranges is `(Start1 <= End2) && (Start2 <= End1)`
         self.start.less_or_equal(&other.end) && other.start.less_or_equal(&self.end)
     }
+
+    /// Is the given version within the range? Versions are compared by
+    /// precedence, so pre-releases are handled like any other version.
+    pub fn contains(&self, version: &Version) -> bool {
+        let above_start = match &self.start {
+            Bound::Unbounded => true,
+            Bound::Exclusive(start) => cmp_precedence(version, start) == Ordering::Greater,
+            Bound::Inclusive(start) => cmp_precedence(version, start) != Ordering::Less,
+        };
+
+        let below_end = match &self.end {
+            Bound::Unbounded => true,
+            Bound::Exclusive(end) => cmp_precedence(version, end) == Ordering::Less,
+            Bound::Inclusive(end) => cmp_precedence(version, end) != Ordering::Greater,
+        };
+
+        above_start && below_end
+    }
 }
 
 impl Display for UnaffectedRange {
@@ -114,10 +132,11 @@ impl TryFrom<&semver::VersionReq> for UnaffectedRange {
 
     fn try_from(input: &semver::VersionReq) -> Result<Self, Self::Error> {
         if input.comparators.len() > 2 {
-            fail!(
-                BadParam,
-                format!("Too many comparators in version specification: {}", input)
-            );
+            return Err(VersionError::UnsupportedRequirement {
+                reason: "Too many comparators in version specification",
+                req: input.to_string(),
+            }
+            .into());
         }
         // If one of the bounds is not specified, it's unbounded,
         // e.g. ["> 0.5"] means the lower bound is 0.5 and there is no upper bound
@@ -132,58 +151,68 @@ impl TryFrom<&semver::VersionReq> for UnaffectedRange {
                 // However, `semver` crate recognizes more operators than Cargo supports
                 Op::Greater => {
                     if start != Bound::Unbounded {
-                        fail!(
-                            BadParam,
-                            format!("More than one lower bound in the same range: {}", input)
-                        );
+                        return Err(VersionError::UnsupportedRequirement {
+                            reason: "More than one lower bound in the same range",
+                            req: input.to_string(),
+                        }
+                        .into());
                     }
                     start = Bound::Exclusive(comp_to_ver(comparator));
                 }
                 Op::GreaterEq => {
                     if start != Bound::Unbounded {
-                        fail!(
-                            BadParam,
-                            format!("More than one lower bound in the same range: {}", input)
-                        );
+                        return Err(VersionError::UnsupportedRequirement {
+                            reason: "More than one lower bound in the same range",
+                            req: input.to_string(),
+                        }
+                        .into());
                     }
                     start = Bound::Inclusive(comp_to_ver(comparator));
                 }
                 Op::Less => {
                     if end != Bound::Unbounded {
-                        fail!(
-                            BadParam,
-                            format!("More than one upper bound in the same range: {}", input)
-                        );
+                        return Err(VersionError::UnsupportedRequirement {
+                            reason: "More than one upper bound in the same range",
+                            req: input.to_string(),
+                        }
+                        .into());
                     }
                     end = Bound::Exclusive(comp_to_ver(comparator));
                 }
                 Op::LessEq => {
                     if end != Bound::Unbounded {
-                        fail!(
-                            BadParam,
-                            format!("More than one upper bound in the same range: {}", input)
-                        );
+                        return Err(VersionError::UnsupportedRequirement {
+                            reason: "More than one upper bound in the same range",
+                            req: input.to_string(),
+                        }
+                        .into());
                     }
                     end = Bound::Inclusive(comp_to_ver(comparator));
                 }
                 Op::Exact => {
                     if input.comparators.len() != 1 {
-                        fail!(BadParam, "Selectors that define an exact version (e.g. '=1.0') must be alone in their range");
+                        return Err(VersionError::CompoundSelector(
+                            "Selectors that define an exact version (e.g. '=1.0') must be alone in their range",
+                        )
+                        .into());
                     }
                     start = Bound::Inclusive(comp_to_ver(comparator));
                     end = Bound::Inclusive(comp_to_ver(comparator));
                 }
                 Op::Caret => {
                     if input.comparators.len() != 1 {
-                        fail!(BadParam, "Selectors that define both the upper and lower bound (e.g. '^1.0') must be alone in their range");
+                        return Err(VersionError::CompoundSelector(
+                            "Selectors that define both the upper and lower bound (e.g. '^1.0') must be alone in their range",
+                        )
+                        .into());
                     }
                     let start_version = comp_to_ver(comparator);
                     let mut end_version = if start_version.major == 0 {
                         match (comparator.minor, comparator.patch) {
                             // ^0.0.x
-                            (Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+                            (Some(0), Some(patch)) => Version::new(0, 0, bump(patch, input)?),
                             // ^0.x and ^0.x.x
-                            (Some(minor), _) => Version::new(0, minor + 1, 0),
+                            (Some(minor), _) => Version::new(0, bump(minor, input)?, 0),
                             // ^0
                             (None, None) => Version::new(1, 0, 0),
                             (None, Some(_)) => unreachable!(
@@ -191,7 +220,7 @@ impl TryFrom<&semver::VersionReq> for UnaffectedRange {
                             ),
                         }
                     } else {
-                        Version::new(&start_version.major + 1, 0, 0)
+                        Version::new(bump(start_version.major, input)?, 0, 0)
                     };
                     // -0 is the lowest possible prerelease.
                     // If we didn't append it, e.g. ^1.0.0 would match 2.0.0-alpha1
@@ -201,13 +230,16 @@ impl TryFrom<&semver::VersionReq> for UnaffectedRange {
                 }
                 Op::Tilde => {
                     if input.comparators.len() != 1 {
-                        fail!(BadParam, "Selectors that define both the upper and lower bound (e.g. '~1.0') must be alone in their range");
+                        return Err(VersionError::CompoundSelector(
+                            "Selectors that define both the upper and lower bound (e.g. '~1.0') must be alone in their range",
+                        )
+                        .into());
                     }
                     let start_version = comp_to_ver(comparator);
                     let major = comparator.major;
                     let mut end_version = match (comparator.minor, comparator.patch) {
-                        (None, None) => Version::new(major + 1, 0, 0),
-                        (Some(minor), _) => Version::new(major, minor + 1, 0),
+                        (None, None) => Version::new(bump(major, input)?, 0, 0),
+                        (Some(minor), _) => Version::new(major, bump(minor, input)?, 0),
                         (None, Some(_)) => {
                             unreachable!("Comparator specifies patch version but not minor version")
                         }
@@ -220,11 +252,7 @@ impl TryFrom<&semver::VersionReq> for UnaffectedRange {
                 }
                 _ => {
                     // the struct is non-exhaustive, we have to do this
-                    fail!(
-                        BadParam,
-                        "Unsupported operator in version specification: '{}'",
-                        comparator
-                    );
+                    return Err(VersionError::UnsupportedOperator(comparator.to_string()).into());
                 }
             }
         }
@@ -232,6 +260,18 @@ impl TryFrom<&semver::VersionReq> for UnaffectedRange {
     }
 }
 
+/// Increments a version component, erroring instead of overflowing on
+/// pathological inputs such as `^18446744073709551615`
+fn bump(component: u64, input: &semver::VersionReq) -> Result<u64, Error> {
+    component.checked_add(1).ok_or_else(|| {
+        VersionError::UnsupportedRequirement {
+            reason: "Version component out of range in version specification",
+            req: input.to_string(),
+        }
+        .into()
+    })
+}
+
 /// Strips comparison operators from a Comparator and turns it into a Version.
 /// Would have been better implemented by `into` but these are foreign types
 fn comp_to_ver(c: &Comparator) -> Version {
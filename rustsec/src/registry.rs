@@ -1,40 +1,79 @@
-//! Support for interacting with the local crates.io registry index
+//! Support for interacting with the crates.io registry index.
+//!
+//! The index can be accessed through any [`Backend`]: a local git checkout
+//! ([`GitBackend`]) or, with the `sparse` feature, the sparse HTTP protocol
+//! ([`SparseBackend`]). Either way, lookups go through an [`Index`] which
+//! caches the releases of every package it has queried.
+
+mod git;
+#[cfg(feature = "sparse")]
+mod sparse;
+
+pub use self::git::GitBackend;
+#[cfg(feature = "sparse")]
+pub use self::sparse::SparseBackend;
 
 use crate::{
-    error::{Error, ErrorKind},
-    package,
+    error::{Error, RegistryError},
+    package::{self, Checksum},
+    version, Map,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
 };
 
-/// Crates.io registry index (local copy)
-pub struct Index(crates_index::Index);
+/// Source of registry index entries
+pub trait Backend: Send + Sync {
+    /// Get all releases of a particular package, erroring with
+    /// [`ErrorKind::NotFound`](crate::error::ErrorKind::NotFound) if the package isn't in the index
+    fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, Error>;
+}
+
+/// Crates.io registry index
+pub struct Index {
+    /// Backend the index is read from
+    backend: Box<dyn Backend>,
+
+    /// Releases of the packages which have been looked up so far
+    cache: Mutex<Map<package::Name, Vec<IndexPackage>>>,
+
+    /// Number of lookups answered from the cache
+    cache_hits: AtomicUsize,
+
+    /// Number of lookups which went to the backend
+    cache_misses: AtomicUsize,
+}
 
 impl Index {
     /// Open the local crates.io index, fetching it if it doesn't exist, and
     /// updating it if it does.
     pub fn fetch() -> Result<Self, Error> {
-        let index = crates_index::Index::new_cargo_default();
-
-        if index.exists() {
-            index.update()?;
-        } else {
-            index.retrieve()?;
-        }
-
-        Ok(Index(index))
+        Ok(Self::with_backend(GitBackend::fetch()?))
     }
 
     /// Open the local crates.io index, erroring if it hasn't been fetched yet
     pub fn open() -> Result<Self, Error> {
-        let index = crates_index::Index::new_cargo_default();
+        Ok(Self::with_backend(GitBackend::open()?))
+    }
 
-        if !index.exists() {
-            fail!(
-                ErrorKind::Registry,
-                "crates.io registry has not been fetched yet"
-            );
-        }
+    /// Access the crates.io index via the sparse HTTP protocol
+    #[cfg(feature = "sparse")]
+    pub fn sparse() -> Self {
+        Self::with_backend(SparseBackend::default())
+    }
 
-        Ok(Index(index))
+    /// Access the index through the given backend
+    pub fn with_backend(backend: impl Backend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+            cache: Mutex::new(Map::new()),
+            cache_hits: AtomicUsize::new(0),
+            cache_misses: AtomicUsize::new(0),
+        }
     }
 
     /// Find an entry for a particular package in the index
@@ -43,33 +82,44 @@ impl Index {
         package: &package::Name,
         version: &package::Version,
     ) -> Result<IndexPackage, Error> {
-        let crate_releases = self.0.crate_(package.as_str()).ok_or_else(|| {
-            format_err!(
-                ErrorKind::NotFound,
-                "no results for: {} {}",
-                &package,
-                &version
-            )
-        })?;
-
-        let crate_release = crate_releases
-            .versions()
-            .iter()
-            .find(|crate_version| crate_version.version() == version.to_string())
-            .ok_or_else(|| {
-                format_err!(
-                    ErrorKind::NotFound,
-                    "no results for: {} {}",
-                    &package,
-                    &version
-                )
-            })?;
-
-        Ok(IndexPackage::from(crate_release))
+        self.releases(package)?
+            .into_iter()
+            .find(|release| version::eq_precedence(&release.version, version))
+            .ok_or_else(|| RegistryError::NotFound(format!("{} {}", package, version)).into())
+    }
+
+    /// Get all releases of a particular package in the index
+    pub fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, Error> {
+        if let Some(releases) = self.cache.lock().unwrap().get(package) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(releases.clone());
+        }
+
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+
+        let releases = self.backend.releases(package)?;
+
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(package.clone(), releases.clone());
+
+        Ok(releases)
+    }
+
+    /// Get the number of lookups which were answered from the cache
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of lookups which had to query the backend
+    pub fn cache_misses(&self) -> usize {
+        self.cache_misses.load(Ordering::Relaxed)
     }
 }
 
 /// Release of the package in the crates.io registry
+#[derive(Clone, Debug)]
 pub struct IndexPackage {
     /// Name of this package
     pub package: package::Name,
@@ -79,14 +129,46 @@ pub struct IndexPackage {
 
     /// Is this package yanked?
     pub is_yanked: bool,
+
+    /// SHA-256 checksum of the release's `.crate` file (if the index
+    /// records it)
+    pub checksum: Option<Checksum>,
+
+    /// When this release was published (if the index records it, which the
+    /// sparse index does for recent releases)
+    pub published: Option<SystemTime>,
 }
 
-impl From<&crates_index::Version> for IndexPackage {
-    fn from(crate_release: &crates_index::Version) -> IndexPackage {
-        IndexPackage {
-            package: crate_release.name().parse().unwrap(),
-            version: crate_release.version().parse().unwrap(),
-            is_yanked: crate_release.is_yanked(),
+#[cfg(test)]
+mod tests {
+    use super::{Backend, Index, IndexPackage};
+    use crate::{package, Error};
+
+    /// Backend which has a single release of every package
+    struct FakeBackend;
+
+    impl Backend for FakeBackend {
+        fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, Error> {
+            Ok(vec![IndexPackage {
+                package: package.clone(),
+                version: "1.0.0".parse().unwrap(),
+                is_yanked: false,
+                checksum: None,
+                published: None,
+            }])
         }
     }
+
+    #[test]
+    fn counts_cache_hits_and_misses() {
+        let index = Index::with_backend(FakeBackend);
+        let name = "base".parse().unwrap();
+
+        for _ in 0..3 {
+            index.releases(&name).unwrap();
+        }
+
+        assert_eq!(index.cache_hits(), 2);
+        assert_eq!(index.cache_misses(), 1);
+    }
 }
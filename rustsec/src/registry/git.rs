@@ -0,0 +1,83 @@
+//! Registry index backend for a local git checkout of the index
+
+use super::{Backend, IndexPackage};
+use crate::{
+    error::{Error, RegistryError},
+    package::{self, Checksum},
+};
+use std::convert::TryFrom;
+
+/// Local git checkout of the crates.io index
+pub struct GitBackend(crates_index::Index);
+
+impl GitBackend {
+    /// Open the local crates.io index, fetching it if it doesn't exist, and
+    /// updating it if it does.
+    pub fn fetch() -> Result<Self, Error> {
+        let index = crates_index::Index::new_cargo_default();
+
+        if index.exists() {
+            index.update()?;
+        } else {
+            index.retrieve()?;
+        }
+
+        Ok(GitBackend(index))
+    }
+
+    /// Open the local crates.io index, erroring if it hasn't been fetched yet
+    pub fn open() -> Result<Self, Error> {
+        let index = crates_index::Index::new_cargo_default();
+
+        if !index.exists() {
+            return Err(RegistryError::NotFetched.into());
+        }
+
+        Ok(GitBackend(index))
+    }
+}
+
+impl Backend for GitBackend {
+    fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, Error> {
+        let crate_releases = self
+            .0
+            .crate_(package.as_str())
+            .ok_or_else(|| RegistryError::NotFound(package.to_string()))?;
+
+        crate_releases
+            .versions()
+            .iter()
+            .map(IndexPackage::try_from)
+            .collect()
+    }
+}
+
+impl TryFrom<&crates_index::Version> for IndexPackage {
+    type Error = Error;
+
+    fn try_from(crate_release: &crates_index::Version) -> Result<IndexPackage, Error> {
+        let malformed = |reason: String| RegistryError::MalformedEntry {
+            location: format!(
+                "crates.io index entry for {} {}",
+                crate_release.name(),
+                crate_release.version()
+            ),
+            reason,
+        };
+
+        Ok(IndexPackage {
+            package: crate_release
+                .name()
+                .parse::<package::Name>()
+                .map_err(|e| malformed(e.to_string()))?,
+            version: crate_release
+                .version()
+                .parse::<package::Version>()
+                .map_err(|e| malformed(e.to_string()))?,
+            is_yanked: crate_release.is_yanked(),
+            checksum: Some(Checksum::from(*crate_release.checksum())),
+            // Not exposed by `crates_index`
+            published: None,
+        })
+    }
+}
@@ -0,0 +1,158 @@
+//! Registry index backend for the sparse HTTP protocol
+
+use super::{Backend, IndexPackage};
+use crate::{
+    error::{Error, RegistryError},
+    package,
+};
+use serde::Deserialize;
+
+/// Base URL of the crates.io sparse index
+pub const CRATES_IO_SPARSE_INDEX_URL: &str = "https://index.crates.io";
+
+/// Registry index accessed via the sparse HTTP protocol, which fetches only
+/// the entries of the packages being looked up
+#[derive(Clone, Debug)]
+pub struct SparseBackend {
+    /// Base URL of the index
+    url: String,
+}
+
+impl SparseBackend {
+    /// Access the sparse index located at the given base URL
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_owned(),
+        }
+    }
+
+    /// Get the URL of the index file for the given package
+    fn file_url(&self, package: &package::Name) -> String {
+        format!("{}/{}", self.url, file_path(package.as_str()))
+    }
+}
+
+impl Default for SparseBackend {
+    fn default() -> Self {
+        Self::new(CRATES_IO_SPARSE_INDEX_URL)
+    }
+}
+
+impl Backend for SparseBackend {
+    fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, Error> {
+        let url = self.file_url(package);
+
+        let response = match crate::http::agent()?.get(&url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) | Err(ureq::Error::Status(410, _)) => {
+                return Err(RegistryError::NotFound(package.to_string()).into());
+            }
+            Err(e) => {
+                return Err(RegistryError::Fetch {
+                    reason: e.to_string(),
+                    url,
+                }
+                .into())
+            }
+        };
+
+        let body = response.into_string()?;
+        Ok(parse_releases(&body))
+    }
+}
+
+/// Parse the releases listed in an index file, skipping lines which can't be
+/// parsed (e.g. releases whose versions use a syntax newer than we support)
+/// rather than failing the whole package
+fn parse_releases(body: &str) -> Vec<IndexPackage> {
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(parse_release)
+        .collect()
+}
+
+/// Parse a single line of an index file
+fn parse_release(line: &str) -> Option<IndexPackage> {
+    let entry: Entry = serde_json::from_str(line).ok()?;
+
+    Some(IndexPackage {
+        package: entry.name.parse().ok()?,
+        version: entry.vers.parse().ok()?,
+        is_yanked: entry.yanked,
+        checksum: entry.cksum.as_deref().and_then(|cksum| cksum.parse().ok()),
+        published: entry
+            .pubtime
+            .as_deref()
+            .and_then(|pubtime| humantime::parse_rfc3339_weak(pubtime).ok()),
+    })
+}
+
+/// Entry in an index file (one per line, each describing a release)
+#[derive(Deserialize)]
+struct Entry {
+    /// Name of the package
+    name: String,
+
+    /// Version of the release
+    vers: String,
+
+    /// Is this release yanked?
+    #[serde(default)]
+    yanked: bool,
+
+    /// SHA-256 checksum of the `.crate` file (hex-encoded)
+    #[serde(default)]
+    cksum: Option<String>,
+
+    /// When the release was published (RFC 3339, only present for releases
+    /// published since crates.io started recording it in the index)
+    #[serde(default)]
+    pubtime: Option<String>,
+}
+
+/// Compute the path of a package's index file, following the same layout as
+/// the git index (e.g. `se/rd/serde`)
+fn file_path(name: &str) -> String {
+    let name = name.to_lowercase();
+
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_path, parse_releases};
+
+    #[test]
+    fn index_file_paths() {
+        assert_eq!(file_path("a"), "1/a");
+        assert_eq!(file_path("ab"), "2/ab");
+        assert_eq!(file_path("abc"), "3/a/abc");
+        assert_eq!(file_path("Serde_JSON"), "se/rd/serde_json");
+    }
+
+    #[test]
+    fn skip_malformed_lines() {
+        let body = r#"{"name":"base","vers":"1.0.0","yanked":false}
+not json
+{"name":"base","vers":"not.a.version"}
+
+{"name":"base","vers":"1.1.0","yanked":true}
+"#;
+
+        let releases = parse_releases(body);
+        let versions = releases
+            .iter()
+            .map(|release| (release.version.to_string(), release.is_yanked))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            versions,
+            [("1.0.0".to_owned(), false), ("1.1.0".to_owned(), true)]
+        );
+    }
+}
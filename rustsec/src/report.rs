@@ -3,26 +3,63 @@
 //! These types map directly to the JSON report generated by `cargo-audit`,
 //! but also provide the core reporting functionality used in general.
 
+mod aggregate;
+#[cfg(feature = "dependency-tree")]
+mod duplicates;
+#[cfg(test)]
+mod fixtures;
+mod fork;
+mod glob;
+mod ignore;
+mod naming;
+mod overrides;
+mod owners;
+mod promotion;
+mod review;
+mod stats;
+mod targets;
+mod tool;
+
+#[cfg(feature = "dependency-tree")]
+use self::duplicates::Duplicates;
+#[cfg(feature = "dependency-tree")]
+use crate::cargo_lock::{dependency::Tree, Dependency};
+
+pub use self::{
+    aggregate::{AggregateReport, AggregateSummary, ProjectReport},
+    fork::{AcceptedVulnerability, PatchedFork},
+    ignore::IgnorePattern,
+    naming::{FieldNaming, Named},
+    overrides::Override,
+    owners::Owners,
+    promotion::PromotionRule,
+    review::{ReviewRecords, ReviewStatus, DEFAULT_CRITERIA, SUPPLY_CHAIN_DIR},
+    stats::Stats,
+    targets::TargetReport,
+    tool::ToolInfo,
+};
+
 use crate::{
     advisory,
-    database::{scope, Database, Query},
-    lockfile::Lockfile,
-    map,
-    platforms::target::{Arch, OS},
-    vulnerability::Vulnerability,
+    database::{scope, Database, Query, Source},
+    dependency::{self, Relationship},
+    lockfile::{Lockfile, SkippedEntry},
+    map, package,
+    platforms::{
+        target::{Arch, OS},
+        Platform,
+    },
+    vulnerability::{FixStatus, Vulnerability},
     warning::{self, Warning},
     Map,
 };
 use serde::{Deserialize, Serialize};
-
-#[cfg(feature = "git")]
 use std::time::SystemTime;
 
 /// Vulnerability report for a given lockfile
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Report {
     /// Information about the advisory database
-    #[cfg(feature = "git")]
     pub database: DatabaseInfo,
 
     /// Information about the audited lockfile
@@ -34,57 +71,734 @@ pub struct Report {
     /// Vulnerabilities detected in project
     pub vulnerabilities: VulnerabilityInfo,
 
+    /// Vulnerabilities which were accepted rather than reported, since the
+    /// affected packages come from patched forks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accepted: Vec<AcceptedVulnerability>,
+
     /// Warnings about dependencies (from e.g. informational advisories)
     pub warnings: WarningInfo,
+
+    /// Per-workspace-member summaries of vulnerable dependencies
+    #[serde(
+        rename = "workspace-members",
+        alias = "workspace_members",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub workspace_members: Vec<WorkspaceMemberInfo>,
+
+    /// Notices about parts of the audit which were skipped or degraded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notices: Vec<String>,
+
+    /// Timing and cache diagnostics (only collected on request)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<Stats>,
+
+    /// Tool which generated the report (set by the tool itself)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<ToolInfo>,
+
+    /// Findings affecting each target, when the report was generated for
+    /// several targets (see [`Settings::targets`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<TargetReport>,
 }
 
 impl Report {
     /// Generate a report for the given advisory database and lockfile
     pub fn generate(db: &Database, lockfile: &Lockfile, settings: &Settings) -> Self {
         let package_scope = settings.package_scope.as_ref().cloned().unwrap_or_default();
+        let mut accepted = vec![];
 
         let vulnerabilities = db
             .query_vulnerabilities(lockfile, &settings.query(), package_scope)
             .into_iter()
-            .filter(|vuln| !settings.ignore.contains(&vuln.advisory.id))
-            .collect();
+            .filter(|vuln| !settings.is_ignored(&vuln.advisory))
+            .map(|mut vuln| {
+                vuln.severity =
+                    advisory::SeverityInfo::new(&vuln.advisory, &settings.severity_fallback);
+                vuln
+            })
+            .filter_map(
+                |vuln| match settings.patched_fork(&vuln.package, &vuln.advisory) {
+                    Some(fork) => {
+                        accepted.push(AcceptedVulnerability {
+                            vulnerability: vuln,
+                            patched_fork: fork.clone(),
+                        });
+                        None
+                    }
+                    None => Some(vuln),
+                },
+            )
+            .collect::<Vec<_>>();
 
-        let warnings = find_warnings(db, lockfile, settings);
+        let mut warnings = find_warnings(db, lockfile, settings);
 
-        Self {
-            #[cfg(feature = "git")]
+        // Optionally downgrade vulnerabilities in overridden packages to warnings
+        let (overridden, vulnerabilities): (Vec<_>, Vec<_>) = vulnerabilities
+            .into_iter()
+            .partition(|vuln| settings.overrides_as_warnings && vuln.overridden.is_some());
+
+        for vuln in overridden {
+            let mut warning = Warning::new(
+                warning::Kind::Overridden,
+                &vuln.package,
+                Some(vuln.advisory),
+                Some(vuln.versions),
+            );
+            warning.severity = vuln.severity;
+
+            warnings
+                .entry(warning::Kind::Overridden)
+                .or_insert_with(Vec::new)
+                .push(warning);
+        }
+
+        let mut report = Self {
             database: DatabaseInfo::new(db),
             lockfile: LockfileInfo::new(lockfile),
             settings: settings.clone(),
             vulnerabilities: VulnerabilityInfo::new(vulnerabilities),
+            accepted,
             warnings,
+            workspace_members: vec![],
+            notices: vec![],
+            stats: None,
+            tool: None,
+            targets: vec![],
+        };
+
+        if settings.targets.len() > 1 {
+            report.targets = TargetReport::generate_all(db, lockfile, settings, &settings.targets);
+        }
+
+        report.annotate_owners();
+        report.annotate_dependencies(lockfile, &[]);
+        report.annotate_fix_status(db);
+        report.promote_warnings();
+        report
+    }
+
+    /// Remove vulnerabilities and warnings about packages which are also
+    /// present (with the same version and source) in the given baseline
+    /// lockfile, i.e. only keep findings about packages which were added or
+    /// changed relative to the baseline.
+    ///
+    /// Returns the number of findings which were removed.
+    pub fn exclude_baseline(&mut self, baseline: &Lockfile) -> usize {
+        let baseline = baseline
+            .packages
+            .iter()
+            .map(|package| (&package.name, &package.version, &package.source))
+            .collect::<crate::Set<_>>();
+
+        let is_new = |package: &package::Package| {
+            !baseline.contains(&(&package.name, &package.version, &package.source))
+        };
+
+        let mut removed = 0;
+
+        let mut vulnerabilities = std::mem::take(&mut self.vulnerabilities.list);
+        vulnerabilities.retain(|vuln| is_new(&vuln.package));
+        removed += self.vulnerabilities.count - vulnerabilities.len();
+        self.vulnerabilities = VulnerabilityInfo::new(vulnerabilities);
+
+        for (kind, mut warnings) in std::mem::take(&mut self.warnings) {
+            let count = warnings.len();
+            warnings.retain(|warning| is_new(&warning.package));
+            removed += count - warnings.len();
+
+            if !warnings.is_empty() {
+                self.warnings.insert(kind, warnings);
+            }
+        }
+
+        // Per-target sections duplicate the findings above, so aren't counted
+        for target in &mut self.targets {
+            let mut vulnerabilities = std::mem::take(&mut target.vulnerabilities.list);
+            vulnerabilities.retain(|vuln| is_new(&vuln.package));
+            target.vulnerabilities = VulnerabilityInfo::new(vulnerabilities);
+
+            for warnings in target.warnings.values_mut() {
+                warnings.retain(|warning| is_new(&warning.package));
+            }
+
+            target.warnings.retain(|_, warnings| !warnings.is_empty());
+        }
+
+        removed
+    }
+
+    /// Annotate each vulnerability and warning with the team which owns the
+    /// affected package, according to [`Settings::owners`].
+    ///
+    /// This is done automatically by [`Report::generate`], but needs to be
+    /// repeated if warnings are added to the report afterwards.
+    pub fn annotate_owners(&mut self) {
+        let owners = &self.settings.owners;
+
+        if owners.is_empty() {
+            return;
+        }
+
+        let owner = |package: &package::Package| owners.owner(&package.name).map(String::from);
+
+        for vuln in &mut self.vulnerabilities.list {
+            vuln.owner = owner(&vuln.package);
+        }
+
+        for warning in self.warnings.values_mut().flatten() {
+            warning.owner = owner(&warning.package);
+        }
+    }
+
+    /// Annotate each vulnerability and warning with whether the affected
+    /// package has been reviewed according to the given `cargo vet` records,
+    /// to tell vulnerable but audited packages apart from unreviewed ones.
+    pub fn annotate_review_status(&mut self, records: &ReviewRecords) {
+        for vuln in &mut self.vulnerabilities.list {
+            vuln.review = Some(records.status(&vuln.package.name, &vuln.package.version));
+        }
+
+        for warning in self.warnings.values_mut().flatten() {
+            warning.review = Some(records.status(&warning.package.name, &warning.package.version));
+        }
+    }
+
+    /// Annotate vulnerabilities and warnings with the minimum depth of the
+    /// affected package in the lockfile's dependency graph, and whether it's
+    /// a direct or transitive dependency of a workspace member.
+    ///
+    /// Members are the local packages in the lockfile whose names are in
+    /// `members`. If it's empty, they're the local packages which nothing
+    /// else depends on, which excludes members other members depend on.
+    ///
+    /// This is done automatically by [`Report::generate`] (without a list of
+    /// members), but needs to be repeated if findings are added to the report
+    /// afterwards.
+    pub fn annotate_dependencies(&mut self, lockfile: &Lockfile, members: &[package::Name]) {
+        let depths = dependency::depths(lockfile, members);
+        let depth =
+            |package: &package::Package| depths.get(&(&package.name, &package.version)).copied();
+
+        for vuln in &mut self.vulnerabilities.list {
+            vuln.depth = depth(&vuln.package);
+            vuln.relationship = vuln.depth.map(Relationship::from_depth);
+        }
+
+        for warning in self.warnings.values_mut().flatten() {
+            warning.depth = depth(&warning.package);
+            warning.relationship = warning.depth.map(Relationship::from_depth);
         }
     }
+
+    /// Promote warnings matched by any of the [`Settings::promote`] rules to
+    /// errors, recording the result in [`Warning::promoted`].
+    ///
+    /// Rules with a `max_depth` rely on [`Report::annotate_dependencies`]
+    /// having been run first. Both are done automatically by
+    /// [`Report::generate`], but need to be repeated if warnings are added to
+    /// the report afterwards.
+    pub fn promote_warnings(&mut self) {
+        let rules = &self.settings.promote;
+
+        if rules.is_empty() {
+            return;
+        }
+
+        for warning in self.warnings.values_mut().flatten() {
+            // Checksum mismatches are always errors
+            warning.promoted = warning.is_checksum_mismatch()
+                || rules
+                    .iter()
+                    .any(|rule| rule.matches(warning, warning.depth));
+        }
+    }
+
+    /// Compare the checksums of crates.io packages in the lockfile against
+    /// the registry index, adding a [`warning::Kind::ChecksumMismatch`]
+    /// warning for each package whose checksum differs, since it may have
+    /// been tampered with or substituted.
+    ///
+    /// These warnings are always promoted to errors. Packages without a
+    /// checksum in either the lockfile or the index aren't checked.
+    #[cfg(feature = "db-git")]
+    pub fn check_checksums(&mut self, lockfile: &Lockfile, index: &crate::registry::Index) {
+        for package in &lockfile.packages {
+            let is_crates_io = package
+                .source
+                .as_ref()
+                .map_or(false, |source| source.is_default_registry());
+
+            let locked = match &package.checksum {
+                Some(checksum) if is_crates_io => checksum,
+                _ => continue,
+            };
+
+            let expected = match index.find(&package.name, &package.version) {
+                Ok(release) => match release.checksum {
+                    Some(checksum) => checksum,
+                    None => continue,
+                },
+                Err(_) => continue,
+            };
+
+            if locked == &expected {
+                continue;
+            }
+
+            let mut warning = Warning::new(warning::Kind::ChecksumMismatch, package, None, None);
+            warning.expected_checksum = Some(expected);
+            warning.promoted = true;
+
+            self.warnings
+                .entry(warning::Kind::ChecksumMismatch)
+                .or_insert_with(Vec::new)
+                .push(warning);
+        }
+    }
+
+    /// Determine how each vulnerability can be fixed by looking up the
+    /// releases of the vulnerable package in the registry index, populating
+    /// [`Vulnerability::remedy`] and refining [`Vulnerability::remediation`].
+    ///
+    /// Vulnerabilities about packages which aren't in the index are left
+    /// unclassified.
+    #[cfg(feature = "db-git")]
+    pub fn classify_remedies(&mut self, index: &crate::registry::Index) {
+        for vuln in &mut self.vulnerabilities.list {
+            let releases = match index.releases(&vuln.package.name) {
+                Ok(releases) => releases,
+                Err(_) => continue,
+            };
+
+            let releases = releases
+                .iter()
+                .filter(|release| !release.is_yanked)
+                .map(|release| &release.version)
+                .collect::<Vec<_>>();
+
+            vuln.remedy = Some(crate::vulnerability::Remedy::new(
+                &vuln.package,
+                &vuln.versions,
+                releases.iter().cloned(),
+            ));
+
+            let remediation = crate::vulnerability::Remediation::from_releases(
+                &vuln.package,
+                &vuln.versions,
+                &vuln.advisory.description,
+                releases,
+            );
+
+            let unmaintained = vuln.fix_status == Some(FixStatus::Unmaintained);
+            vuln.fix_status = Some(FixStatus::new(&remediation, unmaintained));
+            vuln.remediation = Some(remediation);
+        }
+    }
+
+    /// Annotate each vulnerability with when the earliest release fixing it
+    /// was published, and how many days ago that was (as of `now`).
+    ///
+    /// A release fixes a vulnerability if it's newer than the vulnerable
+    /// version, not yanked, and not vulnerable itself. Vulnerabilities are
+    /// left unannotated if the index has no publication date for the fix.
+    #[cfg(feature = "db-git")]
+    pub fn annotate_fix_age(&mut self, index: &crate::registry::Index, now: SystemTime) {
+        for vuln in &mut self.vulnerabilities.list {
+            let releases = match index.releases(&vuln.package.name) {
+                Ok(releases) => releases,
+                Err(_) => continue,
+            };
+
+            let package_version = &vuln.package.version;
+            let versions = &vuln.versions;
+
+            vuln.fix_age = releases
+                .into_iter()
+                .filter(|release| {
+                    !release.is_yanked
+                        && crate::version::cmp_precedence(&release.version, package_version)
+                            == std::cmp::Ordering::Greater
+                        && !versions.is_vulnerable(&release.version)
+                })
+                .min_by(|a, b| crate::version::cmp_precedence(&a.version, &b.version))
+                .and_then(|fix| {
+                    let published = fix.published?;
+                    Some(crate::vulnerability::FixAge::new(
+                        fix.version,
+                        published,
+                        now,
+                    ))
+                });
+        }
+    }
+
+    /// Annotate each vulnerability with whether a fix for it has been
+    /// released, or is unlikely to be because the database has an
+    /// unmaintained advisory about the crate.
+    ///
+    /// This is done automatically by [`Report::generate`] (based on the
+    /// patched versions listed in advisories), but needs to be repeated after
+    /// [`Report::classify_remedies`] to account for crates which turn out to
+    /// have no fixed release.
+    pub fn annotate_fix_status(&mut self, db: &Database) {
+        for vuln in &mut self.vulnerabilities.list {
+            let remediation = match &vuln.remediation {
+                Some(remediation) => remediation,
+                None => continue,
+            };
+
+            let query = Query::crate_scope()
+                .package(vuln.package.name.clone())
+                .informational_kind(advisory::Informational::Unmaintained);
+
+            let unmaintained = !db.query(&query).is_empty();
+            vuln.fix_status = Some(FixStatus::new(remediation, unmaintained));
+        }
+    }
+
+    /// Annotate vulnerabilities with at most `limit` of the shortest inverse
+    /// dependency paths from the vulnerable package to a root package (see
+    /// [`cargo_lock::dependency::Tree::shortest_inverse_paths`]), populating
+    /// [`Vulnerability::affected_paths`].
+    ///
+    /// Dependency cycles (which are possible via dev-dependencies) are
+    /// handled, and only as much of the graph is explored as is needed to
+    /// find the paths, so this is cheap even for large lockfiles.
+    #[cfg(feature = "dependency-tree")]
+    pub fn annotate_affected_paths(
+        &mut self,
+        lockfile: &Lockfile,
+        limit: usize,
+    ) -> Result<(), crate::Error> {
+        let tree = lockfile.dependency_tree()?;
+
+        for vuln in &mut self.vulnerabilities.list {
+            vuln.affected_paths = shortest_paths(&tree, &vuln.package, limit);
+        }
+
+        Ok(())
+    }
+
+    /// Add a [`warning::Kind::Duplicate`] warning for each package which is
+    /// present in the lockfile in several versions, other than the newest.
+    ///
+    /// Older copies of packages bloat builds and are often where stale,
+    /// vulnerable code lingers. Each warning lists all of the versions which
+    /// are present, along with at most `limit` of the shortest dependency
+    /// paths keeping the older version in the graph.
+    #[cfg(feature = "dependency-tree")]
+    pub fn check_duplicates(
+        &mut self,
+        lockfile: &Lockfile,
+        limit: usize,
+    ) -> Result<(), crate::Error> {
+        let tree = lockfile.dependency_tree()?;
+
+        for duplicates in Duplicates::find(lockfile) {
+            let versions = duplicates.versions();
+
+            for package in duplicates.older() {
+                let mut warning = Warning::new(warning::Kind::Duplicate, package, None, None);
+                warning.duplicate_versions = versions.clone();
+                warning.affected_paths = shortest_paths(&tree, package, limit);
+
+                self.warnings
+                    .entry(warning::Kind::Duplicate)
+                    .or_insert_with(Vec::new)
+                    .push(warning);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add a [`warning::Kind::OwnCrate`] warning for each advisory about one
+    /// of the project's own local packages (e.g. workspace members which are
+    /// also published to crates.io), i.e. advisories the project is the
+    /// subject of rather than affected by through its dependencies.
+    ///
+    /// Local packages are normally excluded from audits, so this is skipped
+    /// when the package scope includes them (they're reported as usual).
+    pub fn check_own_crates(&mut self, db: &Database, lockfile: &Lockfile) {
+        let package_scope = self
+            .settings
+            .package_scope
+            .as_ref()
+            .cloned()
+            .unwrap_or_default();
+
+        if !package_scope.is_remote() {
+            return;
+        }
+
+        let mut own_crate_warnings = vec![];
+
+        for package in &lockfile.packages {
+            let is_local = package
+                .source
+                .as_ref()
+                .map_or(true, |source| source.is_path());
+
+            if !is_local {
+                continue;
+            }
+
+            for &informational in &[false, true] {
+                let query = self
+                    .settings
+                    .query()
+                    .informational(informational)
+                    .package_version(package.name.clone(), package.version.clone());
+
+                for advisory in db.query(&query) {
+                    if self.settings.is_ignored(&advisory.metadata) {
+                        continue;
+                    }
+
+                    own_crate_warnings.push(Warning::new(
+                        warning::Kind::OwnCrate,
+                        package,
+                        Some(advisory.metadata.clone()),
+                        Some(advisory.versions.clone()),
+                    ));
+                }
+            }
+        }
+
+        if !own_crate_warnings.is_empty() {
+            self.warnings
+                .entry(warning::Kind::OwnCrate)
+                .or_insert_with(Vec::new)
+                .extend(own_crate_warnings);
+        }
+    }
+
+    /// Add a [`warning::Kind::MalformedLockfile`] warning for each of the
+    /// given entries which were skipped when parsing the lockfile leniently
+    /// (see [`Lockfile::parse_lenient`]), so the parts of the lockfile which
+    /// weren't audited can be denied like any other warning.
+    ///
+    /// Warnings are about the package the entry belongs to. Entries which
+    /// can't be attributed to a package (e.g. malformed sections) are
+    /// attributed to the lockfile's root package, or else its first local
+    /// package, and are omitted if there's neither.
+    pub fn check_malformed_entries(&mut self, lockfile: &Lockfile, skipped: &[SkippedEntry]) {
+        let fallback = lockfile.root.as_ref().or_else(|| {
+            lockfile
+                .packages
+                .iter()
+                .find(|package| package.source.is_none())
+        });
+
+        let mut malformed_warnings = vec![];
+
+        for entry in skipped {
+            let package = match &entry.package {
+                Some(dependency) => lockfile
+                    .packages
+                    .iter()
+                    .find(|package| dependency.matches(package))
+                    .cloned()
+                    .unwrap_or_else(|| package::Package {
+                        name: dependency.name.clone(),
+                        version: dependency.version.clone(),
+                        source: dependency.source.clone(),
+                        checksum: None,
+                        dependencies: vec![],
+                        replace: None,
+                    }),
+                None => match fallback {
+                    Some(package) => package.clone(),
+                    None => continue,
+                },
+            };
+
+            let mut warning = Warning::new(warning::Kind::MalformedLockfile, &package, None, None);
+            warning.malformed_entry = Some(format!("{}: {}", entry.entry, entry.reason));
+            malformed_warnings.push(warning);
+        }
+
+        if !malformed_warnings.is_empty() {
+            self.warnings
+                .entry(warning::Kind::MalformedLockfile)
+                .or_insert_with(Vec::new)
+                .extend(malformed_warnings);
+        }
+    }
+
+    /// Summarize the vulnerable dependencies of each of the given workspace
+    /// members, populating [`Report::workspace_members`].
+    ///
+    /// Members are local packages (i.e. ones without a source) in the
+    /// lockfile whose names are in `members`.
+    #[cfg(feature = "dependency-tree")]
+    pub fn summarize_workspace_members(
+        &mut self,
+        lockfile: &Lockfile,
+        members: &[package::Name],
+    ) -> Result<(), crate::Error> {
+        use crate::Set;
+
+        let tree = lockfile.dependency_tree()?;
+        let graph = tree.graph();
+        let mut summaries = vec![];
+
+        for (dependency, &root) in tree.nodes() {
+            let member = &graph[root];
+
+            if member.source.is_some() || !members.contains(&member.name) {
+                continue;
+            }
+
+            let deps = tree
+                .transitive_dependencies(root)
+                .iter()
+                .map(|&node| Dependency::from(&graph[node]))
+                .collect::<Set<_>>();
+
+            let vulns = self
+                .vulnerabilities
+                .list
+                .iter()
+                .filter(|vuln| deps.contains(&Dependency::from(&vuln.package)))
+                .collect::<Vec<_>>();
+
+            summaries.push(WorkspaceMemberInfo {
+                name: dependency.name.clone(),
+                version: dependency.version.clone(),
+                vulnerable_dependencies: vulns
+                    .iter()
+                    .map(|vuln| (&vuln.package.name, &vuln.package.version))
+                    .collect::<Set<_>>()
+                    .len(),
+                highest_severity: vulns
+                    .iter()
+                    .filter_map(|vuln| vuln.severity.map(|info| info.severity))
+                    .max(),
+            });
+        }
+
+        summaries.sort_by(|a, b| a.name.cmp(&b.name));
+        self.workspace_members = summaries;
+        Ok(())
+    }
 }
 
 /// Options to use when generating the report
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Settings {
     /// CPU architecture
+    #[serde(alias = "target-arch")]
     pub target_arch: Option<Arch>,
 
     /// Operating system
+    #[serde(alias = "target-os")]
     pub target_os: Option<OS>,
 
+    /// Target triples (e.g. `x86_64-unknown-linux-musl`): advisories which
+    /// are specific to other platforms are omitted. Unknown triples are
+    /// ignored.
+    ///
+    /// When there are several, the report also contains a section for each
+    /// one (see [`Report::targets`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+
+    /// CPU architectures to exclude: advisories which only affect these are
+    /// omitted, even if they match the targets above
+    #[serde(
+        alias = "exclude-target-arch",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub exclude_target_arch: Vec<Arch>,
+
+    /// Operating systems to exclude: advisories which only affect these are
+    /// omitted, even if they match the targets above
+    #[serde(
+        alias = "exclude-target-os",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub exclude_target_os: Vec<OS>,
+
     /// Severity threshold to alert at
     pub severity: Option<advisory::Severity>,
 
-    /// List of advisory IDs to ignore
-    pub ignore: Vec<advisory::Id>,
+    /// Rules for matching pre-release versions in the lockfile
+    #[serde(default)]
+    pub prerelease: advisory::PrereleaseMatching,
+
+    /// Severities for advisories without CVSS information, by advisory type
+    #[serde(alias = "severity-fallback", default)]
+    pub severity_fallback: advisory::SeverityFallback,
+
+    /// Advisory IDs, or patterns matching advisory IDs or package names,
+    /// to ignore (see [`IgnorePattern`])
+    pub ignore: Vec<IgnorePattern>,
 
     /// Types of informational advisories to generate warnings for
+    #[serde(alias = "informational-warnings")]
     pub informational_warnings: Vec<advisory::Informational>,
 
     /// Scope of packages which should be considered for audit
+    #[serde(alias = "package-scope")]
     pub package_scope: Option<scope::Package>,
+
+    /// Audit against the advisory database as it was on this date
+    #[serde(alias = "as-of", default)]
+    pub as_of: Option<advisory::Date>,
+
+    /// Teams which own packages, used to annotate vulnerabilities and warnings
+    #[serde(default, skip_serializing_if = "Owners::is_empty")]
+    pub owners: Owners,
+
+    /// Rules which promote warnings to errors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub promote: Vec<PromotionRule>,
+
+    /// Git sources of patched forks, whose vulnerabilities are accepted
+    /// rather than reported
+    #[serde(
+        alias = "patched-forks",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub patched_forks: Vec<PatchedFork>,
+
+    /// Report vulnerabilities in packages overridden via `[patch]` or
+    /// `[replace]` (see [`Override`]) as warnings rather than errors
+    #[serde(alias = "overrides-as-warnings", default)]
+    pub overrides_as_warnings: bool,
+
+    /// Only report on the advisories with these IDs (see [`Query::only_ids`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<advisory::Id>,
 }
 
 impl Settings {
+    /// Is the given advisory matched by any of the ignore patterns?
+    pub fn is_ignored(&self, metadata: &advisory::Metadata) -> bool {
+        self.ignore.iter().any(|pattern| pattern.matches(metadata))
+    }
+
+    /// Find the patched fork which exempts the given package from the given
+    /// advisory, if any
+    pub fn patched_fork(
+        &self,
+        package: &package::Package,
+        metadata: &advisory::Metadata,
+    ) -> Option<&PatchedFork> {
+        self.patched_forks
+            .iter()
+            .find(|fork| fork.matches(package, metadata))
+    }
+
     /// Get a query which corresponds to the configured report settings.
     /// Note that queries can't filter ignored advisories, so this happens in
     /// a separate pass
@@ -99,39 +813,86 @@ impl Settings {
             query = query.target_os(target_os);
         }
 
+        for target in &self.targets {
+            if let Some(platform) = Platform::find(target) {
+                query = query.target(platform);
+            }
+        }
+
+        for &arch in &self.exclude_target_arch {
+            query = query.exclude_target_arch(arch);
+        }
+
+        for &os in &self.exclude_target_os {
+            query = query.exclude_target_os(os);
+        }
+
         if let Some(severity) = self.severity {
             query = query.severity(severity);
         }
 
+        query = query.prerelease_matching(self.prerelease);
+
+        if !self.severity_fallback.is_empty() {
+            query = query.severity_fallback(self.severity_fallback.clone());
+        }
+
+        if let Some(as_of) = &self.as_of {
+            query = query.as_of(as_of.clone());
+        }
+
+        if !self.only.is_empty() {
+            query = query.only_ids(&self.only);
+        }
+
         query
     }
 }
 
 /// Information about the advisory database
-#[cfg(feature = "git")]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct DatabaseInfo {
     /// Number of advisories in the database
-    #[serde(rename = "advisory-count")]
+    #[serde(rename = "advisory-count", alias = "advisory_count")]
     pub advisory_count: usize,
 
-    /// Git commit hash for the last commit to the database
-    #[serde(rename = "last-commit")]
+    /// Git commit hash for the last commit to the database (`None` if it
+    /// wasn't loaded from a git checkout)
+    #[serde(rename = "last-commit", alias = "last_commit", default)]
     pub last_commit: Option<String>,
 
-    /// Date when the advisory database was last committed to
-    #[serde(rename = "last-updated", with = "humantime_serde")]
+    /// Date when the advisory database was last committed to (`None` if it
+    /// wasn't loaded from a git checkout)
+    #[serde(
+        rename = "last-updated",
+        alias = "last_updated",
+        default,
+        with = "humantime_serde"
+    )]
     pub last_updated: Option<SystemTime>,
+
+    /// Where the advisory database was loaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
 }
 
-#[cfg(feature = "git")]
 impl DatabaseInfo {
     /// Create database information from the advisory db
     pub fn new(db: &Database) -> Self {
+        #[cfg(feature = "db-git")]
+        let (last_commit, last_updated) = match db.latest_commit() {
+            Some(commit) => (Some(commit.commit_id.clone()), Some(commit.timestamp)),
+            None => (None, None),
+        };
+
+        #[cfg(not(feature = "db-git"))]
+        let (last_commit, last_updated) = (None, None);
+
         Self {
             advisory_count: db.iter().count(),
-            last_commit: db.latest_commit().map(|c| c.commit_id.clone()),
-            last_updated: db.latest_commit().map(|c| c.timestamp),
+            last_commit,
+            last_updated,
+            source: Some(db.source().clone()),
         }
     }
 }
@@ -140,8 +901,18 @@ impl DatabaseInfo {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LockfileInfo {
     /// Number of dependencies in the lock file
-    #[serde(rename = "dependency-count")]
+    #[serde(rename = "dependency-count", alias = "dependency_count")]
     dependency_count: usize,
+
+    /// Is the lock file in sync with `Cargo.toml`? (`None` if not checked,
+    /// e.g. because `cargo audit --locked` wasn't used)
+    #[serde(
+        rename = "in-sync",
+        alias = "in_sync",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub in_sync: Option<bool>,
 }
 
 impl LockfileInfo {
@@ -149,6 +920,7 @@ impl LockfileInfo {
     pub fn new(lockfile: &Lockfile) -> Self {
         Self {
             dependency_count: lockfile.packages.len(),
+            in_sync: None,
         }
     }
 }
@@ -177,6 +949,30 @@ impl VulnerabilityInfo {
     }
 }
 
+/// Summary of the vulnerable dependencies of a workspace member
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WorkspaceMemberInfo {
+    /// Name of the workspace member
+    pub name: package::Name,
+
+    /// Version of the workspace member
+    pub version: crate::Version,
+
+    /// Number of vulnerable packages among the member's transitive dependencies
+    #[serde(rename = "vulnerable-dependencies", alias = "vulnerable_dependencies")]
+    pub vulnerable_dependencies: usize,
+
+    /// Highest severity of the vulnerabilities affecting the member
+    /// (`None` if unknown or there are no vulnerabilities)
+    #[serde(
+        rename = "highest-severity",
+        alias = "highest_severity",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub highest_severity: Option<advisory::Severity>,
+}
+
 /// Information about warnings
 pub type WarningInfo = Map<warning::Kind, Vec<Warning>>;
 
@@ -189,34 +985,7 @@ pub fn find_warnings(db: &Database, lockfile: &Lockfile, settings: &Settings) ->
 
     // TODO(tarcieri): abstract `Cargo.lock` query logic between vulnerabilities/warnings
     for advisory_vuln in db.query_vulnerabilities(lockfile, &query, package_scope) {
-        let advisory = &advisory_vuln.advisory;
-
-        if settings.ignore.contains(&advisory.id) {
-            continue;
-        }
-
-        if settings
-            .informational_warnings
-            .iter()
-            .any(|info| Some(info) == advisory.informational.as_ref())
-        {
-            let warning_kind = match advisory
-                .informational
-                .as_ref()
-                .expect("informational advisory")
-                .warning_kind()
-            {
-                Some(kind) => kind,
-                None => continue,
-            };
-
-            let warning = Warning::new(
-                warning_kind,
-                &advisory_vuln.package,
-                Some(advisory.clone()),
-                Some(advisory_vuln.versions.clone()),
-            );
-
+        if let Some(warning) = informational_warning(&advisory_vuln, settings) {
             match warnings.entry(warning.kind) {
                 map::Entry::Occupied(entry) => (*entry.into_mut()).push(warning),
                 map::Entry::Vacant(entry) => {
@@ -228,3 +997,120 @@ pub fn find_warnings(db: &Database, lockfile: &Lockfile, settings: &Settings) ->
 
     warnings
 }
+
+/// A finding which causes an audit to fail
+#[derive(Clone, Debug)]
+pub enum Finding {
+    /// Vulnerable package
+    Vulnerability(Vulnerability),
+
+    /// Warning of a denied kind
+    Warning(Warning),
+}
+
+/// Find the first vulnerability, or warning of one of the `deny` kinds,
+/// without generating a full report.
+///
+/// Vulnerabilities take precedence over warnings. Warnings which can't be
+/// determined from the advisory database alone (i.e. yanked crates) are
+/// never returned.
+pub fn find_first(
+    db: &Database,
+    lockfile: &Lockfile,
+    settings: &Settings,
+    deny: &[warning::Kind],
+) -> Option<Finding> {
+    let package_scope = settings.package_scope.as_ref().cloned().unwrap_or_default();
+
+    let query = settings.query();
+    let vulnerability = db
+        .iter_vulnerabilities(lockfile, &query, package_scope.clone())
+        .find(|vuln| {
+            !settings.is_ignored(&vuln.advisory)
+                && settings
+                    .patched_fork(&vuln.package, &vuln.advisory)
+                    .is_none()
+        });
+
+    if let Some(vuln) = vulnerability {
+        return Some(Finding::Vulnerability(vuln));
+    }
+
+    if deny.is_empty() && settings.promote.is_empty() {
+        return None;
+    }
+
+    let depths = if settings.promote.is_empty() {
+        Map::new()
+    } else {
+        dependency::depths(lockfile, &[])
+    };
+
+    let query = settings.query().informational(true);
+    let finding = db
+        .iter_vulnerabilities(lockfile, &query, package_scope)
+        .filter_map(|vuln| informational_warning(&vuln, settings))
+        .find(|warning| {
+            let depth = depths
+                .get(&(&warning.package.name, &warning.package.version))
+                .copied();
+
+            deny.contains(&warning.kind)
+                || settings
+                    .promote
+                    .iter()
+                    .any(|rule| rule.matches(warning, depth))
+        })
+        .map(Finding::Warning);
+
+    finding
+}
+
+/// Get at most `limit` of the shortest inverse dependency paths from the
+/// given package to a root package
+#[cfg(feature = "dependency-tree")]
+fn shortest_paths(
+    tree: &Tree,
+    package: &package::Package,
+    limit: usize,
+) -> Option<Vec<Vec<Dependency>>> {
+    let node = *tree.nodes().get(&Dependency::from(package))?;
+    let graph = tree.graph();
+
+    let paths = tree
+        .shortest_inverse_paths(node, limit)
+        .into_iter()
+        .map(|path| {
+            path.into_iter()
+                .map(|node| Dependency::from(&graph[node]))
+                .collect()
+        })
+        .collect();
+
+    Some(paths)
+}
+
+/// Convert a match for an informational advisory into a [`Warning`], if
+/// the advisory isn't ignored and warnings are enabled for its kind
+fn informational_warning(advisory_vuln: &Vulnerability, settings: &Settings) -> Option<Warning> {
+    let advisory = &advisory_vuln.advisory;
+
+    if settings.is_ignored(advisory) {
+        return None;
+    }
+
+    let informational = advisory.informational.as_ref()?;
+
+    if !settings.informational_warnings.contains(informational) {
+        return None;
+    }
+
+    let mut warning = Warning::new(
+        informational.warning_kind()?,
+        &advisory_vuln.package,
+        Some(advisory.clone()),
+        Some(advisory_vuln.versions.clone()),
+    );
+    warning.severity = advisory::SeverityInfo::new(advisory, &settings.severity_fallback);
+    Some(warning)
+}
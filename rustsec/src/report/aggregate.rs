@@ -0,0 +1,120 @@
+//! Reports aggregated across several projects
+
+use super::Report;
+use crate::Set;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Report aggregated across several projects (e.g. all of the `Cargo.lock`
+/// files in a directory tree), grouped by project
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AggregateReport {
+    /// Summary of the findings across all projects
+    pub summary: AggregateSummary,
+
+    /// Reports for each of the audited projects
+    pub projects: Vec<ProjectReport>,
+
+    /// Notices about projects which couldn't be audited
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notices: Vec<String>,
+}
+
+impl AggregateReport {
+    /// Aggregate the reports for the given projects
+    pub fn new(projects: Vec<ProjectReport>) -> Self {
+        Self {
+            summary: AggregateSummary::new(&projects),
+            projects,
+            notices: vec![],
+        }
+    }
+}
+
+/// Report for an individual project within an [`AggregateReport`]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProjectReport {
+    /// Path to the project's lockfile
+    pub lockfile: PathBuf,
+
+    /// Report for the project
+    pub report: Report,
+}
+
+/// Summary of the findings across all projects in an [`AggregateReport`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AggregateSummary {
+    /// Number of projects audited
+    pub projects: usize,
+
+    /// Number of projects with at least one vulnerability
+    #[serde(rename = "vulnerable-projects", alias = "vulnerable_projects")]
+    pub vulnerable_projects: usize,
+
+    /// Total number of vulnerabilities across all projects
+    pub vulnerabilities: usize,
+
+    /// Number of distinct vulnerable package versions and advisories, i.e.
+    /// counting vulnerabilities found in several projects once
+    #[serde(rename = "unique-vulnerabilities", alias = "unique_vulnerabilities")]
+    pub unique_vulnerabilities: usize,
+
+    /// Number of distinct advisories for which vulnerabilities were found
+    #[serde(rename = "unique-advisories", alias = "unique_advisories")]
+    pub unique_advisories: usize,
+
+    /// Total number of warnings across all projects
+    pub warnings: usize,
+
+    /// Number of distinct warnings, i.e. counting warnings about the same
+    /// package version found in several projects once
+    #[serde(rename = "unique-warnings", alias = "unique_warnings")]
+    pub unique_warnings: usize,
+}
+
+impl AggregateSummary {
+    /// Summarize the findings in the given project reports
+    pub fn new(projects: &[ProjectReport]) -> Self {
+        let mut summary = Self {
+            projects: projects.len(),
+            ..Self::default()
+        };
+
+        let mut vulnerabilities = Set::new();
+        let mut advisories = Set::new();
+        let mut warnings = Set::new();
+
+        for project in projects {
+            let report = &project.report;
+
+            if report.vulnerabilities.found {
+                summary.vulnerable_projects += 1;
+            }
+
+            for vuln in &report.vulnerabilities.list {
+                summary.vulnerabilities += 1;
+                advisories.insert(&vuln.advisory.id);
+                vulnerabilities.insert((
+                    &vuln.advisory.id,
+                    &vuln.package.name,
+                    &vuln.package.version,
+                ));
+            }
+
+            for warning in report.warnings.values().flatten() {
+                summary.warnings += 1;
+                warnings.insert((
+                    warning.kind,
+                    warning.advisory.as_ref().map(|advisory| &advisory.id),
+                    &warning.package.name,
+                    &warning.package.version,
+                ));
+            }
+        }
+
+        summary.unique_vulnerabilities = vulnerabilities.len();
+        summary.unique_advisories = advisories.len();
+        summary.unique_warnings = warnings.len();
+        summary
+    }
+}
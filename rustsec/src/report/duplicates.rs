@@ -0,0 +1,117 @@
+//! Packages present in several versions in a lockfile
+
+use crate::{lockfile::Lockfile, package::Package, version::cmp_precedence, Map, Version};
+use std::cmp::Ordering;
+
+/// Packages with the same name present in several versions in a lockfile
+#[derive(Clone, Debug)]
+pub(crate) struct Duplicates<'a> {
+    /// All of the packages with the name, from oldest to newest version
+    pub packages: Vec<&'a Package>,
+}
+
+impl<'a> Duplicates<'a> {
+    /// Find the packages present in several versions in the given lockfile.
+    ///
+    /// Packages with the same version from different sources (e.g. a git
+    /// fork of a crates.io package) aren't considered duplicates unless
+    /// other versions are present too.
+    pub fn find(lockfile: &'a Lockfile) -> Vec<Self> {
+        let mut by_name = Map::new();
+
+        for package in &lockfile.packages {
+            by_name
+                .entry(&package.name)
+                .or_insert_with(Vec::new)
+                .push(package);
+        }
+
+        by_name
+            .into_iter()
+            .filter_map(|(_, mut packages)| {
+                packages.sort_by(|a, b| cmp_precedence(&a.version, &b.version));
+
+                let newest = &packages.last()?.version;
+                let is_duplicated = packages
+                    .iter()
+                    .any(|package| cmp_precedence(&package.version, newest) == Ordering::Less);
+
+                if is_duplicated {
+                    Some(Self { packages })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Get the packages whose version is older than the newest one present
+    pub fn older(&self) -> impl Iterator<Item = &'a Package> + '_ {
+        let newest = &self.packages.last().unwrap().version;
+
+        self.packages
+            .iter()
+            .copied()
+            .filter(move |package| cmp_precedence(&package.version, newest) == Ordering::Less)
+    }
+
+    /// Get the distinct versions present, from oldest to newest
+    pub fn versions(&self) -> Vec<Version> {
+        let mut versions = self
+            .packages
+            .iter()
+            .map(|package| package.version.clone())
+            .collect::<Vec<_>>();
+
+        versions.dedup();
+        versions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Duplicates;
+    use crate::lockfile::Lockfile;
+
+    #[test]
+    fn find_duplicates() {
+        let lockfile: Lockfile = r#"
+[[package]]
+name = "base64"
+version = "0.13.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "base64"
+version = "0.9.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "base64"
+version = "0.9.3"
+source = "git+https://github.com/example/base64#0123456789abcdef0123456789abcdef01234567"
+
+[[package]]
+name = "smallvec"
+version = "1.6.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#
+        .parse()
+        .unwrap();
+
+        let duplicates = Duplicates::find(&lockfile);
+        assert_eq!(duplicates.len(), 1);
+
+        let base64 = &duplicates[0];
+        assert_eq!(
+            base64.versions(),
+            vec!["0.9.3".parse().unwrap(), "0.13.0".parse().unwrap()]
+        );
+
+        let older = base64.older().collect::<Vec<_>>();
+        assert_eq!(older.len(), 2);
+        assert!(older
+            .iter()
+            .all(|package| package.version == "0.9.3".parse().unwrap()));
+    }
+}
@@ -0,0 +1,25 @@
+//! Packages and advisory metadata shared by the tests of report settings
+
+use crate::{advisory::Metadata, package::Package};
+
+/// Version 1.0.0 of the `base` package from the given source
+pub(super) fn package(source: &str) -> Package {
+    Package {
+        name: "base".parse().unwrap(),
+        version: "1.0.0".parse().unwrap(),
+        source: Some(source.parse().unwrap()),
+        checksum: None,
+        dependencies: vec![],
+        replace: None,
+    }
+}
+
+/// Metadata of an advisory with the given ID and aliases about the given
+/// package
+pub(super) fn metadata(id: &str, package: &str, aliases: &[&str]) -> Metadata {
+    toml::from_str(&format!(
+        "id = \"{}\"\npackage = \"{}\"\ndate = \"2019-01-01\"\naliases = {:?}",
+        id, package, aliases
+    ))
+    .unwrap()
+}
@@ -0,0 +1,221 @@
+//! Patched forks of vulnerable packages, whose vulnerabilities are accepted
+//! rather than reported
+
+use super::IgnorePattern;
+use crate::{
+    advisory,
+    error::{Error, ErrorKind},
+    package::Package,
+    vulnerability::Vulnerability,
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Minimum length of (abbreviated) fork revisions, so they can't match
+/// unrelated commits by accident (the same minimum as `git` abbreviations)
+const MIN_REV_LEN: usize = 7;
+
+/// Git source of patched forks of packages (e.g. used via `[patch]`),
+/// exempting packages from that source from the given advisories.
+///
+/// Parsed from `URL` or `URL#REV` when given on the command line.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatchedFork {
+    /// URL of the git repository the fork comes from
+    pub url: String,
+
+    /// Commit the fork must be locked to (may be abbreviated to at least 7
+    /// hex characters), or any commit if `None`
+    #[serde(
+        default,
+        deserialize_with = "deserialize_rev",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rev: Option<String>,
+
+    /// Advisories the fork is patched against, or all advisories if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub advisories: Vec<IgnorePattern>,
+}
+
+impl PatchedFork {
+    /// Does this fork exempt the given package from the given advisory?
+    pub fn matches(&self, package: &Package, metadata: &advisory::Metadata) -> bool {
+        let source = match &package.source {
+            Some(source) if source.is_git() => source,
+            _ => return false,
+        };
+
+        if normalize_url(source.url().as_str()) != normalize_url(&self.url) {
+            return false;
+        }
+
+        if let Some(rev) = &self.rev {
+            match source.precise() {
+                Some(precise) if precise.starts_with(rev.as_str()) => (),
+                _ => return false,
+            }
+        }
+
+        self.advisories.is_empty()
+            || self
+                .advisories
+                .iter()
+                .any(|pattern| pattern.matches(metadata))
+    }
+}
+
+impl Display for PatchedFork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.url)?;
+
+        if let Some(rev) = &self.rev {
+            write!(f, "#{}", rev)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PatchedFork {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut parts = s.splitn(2, '#');
+        let url = parts.next().unwrap_or_default();
+
+        if url.is_empty() {
+            fail!(ErrorKind::Parse, "empty patched fork URL: {}", s);
+        }
+
+        let rev = match parts.next() {
+            Some(rev) => Some(parse_rev(rev)?),
+            None => None,
+        };
+
+        Ok(Self {
+            url: url.to_owned(),
+            rev,
+            advisories: vec![],
+        })
+    }
+}
+
+/// Vulnerability which was accepted rather than reported, since the affected
+/// package comes from a patched fork
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AcceptedVulnerability {
+    /// Vulnerability which would otherwise have been reported
+    pub vulnerability: Vulnerability,
+
+    /// Patched fork the package comes from
+    #[serde(alias = "patched-fork")]
+    pub patched_fork: PatchedFork,
+}
+
+/// Parse a (possibly abbreviated) commit hash
+fn parse_rev(rev: &str) -> Result<String, Error> {
+    if rev.len() < MIN_REV_LEN || !rev.chars().all(|c| c.is_ascii_hexdigit()) {
+        fail!(
+            ErrorKind::Parse,
+            "invalid patched fork revision: {} (expected at least {} hex characters)",
+            rev,
+            MIN_REV_LEN
+        );
+    }
+
+    Ok(rev.to_owned())
+}
+
+/// Deserialize an optional revision, validating it with [`parse_rev`]
+fn deserialize_rev<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|rev| parse_rev(&rev).map_err(|e| D::Error::custom(format!("{}", e))))
+        .transpose()
+}
+
+/// Normalize a git URL for comparison, ignoring a trailing `/` or `.git`
+fn normalize_url(url: &str) -> &str {
+    let url = url.trim_end_matches('/');
+    url.strip_suffix(".git").unwrap_or(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatchedFork;
+    use crate::{
+        advisory::Metadata,
+        report::fixtures::{self, package},
+    };
+
+    fn metadata(id: &str) -> Metadata {
+        fixtures::metadata(id, "base", &[])
+    }
+
+    #[test]
+    fn matches_git_source() {
+        let fork = "https://github.com/example/base.git#abc123d"
+            .parse::<PatchedFork>()
+            .unwrap();
+        let advisory = metadata("RUSTSEC-2019-0001");
+
+        assert!(fork.matches(
+            &package("git+https://github.com/example/base#abc123def"),
+            &advisory
+        ));
+        assert!(!fork.matches(
+            &package("git+https://github.com/example/base#def456abc"),
+            &advisory
+        ));
+        assert!(!fork.matches(
+            &package("git+https://github.com/other/base#abc123def"),
+            &advisory
+        ));
+        assert!(!fork.matches(
+            &package("registry+https://github.com/rust-lang/crates.io-index"),
+            &advisory
+        ));
+    }
+
+    #[test]
+    fn matches_advisories() {
+        let fork = PatchedFork {
+            url: "https://github.com/example/base".to_owned(),
+            rev: None,
+            advisories: vec!["RUSTSEC-2019-0001".parse().unwrap()],
+        };
+        let package = package("git+https://github.com/example/base?rev=abc#abc123def");
+
+        assert!(fork.matches(&package, &metadata("RUSTSEC-2019-0001")));
+        assert!(!fork.matches(&package, &metadata("RUSTSEC-2019-0002")));
+    }
+
+    #[test]
+    fn parse_revs() {
+        let rev = |s: &str| s.parse::<PatchedFork>().map(|fork| fork.rev);
+
+        assert_eq!(rev("https://github.com/example/base").unwrap(), None);
+        assert_eq!(
+            rev("https://github.com/example/base#abc123d").unwrap(),
+            Some("abc123d".to_owned())
+        );
+        assert!(rev("https://github.com/example/base#").is_err());
+        assert!(rev("https://github.com/example/base#a").is_err());
+        assert!(rev("https://github.com/example/base#abc123").is_err());
+        assert!(rev("https://github.com/example/base#main123").is_err());
+
+        let config = |rev: &str| {
+            toml::from_str::<PatchedFork>(&format!(
+                "url = \"https://github.com/example/base\"\nrev = \"{}\"",
+                rev
+            ))
+        };
+
+        assert!(config("abc123d").is_ok());
+        assert!(config("a").is_err());
+    }
+}
@@ -0,0 +1,46 @@
+//! Simple glob patterns where `*` matches any sequence of characters
+
+/// Match a string against a pattern containing `*` wildcards
+pub(crate) fn matches(pattern: &str, s: &str) -> bool {
+    let mut parts = pattern.split('*');
+
+    // `split` always yields at least one part
+    let prefix = parts.next().unwrap();
+
+    let mut rest = match s.strip_prefix(prefix) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+
+    let (suffix, middle) = match parts.split_last() {
+        Some(split) => split,
+        None => return rest.is_empty(),
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[(pos + part.len())..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn patterns() {
+        assert!(matches("tokio", "tokio"));
+        assert!(!matches("tokio", "tokio-util"));
+        assert!(matches("tokio*", "tokio-util"));
+        assert!(matches("*-sys", "openssl-sys"));
+        assert!(matches("a*b*c", "abc"));
+        assert!(!matches("a*b*c", "acb"));
+        assert!(!matches("ab*ba", "aba"));
+    }
+}
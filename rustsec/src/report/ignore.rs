@@ -0,0 +1,161 @@
+//! Patterns matching advisories to ignore
+
+use super::glob;
+use crate::{
+    advisory,
+    error::{Error, ErrorKind},
+};
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Prefix of patterns which match package names rather than advisory IDs
+const PACKAGE_PREFIX: &str = "package:";
+
+/// Pattern matching advisories to ignore.
+///
+/// Patterns may contain `*` wildcards, each of which matches any sequence of
+/// characters, and are written as either:
+///
+/// - an advisory ID or ID pattern (e.g. `RUSTSEC-2019-0001`, `RUSTSEC-2019-*`),
+///   which also matches the advisory's aliases (e.g. `CVE-2019-*`)
+/// - `package:` followed by a package name or name pattern (e.g. `package:foo-*`),
+///   which matches all advisories about the matching packages
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum IgnorePattern {
+    /// Advisory ID or pattern matching advisory IDs
+    Id(String),
+
+    /// Pattern matching the names of affected packages
+    Package(String),
+}
+
+impl IgnorePattern {
+    /// Does this pattern match the given advisory?
+    pub fn matches(&self, metadata: &advisory::Metadata) -> bool {
+        match self {
+            IgnorePattern::Id(pattern) => {
+                glob::matches(pattern, metadata.id.as_str())
+                    || metadata
+                        .aliases
+                        .iter()
+                        .any(|alias| glob::matches(pattern, alias.as_str()))
+            }
+            IgnorePattern::Package(pattern) => glob::matches(pattern, metadata.package.as_str()),
+        }
+    }
+
+    /// Is this pattern a single advisory ID (i.e. without wildcards)?
+    pub fn as_id(&self) -> Option<advisory::Id> {
+        match self {
+            IgnorePattern::Id(pattern) if !pattern.contains('*') => pattern.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<advisory::Id> for IgnorePattern {
+    fn from(id: advisory::Id) -> Self {
+        IgnorePattern::Id(id.into())
+    }
+}
+
+impl Display for IgnorePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IgnorePattern::Id(pattern) => f.write_str(pattern),
+            IgnorePattern::Package(pattern) => write!(f, "{}{}", PACKAGE_PREFIX, pattern),
+        }
+    }
+}
+
+impl FromStr for IgnorePattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        if let Some(pattern) = s.strip_prefix(PACKAGE_PREFIX) {
+            if pattern.is_empty() {
+                fail!(ErrorKind::Parse, "empty package pattern: {}", s);
+            }
+
+            return Ok(IgnorePattern::Package(pattern.to_owned()));
+        }
+
+        if s.is_empty() {
+            fail!(ErrorKind::Parse, "empty advisory ID pattern");
+        }
+
+        // Ensure advisory IDs without wildcards are well-formed
+        if !s.contains('*') {
+            s.parse::<advisory::Id>()?;
+        }
+
+        Ok(IgnorePattern::Id(s.to_owned()))
+    }
+}
+
+impl Serialize for IgnorePattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for IgnorePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::from_str(&String::deserialize(deserializer)?)
+            .map_err(|e| D::Error::custom(format!("{}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IgnorePattern;
+    use crate::report::fixtures::metadata;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "RUSTSEC-2019-0001".parse::<IgnorePattern>().unwrap(),
+            IgnorePattern::Id("RUSTSEC-2019-0001".to_owned())
+        );
+        assert_eq!(
+            "package:foo-*".parse::<IgnorePattern>().unwrap(),
+            IgnorePattern::Package("foo-*".to_owned())
+        );
+        assert!("RUSTSEC-19-1".parse::<IgnorePattern>().is_err());
+        assert!("package:".parse::<IgnorePattern>().is_err());
+        assert!("".parse::<IgnorePattern>().is_err());
+    }
+
+    #[test]
+    fn id_patterns() {
+        let advisory = metadata("RUSTSEC-2019-0031", "spin", &["CVE-2019-15541"]);
+
+        assert!(IgnorePattern::Id("RUSTSEC-2019-0031".to_owned()).matches(&advisory));
+        assert!(IgnorePattern::Id("RUSTSEC-2019-*".to_owned()).matches(&advisory));
+        assert!(IgnorePattern::Id("CVE-2019-*".to_owned()).matches(&advisory));
+        assert!(!IgnorePattern::Id("RUSTSEC-2020-*".to_owned()).matches(&advisory));
+        assert!(!IgnorePattern::Id("RUSTSEC-2019-003".to_owned()).matches(&advisory));
+    }
+
+    #[test]
+    fn package_patterns() {
+        let advisory = metadata("RUSTSEC-2020-0001", "foo-bar", &[]);
+
+        assert!(IgnorePattern::Package("foo-*".to_owned()).matches(&advisory));
+        assert!(IgnorePattern::Package("foo-bar".to_owned()).matches(&advisory));
+        assert!(!IgnorePattern::Package("foo".to_owned()).matches(&advisory));
+    }
+
+    #[test]
+    fn round_trip() {
+        for pattern in &["RUSTSEC-2019-*", "package:foo-*"] {
+            assert_eq!(
+                pattern.parse::<IgnorePattern>().unwrap().to_string(),
+                *pattern
+            );
+        }
+    }
+}
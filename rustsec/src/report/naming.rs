@@ -0,0 +1,511 @@
+//! Consistent naming of the fields of serialized reports.
+//!
+//! For historical reasons, reports mix kebab-case (e.g. `advisory-count`)
+//! and snake_case (e.g. `fix_status`) field names. Renaming them would break
+//! existing consumers, so they're serialized as-is by default, but reports
+//! can be serialized with every field name in one style instead (see
+//! [`FieldNaming::apply`]).
+
+use crate::{
+    error::{Error, ErrorKind},
+    Set,
+};
+use serde::{ser, Deserialize, Serialize, Serializer};
+use std::{borrow::Cow, cell::RefCell, fmt, str::FromStr};
+
+/// Naming style for the fields of serialized reports
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FieldNaming {
+    /// Field names as they've always been serialized: a mix of kebab-case
+    /// and snake_case (default)
+    Legacy,
+
+    /// Every field name in kebab-case (e.g. `advisory-count`, `fix-status`)
+    KebabCase,
+
+    /// Every field name in snake_case (e.g. `advisory_count`, `fix_status`)
+    SnakeCase,
+}
+
+impl FieldNaming {
+    /// Get a `str` representing this naming style
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FieldNaming::Legacy => "legacy",
+            FieldNaming::KebabCase => "kebab-case",
+            FieldNaming::SnakeCase => "snake-case",
+        }
+    }
+
+    /// Wrap a value (e.g. a [`crate::Report`]) so the fields of all of the
+    /// structs within it are named in this style when it's serialized.
+    ///
+    /// Only struct field names are renamed: the keys of maps (e.g. the kinds
+    /// of [`crate::report::WarningInfo`]) and enum variants are data, and are
+    /// left as-is. Report structs accept field names in either style when
+    /// they're deserialized, so renamed reports can be read back.
+    pub fn apply<T: Serialize + ?Sized>(self, value: &T) -> Named<'_, T> {
+        Named {
+            value,
+            naming: self,
+        }
+    }
+
+    /// Convert a field name to this naming style
+    pub fn convert(self, name: &str) -> Cow<'_, str> {
+        match self {
+            FieldNaming::KebabCase if name.contains('_') => Cow::Owned(name.replace('_', "-")),
+            FieldNaming::SnakeCase if name.contains('-') => Cow::Owned(name.replace('-', "_")),
+            _ => Cow::Borrowed(name),
+        }
+    }
+
+    /// Convert a field name to this naming style, interning the result
+    /// since serializers require field names to be `'static`
+    fn convert_static(self, name: &'static str) -> &'static str {
+        match self.convert(name) {
+            Cow::Borrowed(name) => name,
+            Cow::Owned(converted) => intern(converted),
+        }
+    }
+}
+
+impl Default for FieldNaming {
+    fn default() -> Self {
+        FieldNaming::Legacy
+    }
+}
+
+impl fmt::Display for FieldNaming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FieldNaming {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "legacy" => Ok(FieldNaming::Legacy),
+            "kebab-case" => Ok(FieldNaming::KebabCase),
+            "snake-case" => Ok(FieldNaming::SnakeCase),
+            other => fail!(ErrorKind::Parse, "invalid field naming: {}", other),
+        }
+    }
+}
+
+thread_local! {
+    /// Field names converted so far
+    static INTERNED: RefCell<Set<&'static str>> = RefCell::new(Set::new());
+}
+
+/// Get a `'static` copy of a converted field name, leaking each distinct
+/// name once
+fn intern(name: String) -> &'static str {
+    INTERNED.with(|interned| {
+        let mut interned = interned.borrow_mut();
+
+        if let Some(&existing) = interned.get(name.as_str()) {
+            return existing;
+        }
+
+        let leaked: &'static str = Box::leak(name.into_boxed_str());
+        interned.insert(leaked);
+        leaked
+    })
+}
+
+/// Value whose fields are renamed according to a [`FieldNaming`] when it's
+/// serialized (see [`FieldNaming::apply`])
+#[derive(Debug)]
+pub struct Named<'a, T: ?Sized> {
+    /// Value to serialize
+    value: &'a T,
+
+    /// Naming style for fields
+    naming: FieldNaming,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Named<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Renamer {
+            inner: serializer,
+            naming: self.naming,
+        })
+    }
+}
+
+/// Serializer which renames struct fields before passing them to an inner
+/// serializer
+struct Renamer<S> {
+    inner: S,
+    naming: FieldNaming,
+}
+
+impl<S> Renamer<S> {
+    /// Wrap a nested value so its fields are renamed too
+    fn nested<'a, T: ?Sized>(&self, value: &'a T) -> Named<'a, T> {
+        Named {
+            value,
+            naming: self.naming,
+        }
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for Renamer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    forward!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        let value = self.nested(value);
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let naming = self.naming;
+        Ok(Compound::new(self.inner.serialize_seq(len)?, naming))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let naming = self.naming;
+        Ok(Compound::new(self.inner.serialize_tuple(len)?, naming))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let naming = self.naming;
+        let inner = self.inner.serialize_tuple_struct(name, len)?;
+        Ok(Compound::new(inner, naming))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let naming = self.naming;
+        let inner = self
+            .inner
+            .serialize_tuple_variant(name, variant_index, variant, len)?;
+        Ok(Compound::new(inner, naming))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let naming = self.naming;
+        Ok(Compound::new(self.inner.serialize_map(len)?, naming))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let naming = self.naming;
+        Ok(Compound::new(
+            self.inner.serialize_struct(name, len)?,
+            naming,
+        ))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let naming = self.naming;
+        let inner = self
+            .inner
+            .serialize_struct_variant(name, variant_index, variant, len)?;
+        Ok(Compound::new(inner, naming))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Compound serializer (e.g. for a struct or sequence) whose elements are
+/// renamed
+struct Compound<C> {
+    inner: C,
+    naming: FieldNaming,
+}
+
+impl<C> Compound<C> {
+    fn new(inner: C, naming: FieldNaming) -> Self {
+        Self { inner, naming }
+    }
+
+    /// Wrap an element so its fields are renamed too
+    fn nested<'a, T: ?Sized>(&self, value: &'a T) -> Named<'a, T> {
+        Named {
+            value,
+            naming: self.naming,
+        }
+    }
+}
+
+impl<C: ser::SerializeSeq> ser::SerializeSeq for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTuple> ser::SerializeTuple for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleStruct> ser::SerializeTupleStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeTupleVariant> ser::SerializeTupleVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeMap> ser::SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        let key = self.nested(key);
+        self.inner.serialize_key(&key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner.serialize_value(&value)
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStruct> ser::SerializeStruct for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner
+            .serialize_field(self.naming.convert_static(key), &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(self.naming.convert_static(key))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+impl<C: ser::SerializeStructVariant> ser::SerializeStructVariant for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        let value = self.nested(value);
+        self.inner
+            .serialize_field(self.naming.convert_static(key), &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.inner.skip_field(self.naming.convert_static(key))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.inner.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FieldNaming;
+    use crate::Map;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Inner {
+        fix_status: &'static str,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        #[serde(rename = "advisory-count")]
+        advisory_count: usize,
+        last_commit: Option<&'static str>,
+        by_kind: Map<&'static str, usize>,
+        inner: Inner,
+    }
+
+    #[test]
+    fn rename_fields() {
+        let mut by_kind = Map::new();
+        by_kind.insert("checksum-mismatch", 1);
+        by_kind.insert("some_kind", 2);
+
+        let value = Outer {
+            advisory_count: 3,
+            last_commit: Some("abc123"),
+            by_kind,
+            inner: Inner {
+                fix_status: "no-fix-yet",
+            },
+        };
+
+        let serialize = |naming: FieldNaming| toml::to_string(&naming.apply(&value)).unwrap();
+
+        let kebab = serialize(FieldNaming::KebabCase);
+        assert!(kebab.contains("advisory-count = 3"));
+        assert!(kebab.contains("last-commit = \"abc123\""));
+        assert!(kebab.contains("fix-status = \"no-fix-yet\""));
+        assert!(kebab.contains("[by-kind]"));
+        assert!(kebab.contains("[inner]"));
+
+        let snake = serialize(FieldNaming::SnakeCase);
+        assert!(snake.contains("advisory_count = 3"));
+        assert!(snake.contains("last_commit = \"abc123\""));
+        assert!(snake.contains("fix_status = \"no-fix-yet\""));
+
+        // Map keys and values are data, so they're never renamed
+        for output in &[&kebab, &snake] {
+            assert!(output.contains("checksum-mismatch = 1"));
+            assert!(output.contains("some_kind = 2"));
+        }
+
+        let legacy = serialize(FieldNaming::Legacy);
+        assert!(legacy.contains("advisory-count = 3"));
+        assert!(legacy.contains("last_commit = \"abc123\""));
+    }
+}
@@ -0,0 +1,88 @@
+//! Packages overridden via `[patch]` or `[replace]`, whose code may differ
+//! from the registry release advisories are matched against
+
+use crate::{
+    cargo_lock::{Dependency, SourceId},
+    package::Package,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// How a package in the lockfile overrides the registry release of the same
+/// name and version
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Override {
+    /// Package comes from a git repository rather than the registry (e.g.
+    /// via `[patch]`)
+    Patch {
+        /// Source the package comes from
+        source: SourceId,
+    },
+
+    /// Package is replaced by another one via `[replace]`
+    Replace {
+        /// Package it is replaced by
+        replacement: Dependency,
+    },
+}
+
+impl Override {
+    /// Detect whether the given package from a lockfile is overridden
+    pub fn detect(package: &Package) -> Option<Self> {
+        if let Some(replacement) = &package.replace {
+            return Some(Override::Replace {
+                replacement: replacement.clone(),
+            });
+        }
+
+        match &package.source {
+            Some(source) if source.is_git() => Some(Override::Patch {
+                source: source.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Override {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Override::Patch { source } => write!(f, "patched with {}", source),
+            Override::Replace { replacement } => write!(f, "replaced by {}", replacement),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Override;
+    use crate::{cargo_lock::Dependency, package::Package, report::fixtures};
+
+    fn package(source: &str, replace: Option<Dependency>) -> Package {
+        Package {
+            replace,
+            ..fixtures::package(source)
+        }
+    }
+
+    #[test]
+    fn detect() {
+        let registry = "registry+https://github.com/rust-lang/crates.io-index";
+        let git = "git+https://github.com/example/base#abc123def";
+
+        assert_eq!(Override::detect(&package(registry, None)), None);
+        assert_eq!(
+            Override::detect(&package(git, None)),
+            Some(Override::Patch {
+                source: git.parse().unwrap()
+            })
+        );
+
+        let replacement = Dependency::from(&package(git, None));
+        assert_eq!(
+            Override::detect(&package(registry, Some(replacement.clone()))),
+            Some(Override::Replace { replacement })
+        );
+    }
+}
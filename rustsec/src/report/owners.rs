@@ -0,0 +1,60 @@
+//! Mapping of packages to the teams which own them
+
+use super::glob;
+use crate::{package, Map};
+use serde::{Deserialize, Serialize};
+
+/// Mapping of package name patterns to owning team labels.
+///
+/// Patterns are package names which may contain `*` wildcards, each of
+/// which matches any sequence of characters (e.g. `tokio-*`). When several
+/// patterns match a package, the most specific one (i.e. the one with the
+/// most non-wildcard characters) wins.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Owners(Map<String, String>);
+
+impl Owners {
+    /// Create a new, empty set of owners
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assign packages matching the given pattern to the given team
+    pub fn insert(&mut self, pattern: impl Into<String>, team: impl Into<String>) {
+        self.0.insert(pattern.into(), team.into());
+    }
+
+    /// Are there no ownership rules?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Find the team which owns the package with the given name
+    pub fn owner(&self, name: &package::Name) -> Option<&str> {
+        self.0
+            .iter()
+            .filter(|(pattern, _)| glob::matches(pattern, name.as_str()))
+            .max_by_key(|(pattern, _)| pattern.chars().filter(|&c| c != '*').count())
+            .map(|(_, team)| team.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Owners;
+
+    #[test]
+    fn most_specific_owner() {
+        let mut owners = Owners::new();
+        owners.insert("*", "platform");
+        owners.insert("tokio*", "async");
+        owners.insert("tokio-util", "networking");
+
+        let owner = |name: &str| owners.owner(&name.parse().unwrap()).map(String::from);
+
+        assert_eq!(owner("tokio-util").as_deref(), Some("networking"));
+        assert_eq!(owner("tokio").as_deref(), Some("async"));
+        assert_eq!(owner("serde").as_deref(), Some("platform"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Rules which promote warnings to errors
+
+use super::glob;
+use crate::warning::{self, Warning};
+use serde::{Deserialize, Serialize};
+
+/// Rule which promotes matching warnings (e.g. about unmaintained crates)
+/// to errors, so they fail an audit the same way vulnerabilities do.
+///
+/// Each criterion which is left empty matches any warning, e.g. a rule with
+/// only `kinds = ["unmaintained"]` and `max_depth = 1` promotes warnings
+/// about unmaintained direct dependencies of workspace members.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromotionRule {
+    /// Kinds of warnings to promote
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<warning::Kind>,
+
+    /// Names of packages to promote warnings about, which may contain `*`
+    /// wildcards (e.g. `tokio-*`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<String>,
+
+    /// Maximum depth in the dependency graph of packages to promote
+    /// warnings about, where direct dependencies of workspace members have
+    /// depth 1
+    #[serde(alias = "max-depth", default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+}
+
+impl PromotionRule {
+    /// Does this rule promote the given warning about a package at the given
+    /// depth in the dependency graph (if known)?
+    pub fn matches(&self, warning: &Warning, depth: Option<usize>) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&warning.kind) {
+            return false;
+        }
+
+        if !self.packages.is_empty()
+            && !self
+                .packages
+                .iter()
+                .any(|pattern| glob::matches(pattern, warning.package.name.as_str()))
+        {
+            return false;
+        }
+
+        if let Some(max_depth) = self.max_depth {
+            match depth {
+                Some(depth) if depth <= max_depth => (),
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PromotionRule;
+    use crate::{
+        package::Package,
+        warning::{Kind, Warning},
+    };
+
+    #[test]
+    fn matching_rules() {
+        let package = Package {
+            name: "openssl-sys".parse().unwrap(),
+            version: "0.9.0".parse().unwrap(),
+            source: None,
+            checksum: None,
+            dependencies: vec![],
+            replace: None,
+        };
+        let warning = Warning::new(Kind::Unmaintained, &package, None, None);
+
+        assert!(PromotionRule::default().matches(&warning, None));
+
+        let rule = PromotionRule {
+            kinds: vec![Kind::Unmaintained],
+            packages: vec!["openssl*".to_owned()],
+            max_depth: Some(1),
+        };
+        assert!(rule.matches(&warning, Some(1)));
+        assert!(!rule.matches(&warning, Some(2)));
+        assert!(!rule.matches(&warning, None));
+
+        let rule = PromotionRule {
+            kinds: vec![Kind::Yanked],
+            ..PromotionRule::default()
+        };
+        assert!(!rule.matches(&warning, Some(1)));
+    }
+}
@@ -0,0 +1,384 @@
+//! Review status of packages according to `cargo vet` audit records
+
+use crate::{
+    error::{Error, ErrorKind},
+    fs, package, Map, Set, Version, VersionReq,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::Path};
+use toml::Value;
+
+/// Name of the directory `cargo vet` stores its records in, alongside
+/// `Cargo.lock`
+pub const SUPPLY_CHAIN_DIR: &str = "supply-chain";
+
+/// Criteria audits must certify for a package to count as audited, unless
+/// others are given (the criteria `cargo vet` requires by default)
+pub const DEFAULT_CRITERIA: &str = "safe-to-deploy";
+
+/// Implications between the criteria built into `cargo vet`
+const BUILTIN_IMPLIES: &[(&str, &str)] = &[("safe-to-deploy", "safe-to-run")];
+
+/// Whether a package has been reviewed, according to `cargo vet`
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReviewStatus {
+    /// The version was audited, either in full or via deltas from an audited
+    /// version (including audits imported from other organizations)
+    Audited,
+
+    /// The version is exempted from auditing (e.g. it was trusted when
+    /// `cargo vet` was first set up), but hasn't actually been audited
+    Exempted,
+
+    /// An audit found the version violates the audit criteria
+    Violation,
+
+    /// The version hasn't been audited
+    Unreviewed,
+}
+
+impl ReviewStatus {
+    /// Get a `str` representing this review status
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReviewStatus::Audited => "audited",
+            ReviewStatus::Exempted => "exempted",
+            ReviewStatus::Violation => "violation",
+            ReviewStatus::Unreviewed => "unreviewed",
+        }
+    }
+}
+
+impl fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Audit records of a single package
+#[derive(Clone, Debug, Default)]
+struct PackageRecords {
+    /// Versions which were audited in full
+    full: Set<Version>,
+
+    /// Audited changes between two versions
+    deltas: Vec<(Version, Version)>,
+
+    /// Versions found to violate the audit criteria
+    violations: Vec<VersionReq>,
+
+    /// Versions exempted from auditing
+    exemptions: Set<Version>,
+}
+
+/// Audit records from a `cargo vet` supply chain directory (`audits.toml`,
+/// `imports.lock`, and the exemptions in `config.toml`), used to determine
+/// the [`ReviewStatus`] of packages.
+///
+/// Only audits and exemptions for the required criteria (or criteria which
+/// imply them) count, whereas violations count whatever their criteria.
+#[derive(Clone, Debug, Default)]
+pub struct ReviewRecords {
+    /// Records of each package
+    packages: Map<package::Name, PackageRecords>,
+}
+
+impl ReviewRecords {
+    /// Load the records in the given `supply-chain` directory, requiring
+    /// the [`DEFAULT_CRITERIA`]. Files which don't exist are skipped.
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        Self::load_with_criteria(dir, DEFAULT_CRITERIA)
+    }
+
+    /// Load the records in the given `supply-chain` directory, requiring
+    /// the given criteria (e.g. `safe-to-run`). Files which don't exist are
+    /// skipped.
+    pub fn load_with_criteria(dir: &Path, criteria: &str) -> Result<Self, Error> {
+        let mut records = Self::default();
+
+        // Custom criteria (and what they imply) are defined in `audits.toml`
+        let audits = read_toml(&dir.join("audits.toml"))?;
+        let satisfying = satisfying_criteria(audits.as_ref(), criteria);
+
+        if let Some(audits) = &audits {
+            records.add_audits(audits, &satisfying)?;
+        }
+
+        // Audits imported from other organizations' audit sets, which define
+        // their own custom criteria
+        if let Some(imports) = read_toml(&dir.join("imports.lock"))? {
+            for (_, imported) in table(&imports, "audits") {
+                records.add_audits(imported, &satisfying_criteria(Some(imported), criteria))?;
+            }
+        }
+
+        if let Some(config) = read_toml(&dir.join("config.toml"))? {
+            for (name, entries) in table(&config, "exemptions") {
+                for entry in array(entries).filter(|entry| certifies(entry, &satisfying)) {
+                    if let Some(version) = entry.get("version").and_then(Value::as_str) {
+                        records
+                            .package(name)?
+                            .exemptions
+                            .insert(parse_version(version)?);
+                    }
+                }
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Determine the review status of the given version of a package
+    pub fn status(&self, name: &package::Name, version: &Version) -> ReviewStatus {
+        let records = match self.packages.get(name) {
+            Some(records) => records,
+            None => return ReviewStatus::Unreviewed,
+        };
+
+        if records.violations.iter().any(|req| req.matches(version)) {
+            ReviewStatus::Violation
+        } else if records.reaches(&records.full, version) {
+            ReviewStatus::Audited
+        } else if records.reaches(&records.exemptions, version) {
+            ReviewStatus::Exempted
+        } else {
+            ReviewStatus::Unreviewed
+        }
+    }
+
+    /// Add the audits in the `audits` table of the given document, only
+    /// counting audits for one of the given criteria
+    fn add_audits(&mut self, document: &Value, satisfying: &Set<String>) -> Result<(), Error> {
+        for (name, entries) in table(document, "audits") {
+            for entry in array(entries) {
+                let field = |key: &str| entry.get(key).and_then(Value::as_str);
+                let counts = certifies(entry, satisfying);
+
+                if field("violation").is_none() && !counts {
+                    continue;
+                }
+
+                if let Some(version) = field("version") {
+                    let version = parse_version(version)?;
+                    self.package(name)?.full.insert(version);
+                } else if let Some(delta) = field("delta") {
+                    let mut parts = delta.splitn(2, "->");
+                    let from = parse_version(parts.next().unwrap_or_default())?;
+                    let to = parse_version(parts.next().unwrap_or_default())?;
+                    self.package(name)?.deltas.push((from, to));
+                } else if let Some(violation) = field("violation") {
+                    let req = violation.parse::<VersionReq>().map_err(|e| {
+                        format_err!(ErrorKind::Parse, "invalid violation {:?}: {}", violation, e)
+                    })?;
+                    self.package(name)?.violations.push(req);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the records of the package with the given name
+    fn package(&mut self, name: &str) -> Result<&mut PackageRecords, Error> {
+        Ok(self.packages.entry(name.parse()?).or_default())
+    }
+}
+
+impl PackageRecords {
+    /// Can the given version be reached from any of the given versions by
+    /// following audited deltas?
+    fn reaches(&self, roots: &Set<Version>, version: &Version) -> bool {
+        let mut reached = roots.clone();
+        let mut frontier = roots.iter().cloned().collect::<Vec<_>>();
+
+        while let Some(from) = frontier.pop() {
+            for (_, to) in self.deltas.iter().filter(|(start, _)| start == &from) {
+                if reached.insert(to.clone()) {
+                    frontier.push(to.clone());
+                }
+            }
+        }
+
+        reached.contains(version)
+    }
+}
+
+/// Get the criteria which satisfy the required criteria, i.e. the required
+/// criteria and those which imply them (directly or transitively), according
+/// to the built-in criteria and those defined in the given document
+fn satisfying_criteria(document: Option<&Value>, required: &str) -> Set<String> {
+    let mut implies = BUILTIN_IMPLIES
+        .iter()
+        .map(|&(from, to)| (from.to_owned(), to.to_owned()))
+        .collect::<Vec<_>>();
+
+    if let Some(document) = document {
+        for (name, definition) in table(document, "criteria") {
+            for implied in strings(definition.get("implies")) {
+                implies.push((name.clone(), implied.to_owned()));
+            }
+        }
+    }
+
+    let mut satisfying = Set::new();
+    satisfying.insert(required.to_owned());
+    let mut frontier = vec![required.to_owned()];
+
+    while let Some(criteria) = frontier.pop() {
+        for (from, _) in implies.iter().filter(|(_, to)| to == &criteria) {
+            if satisfying.insert(from.clone()) {
+                frontier.push(from.clone());
+            }
+        }
+    }
+
+    satisfying
+}
+
+/// Does the given audit or exemption entry certify any of the given criteria?
+fn certifies(entry: &Value, satisfying: &Set<String>) -> bool {
+    strings(entry.get("criteria"))
+        .iter()
+        .any(|criteria| satisfying.contains(*criteria))
+}
+
+/// Get the strings in a TOML value which is either a string or an array of
+/// them (like `criteria` fields)
+fn strings(value: Option<&Value>) -> Vec<&str> {
+    match value {
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        Some(value) => value.as_str().into_iter().collect(),
+        None => vec![],
+    }
+}
+
+/// Read and parse a TOML file, if it exists
+fn read_toml(path: &Path) -> Result<Option<Value>, Error> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let toml_string = fs::read_to_string(path)?;
+
+    toml_string
+        .parse::<Value>()
+        .map(Some)
+        .map_err(|e| format_err!(ErrorKind::Parse, "error parsing {}: {}", path.display(), e))
+}
+
+/// Get the table with the given key in a TOML document (or an empty one)
+fn table<'a>(document: &'a Value, key: &str) -> impl Iterator<Item = (&'a String, &'a Value)> {
+    document
+        .get(key)
+        .and_then(Value::as_table)
+        .into_iter()
+        .flatten()
+}
+
+/// Get the entries of a TOML array (or nothing if it isn't one)
+fn array(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+/// Parse a version in an audit record
+fn parse_version(version: &str) -> Result<Version, Error> {
+    version
+        .trim()
+        .parse()
+        .map_err(|e| format_err!(ErrorKind::Parse, "invalid version {:?}: {}", version, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReviewRecords, ReviewStatus};
+    use std::fs;
+
+    #[test]
+    fn review_status() {
+        let dir = tempfile::tempdir().unwrap();
+
+        fs::write(
+            dir.path().join("audits.toml"),
+            r#"
+[[audits.base64]]
+who = "Alice <alice@example.com>"
+criteria = "safe-to-deploy"
+version = "0.13.0"
+
+[[audits.base64]]
+who = "Alice <alice@example.com>"
+criteria = "safe-to-deploy"
+delta = "0.13.0 -> 0.13.1"
+
+[[audits.smallvec]]
+who = "Bob <bob@example.com>"
+criteria = "safe-to-run"
+violation = "<1.6.1"
+
+[[audits.libc]]
+who = "Bob <bob@example.com>"
+criteria = "safe-to-run"
+version = "0.2.90"
+
+[[audits.rand]]
+who = "Carol <carol@example.com>"
+criteria = ["reviewed", "crypto-reviewed"]
+version = "0.8.0"
+
+[criteria.reviewed]
+description = "Reviewed in full"
+implies = "safe-to-deploy"
+
+[criteria.crypto-reviewed]
+description = "Reviewed by a cryptographer"
+implies = ["reviewed"]
+"#,
+        )
+        .unwrap();
+
+        fs::write(
+            dir.path().join("config.toml"),
+            r#"
+[[exemptions.base64]]
+version = "0.12.3"
+criteria = "safe-to-deploy"
+"#,
+        )
+        .unwrap();
+
+        let records = ReviewRecords::load(dir.path()).unwrap();
+        let status = |name: &str, version: &str| {
+            records.status(&name.parse().unwrap(), &version.parse().unwrap())
+        };
+
+        assert_eq!(status("base64", "0.13.0"), ReviewStatus::Audited);
+        assert_eq!(status("base64", "0.13.1"), ReviewStatus::Audited);
+        assert_eq!(status("base64", "0.12.3"), ReviewStatus::Exempted);
+        assert_eq!(status("base64", "0.11.0"), ReviewStatus::Unreviewed);
+        assert_eq!(status("smallvec", "1.6.0"), ReviewStatus::Violation);
+        assert_eq!(status("serde", "1.0.0"), ReviewStatus::Unreviewed);
+
+        // Audits only count for the required criteria (or ones implying them)
+        assert_eq!(status("libc", "0.2.90"), ReviewStatus::Unreviewed);
+        assert_eq!(status("rand", "0.8.0"), ReviewStatus::Audited);
+
+        let records = ReviewRecords::load_with_criteria(dir.path(), "safe-to-run").unwrap();
+        let status = |name: &str, version: &str| {
+            records.status(&name.parse().unwrap(), &version.parse().unwrap())
+        };
+
+        assert_eq!(status("libc", "0.2.90"), ReviewStatus::Audited);
+        assert_eq!(status("rand", "0.8.0"), ReviewStatus::Audited);
+        assert_eq!(status("base64", "0.12.3"), ReviewStatus::Exempted);
+
+        let records = ReviewRecords::load_with_criteria(dir.path(), "reviewed").unwrap();
+        let status = |name: &str, version: &str| {
+            records.status(&name.parse().unwrap(), &version.parse().unwrap())
+        };
+
+        assert_eq!(status("base64", "0.13.0"), ReviewStatus::Unreviewed);
+        assert_eq!(status("base64", "0.12.3"), ReviewStatus::Unreviewed);
+        assert_eq!(status("rand", "0.8.0"), ReviewStatus::Audited);
+    }
+}
@@ -0,0 +1,62 @@
+//! Diagnostics about where the time spent on an audit went
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Timing and cache diagnostics for an audit, useful for figuring out why
+/// audits are slow on a particular machine (e.g. a CI runner)
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Stats {
+    /// Time spent fetching the advisory database in milliseconds, if it
+    /// was fetched
+    #[serde(
+        rename = "fetch-ms",
+        alias = "fetch_ms",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fetch_ms: Option<u64>,
+
+    /// Time spent updating the registry index in milliseconds, if it was
+    /// updated
+    #[serde(
+        rename = "index-update-ms",
+        alias = "index_update_ms",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub index_update_ms: Option<u64>,
+
+    /// Time spent loading and parsing the advisory database in milliseconds
+    #[serde(rename = "load-ms", alias = "load_ms")]
+    pub load_ms: u64,
+
+    /// Time spent matching the lockfile against the advisory database and
+    /// registry index in milliseconds
+    #[serde(rename = "match-ms", alias = "match_ms")]
+    pub match_ms: u64,
+
+    /// Number of registry index lookups answered from its in-memory cache
+    #[serde(rename = "index-cache-hits", alias = "index_cache_hits")]
+    pub index_cache_hits: usize,
+
+    /// Number of registry index lookups which had to go to its backend
+    #[serde(rename = "index-cache-misses", alias = "index_cache_misses")]
+    pub index_cache_misses: usize,
+
+    /// Size of the advisory database on disk in bytes (if known)
+    #[serde(
+        rename = "database-size",
+        alias = "database_size",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub database_size: Option<u64>,
+}
+
+impl Stats {
+    /// Convert a duration to the whole milliseconds used by these stats
+    pub fn millis(duration: Duration) -> u64 {
+        duration.as_millis() as u64
+    }
+}
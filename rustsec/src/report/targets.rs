@@ -0,0 +1,58 @@
+//! Per-target sections of reports about several target platforms
+
+use super::{Report, Settings, VulnerabilityInfo, WarningInfo};
+use crate::{database::Database, lockfile::Lockfile};
+use serde::{Deserialize, Serialize};
+
+/// Findings which affect a particular target triple, for reports generated
+/// for several targets at once (i.e. with more than one of
+/// [`Settings::targets`])
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TargetReport {
+    /// Target triple (e.g. `x86_64-pc-windows-msvc`)
+    pub target: String,
+
+    /// Vulnerabilities which affect this target
+    pub vulnerabilities: VulnerabilityInfo,
+
+    /// Warnings which affect this target
+    pub warnings: WarningInfo,
+}
+
+impl TargetReport {
+    /// Generate a section for each of the given targets, in parallel (one
+    /// thread per target) against the same database
+    pub fn generate_all(
+        db: &Database,
+        lockfile: &Lockfile,
+        settings: &Settings,
+        targets: &[String],
+    ) -> Vec<Self> {
+        crossbeam_utils::thread::scope(|scope| {
+            let handles = targets
+                .iter()
+                .map(|target| scope.spawn(move |_| Self::generate(db, lockfile, settings, target)))
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("target report thread panicked"))
+                .collect()
+        })
+        .expect("target report thread panicked")
+    }
+
+    /// Generate the section for a single target
+    pub fn generate(db: &Database, lockfile: &Lockfile, settings: &Settings, target: &str) -> Self {
+        let mut settings = settings.clone();
+        settings.targets = vec![target.to_owned()];
+
+        let report = Report::generate(db, lockfile, &settings);
+
+        Self {
+            target: target.to_owned(),
+            vulnerabilities: report.vulnerabilities,
+            warnings: report.warnings,
+        }
+    }
+}
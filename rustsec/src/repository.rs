@@ -2,5 +2,8 @@
 
 pub mod signature;
 
-#[cfg(feature = "git")]
+#[cfg(feature = "db-git")]
 pub mod git;
+
+#[cfg(feature = "mirror")]
+pub mod mirror;
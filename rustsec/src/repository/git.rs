@@ -6,9 +6,13 @@ mod gitpath;
 mod modification_time;
 mod repository;
 
-pub use self::{authentication::with_authentication, commit::Commit, repository::Repository};
+pub use self::{
+    authentication::with_authentication,
+    commit::Commit,
+    repository::{Change, Repository},
+};
 
-#[cfg(feature = "osv-I-know-this-is-unstable")]
+#[cfg(feature = "osv")]
 pub use self::{gitpath::GitPath, modification_time::GitModificationTimes};
 
 /// Location of the RustSec advisory database for crates.io
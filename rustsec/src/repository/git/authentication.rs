@@ -2,7 +2,7 @@
 //!
 //! <https://github.com/rust-lang/cargo/blob/5102de2/src/cargo/sources/git/utils.rs#L410>
 
-use crate::error::{Error, ErrorKind};
+use crate::error::{Error, RepositoryError};
 use std::env;
 
 /// Prepare the authentication callbacks for cloning a git repository.
@@ -214,7 +214,7 @@ where
                 );
             }
         }
-        format_err!(ErrorKind::Repo, "{}", msg)
+        RepositoryError::Authentication(msg)
     })?;
 
     Ok(res)
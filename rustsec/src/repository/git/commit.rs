@@ -1,7 +1,7 @@
 //! Commits to the advisory DB git repository
 
 use crate::{
-    error::{Error, ErrorKind},
+    error::{Error, RepositoryError},
     repository::{git::Repository, signature::Signature},
 };
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -38,14 +38,15 @@ impl Commit {
     pub(crate) fn from_repo_head(repo: &Repository) -> Result<Self, Error> {
         let head = repo.repo.head()?;
 
-        let oid = head.target().ok_or_else(|| {
-            format_err!(
-                ErrorKind::Repo,
-                "no ref target for: {}",
-                repo.path.display()
-            )
-        })?;
+        let oid = head
+            .target()
+            .ok_or_else(|| RepositoryError::NoRefTarget(repo.path.clone()))?;
 
+        Self::from_oid(repo, oid)
+    }
+
+    /// Get information about the commit with the given ID
+    pub(crate) fn from_oid(repo: &Repository, oid: git2::Oid) -> Result<Self, Error> {
         let commit_id = oid.to_string();
         let commit_object = repo.repo.find_object(oid, Some(git2::ObjectType::Commit))?;
         let commit = commit_object.as_commit().unwrap();
@@ -53,7 +54,7 @@ impl Commit {
 
         let summary = commit
             .summary()
-            .ok_or_else(|| format_err!(ErrorKind::Repo, "no commit summary for {}", commit_id))?
+            .ok_or_else(|| RepositoryError::NoCommitSummary(commit_id.clone()))?
             .to_owned();
 
         let (signature, signed_data) = match repo.repo.extract_signature(&oid, None) {
@@ -1,33 +1,41 @@
-#![cfg(feature = "osv-I-know-this-is-unstable")]
+#![cfg(feature = "osv")]
 
 use std::path::Path;
 
-use crate::{Error, ErrorKind};
+use crate::{error::RepositoryError, Error};
 
 use super::Repository;
 
-/// A path *relative to the root of the git repository* that is guaranteed to be tracked by Git.
-/// This type is immutable.
+/// A path *relative to the root of the git repository* that is guaranteed to be tracked by Git
+/// at a particular commit. This type is immutable.
 pub struct GitPath<'a> {
     repo: &'a Repository,
     path: &'a Path,
+    commit_id: String,
+    blob_id: String,
 }
 
 impl<'a> GitPath<'a> {
-    /// Creates a new `GitPath`, validating that this file is tracked in Git
+    /// Creates a new `GitPath`, validating that this file is tracked in Git at `HEAD`
     pub fn new(repo: &'a Repository, path: &'a Path) -> Result<Self, Error> {
+        Self::at_commit(repo, path, "HEAD")
+    }
+
+    /// Creates a new `GitPath`, validating that this file is tracked in Git at the given
+    /// revision (e.g. a commit hash, a tag such as a database release, or `HEAD`)
+    pub fn at_commit(repo: &'a Repository, path: &'a Path, rev: &str) -> Result<Self, Error> {
         // Validate that the path is relative for better feedback to API users
         if path.has_root() {
-            fail!(
-                ErrorKind::BadParam,
-                "{} is not a relative path",
-                path.display()
-            );
+            return Err(RepositoryError::NotRelative(path.to_owned()).into());
         }
-        let commit_id = repo.repo.refname_to_id("HEAD")?;
-        let commit = repo.repo.find_commit(commit_id)?;
-        commit.tree()?.get_path(path)?;
-        Ok(GitPath { repo, path })
+        let commit = repo.repo.revparse_single(rev)?.peel_to_commit()?;
+        let entry = commit.tree()?.get_path(path)?;
+        Ok(GitPath {
+            repo,
+            path,
+            commit_id: commit.id().to_string(),
+            blob_id: entry.id().to_string(),
+        })
     }
 
     /// A path *relative to the root of the git repository* that is guaranteed to be tracked by Git
@@ -39,4 +47,15 @@ impl<'a> GitPath<'a> {
     pub fn repository(&self) -> &'a Repository {
         self.repo
     }
+
+    /// ID of the commit the path was validated against
+    pub fn commit_id(&self) -> &str {
+        &self.commit_id
+    }
+
+    /// ID of the blob the path resolves to at that commit (or of the tree, for directories),
+    /// which identifies its contents and so can be used as a cache key
+    pub fn blob_id(&self) -> &str {
+        &self.blob_id
+    }
 }
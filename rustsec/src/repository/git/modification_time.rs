@@ -1,14 +1,40 @@
-#![cfg(feature = "osv-I-know-this-is-unstable")]
+#![cfg(feature = "osv")]
 
 use crate::error::Error;
 use git2::Time;
-use std::{cmp::max, collections::HashMap, path::PathBuf};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use super::GitPath;
 
 /// Tracks the time of latest modification of files in git.
+///
+/// Walking the history is expensive, so this can be serialized and reused
+/// (e.g. cached between runs, or shared between exporters) for as long as
+/// [`GitModificationTimes::commit_id`] is still the latest commit.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
 pub struct GitModificationTimes {
-    mtimes: HashMap<PathBuf, Time>,
+    /// ID of the commit the history was walked from
+    commit_id: String,
+
+    /// Times of the first and latest modification of each file
+    files: BTreeMap<PathBuf, FileTimes>,
+}
+
+/// Times of the first and latest commits which modified a file
+#[derive(Copy, Clone, Deserialize, Eq, PartialEq, Serialize)]
+struct FileTimes {
+    /// Time of the latest modification
+    #[serde(with = "time")]
+    mtime: Time,
+
+    /// Time of the first modification, i.e. when the file was created
+    #[serde(with = "time")]
+    ctime: Time,
 }
 
 impl GitModificationTimes {
@@ -22,8 +48,9 @@ impl GitModificationTimes {
         // as does git2-rs: https://github.com/rust-lang/git2-rs/issues/588
         // To make sure this works I've verified it against a naive shell script using `git log`
         // as well as `git whatchanged`
-        let mut mtimes: HashMap<PathBuf, Time> = HashMap::new();
+        let mut files: BTreeMap<PathBuf, FileTimes> = BTreeMap::new();
         let repo = git2::Repository::open(repo.path())?;
+        let commit_id = repo.head()?.peel_to_commit()?.id().to_string();
         let mut revwalk = repo.revwalk()?;
         revwalk.set_sorting(git2::Sort::TIME)?;
         revwalk.push_head()?;
@@ -31,28 +58,134 @@ impl GitModificationTimes {
             let commit_id = commit_id?;
             let commit = repo.find_commit(commit_id)?;
             // Ignore merge commits (2+ parents) because that's what 'git whatchanged' does.
-            // Ignore commit with 0 parents (initial commit) because there's nothing to diff against
-            if commit.parent_count() == 1 {
-                let prev_commit = commit.parent(0)?;
+            // Diff the initial commit (0 parents) against an empty tree, so the files it
+            // added have times too (like 'git whatchanged --root')
+            if commit.parent_count() <= 1 {
+                let prev_tree = match commit.parents().next() {
+                    Some(prev_commit) => Some(prev_commit.tree()?),
+                    None => None,
+                };
                 let tree = commit.tree()?;
-                let prev_tree = prev_commit.tree()?;
-                let diff = repo.diff_tree_to_tree(Some(&prev_tree), Some(&tree), None)?;
+                let diff = repo.diff_tree_to_tree(prev_tree.as_ref(), Some(&tree), None)?;
                 for delta in diff.deltas() {
                     let file_path = delta.new_file().path().unwrap();
                     let file_mod_time = commit.time();
-                    mtimes
+                    files
                         .entry(file_path.to_owned())
-                        .and_modify(|t| *t = max(*t, file_mod_time))
-                        .or_insert(file_mod_time);
+                        .and_modify(|t| {
+                            t.mtime = max(t.mtime, file_mod_time);
+                            t.ctime = min(t.ctime, file_mod_time);
+                        })
+                        .or_insert(FileTimes {
+                            mtime: file_mod_time,
+                            ctime: file_mod_time,
+                        });
                 }
             }
         }
-        Ok(GitModificationTimes { mtimes })
+        Ok(GitModificationTimes { commit_id, files })
+    }
+
+    /// ID of the commit these times were collected from. If the repository
+    /// has moved on since, the times may be out of date.
+    pub fn commit_id(&self) -> &str {
+        &self.commit_id
     }
 
     /// Looks up the Git modification time for a given file path.
     /// The path must be relative to the root of the repository.
     pub fn for_path(&self, path: GitPath<'_>) -> &Time {
-        self.mtimes.get(path.path()).unwrap()
+        self.get(path).unwrap()
+    }
+
+    /// Looks up the Git modification time for a given file path, returning
+    /// `None` if the file has never been committed.
+    /// The path must be relative to the root of the repository.
+    pub fn get(&self, path: GitPath<'_>) -> Option<&Time> {
+        self.files.get(path.path()).map(|times| &times.mtime)
+    }
+
+    /// Looks up the time the file at the given path was first committed,
+    /// returning `None` if the file has never been committed.
+    /// The path must be relative to the root of the repository.
+    pub fn created(&self, path: GitPath<'_>) -> Option<&Time> {
+        self.files.get(path.path()).map(|times| &times.ctime)
+    }
+
+    /// Iterate over the path (relative to the root of the repository),
+    /// modification time, and creation time of every file, sorted by path
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &Time, &Time)> {
+        self.files
+            .iter()
+            .map(|(path, times)| (path.as_path(), &times.mtime, &times.ctime))
+    }
+}
+
+/// Serialize [`Time`] as seconds since the UNIX epoch plus a UTC offset
+mod time {
+    use git2::Time;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize, Serialize)]
+    struct Timestamp {
+        seconds: i64,
+        offset_minutes: i32,
+    }
+
+    pub(super) fn serialize<S: Serializer>(time: &Time, serializer: S) -> Result<S::Ok, S::Error> {
+        Timestamp {
+            seconds: time.seconds(),
+            offset_minutes: time.offset_minutes(),
+        }
+        .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Time, D::Error> {
+        let timestamp = Timestamp::deserialize(deserializer)?;
+        Ok(Time::new(timestamp.seconds, timestamp.offset_minutes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileTimes, GitModificationTimes};
+    use git2::Time;
+    use std::path::Path;
+
+    #[test]
+    fn iter_and_roundtrip() {
+        let mut mod_times = GitModificationTimes {
+            commit_id: "0123456789abcdef0123456789abcdef01234567".to_owned(),
+            files: Default::default(),
+        };
+
+        for (path, mtime, ctime) in &[("crates/b/b.md", 300, 200), ("crates/a/a.md", 100, 50)] {
+            mod_times.files.insert(
+                path.into(),
+                FileTimes {
+                    mtime: Time::new(*mtime, 60),
+                    ctime: Time::new(*ctime, 0),
+                },
+            );
+        }
+
+        let entries = mod_times
+            .iter()
+            .map(|(path, mtime, ctime)| (path, mtime.seconds(), ctime.seconds()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                (Path::new("crates/a/a.md"), 100, 50),
+                (Path::new("crates/b/b.md"), 300, 200)
+            ]
+        );
+
+        let serialized = toml::to_string(&mod_times).unwrap();
+        let deserialized: GitModificationTimes = toml::from_str(&serialized).unwrap();
+        assert!(deserialized == mod_times);
+        assert_eq!(deserialized.iter().next().unwrap().1.offset_minutes(), 60);
     }
 }
@@ -2,10 +2,13 @@
 
 use super::{with_authentication, Commit, DEFAULT_URL};
 use crate::{
-    error::{Error, ErrorKind},
+    error::{Error, RepositoryError},
     fs,
 };
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 /// Directory under `~/.cargo` where the advisory-db repo will be kept
 const ADVISORY_DB_DIRECTORY: &str = "advisory-db";
@@ -16,6 +19,19 @@ const LOCAL_REF: &str = "refs/heads/main";
 /// Ref for the `main` branch in the remote repository
 const REMOTE_REF: &str = "refs/remotes/origin/main";
 
+/// How a file changed between two revisions of a repository
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Change {
+    /// File was added
+    Added,
+
+    /// File was modified
+    Modified,
+
+    /// File was deleted
+    Deleted,
+}
+
 /// Git repository for a Rust advisory DB
 pub struct Repository {
     /// Path to the Git repository
@@ -40,53 +56,103 @@ impl Repository {
         Self::fetch(DEFAULT_URL, Repository::default_path(), true)
     }
 
-    /// Create a new [`GitRepository`] with the given URL and path
+    /// Create a new [`GitRepository`] with the given URL and path.
+    ///
+    /// Only fast-forward updates are applied to an existing checkout: if the
+    /// upstream history has diverged (e.g. after a force push), an error is
+    /// returned. See [`Repository::fetch_or_repair`] to recover automatically.
     pub fn fetch<P: Into<PathBuf>>(
         url: &str,
         into_path: P,
         ensure_fresh: bool,
     ) -> Result<Self, Error> {
+        let path = into_path.into();
+        Self::prepare(url, &path)?;
+
+        let (repo, latest_commit) = Self::update(url, &path)?;
+        Self::verify(repo, latest_commit, ensure_fresh)
+    }
+
+    /// Fetch a repository like [`Repository::fetch`], but if the existing
+    /// checkout is corrupted or the upstream history has diverged from it,
+    /// re-clone it into a fresh directory instead of failing.
+    ///
+    /// The existing checkout is never deleted: it's moved aside to a sibling
+    /// directory named `<path>.bak-<unix time>`. Paths other than
+    /// [`Repository::default_path`] are only repaired if they're git
+    /// checkouts (i.e. contain a `.git` directory).
+    ///
+    /// The `on_repair` callback is invoked with the error which triggered
+    /// the repair before the repository is re-cloned. Network and
+    /// authentication errors never trigger a repair.
+    pub fn fetch_or_repair<P, F>(
+        url: &str,
+        into_path: P,
+        ensure_fresh: bool,
+        mut on_repair: F,
+    ) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+        F: FnMut(&Error),
+    {
+        let path = into_path.into();
+        Self::prepare(url, &path)?;
+
+        let (repo, latest_commit) = match Self::update(url, &path) {
+            Ok(result) => result,
+            Err(e) if path.exists() && is_repairable(&e) && is_checkout(&path) => {
+                on_repair(&e);
+                Self::reclone(url, &path)?;
+                Self::checkout(&path)?
+            }
+            Err(e) => return Err(e),
+        };
+
+        Self::verify(repo, latest_commit, ensure_fresh)
+    }
+
+    /// Validate the URL and create the parent directory of the checkout
+    fn prepare(url: &str, path: &Path) -> Result<(), Error> {
         if !url.starts_with("https://") {
-            fail!(
-                ErrorKind::BadParam,
-                "expected {} to start with https://",
-                url
-            );
+            return Err(RepositoryError::InsecureUrl(url.to_owned()).into());
         }
 
-        let path = into_path.into();
+        if cfg!(not(feature = "git-https")) {
+            return Err(RepositoryError::HttpsUnsupported(url.to_owned()).into());
+        }
 
         if let Some(parent) = path.parent() {
             if !parent.is_dir() {
                 fs::create_dir_all(parent)?;
             }
         } else {
-            fail!(ErrorKind::BadParam, "invalid directory: {}", path.display())
+            return Err(RepositoryError::InvalidDirectory(path.to_owned()).into());
         }
 
         // Avoid libgit2 errors in the case the directory exists but is
         // otherwise empty.
         //
         // See: https://github.com/RustSec/cargo-audit/issues/32
-        if path.is_dir() && fs::read_dir(&path)?.next().is_none() {
-            fs::remove_dir(&path)?;
+        if path.is_dir() && fs::read_dir(path)?.next().is_none() {
+            fs::remove_dir(path)?;
         }
 
+        Ok(())
+    }
+
+    /// Clone the repository, or fast-forward an existing checkout, and check
+    /// out the latest commit
+    fn update(url: &str, path: &Path) -> Result<(Self, Commit), Error> {
         let git_config = git2::Config::new()?;
 
         with_authentication(url, &git_config, |f| {
+            let mut fetch_opts = fetch_options();
             let mut callbacks = git2::RemoteCallbacks::new();
             callbacks.credentials(f);
-
-            let mut proxy_opts = git2::ProxyOptions::new();
-            proxy_opts.auto();
-
-            let mut fetch_opts = git2::FetchOptions::new();
             fetch_opts.remote_callbacks(callbacks);
-            fetch_opts.proxy_options(proxy_opts);
 
             if path.exists() {
-                let repo = git2::Repository::open(&path)?;
+                let repo = git2::Repository::open(path)?;
                 let refspec = LOCAL_REF.to_owned() + ":" + REMOTE_REF;
 
                 // Fetch remote packfiles and update tips
@@ -97,9 +163,25 @@ impl Repository {
                 let remote_main_ref = repo.find_reference(REMOTE_REF)?;
                 let remote_target = remote_main_ref.target().unwrap();
 
-                // Set the local main ref to match the remote
+                // Fast-forward the local main ref to match the remote
                 match repo.find_reference(LOCAL_REF) {
                     Ok(mut local_main_ref) => {
+                        if let Some(local_target) = local_main_ref.target() {
+                            if local_target != remote_target
+                                && !repo.graph_descendant_of(remote_target, local_target)?
+                            {
+                                let diverged = Diverged {
+                                    local: local_target,
+                                    remote: remote_target,
+                                };
+
+                                return Err(Error::from(RepositoryError::Diverged(
+                                    diverged.to_string(),
+                                ))
+                                .with_source(diverged));
+                            }
+                        }
+
                         local_main_ref.set_target(
                             remote_target,
                             &format!(
@@ -112,7 +194,7 @@ impl Repository {
                         // TODO(tarcieri): remove this workaround after repos have migrated
                         let old_ref = repo.find_reference("refs/heads/master")?;
                         git2::Branch::wrap(old_ref).rename("main", true)?;
-                        Self::fetch(url, &path, ensure_fresh)?;
+                        Self::update(url, path)?;
                     }
                     Err(e) => {
                         return Err(e.into());
@@ -121,35 +203,71 @@ impl Repository {
             } else {
                 git2::build::RepoBuilder::new()
                     .fetch_options(fetch_opts)
-                    .clone(url, &path)?;
+                    .clone(url, path)?;
             }
 
             Ok(())
         })?;
 
+        Self::checkout(path)
+    }
+
+    /// Clone the repository into a fresh directory, moving the existing
+    /// checkout at `path` aside and replacing it once the clone has succeeded
+    fn reclone(url: &str, path: &Path) -> Result<(), Error> {
+        let mut fresh_name = path.file_name().unwrap_or_default().to_owned();
+        fresh_name.push(".repair");
+        let fresh_path = path.with_file_name(fresh_name);
+
+        if fresh_path.exists() {
+            fs::remove_dir_all(&fresh_path)?;
+        }
+
+        let git_config = git2::Config::new()?;
+
+        with_authentication(url, &git_config, |f| {
+            let mut fetch_opts = fetch_options();
+            let mut callbacks = git2::RemoteCallbacks::new();
+            callbacks.credentials(f);
+            fetch_opts.remote_callbacks(callbacks);
+
+            git2::build::RepoBuilder::new()
+                .fetch_options(fetch_opts)
+                .clone(url, &fresh_path)?;
+
+            Ok(())
+        })?;
+
+        fs::rename(path, backup_path(path, SystemTime::now()))?;
+        fs::rename(&fresh_path, path)?;
+        Ok(())
+    }
+
+    /// Open the checkout at the given path and reset it to the latest commit
+    fn checkout(path: &Path) -> Result<(Self, Commit), Error> {
         let repo = Self::open(path)?;
         let latest_commit = repo.latest_commit()?;
         latest_commit.reset(&repo)?;
+        Ok((repo, latest_commit))
+    }
 
+    /// Ensure the latest commit of a freshly fetched repository is signed and
+    /// (optionally) recent
+    fn verify(repo: Self, latest_commit: Commit, ensure_fresh: bool) -> Result<Self, Error> {
         // Any commits we fetch should always be signed
         // TODO: verify signatures against GitHub's public key
         if latest_commit.signature.is_none() {
-            fail!(
-                ErrorKind::Repo,
-                "no signature on commit {}: {} ({})",
-                latest_commit.commit_id,
-                latest_commit.summary,
-                latest_commit.author
-            );
+            return Err(RepositoryError::Unsigned {
+                commit_id: latest_commit.commit_id,
+                summary: latest_commit.summary,
+                author: latest_commit.author,
+            }
+            .into());
         }
 
         // Ensure that the upstream repository hasn't gone stale
         if ensure_fresh && !latest_commit.is_fresh() {
-            fail!(
-                ErrorKind::Repo,
-                "repository is stale (last commit: {:?})",
-                latest_commit.timestamp
-            );
+            return Err(RepositoryError::Stale(format!("{:?}", latest_commit.timestamp)).into());
         }
 
         Ok(repo)
@@ -163,7 +281,7 @@ impl Repository {
         if repo.state() == git2::RepositoryState::Clean {
             Ok(Self { path, repo })
         } else {
-            fail!(ErrorKind::Repo, "bad repository state: {:?}", repo.state())
+            Err(RepositoryError::BadState(format!("{:?}", repo.state())).into())
         }
     }
 
@@ -172,8 +290,277 @@ impl Repository {
         Commit::from_repo_head(self)
     }
 
+    /// Get the ID of the latest commit reachable from `HEAD` which was made
+    /// before the given time, or `None` if all commits are newer
+    pub fn commit_before(&self, time: SystemTime) -> Result<Option<String>, Error> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        revwalk.push_head()?;
+
+        for commit_id in revwalk {
+            let commit = self.repo.find_commit(commit_id?)?;
+            let timestamp = UNIX_EPOCH + Duration::from_secs(commit.time().seconds() as u64);
+
+            if timestamp < time {
+                return Ok(Some(commit.id().to_string()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Get information about the most recent `n` commits reachable from
+    /// `HEAD`, newest first
+    pub fn recent_commits(&self, n: usize) -> Result<Vec<Commit>, Error> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        revwalk.push_head()?;
+
+        revwalk
+            .take(n)
+            .map(|commit_id| Commit::from_oid(self, commit_id?))
+            .collect()
+    }
+
+    /// Get the ID of the commit the local `main` branch pointed to before it
+    /// was most recently updated by [`Repository::fetch`], or `None` if it
+    /// hasn't been updated since the repository was cloned
+    pub fn previous_commit(&self) -> Result<Option<String>, Error> {
+        let reflog = self.repo.reflog(LOCAL_REF)?;
+
+        // Skip entries which didn't move the branch (e.g. resets)
+        Ok(reflog
+            .iter()
+            .find(|entry| !entry.id_old().is_zero() && entry.id_old() != entry.id_new())
+            .map(|entry| entry.id_old().to_string()))
+    }
+
+    /// Get the paths (relative to the repository root) of files which were
+    /// added between two revisions (e.g. commit hashes, tags, or `HEAD`).
+    ///
+    /// If `from` is `None`, every file present at `to` is considered added.
+    pub fn added_files(&self, from: Option<&str>, to: &str) -> Result<Vec<PathBuf>, Error> {
+        Ok(self
+            .changed_files(from, to)?
+            .into_iter()
+            .filter(|(change, _)| *change == Change::Added)
+            .map(|(_, path)| path)
+            .collect())
+    }
+
+    /// Get the paths (relative to the repository root) of files which were
+    /// added, modified, or deleted between two revisions.
+    ///
+    /// If `from` is `None`, every file present at `to` is considered added.
+    pub fn changed_files(
+        &self,
+        from: Option<&str>,
+        to: &str,
+    ) -> Result<Vec<(Change, PathBuf)>, Error> {
+        let tree = |rev: &str| -> Result<git2::Tree<'_>, Error> {
+            Ok(self.repo.revparse_single(rev)?.peel_to_tree()?)
+        };
+
+        let old_tree = match from {
+            Some(rev) => Some(tree(rev)?),
+            None => None,
+        };
+
+        let new_tree = tree(to)?;
+        let diff = self
+            .repo
+            .diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| {
+                let change = match delta.status() {
+                    git2::Delta::Added => Change::Added,
+                    git2::Delta::Deleted => return Some((Change::Deleted, delta.old_file())),
+                    git2::Delta::Modified => Change::Modified,
+                    _ => return None,
+                };
+
+                Some((change, delta.new_file()))
+            })
+            .filter_map(|(change, file)| file.path().map(|path| (change, path.to_owned())))
+            .collect())
+    }
+
+    /// Get information about the commit a revision (e.g. a commit hash, a
+    /// tag, or `HEAD`) refers to
+    pub fn find_commit(&self, rev: &str) -> Result<Commit, Error> {
+        let commit = self.repo.revparse_single(rev)?.peel_to_commit()?;
+        Commit::from_oid(self, commit.id())
+    }
+
+    /// Get the paths (relative to the repository root) of all files at a
+    /// revision, read from git's object database rather than the working
+    /// tree (so this also works for bare repositories)
+    pub fn files_at(&self, rev: &str) -> Result<Vec<PathBuf>, Error> {
+        let tree = self.repo.revparse_single(rev)?.peel_to_tree()?;
+        let mut files = vec![];
+
+        tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob) {
+                if let Some(name) = entry.name() {
+                    files.push(Path::new(root).join(name));
+                }
+            }
+
+            git2::TreeWalkResult::Ok
+        })?;
+
+        Ok(files)
+    }
+
+    /// Read the contents of a file (given by its path relative to the
+    /// repository root) at a revision from git's object database, without
+    /// checking the revision out
+    pub fn read_file_at(&self, rev: &str, path: &Path) -> Result<Vec<u8>, Error> {
+        let tree = self.repo.revparse_single(rev)?.peel_to_tree()?;
+        let object = tree.get_path(path)?.to_object(&self.repo)?;
+
+        match object.as_blob() {
+            Some(blob) => Ok(blob.content().to_vec()),
+            None => Err(RepositoryError::NotAFile {
+                path: path.to_owned(),
+                rev: rev.to_owned(),
+            }
+            .into()),
+        }
+    }
+
     /// Path to the local checkout of a git repository
     pub fn path(&self) -> &Path {
         self.path.as_ref()
     }
 }
+
+/// Options for fetching from a remote, using the system's proxy settings
+fn fetch_options<'a>() -> git2::FetchOptions<'a> {
+    let mut proxy_opts = git2::ProxyOptions::new();
+    proxy_opts.auto();
+
+    let mut fetch_opts = git2::FetchOptions::new();
+    fetch_opts.proxy_options(proxy_opts);
+    fetch_opts
+}
+
+/// Upstream history has diverged from a local checkout (e.g. after a force
+/// push), so it can't be fast-forwarded
+#[derive(Debug, thiserror::Error)]
+#[error("{remote} is not a fast-forward of {local}")]
+struct Diverged {
+    /// Tip of the local checkout
+    local: git2::Oid,
+
+    /// Tip of the remote
+    remote: git2::Oid,
+}
+
+/// Can the given error from updating a checkout be fixed by re-cloning it?
+///
+/// Errors which happened while talking to the remote (e.g. network or
+/// authentication failures) would just recur, as would filesystem errors,
+/// so only diverged history and other git errors (e.g. corrupted objects or
+/// refs) are considered repairable.
+fn is_repairable(err: &Error) -> bool {
+    if err.source_as::<Diverged>().is_some() {
+        return true;
+    }
+
+    match err.source_as::<git2::Error>() {
+        Some(e) => !matches!(
+            e.class(),
+            git2::ErrorClass::Net
+                | git2::ErrorClass::Http
+                | git2::ErrorClass::Ssl
+                | git2::ErrorClass::Ssh
+                | git2::ErrorClass::Callback
+                | git2::ErrorClass::Os
+        ),
+        None => false,
+    }
+}
+
+/// Is the given path one which may be re-cloned, i.e. the default location
+/// of the advisory DB or a git checkout?
+fn is_checkout(path: &Path) -> bool {
+    let is_default = home::cargo_home()
+        .map(|cargo_home| path == cargo_home.join(ADVISORY_DB_DIRECTORY))
+        .unwrap_or(false);
+
+    is_default || path.join(".git").is_dir()
+}
+
+/// Get the path to move a checkout to before re-cloning it at the given time
+fn backup_path(path: &Path, time: SystemTime) -> PathBuf {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(format!(".bak-{}", secs));
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backup_path, is_checkout, is_repairable, Diverged};
+    use crate::error::{Error, ErrorKind};
+    use std::{
+        fs,
+        path::Path,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn repairable_errors() {
+        let git_error = |class| {
+            Error::from(git2::Error::new(
+                git2::ErrorCode::GenericError,
+                class,
+                "error",
+            ))
+        };
+
+        assert!(is_repairable(&git_error(git2::ErrorClass::Odb)));
+        assert!(is_repairable(&git_error(git2::ErrorClass::Reference)));
+        assert!(!is_repairable(&git_error(git2::ErrorClass::Net)));
+        assert!(!is_repairable(&git_error(git2::ErrorClass::Http)));
+
+        let oid = git2::Oid::zero();
+        let diverged = Diverged {
+            local: oid,
+            remote: oid,
+        };
+        assert!(is_repairable(
+            &format_err!(ErrorKind::Repo, "diverged").with_source(diverged)
+        ));
+        assert!(!is_repairable(&format_err!(ErrorKind::Repo, "stale")));
+    }
+
+    #[test]
+    fn backup_paths() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+
+        assert_eq!(
+            backup_path(Path::new("/db/advisory-db"), time),
+            Path::new("/db/advisory-db.bak-1600000000")
+        );
+    }
+
+    #[test]
+    fn only_checkouts_are_repaired() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("advisory-db");
+
+        fs::create_dir(&path).unwrap();
+        assert!(!is_checkout(&path));
+
+        fs::create_dir(path.join(".git")).unwrap();
+        assert!(is_checkout(&path));
+    }
+}
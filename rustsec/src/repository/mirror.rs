@@ -0,0 +1,339 @@
+//! Static file mirrors of the advisory database.
+//!
+//! A mirror is a directory containing the advisory database along with a
+//! [`Manifest`] listing the SHA-256 digest of every file. Mirrors can be
+//! hosted on any static file server (e.g. inside an air-gapped network) and
+//! are verified against the manifest when they are fetched or opened.
+
+use crate::{
+    error::{Error, ErrorKind, RepositoryError},
+    fs,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+/// Name of the manifest file at the root of a mirror
+pub const MANIFEST_FILE: &str = "manifest.toml";
+
+/// Directory under `~/.cargo` where mirrors are fetched to by default
+pub const ADVISORY_DB_MIRROR_DIRECTORY: &str = "advisory-db-mirror";
+
+/// Manifest of the files contained in a mirror, along with their digests
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Manifest {
+    /// ID of the advisory DB git commit the mirror was generated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// Files in the mirror
+    #[serde(default, rename = "file")]
+    pub files: Vec<Entry>,
+}
+
+/// File contained in a mirror
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Entry {
+    /// Path to the file relative to the root of the mirror (`/`-separated)
+    pub path: String,
+
+    /// Hex-encoded SHA-256 digest of the file's contents
+    pub sha256: String,
+}
+
+impl Manifest {
+    /// Get the default path where mirrors are fetched to
+    pub fn default_path() -> PathBuf {
+        home::cargo_home()
+            .unwrap_or_else(|err| {
+                panic!("Error locating Cargo home directory: {}", err);
+            })
+            .join(ADVISORY_DB_MIRROR_DIRECTORY)
+    }
+
+    /// Generate a manifest for all files located under the given directory
+    pub fn generate(dir: &Path) -> Result<Self, Error> {
+        let mut files = vec![];
+        collect_files(dir, dir, &mut files)?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            commit: None,
+            files,
+        })
+    }
+
+    /// Load the manifest located at the root of the given mirror directory
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        fs::read_to_string(dir.join(MANIFEST_FILE))?.parse()
+    }
+
+    /// Save this manifest to the root of the given mirror directory
+    pub fn save(&self, dir: &Path) -> Result<(), Error> {
+        let toml = toml::to_string(self).map_err(|e| {
+            format_err!(ErrorKind::Parse, "error serializing manifest: {}", e).with_source(e)
+        })?;
+
+        fs::write(dir.join(MANIFEST_FILE), toml)?;
+        Ok(())
+    }
+
+    /// Compute a hex-encoded SHA-256 digest identifying the snapshot of the
+    /// database described by this manifest, i.e. the paths and digests of
+    /// all of its files
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+
+        for entry in &self.files {
+            hasher.update(format!("{}  {}\n", entry.sha256, entry.path));
+        }
+
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Ensure this manifest has the given digest (see [`Manifest::digest`]).
+    ///
+    /// The manifest is served by the mirror itself, so on its own it only
+    /// detects files which were corrupted or tampered with after it was
+    /// written. Pinning a digest obtained out of band (e.g. the one printed
+    /// by `rustsec-admin mirror`) also detects a compromised mirror.
+    pub fn verify_digest(&self, expected: &str) -> Result<(), Error> {
+        let actual = self.digest();
+
+        if actual != expected.to_ascii_lowercase() {
+            return Err(RepositoryError::DigestMismatch {
+                expected: expected.to_owned(),
+                actual,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Verify the contents of the given mirror directory match this manifest
+    pub fn verify(&self, dir: &Path) -> Result<(), Error> {
+        for entry in &self.files {
+            let path = entry.local_path(dir)?;
+            entry.verify(&fs::read(&path)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fetch a mirror hosted at the given base URL into a local directory.
+    ///
+    /// If an expected manifest digest is given, the manifest is checked
+    /// against it (see [`Manifest::verify_digest`]) before anything else is
+    /// downloaded.
+    ///
+    /// Files which are already present locally with the expected digest are
+    /// not downloaded again. Files left over from a previous fetch which are
+    /// no longer listed in the manifest are removed.
+    pub fn fetch(url: &str, dir: &Path, expected_digest: Option<&str>) -> Result<Self, Error> {
+        let base_url = url.trim_end_matches('/');
+        let manifest: Self = String::from_utf8(download(base_url, MANIFEST_FILE)?)
+            .map_err(|e| RepositoryError::InvalidManifest(e.to_string()))?
+            .parse()?;
+
+        if let Some(expected) = expected_digest {
+            manifest.verify_digest(expected)?;
+        }
+
+        fs::create_dir_all(dir)?;
+
+        let previous = if dir.join(MANIFEST_FILE).exists() {
+            Some(Self::load(dir)?)
+        } else {
+            None
+        };
+
+        for entry in &manifest.files {
+            let path = entry.local_path(dir)?;
+
+            if let Ok(existing) = fs::read(&path) {
+                if entry.verify(&existing).is_ok() {
+                    continue;
+                }
+            }
+
+            let contents = download(base_url, &entry.path)?;
+            entry.verify(&contents)?;
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            fs::write(&path, contents)?;
+        }
+
+        if let Some(previous) = previous {
+            for entry in &previous.files {
+                if !manifest.files.iter().any(|e| e.path == entry.path) {
+                    let path = entry.local_path(dir)?;
+
+                    if path.exists() {
+                        fs::remove_file(path)?;
+                    }
+                }
+            }
+        }
+
+        manifest.save(dir)?;
+        Ok(manifest)
+    }
+}
+
+impl std::str::FromStr for Manifest {
+    type Err = Error;
+
+    fn from_str(toml_string: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(toml_string)?)
+    }
+}
+
+impl Entry {
+    /// Compute the manifest entry for a file with the given contents
+    pub fn new(path: impl Into<String>, contents: &[u8]) -> Self {
+        Self {
+            path: path.into(),
+            sha256: format!("{:x}", Sha256::digest(contents)),
+        }
+    }
+
+    /// Get the path to this file within the given local directory, ensuring
+    /// it can't escape that directory
+    pub fn local_path(&self, dir: &Path) -> Result<PathBuf, Error> {
+        let relative_path = Path::new(&self.path);
+
+        if !relative_path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(RepositoryError::InvalidManifestPath(self.path.clone()).into());
+        }
+
+        Ok(dir.join(relative_path))
+    }
+
+    /// Verify the given file contents match this entry's digest
+    pub fn verify(&self, contents: &[u8]) -> Result<(), Error> {
+        let actual = Self::new(self.path.as_str(), contents);
+
+        if actual.sha256 != self.sha256 {
+            return Err(RepositoryError::ChecksumMismatch {
+                path: self.path.clone(),
+                expected: self.sha256.clone(),
+                actual: actual.sha256,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Recursively compute manifest entries for all files under `dir`
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<Entry>) -> Result<(), Error> {
+    for dir_entry in fs::read_dir(dir)? {
+        let path = dir_entry?.path();
+
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+            continue;
+        }
+
+        // Paths are always below `root`, so this can't fail
+        let relative_path = path.strip_prefix(root).unwrap();
+
+        if relative_path == Path::new(MANIFEST_FILE) {
+            continue;
+        }
+
+        let components = relative_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>();
+
+        files.push(Entry::new(components.join("/"), &fs::read(&path)?));
+    }
+
+    Ok(())
+}
+
+/// Download a file from a mirror
+fn download(base_url: &str, path: &str) -> Result<Vec<u8>, Error> {
+    let url = format!("{}/{}", base_url, path);
+
+    let response = crate::http::agent()?
+        .get(&url)
+        .call()
+        .map_err(|e| RepositoryError::Fetch {
+            reason: e.to_string(),
+            url: url.clone(),
+        })?;
+
+    let mut body = vec![];
+    response.into_reader().read_to_end(&mut body)?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, Manifest};
+
+    #[test]
+    fn generate_and_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("crates")).unwrap();
+        std::fs::write(dir.path().join("crates").join("a.md"), b"advisory").unwrap();
+
+        let manifest = Manifest::generate(dir.path()).unwrap();
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].path, "crates/a.md");
+        manifest.verify(dir.path()).unwrap();
+
+        manifest.save(dir.path()).unwrap();
+        assert_eq!(Manifest::load(dir.path()).unwrap(), manifest);
+        assert_eq!(
+            Manifest::load(dir.path()).unwrap().digest(),
+            manifest.digest()
+        );
+
+        std::fs::write(dir.path().join("crates").join("a.md"), b"tampered").unwrap();
+        assert!(manifest.verify(dir.path()).is_err());
+        assert_ne!(
+            Manifest::generate(dir.path()).unwrap().digest(),
+            manifest.digest()
+        );
+    }
+
+    #[test]
+    fn verify_digest() {
+        let manifest = Manifest {
+            commit: None,
+            files: vec![Entry::new("crates/a.md", b"advisory")],
+        };
+        let digest = manifest.digest();
+
+        manifest.verify_digest(&digest).unwrap();
+        manifest
+            .verify_digest(&digest.to_ascii_uppercase())
+            .unwrap();
+
+        let tampered = Manifest {
+            commit: None,
+            files: vec![Entry::new("crates/a.md", b"tampered")],
+        };
+        assert!(tampered.verify_digest(&digest).is_err());
+    }
+
+    #[test]
+    fn reject_escaping_paths() {
+        let entry = Entry::new("../outside.md", b"");
+        assert!(entry.local_path(std::path::Path::new("mirror")).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! Helpers for checking invariants of advisories.
+//!
+//! These are used by this crate's own test suite, and are available with the
+//! `testing` feature so the same checks can be run against every advisory in
+//! the advisory DB as part of CI. They aren't covered by semver guarantees.
+
+use crate::{
+    advisory::Versions,
+    error::{Error, ErrorKind},
+    osv,
+};
+use semver::{Version, VersionReq};
+
+/// Check that the OSV ranges derived from `versions` classify `version`
+/// identically to the original semver requirements.
+///
+/// Pre-releases are exempt, since semver requirements deliberately don't
+/// match them (which is why OSV ranges are used for matching instead).
+pub fn check_version(versions: &Versions, version: &Version) -> Result<(), Error> {
+    if !version.pre.is_empty() {
+        return Ok(());
+    }
+
+    let semver_vulnerable = !versions
+        .patched()
+        .iter()
+        .chain(versions.unaffected())
+        .any(|req| req.matches(version));
+
+    let osv_vulnerable = osv::ranges_for_advisory(versions)
+        .iter()
+        .any(|range| range.affects(version));
+
+    if semver_vulnerable != osv_vulnerable {
+        fail!(
+            ErrorKind::Version,
+            "version {} is {} according to semver requirements but {} according to OSV ranges",
+            version,
+            classification(semver_vulnerable),
+            classification(osv_vulnerable)
+        );
+    }
+
+    Ok(())
+}
+
+/// Check that the OSV ranges derived from `versions` agree with the original
+/// semver requirements at every version where their classification could
+/// change, i.e. at and around each version mentioned in the requirements.
+pub fn check_ranges(versions: &Versions) -> Result<(), Error> {
+    for version in boundary_versions(versions) {
+        check_version(versions, &version)?;
+    }
+
+    Ok(())
+}
+
+/// Versions at and around each bound of the given version requirements
+pub fn boundary_versions(versions: &Versions) -> Vec<Version> {
+    let mut result = vec![Version::new(0, 0, 0)];
+
+    for req in versions.patched().iter().chain(versions.unaffected()) {
+        result.extend(req_boundaries(req));
+    }
+
+    result.sort();
+    result.dedup();
+    result
+}
+
+/// Versions at and around the bounds of a single requirement
+fn req_boundaries(req: &VersionReq) -> Vec<Version> {
+    let mut result = vec![];
+
+    for comparator in &req.comparators {
+        let major = comparator.major;
+        let minor = comparator.minor.unwrap_or(0);
+        let patch = comparator.patch.unwrap_or(0);
+
+        result.push(Version::new(major, minor, patch));
+        result.push(Version::new(major, minor, patch.saturating_add(1)));
+        result.push(Version::new(major, minor.saturating_add(1), 0));
+        result.push(Version::new(major.saturating_add(1), 0, 0));
+
+        if patch > 0 {
+            result.push(Version::new(major, minor, patch - 1));
+        } else if minor > 0 {
+            result.push(Version::new(major, minor - 1, u64::MAX));
+        } else if major > 0 {
+            result.push(Version::new(major - 1, u64::MAX, u64::MAX));
+        }
+    }
+
+    result
+}
+
+/// Describe whether a version is vulnerable
+fn classification(vulnerable: bool) -> &'static str {
+    if vulnerable {
+        "vulnerable"
+    } else {
+        "not vulnerable"
+    }
+}
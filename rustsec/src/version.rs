@@ -0,0 +1,174 @@
+//! Helpers for comparing versions while ignoring build metadata.
+//!
+//! Per [SemVer 2.0](https://semver.org/#spec-item-10), build metadata (e.g.
+//! the `+build.5` in `1.0.0+build.5`) must be ignored when determining
+//! version precedence. The `Eq` and `Ord` impls of [`Version`] take it into
+//! account however, so `1.0.0+build.5` would otherwise sort after `1.0.0` and
+//! be mistaken for a newer (and potentially patched) release. Everywhere
+//! versions from a lockfile are compared with versions from an advisory or
+//! the registry, these helpers should be used instead.
+//!
+//! [`matches`] evaluates version requirements the same way advisories are
+//! matched, so other tools (e.g. advisory linters, OSV converters and
+//! third-party scanners) can agree with `cargo audit` on which versions a
+//! requirement covers.
+
+use crate::{advisory::PrereleaseMatching, osv::UnaffectedRange, Error, Version, VersionReq};
+use std::{cmp::Ordering, convert::TryFrom};
+
+/// Get a copy of the given version without its build metadata
+pub fn normalize(version: &Version) -> Version {
+    let mut version = version.clone();
+    version.build = Default::default();
+    version
+}
+
+/// Compare versions by SemVer precedence, ignoring build metadata
+pub fn cmp_precedence(a: &Version, b: &Version) -> Ordering {
+    (a.major, a.minor, a.patch)
+        .cmp(&(b.major, b.minor, b.patch))
+        .then_with(|| a.pre.cmp(&b.pre))
+}
+
+/// Are the given versions equal, ignoring build metadata?
+pub fn eq_precedence(a: &Version, b: &Version) -> bool {
+    cmp_precedence(a, b) == Ordering::Equal
+}
+
+/// Does the given version match the given requirement, according to the
+/// rules used for the `patched` and `unaffected` versions of advisories?
+///
+/// Unlike [`VersionReq::matches`], pre-releases aren't special: versions
+/// are compared by [SemVer 2.0 precedence](https://semver.org/#spec-item-11),
+/// ignoring build metadata, so `1.2.0-alpha.1` matches `>= 1.1, < 1.2`
+/// (it precedes `1.2.0`) but not `>= 1.2` (it precedes the release).
+/// Caret and tilde requirements exclude pre-releases of their upper bound,
+/// i.e. `^1.2` doesn't match `2.0.0-alpha.1`.
+///
+/// This corresponds to [`PrereleaseMatching::Strict`] (see [`matches_with`]).
+///
+/// Returns an error for requirements which aren't valid in advisories, e.g.
+/// ones with wildcards (e.g. `1.*`) or several lower or upper bounds.
+pub fn matches(req: &VersionReq, version: &Version) -> Result<bool, Error> {
+    matches_with(req, version, PrereleaseMatching::Strict)
+}
+
+/// Does the given version match the given requirement, matching
+/// pre-releases according to the given rules?
+///
+/// With [`PrereleaseMatching::Permissive`], pre-releases also match if the
+/// release they precede does, e.g. `1.0.0-alpha.2` matches `>= 1.0.0`.
+pub fn matches_with(
+    req: &VersionReq,
+    version: &Version,
+    prerelease: PrereleaseMatching,
+) -> Result<bool, Error> {
+    let range = UnaffectedRange::try_from(req)?;
+
+    Ok(match prerelease {
+        PrereleaseMatching::Strict => range.contains(version),
+        PrereleaseMatching::Permissive => {
+            range.contains(version)
+                || (!version.pre.is_empty()
+                    && range.contains(&Version::new(version.major, version.minor, version.patch)))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cmp_precedence, eq_precedence, matches, matches_with, normalize};
+    use crate::{
+        advisory::{PrereleaseMatching, Versions},
+        Version, VersionReq,
+    };
+    use std::cmp::Ordering;
+
+    fn v(version: &str) -> Version {
+        version.parse().unwrap()
+    }
+
+    #[test]
+    fn ignores_build_metadata() {
+        assert_eq!(normalize(&v("1.0.0+build.5")), v("1.0.0"));
+        assert_eq!(normalize(&v("1.0.0-rc.1+build.5")), v("1.0.0-rc.1"));
+
+        assert!(eq_precedence(&v("1.0.0+build.5"), &v("1.0.0")));
+        assert!(eq_precedence(&v("1.0.0+a"), &v("1.0.0+b")));
+        assert!(!eq_precedence(&v("1.0.0-rc.1+a"), &v("1.0.0+a")));
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            cmp_precedence(&v("1.0.0+build.5"), &v("1.0.1")),
+            Ordering::Less
+        );
+        assert_eq!(
+            cmp_precedence(&v("1.0.0-alpha+zzz"), &v("1.0.0-beta")),
+            Ordering::Less
+        );
+        assert_eq!(
+            cmp_precedence(&v("1.0.0+build.5"), &v("1.0.0-rc.1")),
+            Ordering::Greater
+        );
+    }
+
+    fn req(req: &str) -> VersionReq {
+        req.parse().unwrap()
+    }
+
+    #[test]
+    fn matches_prereleases() {
+        assert!(matches(&req(">= 1.1, < 1.2"), &v("1.2.0-alpha.1")).unwrap());
+        assert!(!matches(&req(">= 1.2"), &v("1.2.0-alpha.1")).unwrap());
+        assert!(matches(&req(">= 1.2"), &v("1.2.1-alpha.1")).unwrap());
+        assert!(matches(&req(">= 1.2.0-rc.1"), &v("1.2.0-rc.2")).unwrap());
+        assert!(!matches(&req("^1.2"), &v("2.0.0-alpha.1")).unwrap());
+        assert!(!matches(&req("~1.2"), &v("1.3.0-alpha.1")).unwrap());
+        assert!(matches(&req("= 1.2.3"), &v("1.2.3+build.5")).unwrap());
+
+        assert!(matches_with(
+            &req(">= 1.2"),
+            &v("1.2.0-alpha.1"),
+            PrereleaseMatching::Permissive
+        )
+        .unwrap());
+        assert!(!matches_with(
+            &req(">= 1.2"),
+            &v("1.1.9-alpha.1"),
+            PrereleaseMatching::Permissive
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn matches_unsupported() {
+        assert!(matches(&req("1.*"), &v("1.0.0")).is_err());
+        assert!(matches(&req(">= 1.0, >= 2.0"), &v("2.0.0")).is_err());
+    }
+
+    /// requirements should match exactly the versions advisories consider
+    /// unaffected
+    #[test]
+    fn matches_agrees_with_advisories() {
+        let patched = req(">= 1.2.3");
+        let versions = Versions::new(vec![patched.clone()], vec![]).unwrap();
+
+        for version in &[
+            "1.2.2",
+            "1.2.3-rc.1",
+            "1.2.3",
+            "1.2.3+build.1",
+            "1.3.0-alpha",
+        ] {
+            for &prerelease in &[PrereleaseMatching::Strict, PrereleaseMatching::Permissive] {
+                let version = v(version);
+                assert_eq!(
+                    matches_with(&patched, &version, prerelease).unwrap(),
+                    !versions.is_vulnerable_with(&version, prerelease)
+                );
+            }
+        }
+    }
+}
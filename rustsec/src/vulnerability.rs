@@ -1,9 +1,26 @@
 //! Vulnerabilities represent the interesection of the [`Advisory`] database
 //! and a particular `Cargo.lock` file.
 
+mod fix_age;
+mod fix_status;
+mod remediation;
+mod remedy;
+
+pub use self::{
+    fix_age::FixAge,
+    fix_status::FixStatus,
+    remediation::{FixCandidate, Remediation},
+    remedy::Remedy,
+};
+
 use crate::{
-    advisory::{self, affected::FunctionPath, Advisory},
+    advisory::{
+        self, affected::FunctionPath, Advisory, Informational, SeverityFallback, SeverityInfo,
+    },
+    cargo_lock::Dependency,
+    dependency::Relationship,
     package::Package,
+    report::{Override, ReviewStatus},
 };
 use serde::{Deserialize, Serialize};
 
@@ -21,16 +38,94 @@ pub struct Vulnerability {
 
     /// Vulnerable package
     pub package: Package,
+
+    /// Severity of the vulnerability, and whether it was declared in the
+    /// advisory or derived from the advisory type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<SeverityInfo>,
+
+    /// Team which owns the vulnerable package (see [`crate::report::Owners`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Minimum depth of the vulnerable package in the dependency graph,
+    /// where direct dependencies of workspace members have depth 1 (see
+    /// [`crate::Report::annotate_dependencies`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+
+    /// Whether the vulnerable package is a direct or transitive dependency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationship: Option<Relationship>,
+
+    /// How the vulnerability can be fixed (only determined when the
+    /// registry index is available, see [`crate::Report::classify_remedies`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remedy: Option<Remedy>,
+
+    /// Candidate fix versions along with the commands to upgrade to them,
+    /// and any workaround described by the advisory (candidates are refined
+    /// using the registry index by [`crate::Report::classify_remedies`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<Remediation>,
+
+    /// Whether a release fixing the vulnerability exists (see
+    /// [`crate::Report::annotate_fix_status`])
+    #[serde(alias = "fix-status", default, skip_serializing_if = "Option::is_none")]
+    pub fix_status: Option<FixStatus>,
+
+    /// When the earliest release fixing the vulnerability was published
+    /// (only determined when the registry index has publication dates, see
+    /// [`crate::Report::annotate_fix_age`])
+    #[serde(alias = "fix-age", default, skip_serializing_if = "Option::is_none")]
+    pub fix_age: Option<FixAge>,
+
+    /// How the vulnerable package overrides the registry release the
+    /// advisory was matched against (if it does)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overridden: Option<Override>,
+
+    /// Whether the vulnerable package has been reviewed (see
+    /// [`crate::Report::annotate_review_status`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewStatus>,
+
+    /// Shortest inverse dependency paths from the vulnerable package to a
+    /// root package, each beginning with the vulnerable package (see
+    /// [`crate::Report::annotate_affected_paths`])
+    #[serde(
+        alias = "affected-paths",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub affected_paths: Option<Vec<Vec<Dependency>>>,
 }
 
 impl Vulnerability {
     /// Create `Vulnerability` about a given [`Advisory`] and [`Package`]
     pub fn new(advisory: &Advisory, package: &Package) -> Self {
+        let remediation =
+            Remediation::from_advisory(package, &advisory.versions, &advisory.metadata.description);
+
+        let unmaintained = advisory.metadata.informational == Some(Informational::Unmaintained);
+        let fix_status = FixStatus::new(&remediation, unmaintained);
+
         Self {
             advisory: advisory.metadata.clone(),
             versions: advisory.versions.clone(),
             affected: advisory.affected.clone(),
             package: package.clone(),
+            severity: SeverityInfo::new(&advisory.metadata, &SeverityFallback::new()),
+            owner: None,
+            depth: None,
+            relationship: None,
+            remedy: None,
+            remediation: Some(remediation),
+            fix_status: Some(fix_status),
+            fix_age: None,
+            overridden: Override::detect(package),
+            review: None,
+            affected_paths: None,
         }
     }
 
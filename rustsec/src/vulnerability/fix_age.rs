@@ -0,0 +1,61 @@
+//! How long a fix for a vulnerability has been available
+
+use crate::Version;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// Number of seconds in a day
+const SECS_PER_DAY: u64 = 86400;
+
+/// When the earliest release fixing a vulnerability was published, to track
+/// how long a fix has been available without being applied (i.e. the age
+/// of the exposure)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FixAge {
+    /// Earliest published release which isn't vulnerable
+    pub version: Version,
+
+    /// When the release was published
+    #[serde(with = "humantime_serde")]
+    pub published: SystemTime,
+
+    /// Number of whole days between the release being published and the
+    /// report being generated
+    pub days: u64,
+}
+
+impl FixAge {
+    /// Compute how long the given release, published at the given time, has
+    /// been available as of `now`
+    pub fn new(version: Version, published: SystemTime, now: SystemTime) -> Self {
+        let days = now
+            .duration_since(published)
+            .map(|age| age.as_secs() / SECS_PER_DAY)
+            .unwrap_or(0);
+
+        Self {
+            version,
+            published,
+            days,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixAge;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn days() {
+        let published = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let now = published + Duration::from_secs(400 * 86400 + 3600);
+        let version = "1.2.3".parse().unwrap();
+
+        assert_eq!(FixAge::new(version, published, now).days, 400);
+
+        // Clock skew shouldn't result in a negative (or wrapped) age
+        let version = "1.2.3".parse().unwrap();
+        assert_eq!(FixAge::new(version, SystemTime::now(), published).days, 0);
+    }
+}
@@ -0,0 +1,81 @@
+//! Whether a fix for a vulnerability is available
+
+use super::Remediation;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Whether a release fixing a vulnerability exists, for prioritizing
+/// findings by how easily they can be fixed
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FixStatus {
+    /// A release which isn't vulnerable exists (without the registry index,
+    /// this means the advisory lists patched versions newer than the
+    /// vulnerable one, which may not have been published yet)
+    Patched,
+
+    /// No release fixing the vulnerability has been published yet
+    NoFixYet,
+
+    /// No release fixing the vulnerability has been published, and the crate
+    /// is unmaintained (according to an informational advisory), so one is
+    /// unlikely to be
+    Unmaintained,
+}
+
+impl FixStatus {
+    /// Determine the fix status from the fix candidates of a vulnerability,
+    /// and whether its crate is unmaintained
+    pub fn new(remediation: &Remediation, unmaintained: bool) -> Self {
+        if !remediation.candidates.is_empty() {
+            FixStatus::Patched
+        } else if unmaintained {
+            FixStatus::Unmaintained
+        } else {
+            FixStatus::NoFixYet
+        }
+    }
+
+    /// Get a `str` representing this fix status
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FixStatus::Patched => "patched",
+            FixStatus::NoFixYet => "no-fix-yet",
+            FixStatus::Unmaintained => "unmaintained",
+        }
+    }
+
+    /// Has a fix been released?
+    pub fn is_patched(self) -> bool {
+        self == FixStatus::Patched
+    }
+}
+
+impl fmt::Display for FixStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixStatus;
+    use crate::vulnerability::{FixCandidate, Remediation};
+
+    #[test]
+    fn from_remediation() {
+        let none = Remediation::default();
+        let some = Remediation {
+            candidates: vec![FixCandidate {
+                version: "1.2.3".parse().unwrap(),
+                semver_compatible: true,
+                command: "cargo update -p example:1.2.0 --precise 1.2.3".to_owned(),
+            }],
+            workaround: None,
+        };
+
+        assert_eq!(FixStatus::new(&some, true), FixStatus::Patched);
+        assert_eq!(FixStatus::new(&none, false), FixStatus::NoFixYet);
+        assert_eq!(FixStatus::new(&none, true), FixStatus::Unmaintained);
+    }
+}
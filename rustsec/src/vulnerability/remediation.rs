@@ -0,0 +1,270 @@
+//! Machine-readable instructions for remediating a vulnerability
+
+use crate::{
+    advisory,
+    osv::ranges_for_advisory,
+    package::Package,
+    version::{cmp_precedence, normalize},
+};
+use semver::{Prerelease, Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Headings of description sections which describe workarounds
+const WORKAROUND_HEADINGS: &[&str] = &["workaround", "workarounds", "mitigation", "mitigations"];
+
+/// How a vulnerability can be remediated, for consumption by tools which
+/// open upgrade PRs and the like
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Remediation {
+    /// Versions which fix the vulnerability, in ascending order. Only the
+    /// earliest fixed version of each semver-compatible series is listed.
+    pub candidates: Vec<FixCandidate>,
+
+    /// Workaround described by the advisory (i.e. the contents of a
+    /// "Workaround" section of its description), if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workaround: Option<String>,
+}
+
+/// Version which fixes a vulnerability
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FixCandidate {
+    /// Version to upgrade to
+    pub version: Version,
+
+    /// Is this version semver-compatible with the vulnerable one, i.e. can
+    /// it be upgraded to without changing any requirements?
+    #[serde(alias = "semver-compatible")]
+    pub semver_compatible: bool,
+
+    /// Command which upgrades to this version: `cargo update --precise` for
+    /// semver-compatible versions, or `cargo add` otherwise (which only
+    /// applies to direct dependencies; the dependents of transitive ones
+    /// need upgrading instead)
+    pub command: String,
+}
+
+impl Remediation {
+    /// Determine the remediation for a vulnerable package from the advisory
+    /// alone: candidates are the versions where its affected ranges end,
+    /// which may not have actually been published
+    pub fn from_advisory(
+        package: &Package,
+        versions: &advisory::Versions,
+        description: &str,
+    ) -> Self {
+        let stable = package.version.pre.is_empty();
+
+        let fixed = ranges_for_advisory(versions)
+            .into_iter()
+            .filter_map(|range| range.fixed().cloned())
+            .map(|mut version| {
+                // Ranges ending after an exclusive bound (e.g. `> 1.2.3`) end
+                // at the lowest pre-release of the next version (`1.2.4-0`)
+                if stable {
+                    version.pre = Prerelease::EMPTY;
+                }
+                version
+            })
+            .collect::<Vec<_>>();
+
+        Self::from_releases(package, versions, description, &fixed)
+    }
+
+    /// Determine the remediation for a vulnerable package given the
+    /// (non-yanked) releases of it published to the registry
+    pub fn from_releases<'a, I>(
+        package: &Package,
+        versions: &advisory::Versions,
+        description: &str,
+        releases: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = &'a Version>,
+    {
+        // Only suggest pre-releases if the current version is one
+        let stable = package.version.pre.is_empty();
+
+        let mut fixed = releases
+            .into_iter()
+            .filter(|release| {
+                cmp_precedence(release, &package.version) == Ordering::Greater
+                    && !versions.is_vulnerable(release)
+                    && (!stable || release.pre.is_empty())
+            })
+            .collect::<Vec<_>>();
+
+        fixed.sort_by(|a, b| cmp_precedence(a, b));
+        fixed.dedup_by(|a, b| compatible(*b).matches(&normalize(*a)));
+
+        // Requirements can't contain build metadata
+        let current = compatible(&package.version);
+
+        let candidates = fixed
+            .into_iter()
+            .map(|version| {
+                let semver_compatible = current.matches(version);
+
+                let command = if semver_compatible {
+                    format!(
+                        "cargo update -p {}:{} --precise {}",
+                        package.name, package.version, version
+                    )
+                } else {
+                    format!("cargo add {}@{}", package.name, version)
+                };
+
+                FixCandidate {
+                    version: version.clone(),
+                    semver_compatible,
+                    command,
+                }
+            })
+            .collect();
+
+        Self {
+            candidates,
+            workaround: workaround(description),
+        }
+    }
+}
+
+/// Get the caret requirement matching versions semver-compatible with the
+/// given one
+fn compatible(version: &Version) -> VersionReq {
+    VersionReq::parse(&format!("^{}", normalize(version)))
+        .expect("caret requirement for a valid version")
+}
+
+/// Extract the "Workaround" section of an advisory's Markdown description
+fn workaround(description: &str) -> Option<String> {
+    let mut lines = description.lines();
+
+    let level = lines.by_ref().find_map(|line| {
+        let (level, heading) = heading(line)?;
+
+        if WORKAROUND_HEADINGS.contains(&heading.to_ascii_lowercase().as_str()) {
+            Some(level)
+        } else {
+            None
+        }
+    })?;
+
+    // The section ends at the next heading of the same or a higher level
+    let section = lines
+        .take_while(|line| heading(line).map_or(true, |(l, _)| l > level))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let section = section.trim();
+
+    if section.is_empty() {
+        None
+    } else {
+        Some(section.to_owned())
+    }
+}
+
+/// Parse an ATX-style Markdown heading into its level and text
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+
+    if level == 0 || !line[level..].starts_with(' ') {
+        return None;
+    }
+
+    Some((level, line[level..].trim().trim_end_matches('#').trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{workaround, Remediation};
+    use crate::{advisory::Versions, package::Package};
+
+    fn package(version: &str) -> Package {
+        Package {
+            name: "example".parse().unwrap(),
+            version: version.parse().unwrap(),
+            source: None,
+            checksum: None,
+            dependencies: vec![],
+            replace: None,
+        }
+    }
+
+    fn versions(patched: &[&str]) -> Versions {
+        Versions::new(
+            patched.iter().map(|req| req.parse().unwrap()).collect(),
+            vec![],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn from_advisory() {
+        let remediation =
+            Remediation::from_advisory(&package("1.2.0"), &versions(&["^1.2.3", "> 2.0.0"]), "");
+
+        let candidates = remediation
+            .candidates
+            .iter()
+            .map(|c| {
+                (
+                    c.version.to_string(),
+                    c.semver_compatible,
+                    c.command.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            candidates,
+            [
+                (
+                    "1.2.3".to_owned(),
+                    true,
+                    "cargo update -p example:1.2.0 --precise 1.2.3"
+                ),
+                ("2.0.1".to_owned(), false, "cargo add example@2.0.1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_releases() {
+        let releases = ["1.2.0", "1.2.3", "1.2.4", "1.3.0", "2.0.0-rc.1", "2.0.0"]
+            .iter()
+            .map(|release| release.parse().unwrap())
+            .collect::<Vec<_>>();
+
+        let remediation =
+            Remediation::from_releases(&package("1.2.0"), &versions(&[">= 1.2.3"]), "", &releases);
+
+        let candidates = remediation
+            .candidates
+            .iter()
+            .map(|c| c.version.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(candidates, ["1.2.3", "2.0.0"]);
+    }
+
+    #[test]
+    fn extract_workaround() {
+        let description = "Something is broken.\n\n\
+            ## Workarounds\n\n\
+            Don't call `frobnicate`.\n\n\
+            ### Details\n\n\
+            Really, don't.\n\n\
+            ## References\n\n\
+            None.";
+
+        assert_eq!(
+            workaround(description).unwrap(),
+            "Don't call `frobnicate`.\n\n### Details\n\nReally, don't."
+        );
+        assert_eq!(workaround("Something is broken."), None);
+        assert_eq!(workaround("#Workaround\nnot a heading"), None);
+    }
+}
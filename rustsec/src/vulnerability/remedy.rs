@@ -0,0 +1,193 @@
+//! Classification of how a vulnerability can be remediated
+
+use crate::{advisory, package::Package, version::cmp_precedence};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// How a vulnerable package can be upgraded to a release which isn't
+/// vulnerable
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Remedy {
+    /// A semver-compatible release is patched, so running `cargo update`
+    /// on the package will fix the vulnerability (provided no dependent
+    /// crate pins it to the vulnerable version)
+    CargoUpdate {
+        /// Earliest semver-compatible release which isn't vulnerable
+        version: Version,
+    },
+
+    /// Only semver-incompatible releases are patched, so the requirements
+    /// of the crates depending on this package need to be upgraded manually
+    ManualUpgrade {
+        /// Earliest release which isn't vulnerable
+        version: Version,
+    },
+
+    /// No release which isn't vulnerable has been published
+    NoFix,
+}
+
+impl Remedy {
+    /// Determine how the given package can be fixed, given the advisory's
+    /// affected versions and the (non-yanked) releases of the package
+    /// published to the registry
+    pub fn new<'a, I>(package: &Package, versions: &advisory::Versions, releases: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Version>,
+    {
+        let mut fixed = releases
+            .into_iter()
+            .filter(|release| {
+                cmp_precedence(release, &package.version) == Ordering::Greater
+                    && !versions.is_vulnerable(release)
+            })
+            .collect::<Vec<_>>();
+
+        fixed.sort_by(|a, b| cmp_precedence(a, b));
+
+        if let Some(version) = fixed
+            .iter()
+            .find(|release| is_compatible(&package.version, release))
+        {
+            return Remedy::CargoUpdate {
+                version: (*version).clone(),
+            };
+        }
+
+        // Only suggest pre-releases if the current version is one
+        let stable = package.version.pre.is_empty();
+
+        match fixed
+            .into_iter()
+            .find(|release| !stable || release.pre.is_empty())
+        {
+            Some(version) => Remedy::ManualUpgrade {
+                version: version.clone(),
+            },
+            None => Remedy::NoFix,
+        }
+    }
+}
+
+/// Would `cargo update` select the given release for a caret requirement on
+/// the current version, i.e. is it semver-compatible and no older?
+///
+/// Versions are compared by precedence, ignoring build metadata. Like Cargo,
+/// pre-releases are only compatible with a pre-release of the same version.
+fn is_compatible(current: &Version, release: &Version) -> bool {
+    let same_series = if current.major > 0 {
+        release.major == current.major
+    } else if current.minor > 0 {
+        release.major == 0 && release.minor == current.minor
+    } else {
+        release.major == 0 && release.minor == 0 && release.patch == current.patch
+    };
+
+    let same_release = (release.major, release.minor, release.patch)
+        == (current.major, current.minor, current.patch);
+
+    same_series
+        && (release.pre.is_empty() || (!current.pre.is_empty() && same_release))
+        && cmp_precedence(release, current) != Ordering::Less
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_compatible, Remedy};
+    use crate::{advisory::Versions, package::Package};
+    use semver::Version;
+
+    fn remedy(current: &str, patched: &[&str], releases: &[&str]) -> Remedy {
+        let package = Package {
+            name: "example".parse().unwrap(),
+            version: current.parse().unwrap(),
+            source: None,
+            checksum: None,
+            dependencies: vec![],
+            replace: None,
+        };
+
+        let versions = Versions::new(
+            patched.iter().map(|req| req.parse().unwrap()).collect(),
+            vec![],
+        )
+        .unwrap();
+
+        let releases = releases
+            .iter()
+            .map(|release| release.parse().unwrap())
+            .collect::<Vec<Version>>();
+
+        Remedy::new(&package, &versions, &releases)
+    }
+
+    #[test]
+    fn cargo_update() {
+        assert_eq!(
+            remedy(
+                "1.2.0",
+                &[">= 1.2.3"],
+                &["1.2.0", "1.2.3", "1.3.0", "2.0.0"]
+            ),
+            Remedy::CargoUpdate {
+                version: "1.2.3".parse().unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn manual_upgrade() {
+        assert_eq!(
+            remedy("0.5.1", &[">= 0.6.0"], &["0.5.1", "0.6.0-rc.1", "0.6.0"]),
+            Remedy::ManualUpgrade {
+                version: "0.6.0".parse().unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn no_fix() {
+        assert_eq!(
+            remedy("1.0.0", &[">= 1.1.0"], &["1.0.0", "1.0.1"]),
+            Remedy::NoFix
+        );
+    }
+
+    #[test]
+    fn build_metadata() {
+        // `1.0.0+vendor.2` is the same (vulnerable) release as `1.0.0+vendor.1`
+        assert_eq!(
+            remedy(
+                "1.0.0+vendor.1",
+                &[">= 1.0.1"],
+                &["1.0.0+vendor.2", "1.0.1+vendor.1"]
+            ),
+            Remedy::CargoUpdate {
+                version: "1.0.1+vendor.1".parse().unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn compatible_releases() {
+        let compatible = |current: &str, release: &str| {
+            is_compatible(&current.parse().unwrap(), &release.parse().unwrap())
+        };
+
+        assert!(compatible("1.2.0", "1.9.0"));
+        assert!(compatible("1.2.0+vendor.1", "1.2.0+vendor.2"));
+        assert!(!compatible("1.2.0", "2.0.0"));
+        assert!(!compatible("1.2.0", "1.1.0"));
+        assert!(!compatible("1.2.0", "1.3.0-rc.1"));
+
+        assert!(compatible("0.2.1", "0.2.5"));
+        assert!(!compatible("0.2.1", "0.3.0"));
+        assert!(!compatible("0.0.1", "0.0.2"));
+
+        assert!(compatible("1.0.0-rc.1", "1.0.0-rc.2"));
+        assert!(compatible("1.0.0-rc.1", "1.0.0"));
+        assert!(!compatible("1.0.0-rc.1", "1.1.0-rc.1"));
+    }
+}
@@ -1,7 +1,18 @@
 //! Warnings sourced from the Advisory DB
 
+#[cfg(feature = "cvss")]
+use crate::advisory::{SeverityFallback, SeverityInfo};
 use crate::error::{Error, ErrorKind};
-use crate::{advisory, package::Package};
+#[cfg(feature = "report")]
+use crate::report::{Override, ReviewStatus};
+use crate::{
+    advisory,
+    cargo_lock::Dependency,
+    dependency::Relationship,
+    package::{Checksum, Package},
+    Version,
+};
+use semver::VersionReq;
 use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
 
@@ -19,6 +30,81 @@ pub struct Warning {
 
     /// Versions impacted by this warning
     pub versions: Option<advisory::Versions>,
+
+    /// Severity of the source advisory (if any), and whether it was declared
+    /// in the advisory or derived from the advisory type
+    #[cfg(feature = "cvss")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<SeverityInfo>,
+
+    /// Team which owns the package (see [`crate::report::Owners`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Minimum depth of the package in the dependency graph, where direct
+    /// dependencies of workspace members have depth 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+
+    /// Whether the package is a direct or transitive dependency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationship: Option<Relationship>,
+
+    /// Was this warning promoted to an error by a promotion rule (see
+    /// [`crate::report::PromotionRule`])?
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub promoted: bool,
+
+    /// How the package overrides the registry release the source advisory
+    /// was matched against (if it does)
+    #[cfg(feature = "report")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overridden: Option<Override>,
+
+    /// Checksum of the package according to the registry index, for
+    /// warnings about packages whose checksum in `Cargo.lock` doesn't match
+    #[serde(
+        alias = "expected-checksum",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expected_checksum: Option<Checksum>,
+
+    /// Whether the package has been reviewed (see
+    /// [`crate::Report::annotate_review_status`])
+    #[cfg(feature = "report")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewStatus>,
+
+    /// All versions of the package in the lockfile, for warnings about
+    /// packages present in several versions (see
+    /// [`crate::Report::check_duplicates`])
+    #[serde(
+        alias = "duplicate-versions",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub duplicate_versions: Vec<Version>,
+
+    /// Shortest inverse dependency paths from the package to a root
+    /// package, i.e. what keeps it in the dependency graph, each beginning
+    /// with the package itself
+    #[serde(
+        alias = "affected-paths",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub affected_paths: Option<Vec<Vec<Dependency>>>,
+
+    /// Entry of `Cargo.lock` which was skipped because it couldn't be
+    /// parsed, for warnings about malformed lockfiles (see
+    /// [`crate::Report::check_malformed_entries`])
+    #[serde(
+        alias = "malformed-entry",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub malformed_entry: Option<String>,
 }
 
 impl Warning {
@@ -29,14 +115,55 @@ impl Warning {
         advisory: Option<advisory::Metadata>,
         versions: Option<advisory::Versions>,
     ) -> Self {
+        #[cfg(feature = "cvss")]
+        let severity = advisory
+            .as_ref()
+            .and_then(|metadata| SeverityInfo::new(metadata, &SeverityFallback::new()));
+
         Self {
             kind,
             package: package.clone(),
             advisory,
             versions,
+            #[cfg(feature = "cvss")]
+            severity,
+            owner: None,
+            depth: None,
+            relationship: None,
+            promoted: false,
+            #[cfg(feature = "report")]
+            overridden: Override::detect(package),
+            expected_checksum: None,
+            #[cfg(feature = "report")]
+            review: None,
+            duplicate_versions: vec![],
+            affected_paths: None,
+            malformed_entry: None,
         }
     }
 
+    /// Get the ID of the source advisory (if any)
+    pub fn id(&self) -> Option<&advisory::Id> {
+        self.advisory.as_ref().map(|metadata| &metadata.id)
+    }
+
+    /// Get the other IDs the source advisory is known by (e.g. CVEs), which
+    /// is useful for deduplicating warnings across tools
+    pub fn aliases(&self) -> &[advisory::Id] {
+        self.advisory
+            .as_ref()
+            .map(|metadata| metadata.aliases.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the versions of the package which resolve this warning (if any)
+    pub fn patched_versions(&self) -> &[VersionReq] {
+        self.versions
+            .as_ref()
+            .map(|versions| versions.patched())
+            .unwrap_or_default()
+    }
+
     /// Is this a warning a `notice` about a crate?
     pub fn is_notice(&self) -> bool {
         self.kind == Kind::Notice
@@ -56,6 +183,38 @@ impl Warning {
     pub fn is_yanked(&self) -> bool {
         self.kind == Kind::Yanked
     }
+
+    /// Is this a warning about a package whose checksum doesn't match the
+    /// registry index?
+    pub fn is_checksum_mismatch(&self) -> bool {
+        self.kind == Kind::ChecksumMismatch
+    }
+
+    /// Is this a warning about a package present in several versions?
+    pub fn is_duplicate(&self) -> bool {
+        self.kind == Kind::Duplicate
+    }
+
+    /// Is this a warning about an advisory affecting one of the project's
+    /// own packages?
+    pub fn is_own_crate(&self) -> bool {
+        self.kind == Kind::OwnCrate
+    }
+
+    /// Is this a warning about a malformed `Cargo.lock` entry?
+    pub fn is_malformed_lockfile(&self) -> bool {
+        self.kind == Kind::MalformedLockfile
+    }
+
+    /// Is this a warning about a vulnerability in an overridden package?
+    pub fn is_overridden(&self) -> bool {
+        self.kind == Kind::Overridden
+    }
+}
+
+/// Is the given flag unset? (used to omit it when serializing)
+fn is_false(flag: &bool) -> bool {
+    !*flag
 }
 
 /// Kinds of warnings
@@ -77,6 +236,33 @@ pub enum Kind {
     /// Yanked packages
     #[serde(rename = "yanked")]
     Yanked,
+
+    /// Vulnerabilities in packages overridden via `[patch]` or `[replace]`,
+    /// downgraded to warnings (see [`crate::report::Settings::overrides_as_warnings`])
+    #[serde(rename = "overridden")]
+    Overridden,
+
+    /// Packages whose checksum in `Cargo.lock` doesn't match the registry
+    /// index, i.e. which may have been tampered with or substituted (see
+    /// [`crate::Report::check_checksums`])
+    #[serde(rename = "checksum-mismatch")]
+    ChecksumMismatch,
+
+    /// Older versions of packages which are present in several versions
+    /// (see [`crate::Report::check_duplicates`])
+    #[serde(rename = "duplicate")]
+    Duplicate,
+
+    /// Advisories about the project's own local packages, e.g. workspace
+    /// members which are also published (see [`crate::Report::check_own_crates`])
+    #[serde(rename = "own-crate")]
+    OwnCrate,
+
+    /// Entries of `Cargo.lock` which were skipped because they couldn't be
+    /// parsed, i.e. which weren't audited (see
+    /// [`crate::Report::check_malformed_entries`])
+    #[serde(rename = "malformed-lockfile")]
+    MalformedLockfile,
 }
 
 impl Kind {
@@ -87,6 +273,11 @@ impl Kind {
             Self::Unmaintained => "unmaintained",
             Self::Unsound => "unsound",
             Self::Yanked => "yanked",
+            Self::Overridden => "overridden",
+            Self::ChecksumMismatch => "checksum-mismatch",
+            Self::Duplicate => "duplicate",
+            Self::OwnCrate => "own-crate",
+            Self::MalformedLockfile => "malformed-lockfile",
         }
     }
 }
@@ -100,6 +291,11 @@ impl FromStr for Kind {
             "unmaintained" => Kind::Unmaintained,
             "unsound" => Kind::Unsound,
             "yanked" => Kind::Yanked,
+            "overridden" => Kind::Overridden,
+            "checksum-mismatch" => Kind::ChecksumMismatch,
+            "duplicate" => Kind::Duplicate,
+            "own-crate" => Kind::OwnCrate,
+            "malformed-lockfile" => Kind::MalformedLockfile,
             other => fail!(ErrorKind::Parse, "invalid warning type: {}", other),
         })
     }
@@ -52,8 +52,12 @@ fn parse_metadata() {
 #[test]
 fn parse_affected() {
     let affected = load_example_v3_advisory().affected.unwrap();
-    assert_eq!(affected.arch[0], platforms::target::Arch::X86);
-    assert_eq!(affected.os[0], platforms::target::OS::Windows);
+
+    #[cfg(feature = "platforms")]
+    {
+        assert_eq!(affected.arch[0], platforms::target::Arch::X86);
+        assert_eq!(affected.os[0], platforms::target::OS::Windows);
+    }
 
     let example_function = "base::belongs::All".parse().unwrap();
     let req = &affected.functions.get(&example_function).unwrap()[0];
@@ -70,6 +74,7 @@ fn parse_aliases() {
 }
 
 /// Parsing of CVSS v3.1 severity vector strings
+#[cfg(feature = "cvss")]
 #[test]
 fn parse_cvss_vector_string() {
     let advisory = load_example_v3_advisory();
@@ -102,3 +107,101 @@ fn parse_patched_version_reqs() {
     assert!(req.matches(&"1.2.3".parse().unwrap()));
     assert!(req.matches(&"1.2.4".parse().unwrap()));
 }
+
+/// Malformed advisories should produce errors rather than panics
+#[test]
+fn parse_malformed_advisories() {
+    let malformed = [
+        "",
+        "ありがとうございます、ありがとうございます、ありがとうございます",
+        "```toml",
+        "```toml\n```",
+        "```toml\n```\n# ",
+        "```toml\nid = \"RUSTSEC-\"\n```\n# Title\n",
+        "```toml\nid = \"RUSTSEC-2001-2101\"\npackage = \"base\"\ndate = \"2001-02-31-01\"\n```\n# Title\n",
+        "```toml\n[advisory]\nid = \"RUSTSEC-2001-2101\"\npackage = \"base\"\ndate = \"2001-02-03\"\n\n[versions]\npatched = [\"^18446744073709551615\"]\n```\n# Title\n",
+    ];
+
+    for advisory in &malformed {
+        assert!(advisory.parse::<rustsec::Advisory>().is_err());
+    }
+}
+
+/// Serializing advisories back to the canonical format
+#[test]
+fn toml_round_trip() {
+    for advisory in &[load_example_v3_advisory(), load_example_v4_advisory()] {
+        let serialized = advisory.to_toml_string().unwrap();
+        assert!(serialized.starts_with("```toml\n[advisory]\nid = \"RUSTSEC-2001-2101\"\n"));
+        assert!(serialized.ends_with("\n# All your base are belong to us\n\nYou have no chance to survive. Make your time.\n"));
+
+        // Fields which are only parsed with optional features are kept
+        // regardless of which features are enabled
+        assert!(serialized.contains("\ncvss = \"CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H\"\n"));
+        assert!(serialized.contains("\narch = [\"x86\"]\n"));
+        assert!(serialized.contains("\nos = [\"windows\"]\n"));
+
+        let reparsed: rustsec::Advisory = serialized.parse().unwrap();
+        assert_eq!(advisory, &reparsed);
+        assert_eq!(serialized, reparsed.to_toml_string().unwrap());
+    }
+}
+
+/// Rendering advisories as human-readable text
+#[cfg(feature = "cvss")]
+#[test]
+fn display_report() {
+    let report = load_example_v3_advisory().display_report(80);
+    let attr = |label: &str, value: &str| format!("{:15}{}", label, value);
+
+    assert_eq!(
+        report.lines().take(7).collect::<Vec<_>>(),
+        [
+            attr("Title:", "All your base are belong to us"),
+            attr("ID:", "RUSTSEC-2001-2101"),
+            attr("Crate:", "base"),
+            attr("Date:", "2001-02-03"),
+            attr("URL:", "https://rustsec.org/advisories/RUSTSEC-2001-2101"),
+            attr("Severity:", "10.0 (critical)"),
+            attr("Solution:", "Upgrade to >=1.2.3"),
+        ]
+    );
+    assert!(report.ends_with("\n\nYou have no chance to survive. Make your time.\n"));
+}
+
+/// Matching affected platforms by target triple
+#[cfg(feature = "platforms")]
+#[test]
+fn affects_platform() {
+    let affected = load_example_v3_advisory().affected.unwrap();
+    let platform = |triple| platforms::Platform::find(triple).unwrap();
+
+    assert!(affected.affects_platform(platform("i686-pc-windows-msvc")));
+    assert!(!affected.affects_platform(platform("x86_64-pc-windows-msvc")));
+    assert!(!affected.affects_platform(platform("i686-unknown-linux-gnu")));
+}
+
+/// Querying for specific kinds of informational advisories
+#[cfg(feature = "report")]
+#[test]
+fn query_informational_kind() {
+    use rustsec::{advisory::Informational, database::Query};
+
+    let unmaintained: rustsec::Advisory = "```toml\n[advisory]\nid = \"RUSTSEC-2001-2102\"\npackage = \"base\"\ndate = \"2001-02-03\"\ninformational = \"unmaintained\"\n\n[versions]\npatched = []\n```\n# Unmaintained\n\nNo longer maintained.\n"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        unmaintained.informational(),
+        Some(&Informational::Unmaintained)
+    );
+    assert!(Query::new()
+        .informational_kind(Informational::Unmaintained)
+        .matches(&unmaintained));
+    assert!(!Query::new()
+        .informational_kind(Informational::Unsound)
+        .matches(&unmaintained));
+    assert!(!Query::new()
+        .informational_kind(Informational::Unmaintained)
+        .matches(&load_example_v3_advisory()));
+}
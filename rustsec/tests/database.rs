@@ -1,11 +1,11 @@
-#![cfg(feature = "git")]
+#![cfg(feature = "db-git")]
 
 use cargo_lock::Lockfile;
 use once_cell::sync::Lazy;
 use rustsec::database::scope;
-use rustsec::database::Query;
+use rustsec::database::{advisory_id, LoadMethod, LoadProgress, Query, Source};
 use rustsec::repository::git::Repository;
-use rustsec::Database;
+use rustsec::{Collection, Database};
 use std::path::Path;
 use std::sync::Mutex;
 
@@ -55,3 +55,559 @@ fn query_vulnerabilities_scope_public() {
     let vuln_all = db.query_vulnerabilities(&lockfile, &Query::crate_scope(), scope::Registry::All);
     assert_eq!(vuln_all.len(), 1);
 }
+
+/// advisories iterated by collection should add up to the whole database
+#[test]
+fn iter_collection() {
+    let db = DEFAULT_DATABASE.lock().unwrap();
+    let crates_count = db.iter_collection(Collection::Crates).count();
+    let rust_count = db.iter_collection(Collection::Rust).count();
+    assert_eq!(crates_count + rust_count, db.iter().count());
+
+    let packages = db.packages();
+    assert!(!packages.is_empty());
+    assert!(packages.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+/// querying packages individually should match querying the whole lockfile
+#[test]
+fn query_package() {
+    let lockfile_path = Path::new("./tests/support/cratesio_cargo.lock");
+    let lockfile =
+        Lockfile::load(lockfile_path).expect("Should find the lock file in support folder.");
+    let db = DEFAULT_DATABASE.lock().unwrap();
+
+    let advisories = lockfile
+        .packages
+        .iter()
+        .flat_map(|pkg| db.query_package(&pkg.name, &pkg.version, pkg.source.as_ref()))
+        .count();
+
+    assert_eq!(advisories, db.vulnerabilities(&lockfile).len());
+
+    let local_pkg = &lockfile.packages[0];
+    assert!(db
+        .query_package(&local_pkg.name, &local_pkg.version, None)
+        .is_empty());
+}
+
+/// the first finding should be the same vulnerability found by a full report
+#[test]
+fn find_first() {
+    let lockfile_path = Path::new("./tests/support/cratesio_cargo.lock");
+    let lockfile =
+        Lockfile::load(lockfile_path).expect("Should find the lock file in support folder.");
+    let db = DEFAULT_DATABASE.lock().unwrap();
+    let settings = rustsec::report::Settings::default();
+
+    match rustsec::report::find_first(&db, &lockfile, &settings, &[]) {
+        Some(rustsec::report::Finding::Vulnerability(vuln)) => {
+            assert_eq!(vuln, db.vulnerabilities(&lockfile)[0])
+        }
+        other => panic!("expected a vulnerability, got {:?}", other),
+    }
+}
+
+/// loading progress should be reported for each advisory file
+#[test]
+fn open_with_progress() {
+    let dir = tempfile::tempdir().unwrap();
+    let package_dir = dir.path().join("crates").join("base");
+    std::fs::create_dir_all(&package_dir).unwrap();
+    std::fs::copy(
+        "./tests/support/example_advisory_v3.md",
+        package_dir.join("RUSTSEC-2001-2101.md"),
+    )
+    .unwrap();
+
+    let mut events = vec![];
+    let db = Database::open_with_progress(dir.path(), |event| {
+        events.push(match event {
+            LoadProgress::Discovered { total } => (0, total),
+            LoadProgress::Parsed { parsed, total, .. } => (parsed, total),
+            other => panic!("unexpected event: {:?}", other),
+        })
+    })
+    .unwrap();
+
+    assert_eq!(db.iter().count(), 1);
+    assert_eq!(events, [(0, 1), (1, 1)]);
+}
+
+/// custom collections declared in `collections.toml` should be loaded and
+/// queryable, ignoring undeclared directories and files in collections which
+/// aren't advisories
+#[test]
+fn custom_collections() {
+    let dir = tempfile::tempdir().unwrap();
+    let package_dir = dir.path().join("firmware").join("base");
+    let docs_dir = dir.path().join("docs").join("guide");
+    std::fs::create_dir_all(&package_dir).unwrap();
+    std::fs::create_dir_all(&docs_dir).unwrap();
+    std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+    std::fs::write(
+        dir.path().join(rustsec::database::COLLECTIONS_FILE),
+        "collections = [\"firmware\"]\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("firmware").join("README.md"), "").unwrap();
+    std::fs::write(package_dir.join("notes.txt"), "").unwrap();
+    std::fs::write(docs_dir.join("intro.md"), "# Introduction").unwrap();
+    std::fs::copy(
+        "./tests/support/example_advisory_v3.md",
+        package_dir.join("RUSTSEC-2001-2101.md"),
+    )
+    .unwrap();
+
+    let db = Database::open(dir.path()).unwrap();
+
+    let firmware = "firmware".parse::<Collection>().unwrap();
+    assert_eq!(db.collections(), [firmware]);
+    assert_eq!(db.iter_collection(firmware).count(), 1);
+    assert!(db.packages().is_empty());
+    assert_eq!(db.collection_packages(firmware).len(), 1);
+
+    let base = "base".parse::<rustsec::package::Name>().unwrap();
+    let query = Query::new().collection(firmware).package(base.clone());
+    assert_eq!(db.query(&query).len(), 1);
+    assert!(db.query(&Query::crate_scope().package(base)).is_empty());
+
+    // Files in declared collections which aren't advisories are errors
+    std::fs::write(package_dir.join("notes.md"), "# Notes").unwrap();
+    assert!(Database::open(dir.path()).is_err());
+}
+
+/// directories which aren't declared as collections aren't loaded, even if
+/// they look like collections
+#[test]
+fn undeclared_collections() {
+    let advisory = include_str!("support/example_advisory_v3.md");
+    let path = Path::new("firmware/base/RUSTSEC-2001-2101.md");
+
+    let db = Database::load_from_files(vec![(path, advisory)], Source::embedded(None)).unwrap();
+    assert_eq!(db.iter().count(), 0);
+
+    let collections_file = Path::new(rustsec::database::COLLECTIONS_FILE);
+    let db = Database::load_from_files(
+        vec![
+            (path, advisory),
+            (collections_file, "collections = [\"firmware\"]"),
+        ],
+        Source::embedded(None),
+    )
+    .unwrap();
+    assert_eq!(db.iter().count(), 1);
+
+    assert!(Database::load_from_files(
+        vec![(collections_file, "collections = \"firmware\"")],
+        Source::embedded(None),
+    )
+    .is_err());
+}
+
+/// loading from git's object database should find the same advisories as
+/// loading from the working tree
+#[test]
+fn load_from_commit() {
+    let db = DEFAULT_DATABASE.lock().unwrap();
+    let repo = Repository::open(Repository::default_path()).unwrap();
+    let from_commit = Database::load_from_commit(&repo, "HEAD").unwrap();
+
+    assert_eq!(from_commit.iter().count(), db.iter().count());
+    assert_eq!(
+        from_commit.latest_commit().unwrap().commit_id,
+        repo.latest_commit().unwrap().commit_id
+    );
+}
+
+/// clones of the database should be queryable from several threads at once,
+/// with memoized queries giving the same results as uncached ones
+#[test]
+fn shared_across_threads() {
+    let lockfile_path = Path::new("./tests/support/cratesio_cargo.lock");
+    let lockfile =
+        Lockfile::load(lockfile_path).expect("Should find the lock file in support folder.");
+    let db = DEFAULT_DATABASE.lock().unwrap().clone();
+    let expected = db.vulnerabilities(&lockfile);
+
+    let handles = (0..4)
+        .map(|_| {
+            let db = db.clone();
+            let lockfile = lockfile.clone();
+            std::thread::spawn(move || db.vulnerabilities(&lockfile))
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+
+    // Repeated queries are answered from the cache
+    assert_eq!(db.vulnerabilities(&lockfile), expected);
+}
+
+/// databases can be loaded from file contents which aren't on disk (e.g. a
+/// snapshot embedded in a binary), skipping files outside of collections
+#[test]
+fn load_from_files() {
+    let advisory = include_str!("support/example_advisory_v3.md");
+    let path = Path::new("crates/base/RUSTSEC-2001-2101.md");
+    let readme = Path::new("README.md");
+
+    let db = Database::load_from_files(
+        vec![(path, advisory), (readme, "# RustSec Advisory Database")],
+        Source::embedded(None),
+    )
+    .unwrap();
+
+    assert_eq!(db.iter().count(), 1);
+    assert!(db.get(&"RUSTSEC-2001-2101".parse().unwrap()).is_some());
+    assert_eq!(db.source().method, LoadMethod::Embedded);
+}
+
+/// advisories about the project's own local packages should be surfaced as
+/// `own-crate` warnings rather than ignored along with other local packages
+#[test]
+fn check_own_crates() {
+    let advisory = include_str!("support/example_advisory_v3.md");
+    let path = Path::new("crates/base/RUSTSEC-2001-2101.md");
+    let db = Database::load_from_files(vec![(path, advisory)], Source::embedded(None)).unwrap();
+
+    let lockfile: Lockfile = r#"
+[[package]]
+name = "base"
+version = "1.0.0"
+"#
+    .parse()
+    .unwrap();
+
+    let mut report = rustsec::Report::generate(&db, &lockfile, &Default::default());
+    assert!(!report.vulnerabilities.found);
+
+    report.check_own_crates(&db, &lockfile);
+
+    let warnings = &report.warnings[&rustsec::warning::Kind::OwnCrate];
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].is_own_crate());
+    assert_eq!(warnings[0].id().unwrap().as_str(), "RUSTSEC-2001-2101");
+}
+
+/// packages whose checksum in the lockfile differs from the registry index's
+/// should be surfaced as promoted `checksum-mismatch` warnings
+#[test]
+fn check_checksums() {
+    use rustsec::{
+        package,
+        registry::{Backend, Index, IndexPackage},
+    };
+
+    const MATCHING: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+    const TAMPERED: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+
+    /// Index which has `MATCHING` as the checksum of `a` and `b`, and no
+    /// checksum for `c`
+    struct FixtureBackend;
+
+    impl Backend for FixtureBackend {
+        fn releases(&self, package: &package::Name) -> Result<Vec<IndexPackage>, rustsec::Error> {
+            let checksum = match package.as_str() {
+                "a" | "b" => Some(MATCHING.parse().unwrap()),
+                "c" => None,
+                other => {
+                    return Err(rustsec::Error::new(
+                        rustsec::ErrorKind::NotFound,
+                        &format!("no such package: {}", other),
+                    ))
+                }
+            };
+
+            Ok(vec![IndexPackage {
+                package: package.clone(),
+                version: "1.0.0".parse().unwrap(),
+                is_yanked: false,
+                checksum,
+                published: None,
+            }])
+        }
+    }
+
+    let db = Database::load_from_files(vec![], Source::embedded(None)).unwrap();
+
+    let package = |name: &str, source: &str, checksum: &str| {
+        format!(
+            "[[package]]\nname = \"{}\"\nversion = \"1.0.0\"\n{}{}\n",
+            name, source, checksum
+        )
+    };
+    let crates_io = "source = \"registry+https://github.com/rust-lang/crates.io-index\"\n";
+    let checksum = |checksum: &str| format!("checksum = \"{}\"\n", checksum);
+
+    // Packages without a checksum in the index, packages which aren't in the
+    // index, and local packages aren't checked
+    let lockfile: Lockfile = [
+        package("a", crates_io, &checksum(MATCHING)),
+        package("b", crates_io, &checksum(TAMPERED)),
+        package("c", crates_io, &checksum(TAMPERED)),
+        package("d", crates_io, &checksum(TAMPERED)),
+        package("e", "", ""),
+    ]
+    .concat()
+    .parse()
+    .unwrap();
+
+    let mut report = rustsec::Report::generate(&db, &lockfile, &Default::default());
+    report.check_checksums(&lockfile, &Index::with_backend(FixtureBackend));
+
+    let warnings = &report.warnings[&rustsec::warning::Kind::ChecksumMismatch];
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].is_checksum_mismatch());
+    assert!(warnings[0].promoted);
+    assert_eq!(warnings[0].package.name.as_str(), "b");
+    assert_eq!(
+        warnings[0].expected_checksum.as_ref().unwrap().to_string(),
+        MATCHING
+    );
+}
+
+/// reports about several targets should have a section per target (in the
+/// order given) with only the findings which affect that target
+#[test]
+fn target_reports() {
+    let advisory = include_str!("support/example_advisory_v3.md");
+    let path = Path::new("crates/base/RUSTSEC-2001-2101.md");
+    let db = Database::load_from_files(vec![(path, advisory)], Source::embedded(None)).unwrap();
+
+    let lockfile: Lockfile = r#"
+[[package]]
+name = "base"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#
+    .parse()
+    .unwrap();
+
+    // The advisory only affects x86 Windows
+    let settings = rustsec::report::Settings {
+        targets: vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "i686-pc-windows-msvc".to_owned(),
+        ],
+        ..Default::default()
+    };
+
+    let report = rustsec::Report::generate(&db, &lockfile, &settings);
+    assert_eq!(report.vulnerabilities.count, 1);
+
+    let targets = report
+        .targets
+        .iter()
+        .map(|target| (target.target.as_str(), target.vulnerabilities.count))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        targets,
+        [("x86_64-unknown-linux-gnu", 0), ("i686-pc-windows-msvc", 1)]
+    );
+
+    // A single target doesn't get a section of its own
+    let settings = rustsec::report::Settings {
+        targets: vec!["i686-pc-windows-msvc".to_owned()],
+        ..Default::default()
+    };
+
+    let report = rustsec::Report::generate(&db, &lockfile, &settings);
+    assert_eq!(report.vulnerabilities.count, 1);
+    assert!(report.targets.is_empty());
+}
+
+/// entries skipped when parsing a lockfile leniently should be surfaced as
+/// `malformed-lockfile` warnings about the packages they belong to
+#[test]
+fn check_malformed_entries() {
+    let db = Database::load_from_files(vec![], Source::embedded(None)).unwrap();
+
+    let (lockfile, skipped) = Lockfile::parse_lenient(
+        r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["dep"]
+
+[[package]]
+name = "dep"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "not-a-checksum"
+
+[metadata]
+broken = 1
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(skipped.len(), 2);
+
+    let mut report = rustsec::Report::generate(&db, &lockfile, &Default::default());
+    report.check_malformed_entries(&lockfile, &skipped);
+
+    let warnings = &report.warnings[&rustsec::warning::Kind::MalformedLockfile];
+    let packages = warnings
+        .iter()
+        .map(|warning| warning.package.name.as_str())
+        .collect::<Vec<_>>();
+
+    // The malformed `[metadata]` section is attributed to the local package
+    assert_eq!(packages, ["dep", "app"]);
+    assert!(warnings
+        .iter()
+        .all(|warning| warning.is_malformed_lockfile()));
+    assert!(warnings[0]
+        .malformed_entry
+        .as_ref()
+        .unwrap()
+        .starts_with("checksum of package `dep 1.0.0`: "));
+}
+
+/// reports with every field named in one style should deserialize back
+/// into the same report
+#[test]
+fn field_naming_round_trip() {
+    use rustsec::report::{AcceptedVulnerability, FieldNaming, Stats, ToolInfo};
+
+    let advisory = include_str!("support/example_advisory_v3.md");
+    let path = Path::new("crates/base/RUSTSEC-2001-2101.md");
+    let db = Database::load_from_files(vec![(path, advisory)], Source::embedded(None)).unwrap();
+
+    let (lockfile, skipped) = Lockfile::parse_lenient(
+        r#"
+version = 3
+
+[[package]]
+name = "base"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "not-a-checksum"
+"#,
+    )
+    .unwrap();
+
+    let settings = rustsec::report::Settings {
+        target_arch: Some("x86".parse().unwrap()),
+        target_os: Some("windows".parse().unwrap()),
+        overrides_as_warnings: true,
+        ..Default::default()
+    };
+
+    // Populate as many multi-word fields as possible
+    let mut report = rustsec::Report::generate(&db, &lockfile, &settings);
+    report.check_malformed_entries(&lockfile, &skipped);
+    assert_eq!(report.vulnerabilities.count, 1);
+
+    let vulnerability = &mut report.vulnerabilities.list[0];
+    vulnerability.fix_status = Some(rustsec::vulnerability::FixStatus::Patched);
+    vulnerability.affected_paths = Some(vec![vec![(&lockfile.packages[0]).into()]]);
+
+    let vulnerability = vulnerability.clone();
+    report.accepted.push(AcceptedVulnerability {
+        vulnerability,
+        patched_fork: "https://github.com/example/base".parse().unwrap(),
+    });
+    report.stats = Some(Stats {
+        fetch_ms: Some(1),
+        index_update_ms: Some(2),
+        load_ms: 3,
+        match_ms: 4,
+        index_cache_hits: 5,
+        index_cache_misses: 6,
+        database_size: Some(7),
+    });
+    report.tool = Some(ToolInfo::new("cargo-audit", "0.0.0"));
+
+    let legacy = serde_json::to_value(&report).unwrap();
+
+    for &(naming, renamed) in &[
+        (FieldNaming::KebabCase, "/vulnerabilities/list/0/fix-status"),
+        (FieldNaming::SnakeCase, "/database/advisory_count"),
+    ] {
+        let json = serde_json::to_string(&naming.apply(&report)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value.pointer(renamed).is_some(), "{} missing", renamed);
+
+        let parsed: rustsec::Report = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), legacy, "{}", naming);
+    }
+}
+
+/// vulnerabilities should be attributed to the workspace members which
+/// (transitively) depend on the vulnerable packages
+#[cfg(feature = "dependency-tree")]
+#[test]
+fn summarize_workspace_members() {
+    let advisory = include_str!("support/example_advisory_v3.md");
+    let path = Path::new("crates/base/RUSTSEC-2001-2101.md");
+    let db = Database::load_from_files(vec![(path, advisory)], Source::embedded(None)).unwrap();
+
+    let lockfile: Lockfile = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["wrapper"]
+
+[[package]]
+name = "base"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "tool"
+version = "0.1.0"
+
+[[package]]
+name = "wrapper"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["base"]
+"#
+    .parse()
+    .unwrap();
+
+    let mut report = rustsec::Report::generate(&db, &lockfile, &Default::default());
+    report
+        .summarize_workspace_members(
+            &lockfile,
+            &["app".parse().unwrap(), "tool".parse().unwrap()],
+        )
+        .unwrap();
+
+    let members = &report.workspace_members;
+    assert_eq!(members.len(), 2);
+
+    assert_eq!(members[0].name.as_str(), "app");
+    assert_eq!(members[0].vulnerable_dependencies, 1);
+    assert_eq!(
+        members[0].highest_severity,
+        Some(rustsec::advisory::Severity::Critical)
+    );
+
+    assert_eq!(members[1].name.as_str(), "tool");
+    assert_eq!(members[1].vulnerable_dependencies, 0);
+    assert_eq!(members[1].highest_severity, None);
+}
+
+/// Advisory IDs are only extracted from the paths of advisory files
+#[test]
+fn advisory_id_from_path() {
+    assert_eq!(
+        advisory_id(Path::new("crates/base64/RUSTSEC-2017-0004.md"))
+            .unwrap()
+            .as_str(),
+        "RUSTSEC-2017-0004"
+    );
+    assert!(advisory_id(Path::new("crates/base64/RUSTSEC-2017-0004.toml")).is_none());
+    assert!(advisory_id(Path::new("crates/RUSTSEC-2017-0004.md")).is_none());
+    assert!(advisory_id(Path::new(".github/ISSUE_TEMPLATE/RUSTSEC-2017-0004.md")).is_none());
+}
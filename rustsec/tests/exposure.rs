@@ -0,0 +1,108 @@
+//! Tests for computing exposure windows from a project's git history
+
+#![cfg(feature = "db-git")]
+
+use rustsec::{database::Source, exposure, report, Database};
+use std::{fs, path::Path};
+
+/// Lockfile containing the given version of the `base` crate
+fn lockfile(version: &str) -> String {
+    format!(
+        "[[package]]\nname = \"base\"\nversion = \"{}\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+        version
+    )
+}
+
+/// Write the given file and commit it at the given time, returning the
+/// commit ID
+fn commit(repo: &git2::Repository, path: &str, contents: &str, time: i64) -> String {
+    let workdir = repo.workdir().unwrap();
+    fs::write(workdir.join(path), contents).unwrap();
+
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(path)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let signature =
+        git2::Signature::new("Test", "test@example.com", &git2::Time::new(time, 0)).unwrap();
+    let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        path,
+        &tree,
+        parent.iter().collect::<Vec<_>>().as_slice(),
+    )
+    .unwrap()
+    .to_string()
+}
+
+fn database() -> Database {
+    let advisory = include_str!("support/example_advisory_v3.md");
+    let path = Path::new("crates/base/RUSTSEC-2001-2101.md");
+    Database::load_from_files(vec![(path, advisory)], Source::embedded(None)).unwrap()
+}
+
+/// a window starts when a vulnerable version enters the lockfile and ends
+/// when it leaves, ignoring commits which don't touch the lockfile
+#[test]
+fn exposure_windows() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(dir.path()).unwrap();
+
+    let introduced = commit(&repo, "Cargo.lock", &lockfile("1.0.0"), 1_000_000);
+    commit(&repo, "README.md", "# Project", 2_000_000);
+    let resolved = commit(&repo, "Cargo.lock", &lockfile("1.2.3"), 3_000_000);
+
+    let windows = exposure::exposure_windows(
+        &database(),
+        &dir.path().join("Cargo.lock"),
+        &report::Settings::default(),
+    )
+    .unwrap();
+
+    assert_eq!(windows.len(), 1);
+    assert_eq!(windows[0].advisory.as_str(), "RUSTSEC-2001-2101");
+    assert_eq!(windows[0].version.to_string(), "1.0.0");
+    assert_eq!(windows[0].introduced.commit_id, introduced);
+    assert_eq!(windows[0].resolved.as_ref().unwrap().commit_id, resolved);
+}
+
+/// commits dated before the Unix epoch are an error rather than wrapping
+/// around to a date in the far future
+#[test]
+fn exposure_windows_pre_epoch() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(dir.path()).unwrap();
+    let commit_id = commit(&repo, "Cargo.lock", &lockfile("1.0.0"), 0);
+
+    // libgit2 wraps negative timestamps around when creating commits, so
+    // replace the commit with one written by hand
+    let tree_id = repo
+        .find_commit(git2::Oid::from_str(&commit_id).unwrap())
+        .unwrap()
+        .tree_id();
+    let pre_epoch = format!(
+        "tree {}\nauthor Test <test@example.com> -1 +0000\n\
+         committer Test <test@example.com> -1 +0000\n\nCargo.lock\n",
+        tree_id
+    );
+    let pre_epoch_id = repo
+        .odb()
+        .unwrap()
+        .write(git2::ObjectType::Commit, pre_epoch.as_bytes())
+        .unwrap();
+    let head = repo.head().unwrap();
+    repo.reference(head.name().unwrap(), pre_epoch_id, true, "pre-epoch commit")
+        .unwrap();
+
+    assert!(exposure::exposure_windows(
+        &database(),
+        &dir.path().join("Cargo.lock"),
+        &report::Settings::default(),
+    )
+    .is_err());
+}
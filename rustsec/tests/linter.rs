@@ -99,3 +99,39 @@ fn invalid_example() {
     let invalid_section = lint.errors()[6].to_string();
     assert_eq!(invalid_section, "invalid key `invalid-section` in toplevel");
 }
+
+/// Advisory whose incident timeline dates are out of order
+const OUT_OF_ORDER_DATES_MD: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2021-0001"
+package = "base"
+date = "2021-02-03"
+discovered = "2021-01-20"
+reported = "2021-01-10"
+patched = "2021-01-25"
+
+[versions]
+patched = [">= 1.2.3"]
+```
+
+# All your base are belong to us
+
+You have no chance to survive. Make your time.
+
+"#;
+
+/// Discovery, report, and patch dates must be in order
+#[test]
+fn out_of_order_dates() {
+    let lint = rustsec::advisory::Linter::lint_string(OUT_OF_ORDER_DATES_MD).unwrap();
+    assert_eq!(lint.errors().len(), 1);
+    assert_eq!(
+        lint.errors()[0].to_string(),
+        "invalid value `2021-01-20` for key `discovered` in [advisory]: dates must be ordered: \
+         discovered <= reported <= patched, and reported <= date"
+    );
+
+    let advisory = lint.advisory();
+    assert_eq!(advisory.reported().unwrap().as_str(), "2021-01-10");
+    assert_eq!(advisory.patched_date().unwrap().as_str(), "2021-01-25");
+}
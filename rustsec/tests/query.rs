@@ -1,8 +1,14 @@
 //! Tests for parsing RustSec advisories
 
+#![cfg(feature = "report")]
 #![warn(rust_2018_idioms, unused_qualifications)]
 
-use rustsec::{advisory::Severity, database::Query, package};
+use platforms::target::{Arch, OS};
+use rustsec::{
+    advisory::{severity::SeveritySource, AdvisoryType, Severity, SeverityFallback, SeverityInfo},
+    database::Query,
+    package,
+};
 
 /// Load example advisory from the filesystem
 fn load_advisory() -> rustsec::Advisory {
@@ -40,3 +46,111 @@ fn matches_severity() {
     let query_matches = Query::new().severity(Severity::Critical);
     assert!(query_matches.matches(&advisory));
 }
+
+#[test]
+fn matches_severity_fallback() {
+    let mut advisory = load_advisory();
+    advisory.metadata.cvss = None;
+
+    // Advisories without CVSS info match regardless of threshold by default
+    assert!(Query::new().severity(Severity::High).matches(&advisory));
+
+    let mut fallback = SeverityFallback::new();
+    fallback.insert(AdvisoryType::Vulnerability, Severity::Medium);
+
+    let query_nomatch = Query::new()
+        .severity(Severity::High)
+        .severity_fallback(fallback.clone());
+    assert!(!query_nomatch.matches(&advisory));
+
+    let info = SeverityInfo::new(&advisory.metadata, &fallback).unwrap();
+    assert_eq!(info.severity, Severity::Medium);
+    assert_eq!(info.source, SeveritySource::Derived);
+
+    // Fallbacks for informational advisories don't apply to vulnerabilities
+    let mut fallback = SeverityFallback::new();
+    fallback.insert(AdvisoryType::Unmaintained, Severity::None);
+    assert!(SeverityInfo::new(&advisory.metadata, &fallback).is_none());
+}
+
+#[test]
+fn parse_severity_fallback() {
+    let fallback: SeverityFallback =
+        toml::from_str("vulnerability = \"high\"\nunsound = \"medium\"").unwrap();
+    assert_eq!(fallback[&AdvisoryType::Vulnerability], Severity::High);
+    assert_eq!(fallback[&AdvisoryType::Unsound], Severity::Medium);
+
+    // Typos are rejected rather than silently ignored
+    assert!(toml::from_str::<SeverityFallback>("unmaintaned = \"none\"").is_err());
+}
+
+#[test]
+fn matches_as_of() {
+    let advisory = load_advisory();
+
+    let query_matches = Query::new().as_of("2001-02-03".parse().unwrap());
+    assert!(query_matches.matches(&advisory));
+
+    let query_nomatch = Query::new().as_of("2001-02-02".parse().unwrap());
+    assert!(!query_nomatch.matches(&advisory));
+}
+
+#[test]
+fn matches_target_arch_and_os() {
+    let advisory = load_advisory();
+
+    assert!(Query::new().target_arch(Arch::X86).matches(&advisory));
+    assert!(!Query::new().target_arch(Arch::X86_64).matches(&advisory));
+
+    assert!(Query::new().target_os(OS::Windows).matches(&advisory));
+    assert!(!Query::new().target_os(OS::Linux).matches(&advisory));
+}
+
+#[test]
+fn excludes_target_arch_and_os() {
+    let mut advisory = load_advisory();
+
+    assert!(!Query::new()
+        .exclude_target_os(OS::Windows)
+        .matches(&advisory));
+    assert!(Query::new().exclude_target_os(OS::Linux).matches(&advisory));
+    assert!(!Query::new()
+        .exclude_target_arch(Arch::X86)
+        .matches(&advisory));
+
+    // Exclusions take precedence over inclusions
+    let query = Query::new()
+        .target_os(OS::Windows)
+        .exclude_target_os(OS::Windows);
+    assert!(!query.matches(&advisory));
+
+    // Advisories which also affect other platforms still match
+    let affected = advisory.affected.as_mut().unwrap();
+    affected.os.push(OS::Linux);
+    assert!(Query::new()
+        .exclude_target_os(OS::Windows)
+        .matches(&advisory));
+
+    // ...as do advisories which aren't platform-specific
+    let affected = advisory.affected.as_mut().unwrap();
+    affected.os.clear();
+    affected.arch.clear();
+    assert!(Query::new()
+        .exclude_target_os(OS::Windows)
+        .exclude_target_arch(Arch::X86)
+        .matches(&advisory));
+}
+
+#[test]
+fn matches_only_ids() {
+    let advisory = load_advisory();
+
+    let query_matches = Query::new().only_ids(&[
+        "RUSTSEC-2024-0001".parse().unwrap(),
+        "RUSTSEC-2001-2101".parse().unwrap(),
+    ]);
+    assert!(query_matches.matches(&advisory));
+
+    let query_nomatch = Query::new().only_ids(&["RUSTSEC-2024-0001".parse().unwrap()]);
+    assert!(!query_nomatch.matches(&advisory));
+}
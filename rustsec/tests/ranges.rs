@@ -0,0 +1,65 @@
+//! Property-based tests for converting semver requirements into OSV ranges
+
+#![cfg(feature = "testing")]
+#![warn(rust_2018_idioms, unused_qualifications)]
+
+use proptest::{collection::vec, prelude::*};
+use rustsec::{
+    advisory::Versions,
+    testing::{check_ranges, check_version},
+    Version, VersionReq,
+};
+
+/// Load example advisory from the filesystem
+fn load_advisory() -> rustsec::Advisory {
+    rustsec::Advisory::load_file("./tests/support/example_advisory_v3.md").unwrap()
+}
+
+/// Small release versions, so that generated requirements frequently interact
+fn version() -> impl Strategy<Value = Version> {
+    (0u64..4, 0u64..4, 0u64..4).prop_map(|(major, minor, patch)| Version::new(major, minor, patch))
+}
+
+/// Version requirements of the forms used in advisories
+fn version_req() -> impl Strategy<Value = VersionReq> {
+    let op = prop_oneof![
+        Just(">="),
+        Just(">"),
+        Just("<"),
+        Just("<="),
+        Just("="),
+        Just("^"),
+        Just("~"),
+    ];
+
+    prop_oneof![
+        (op, version()).prop_map(|(op, v)| format!("{}{}", op, v)),
+        (version(), version()).prop_map(|(a, b)| format!(">= {}, < {}", a, b)),
+    ]
+    .prop_map(|req| req.parse().unwrap())
+}
+
+proptest! {
+    #[test]
+    fn osv_ranges_match_semver(
+        patched in vec(version_req(), 0..3),
+        unaffected in vec(version_req(), 0..3),
+        version in version(),
+    ) {
+        // Overlapping requirements are rejected at parse time
+        if let Ok(versions) = Versions::new(patched, unaffected) {
+            if let Err(e) = check_version(&versions, &version) {
+                return Err(TestCaseError::fail(e.to_string()));
+            }
+
+            if let Err(e) = check_ranges(&versions) {
+                return Err(TestCaseError::fail(e.to_string()));
+            }
+        }
+    }
+}
+
+#[test]
+fn example_advisory_ranges() {
+    check_ranges(&load_advisory().versions).unwrap();
+}
@@ -0,0 +1,205 @@
+//! Tests for querying the history of advisory DB git repositories
+
+#![cfg(feature = "db-git")]
+
+use rustsec::repository::git::{Change, Repository};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Write (or delete, if the contents are `None`) the given files and commit
+/// them to the `main` branch at the given time, returning the commit ID
+fn commit(repo: &git2::Repository, files: &[(&str, Option<&str>)], time: i64) -> String {
+    let workdir = repo.workdir().unwrap();
+    let mut index = repo.index().unwrap();
+
+    for &(path, contents) in files {
+        let full_path = workdir.join(path);
+
+        match contents {
+            Some(contents) => {
+                fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+                fs::write(&full_path, contents).unwrap();
+                index.add_path(Path::new(path)).unwrap();
+            }
+            None => {
+                fs::remove_file(&full_path).unwrap();
+                index.remove_path(Path::new(path)).unwrap();
+            }
+        }
+    }
+
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let signature =
+        git2::Signature::new("Test", "test@example.com", &git2::Time::new(time, 0)).unwrap();
+    let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &format!("Commit at {}", time),
+        &tree,
+        parent.iter().collect::<Vec<_>>().as_slice(),
+    )
+    .unwrap()
+    .to_string()
+}
+
+/// Initialize a repository whose `HEAD` points at the `main` branch
+fn init(dir: &tempfile::TempDir) -> git2::Repository {
+    let repo = git2::Repository::init(dir.path()).unwrap();
+    repo.set_head("refs/heads/main").unwrap();
+    repo
+}
+
+/// commits are listed newest first, and at most as many as were requested
+#[test]
+fn recent_commits() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_repo = init(&dir);
+
+    let first = commit(&git_repo, &[("README.md", Some("1"))], 1_000_000);
+    let second = commit(&git_repo, &[("README.md", Some("2"))], 2_000_000);
+    let third = commit(&git_repo, &[("README.md", Some("3"))], 3_000_000);
+
+    let repo = Repository::open(dir.path()).unwrap();
+
+    let ids = |n| {
+        repo.recent_commits(n)
+            .unwrap()
+            .into_iter()
+            .map(|commit| commit.commit_id)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids(2), vec![third.clone(), second.clone()]);
+    assert_eq!(ids(10), vec![third, second, first]);
+
+    let latest = &repo.recent_commits(1).unwrap()[0];
+    assert_eq!(latest.summary, "Commit at 3000000");
+}
+
+/// the previous commit is the one `main` pointed to before its latest update,
+/// and there's none right after the initial commit
+#[test]
+fn previous_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_repo = init(&dir);
+
+    commit(&git_repo, &[("README.md", Some("1"))], 1_000_000);
+    let repo = Repository::open(dir.path()).unwrap();
+    assert_eq!(repo.previous_commit().unwrap(), None);
+
+    let second = commit(&git_repo, &[("README.md", Some("2"))], 2_000_000);
+    commit(&git_repo, &[("README.md", Some("3"))], 3_000_000);
+    assert_eq!(repo.previous_commit().unwrap(), Some(second));
+}
+
+/// added, modified and deleted files are reported by their path relative to
+/// the repository root, and without a `from` revision everything is added
+#[test]
+fn changed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_repo = init(&dir);
+
+    let first = commit(
+        &git_repo,
+        &[
+            ("crates/a/RUSTSEC-2001-0001.md", Some("a")),
+            ("crates/b/RUSTSEC-2001-0002.md", Some("b")),
+        ],
+        1_000_000,
+    );
+    let second = commit(
+        &git_repo,
+        &[
+            ("crates/a/RUSTSEC-2001-0001.md", Some("a, modified")),
+            ("crates/b/RUSTSEC-2001-0002.md", None),
+            ("crates/c/RUSTSEC-2001-0003.md", Some("c")),
+        ],
+        2_000_000,
+    );
+
+    let repo = Repository::open(dir.path()).unwrap();
+
+    let mut changes = repo.changed_files(Some(&first), &second).unwrap();
+    changes.sort();
+    assert_eq!(
+        changes,
+        vec![
+            (
+                Change::Added,
+                PathBuf::from("crates/c/RUSTSEC-2001-0003.md")
+            ),
+            (
+                Change::Modified,
+                PathBuf::from("crates/a/RUSTSEC-2001-0001.md")
+            ),
+            (
+                Change::Deleted,
+                PathBuf::from("crates/b/RUSTSEC-2001-0002.md")
+            ),
+        ]
+    );
+
+    let mut changes = repo.changed_files(None, &first).unwrap();
+    changes.sort();
+    assert_eq!(
+        changes,
+        vec![
+            (
+                Change::Added,
+                PathBuf::from("crates/a/RUSTSEC-2001-0001.md")
+            ),
+            (
+                Change::Added,
+                PathBuf::from("crates/b/RUSTSEC-2001-0002.md")
+            ),
+        ]
+    );
+}
+
+/// paths can be validated at any revision rather than just `HEAD`, and
+/// resolve to the blob of their contents at that revision
+#[cfg(feature = "osv")]
+#[test]
+fn git_path_at_commit() {
+    use rustsec::repository::git::GitPath;
+
+    let dir = tempfile::tempdir().unwrap();
+    let git_repo = init(&dir);
+
+    let first = commit(&git_repo, &[("crates/a/README.md", Some("1"))], 1_000_000);
+    let second = commit(
+        &git_repo,
+        &[
+            ("crates/a/README.md", Some("2")),
+            ("crates/b/README.md", Some("2")),
+        ],
+        2_000_000,
+    );
+
+    let repo = Repository::open(dir.path()).unwrap();
+    let a = Path::new("crates/a/README.md");
+    let b = Path::new("crates/b/README.md");
+
+    let head = GitPath::new(&repo, a).unwrap();
+    assert_eq!(head.commit_id(), second);
+    assert_eq!(head.path(), a);
+
+    let old = GitPath::at_commit(&repo, a, &first).unwrap();
+    assert_eq!(old.commit_id(), first);
+    assert_ne!(old.blob_id(), head.blob_id());
+
+    // The same contents resolve to the same blob, wherever they are
+    let other = GitPath::at_commit(&repo, b, "HEAD").unwrap();
+    assert_eq!(other.blob_id(), head.blob_id());
+
+    // `b` wasn't tracked yet at the first commit
+    assert!(GitPath::at_commit(&repo, b, &first).is_err());
+    assert!(GitPath::at_commit(&repo, Path::new("/crates/a/README.md"), "HEAD").is_err());
+}